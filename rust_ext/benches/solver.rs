@@ -0,0 +1,68 @@
+//! Speciation solver baselines: one closed-system solve, the 13-point analysis
+//! curve and a 200-point dosing curve, each for caustic-heavy, buffer-region and
+//! bicarbonate-plateau liquors, plus the full `analyze_bicarbonate_core` solver
+//! work on each. Run with `cargo bench --features bench`.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use gl260_rust_ext::bench_support::{
+    MW_CO2, MW_NAOH, analyze_bicarbonate_ph, dosing_curve_ph, solve_closed_ph, staged_ledger_mol,
+};
+use std::hint::black_box;
+
+const SOLUTION_VOLUME_L: f64 = 1.0;
+const NAOH_MASS_G: f64 = 40.0;
+const ANALYSIS_CURVE_POINTS: usize = 13;
+const DOSING_CURVE_POINTS: usize = 200;
+const TARGET_PH: f64 = 8.0;
+
+// name, CO2 charged (g), carbon-to-sodium molar ratio of the single-solve state.
+const COMPOSITIONS: [(&str, f64, f64); 3] = [
+    ("caustic_heavy", 5.0, 0.10),
+    ("buffer_region", 30.0, 0.70),
+    ("bicarbonate_plateau", 55.0, 1.00),
+];
+
+fn solver_benches(c: &mut Criterion) {
+    let naoh_mol = NAOH_MASS_G / MW_NAOH;
+    let na_conc = naoh_mol / SOLUTION_VOLUME_L;
+    let mut single = c.benchmark_group("solve_carbonate_state");
+    for (name, _, carbon_ratio) in COMPOSITIONS {
+        let total_carbon_m = na_conc * carbon_ratio;
+        single.bench_function(name, |b| {
+            b.iter(|| solve_closed_ph(black_box(total_carbon_m), black_box(na_conc)))
+        });
+    }
+    single.finish();
+    for (group_name, points) in [
+        ("analysis_curve", ANALYSIS_CURVE_POINTS),
+        ("dosing_curve", DOSING_CURVE_POINTS),
+    ] {
+        let mut group = c.benchmark_group(group_name);
+        for (name, co2_g, _) in COMPOSITIONS {
+            let ledger = staged_ledger_mol(naoh_mol, co2_g / MW_CO2);
+            group.bench_with_input(BenchmarkId::new(name, points), &ledger, |b, ledger| {
+                b.iter(|| {
+                    dosing_curve_ph(black_box(*ledger), naoh_mol / 2.0, points, SOLUTION_VOLUME_L)
+                })
+            });
+        }
+        group.finish();
+    }
+    let mut analysis = c.benchmark_group("analyze_bicarbonate");
+    for (name, co2_g, _) in COMPOSITIONS {
+        analysis.bench_function(name, |b| {
+            b.iter(|| {
+                analyze_bicarbonate_ph(
+                    black_box(NAOH_MASS_G),
+                    black_box(co2_g),
+                    SOLUTION_VOLUME_L,
+                    TARGET_PH,
+                )
+            })
+        });
+    }
+    analysis.finish();
+}
+
+criterion_group!(benches, solver_benches);
+criterion_main!(benches);
@@ -1,7 +1,9 @@
-use numpy::PyReadonlyArray1;
-use pyo3::exceptions::PyRuntimeError;
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyReadonlyArray1};
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::{PyAny, PyDict, PyList, PyString};
+use pyo3::types::{PyAny, PyBool, PyDict, PyList, PyString};
+use std::cell::{Cell, RefCell};
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, BTreeSet};
 
@@ -10,19 +12,91 @@ const SOL_KA2: f64 = 4.69e-11;
 const SOL_KW: f64 = 1.0e-14;
 const SOL_MW_NAOH: f64 = 39.997;
 const SOL_MW_CO2: f64 = 44.0095;
+const SOL_MW_NA2CO3: f64 = 105.9888;
+const SOL_MW_NAHCO3: f64 = 84.0066;
 const SOL_A_DEBYE: f64 = 0.509;
 const SOL_B_DEBYE: f64 = 0.328;
 const SOL_DAVIES_LIMIT: f64 = 0.5;
 const SOL_DAVIES_COEFF: f64 = 0.3;
+const SOL_NEWTON_STRICT_TOL: f64 = 1e-12;
+const SOL_NEWTON_RELAXED_TOL: f64 = 1e-8;
+const SOL_DILUTE_CARBON_M: f64 = 1e-9;
+const SOL_PHYSICAL_REL_TOL: f64 = 1e-6;
+// Default pass budget and step tolerance of the self-consistent ionic-strength loop.
+const SOL_IONIC_STRENGTH_MAX_ITER: usize = 24;
+const SOL_IONIC_STRENGTH_TOL: f64 = 1e-12;
+// Smallest H2CO3 share a Newton seed keeps once its HCO3/CO3 fractions are scaled.
+const SOL_SEED_MIN_H2CO3_FRACTION: f64 = 1e-3;
+// Floor (and reciprocal cap) on mass-action ratios before they enter log10, so a
+// momentarily vanishing species gives a large finite residual instead of ±inf.
+const SOL_LOG_RATIO_FLOOR: f64 = 1e-300;
+// Relative imbalance above which the stoichiometry audit flags a ledger.
+const STOICHIOMETRY_AUDIT_REL_TOL: f64 = 1e-9;
+// CO2 Henry solubility at 25 °C (mol/(L·atm)) and its van 't Hoff temperature
+// coefficient d(ln kH)/d(1/T) in kelvin (Sander 2015 compilation).
+const SOL_HENRY_KH_CO2_25C: f64 = 0.0339;
+const SOL_HENRY_VANT_HOFF_K: f64 = 2400.0;
+// SIT Debye-Hückel denominator term and Na+ interaction coefficients (kg/mol),
+// NEA-TDB selected values.
+const SIT_B_A: f64 = 1.5;
+const SIT_EPS_NA_HCO3: f64 = 0.0;
+const SIT_EPS_NA_CO3: f64 = -0.08;
+const SIT_EPS_NA_OH: f64 = 0.04;
+const ACTIVITY_MODEL_HYBRID: &str = "hybrid";
+const ACTIVITY_MODEL_SIT: &str = "sit";
+const ACTIVITY_MODEL_DAVIES: &str = "davies";
+const ACTIVITY_MODEL_GUNTELBERG: &str = "guntelberg";
+const ACTIVITY_MODEL_DEBYE_HUCKEL: &str = "debye_huckel";
+const ACTIVITY_MODEL_PITZER: &str = "pitzer";
+const ACTIVITY_MODEL_IDEAL: &str = "ideal";
+const EXCESS_CO2_FATE_DISSOLVED: &str = "dissolved";
+const EXCESS_CO2_FATE_OUTGASSED: &str = "outgassed";
+const REAGENT_CO2: &str = "co2";
+const REAGENT_STRONG_BASE: &str = "strong_base";
+const REAGENT_STRONG_ACID: &str = "strong_acid";
+const REAGENT_NA2CO3: &str = "na2co3";
+const REAGENT_NAHCO3: &str = "nahco3";
 const CYCLE_GAS_CONSTANT: f64 = 0.082057338;
+// Gas-volume conveniences for dosing outputs: liters per mole at STP (0 °C, 1 atm)
+// or NTP (25 °C, 1 atm), and standard cubic feet per mole at the US 60 °F /
+// 14.696 psia convention (379.49 scf per lb-mol).
+const CO2_MOLAR_VOLUME_STP_L: f64 = 22.414;
+const CO2_MOLAR_VOLUME_NTP_L: f64 = 24.465;
+const GAS_VOLUME_BASIS_STP: &str = "stp";
+const GAS_VOLUME_BASIS_NTP: &str = "ntp";
+const GAS_VOLUME_BASIS_CUSTOM: &str = "custom";
+const CO2_SCF_PER_MOL: f64 = 379.49 / 453.592_37;
+// Water-treatment "as CaCO3" basis: grams of CaCO3 per equivalent (100.09 / 2),
+// so mg/L as CaCO3 is meq/L times this factor.
+const CACO3_G_PER_EQ: f64 = 50.04;
 const SOL_PKA1_COEFFS: (f64, f64, f64) = (-1.333e-5, -0.008867, 6.58);
 const SOL_PKA2_COEFFS: (f64, f64, f64) = (-3.5238e-5, -0.010719, 10.62);
+const SOL_PKW_COEFFS: (f64, f64) = (-0.0137, 14.94);
+const PH_CLAMP_MIN: f64 = 0.0;
+const PH_CLAMP_MAX: f64 = 14.3;
+const TEMPERATURE_CLAMP_MIN_C: f64 = -5.0;
+const TEMPERATURE_CLAMP_MAX_C: f64 = 80.0;
 const PLANNING_PLATEAU_CARBONATE_THRESHOLD: f64 = 1e-9;
 const PLANNING_PLATEAU_RELATIVE_THRESHOLD: f64 = 0.02;
+/// pH units the Henderson-Hasselbalch dosing plan may miss the full solve by before
+/// `analyze_bicarbonate_core` flags the plan as untrustworthy.
+const HEURISTIC_PH_GAP_LIMIT: f64 = 0.5;
+/// pH units a slurry reading may differ from the filtrate `measured_ph` before
+/// `analyze_bicarbonate_core` flags undissolved solids or a measurement problem.
+const PH_SOURCE_DISCREPANCY_LIMIT: f64 = 0.3;
 const PLANNING_PLATEAU_PH_MIN: f64 = 8.0;
 const PLANNING_PLATEAU_PH_MAX: f64 = 8.3;
+// Default width, in pool-fraction units, over which the planning clamps fade out
+// past their thresholds so adjacent curve points do not step; planning kernels take
+// a `planning_transition_width` keyword to override it.
+const PLANNING_TRANSITION_WIDTH: f64 = 0.01;
+const PLANNING_ADJUSTMENT_EQUIVALENCE_ANCHOR: &str = "carbonate_only_equivalence";
+const PLANNING_ADJUSTMENT_DEPLETED_PLATEAU: &str = "carbonate_depleted_plateau";
+const PLANNING_ADJUSTMENT_LOW_PH_RESCUE: &str = "low_ph_rescue";
 const SPEC_MODE_FIXED_PCO2: &str = "fixed_pco2";
 const SPEC_MODE_CLOSED: &str = "closed_carbon";
+const FORMULATION_NEWTON_SYSTEM: &str = "newton_system";
+const FORMULATION_PROTON_CONDITION: &str = "proton_condition";
 const AQION_DEFAULT_PH_LOW: f64 = 2.0;
 const AQION_DEFAULT_PH_HIGH: f64 = 12.5;
 const PITZER_A_PHI_25C: f64 = 0.392;
@@ -36,13 +110,44 @@ const RUST_BACKEND_INTERFACE_ID: &str = "gl260_rust_backend";
 const RUST_BACKEND_INTERFACE_VERSION: &str = "3";
 const RUST_BACKEND_MODULE_NAME: &str = env!("CARGO_PKG_NAME");
 const RUST_BACKEND_CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
-const RUST_EXPORTED_KERNELS: [&str; 26] = [
+const RUST_EXPORTED_KERNELS: [&str; 60] = [
     "simulate_reaction_state_with_accounting",
+    "compare_planning_modes",
+    "ph_vs_temperature",
+    "dose_overshoot",
+    "titrate",
+    "add_salt",
+    "blend",
+    "dilute",
+    "degas",
+    "DosingSession",
     "analyze_bicarbonate_core",
+    "analyze_bicarbonate_json",
+    "evaluate_grid",
     "carbonate_state_core",
     "forced_ph_distribution_core",
     "aqion_closed_speciation_core",
     "pitzer_solve_total_carbon_core",
+    "fit_absorption_efficiency",
+    "carbonate_buffer_window_core",
+    "acceptable_co2_window",
+    "minimum_buffer_point",
+    "titration_sharpness",
+    "adaptive_dose_curve",
+    "ph_sensitivity_to_co2",
+    "equivalence_point_phs",
+    "ph_vs_concentration_scale",
+    "speciation_distribution",
+    "carbonate_ratio_from_ph",
+    "ph_from_carbonate_ratio",
+    "activity_coefficient",
+    "co2_to_neutralize_caustic",
+    "target_carbonate_ratio",
+    "max_ph",
+    "co2_saturated_water_ph",
+    "infer_total_carbon",
+    "solve_from_alkalinity_dic",
+    "design_recipe",
     "combined_decimation_indices",
     "combined_required_indices",
     "cycle_detect_markers_core",
@@ -52,6 +157,7 @@ const RUST_EXPORTED_KERNELS: [&str; 26] = [
     "cycle_metrics_core",
     "array_signature_core",
     "analysis_interpolate_reference_series_core",
+    "interpolate_curve",
     "analysis_dashboard_core",
     "measured_ph_uptake_calibration_core",
     "final_report_cycle_stats_rows_core",
@@ -63,7 +169,66 @@ const RUST_EXPORTED_KERNELS: [&str; 26] = [
     "csv_pressure_derivatives_core",
     "reaction_solution_charge_core",
     "reaction_dashboard_core",
+    "model_info",
+    "self_test",
 ];
+// Known-answer tolerances for `self_test`: ideal round trips are exact up to the
+// Newton tolerance; the hybrid reference pins the shipped activity model.
+const SELF_TEST_ROUND_TRIP_PH_TOL: f64 = 1e-8;
+const SELF_TEST_REFERENCE_PH_TOL: f64 = 1e-6;
+const SELF_TEST_HYBRID_BICARBONATE_PH: f64 = 7.032105085384136;
+const SELF_TEST_PKA2_25C: f64 = 10.33;
+const SELF_TEST_PKA2_TOL: f64 = 1e-3;
+const SOLVER_LOG_TARGET: &str = "gl260_rust_ext";
+
+/// Emit a solver event (seeds, fallbacks, clamping) at `debug` on the
+/// `gl260_rust_ext` target. Records go through the `log` facade, which the module
+/// bridges into Python `logging`, so the host decides whether and where they land.
+/// Skipped inside [`detach_solver`] sections, where forwarding would re-acquire the GIL.
+macro_rules! solver_debug {
+    ($($arg:tt)*) => {
+        if !solver_log_muted() {
+            log::debug!(target: SOLVER_LOG_TARGET, $($arg)*)
+        }
+    };
+}
+
+/// Emit a solver event at `trace`: every Newton iteration.
+macro_rules! solver_trace {
+    ($($arg:tt)*) => {
+        if !solver_log_muted() {
+            log::trace!(target: SOLVER_LOG_TARGET, $($arg)*)
+        }
+    };
+}
+
+thread_local! {
+    static SOLVER_LOG_MUTED: Cell<bool> = const { Cell::new(false) };
+}
+
+fn solver_log_muted() -> bool {
+    SOLVER_LOG_MUTED.with(Cell::get)
+}
+
+/// Run pure-Rust solver work with the GIL released. The `pyo3-log` bridge takes the
+/// GIL for every record it forwards, which would serialize the section again, so
+/// solver logging is muted on this thread until `f` returns (or unwinds).
+fn detach_solver<T, F>(py: Python<'_>, f: F) -> T
+where
+    F: Send + FnOnce() -> T,
+    T: Send,
+{
+    struct Unmute(bool);
+    impl Drop for Unmute {
+        fn drop(&mut self) {
+            SOLVER_LOG_MUTED.with(|muted| muted.set(self.0));
+        }
+    }
+    py.detach(|| {
+        let _unmute = Unmute(SOLVER_LOG_MUTED.with(|muted| muted.replace(true)));
+        f()
+    })
+}
 
 #[derive(Clone, Copy)]
 struct LedgerState {
@@ -79,28 +244,216 @@ struct AccountingState {
     co2_consumed_to_bicarbonate_mol: f64,
     co2_consumed_total_mol: f64,
     co2_unconsumed_mol: f64,
+    /// Dissolved excess CO2 stripped by a negative `delta_mol`.
+    co2_degassed_mol: f64,
+    naoh_consumed_mol: f64,
+    /// Free NaOH left after the step, in mol/L; `None` without a volume.
+    free_caustic_m: Option<f64>,
+}
+
+/// A heuristic pH adjustment applied on top of the speciation solve, with the pH
+/// before and after it and the blend weight it was applied at.
+#[derive(Clone, Copy)]
+struct PlanningAdjustment {
+    kind: &'static str,
+    ph_before: f64,
+    ph_after: f64,
+    weight: f64,
+}
+
+/// Share of a NaOH basis that has been converted; `None` for an empty basis.
+fn naoh_conversion_fraction(basis_naoh_mol: f64, remaining_naoh_mol: f64) -> Option<f64> {
+    if basis_naoh_mol > 0.0 {
+        Some(((basis_naoh_mol - remaining_naoh_mol.max(0.0)) / basis_naoh_mol).clamp(0.0, 1.0))
+    } else {
+        None
+    }
+}
+
+/// Specific Ion Interaction Theory coefficients ε(Na+, anion) in kg/mol.
+#[derive(Clone, Copy)]
+struct SitCoefficients {
+    eps_na_hco3: f64,
+    eps_na_co3: f64,
+    eps_na_oh: f64,
+}
+
+impl Default for SitCoefficients {
+    fn default() -> Self {
+        Self {
+            eps_na_hco3: SIT_EPS_NA_HCO3,
+            eps_na_co3: SIT_EPS_NA_CO3,
+            eps_na_oh: SIT_EPS_NA_OH,
+        }
+    }
+}
+
+/// Activity-coefficient model used by the carbonate speciation solvers.
+///
+/// `Hybrid` is the historical Davies (I <= 0.5) / extended Debye-Hückel blend;
+/// `Davies` and `DebyeHuckel` pin one of the two branches at every ionic
+/// strength; `Guntelberg` is Davies without the `0.3·I` term, the textbook
+/// quick estimate; `Sit` adds the linear ε·m interaction terms and stays usable to
+/// I ≈ 3-4; `Pitzer` uses the caller-supplied virial parameters. `Ideal` pins every
+/// coefficient at 1, collapsing the solvers to the textbook concentration-based
+/// equilibrium.
+#[derive(Clone, Copy)]
+enum ActivityModel {
+    Ideal,
+    Hybrid,
+    Davies,
+    Guntelberg,
+    DebyeHuckel,
+    Sit(SitCoefficients),
+    Pitzer(PitzerParamsLite),
+}
+
+/// What happens to CO2 the ledger could not absorb. `Dissolved` keeps
+/// `co2_excess_mol` in the speciation carbon balance as carbonic acid;
+/// `Outgassed` treats it as lost to the headspace, so it stays on the ledger but
+/// is left out of the pH solve.
+#[derive(Clone, Copy, PartialEq)]
+enum ExcessCo2Fate {
+    Dissolved,
+    Outgassed,
+}
+
+/// Reference state behind "standard" gas volumes. `Stp` is 0 °C and 1 atm, `Ntp`
+/// 25 °C and 1 atm; the two differ by about 9 %, so the convention is always
+/// reported next to the volume. `Custom` carries a caller-supplied L/mol.
+#[derive(Clone, Copy, PartialEq)]
+enum GasVolumeBasis {
+    Stp,
+    Ntp,
+    Custom(f64),
+}
+
+impl GasVolumeBasis {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Stp => GAS_VOLUME_BASIS_STP,
+            Self::Ntp => GAS_VOLUME_BASIS_NTP,
+            Self::Custom(_) => GAS_VOLUME_BASIS_CUSTOM,
+        }
+    }
+
+    /// Ideal-gas molar volume in L/mol.
+    fn molar_volume_l(self) -> f64 {
+        match self {
+            Self::Stp => CO2_MOLAR_VOLUME_STP_L,
+            Self::Ntp => CO2_MOLAR_VOLUME_NTP_L,
+            Self::Custom(volume) => volume,
+        }
+    }
+}
+
+/// Titrant dosed by [`titrate`]. `Co2` stages through the CO2 ledger update,
+/// `StrongBase` is NaOH, `StrongAcid` a monoprotic acid with a spectator anion
+/// (HCl), and the two solids go through [`add_salt_impl`].
+#[derive(Clone, Copy, PartialEq)]
+enum Reagent {
+    Co2,
+    StrongBase,
+    StrongAcid,
+    Na2co3,
+    Nahco3,
+}
+
+impl ExcessCo2Fate {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Dissolved => EXCESS_CO2_FATE_DISSOLVED,
+            Self::Outgassed => EXCESS_CO2_FATE_OUTGASSED,
+        }
+    }
+
+    /// Ledger as seen by the speciation solve.
+    fn solve_basis(self, state: LedgerState) -> LedgerState {
+        match self {
+            Self::Dissolved => state,
+            Self::Outgassed => LedgerState {
+                co2_excess_mol: 0.0,
+                ..state
+            },
+        }
+    }
+}
+
+/// Clamping ranges applied to reported pH and to the temperature fed into the
+/// pKa/pKw correlations. Defaults are the `PH_CLAMP_*`/`TEMPERATURE_CLAMP_*` module
+/// constants; callers may widen them deliberately.
+#[derive(Clone, Copy)]
+struct ClampBounds {
+    ph_min: f64,
+    ph_max: f64,
+    temperature_min_c: f64,
+    temperature_max_c: f64,
+}
+
+impl Default for ClampBounds {
+    fn default() -> Self {
+        Self {
+            ph_min: PH_CLAMP_MIN,
+            ph_max: PH_CLAMP_MAX,
+            temperature_min_c: TEMPERATURE_CLAMP_MIN_C,
+            temperature_max_c: TEMPERATURE_CLAMP_MAX_C,
+        }
+    }
 }
 
-fn clamp_temperature(temp_c: f64) -> f64 {
-    temp_c.clamp(-5.0, 80.0)
+impl ClampBounds {
+    fn ph(&self, ph: f64) -> f64 {
+        let clamped = ph.clamp(self.ph_min, self.ph_max);
+        if clamped != ph {
+            solver_debug!("pH {ph} clamped to {clamped}");
+        }
+        clamped
+    }
+
+    fn temperature(&self, temp_c: f64) -> f64 {
+        let clamped = temp_c.clamp(self.temperature_min_c, self.temperature_max_c);
+        if clamped != temp_c {
+            solver_debug!("temperature {temp_c} C clamped to {clamped} C");
+        }
+        clamped
+    }
+
+    /// True when a reported pH sits on a clamp edge rather than a solved value.
+    fn ph_at_bound(&self, ph: f64) -> bool {
+        ph <= self.ph_min || ph >= self.ph_max
+    }
 }
 
+/// Quadratic pKa correlation; `temp_c` must already be clamped by the caller.
 fn estimate_temperature_adjusted_pka(temp_c: f64, coeffs: (f64, f64, f64)) -> f64 {
     let (a, b, c) = coeffs;
-    let t = clamp_temperature(temp_c);
-    (a * t * t + b * t + c).max(0.0)
+    (a * temp_c * temp_c + b * temp_c + c).max(0.0)
 }
 
+/// Linear pKw correlation; `temp_c` must already be clamped by the caller.
 fn carbonate_pkw_from_temp(temp_c: f64) -> f64 {
-    14.94 - 0.0137 * clamp_temperature(temp_c)
+    let (slope, intercept) = SOL_PKW_COEFFS;
+    intercept + slope * temp_c
 }
 
 fn basic_carbonate_constants(
     temperature_c: Option<f64>,
     use_temp_adjusted_constants: bool,
+) -> (f64, f64, f64) {
+    basic_carbonate_constants_within(
+        temperature_c,
+        use_temp_adjusted_constants,
+        ClampBounds::default(),
+    )
+}
+
+fn basic_carbonate_constants_within(
+    temperature_c: Option<f64>,
+    use_temp_adjusted_constants: bool,
+    clamp_bounds: ClampBounds,
 ) -> (f64, f64, f64) {
     if use_temp_adjusted_constants {
-        let t = temperature_c.unwrap_or(25.0);
+        let t = clamp_bounds.temperature(temperature_c.unwrap_or(25.0));
         let pka1 = estimate_temperature_adjusted_pka(t, SOL_PKA1_COEFFS);
         let pka2 = estimate_temperature_adjusted_pka(t, SOL_PKA2_COEFFS);
         let pkw = carbonate_pkw_from_temp(t);
@@ -111,15 +464,80 @@ fn basic_carbonate_constants(
 }
 
 fn resolve_pka2_value(temp_c: Option<f64>, use_temp_adjusted_constants: bool) -> f64 {
+    resolve_pka2_value_within(temp_c, use_temp_adjusted_constants, ClampBounds::default())
+}
+
+fn resolve_pka2_value_within(
+    temp_c: Option<f64>,
+    use_temp_adjusted_constants: bool,
+    clamp_bounds: ClampBounds,
+) -> f64 {
     if use_temp_adjusted_constants {
-        estimate_temperature_adjusted_pka(temp_c.unwrap_or(25.0), SOL_PKA2_COEFFS).max(0.0)
+        let t = clamp_bounds.temperature(temp_c.unwrap_or(25.0));
+        estimate_temperature_adjusted_pka(t, SOL_PKA2_COEFFS).max(0.0)
     } else {
         -SOL_KA2.max(1e-30).log10()
     }
 }
 
-fn clamp_ph_value(ph: f64) -> f64 {
-    ph.clamp(0.0, 14.3)
+/// Henderson-Hasselbalch `[CO3^2-]/[HCO3-]` ratio at a pH. This is the planning
+/// heuristic only: it ignores the activity corrections the full solver applies.
+fn hh_carbonate_ratio(ph: f64, pka2_value: f64) -> f64 {
+    10f64.powf(ph - pka2_value)
+}
+
+/// Inverse of [`hh_carbonate_ratio`]: `pH = pKa2 + log10([CO3^2-]/[HCO3-])`.
+fn hh_ph_from_ratio(ratio: f64, pka2_value: f64) -> f64 {
+    pka2_value + ratio.log10()
+}
+
+/// pH of a carbonate liquor holding no bicarbonate: CO3^2- hydrolysis with
+/// Kb = Kw/Ka2 on top of the free hydroxide already supplied by residual NaOH.
+fn carbonate_hydrolysis_ph(carbonate_m: f64, free_oh_m: f64, ka2: f64, kw: f64) -> f64 {
+    let kb = kw / ka2.max(1e-30);
+    let carbonate = carbonate_m.max(0.0);
+    let base_oh = free_oh_m.max(0.0);
+    // x (base_oh + x) = Kb (C - x), solved in the cancellation-free form.
+    let linear = base_oh + kb;
+    let root = (linear * linear + 4.0 * kb * carbonate).sqrt();
+    let hydrolysed = 2.0 * kb * carbonate / (linear + root).max(1e-300);
+    -kw.max(1e-30).log10() + (base_oh + hydrolysed).max(1e-16).log10()
+}
+
+/// Carbon-free caustic: the exact strong-base charge balance `[Na+] + [H+] = [OH-]`
+/// with `Kw` corrected by the activity coefficients at a self-consistent ionic
+/// strength. Returns `(h, oh, ionic_strength, gammas)` on the concentration scale.
+fn strong_base_solution(
+    na_conc: f64,
+    kw: f64,
+    ionic_strength_cap: Option<f64>,
+    activity_model: ActivityModel,
+) -> (f64, f64, f64, [f64; 5]) {
+    let na_conc = na_conc.max(0.0);
+    let control = IonicStrengthControl::default();
+    let mut ionic_strength = na_conc.max(1e-12);
+    if let Some(cap) = ionic_strength_cap {
+        ionic_strength = ionic_strength.min(cap);
+    }
+    let mut gammas = [1.0_f64; 5];
+    let mut h = kw.sqrt();
+    let mut oh = h;
+    for _ in 0..control.max_iterations {
+        gammas = solubility_gammas(activity_model, ionic_strength, na_conc, h, 0.0, 0.0, oh);
+        let kw_conc = kw / (gammas[1] * gammas[4]).max(1e-18);
+        oh = 0.5 * (na_conc + (na_conc * na_conc + 4.0 * kw_conc).sqrt());
+        h = kw_conc / oh.max(1e-300);
+        let mut new_i = 0.5 * (na_conc + h + oh);
+        if let Some(cap) = ionic_strength_cap {
+            new_i = new_i.min(cap);
+        }
+        let settled = (new_i - ionic_strength).abs() < control.tolerance;
+        ionic_strength = new_i;
+        if settled {
+            break;
+        }
+    }
+    (h, oh, ionic_strength, gammas)
 }
 
 fn solubility_extended_debye_huckel(ionic_strength: f64, charge: i32, ion_size_nm: f64) -> f64 {
@@ -135,51 +553,213 @@ fn solubility_extended_debye_huckel(ionic_strength: f64, charge: i32, ion_size_n
     10f64.powf(exponent)
 }
 
+fn solubility_davies(ionic_strength: f64, charge: i32) -> f64 {
+    let sqrt_i = ionic_strength.max(1e-12).sqrt();
+    let log_gamma = -SOL_A_DEBYE
+        * f64::from(charge * charge)
+        * ((sqrt_i / (1.0 + sqrt_i)) - SOL_DAVIES_COEFF * ionic_strength);
+    10f64.powf(log_gamma)
+}
+
+/// Güntelberg approximation: `log10 γ = -A z² √I / (1 + √I)`.
+fn solubility_guntelberg(ionic_strength: f64, charge: i32) -> f64 {
+    let sqrt_i = ionic_strength.max(1e-12).sqrt();
+    10f64.powf(-SOL_A_DEBYE * f64::from(charge * charge) * sqrt_i / (1.0 + sqrt_i))
+}
+
 fn solubility_activity_coefficient(ionic_strength: f64, charge: i32, ion_size_nm: f64) -> f64 {
     if ionic_strength <= SOL_DAVIES_LIMIT {
-        let sqrt_i = ionic_strength.max(1e-12).sqrt();
-        let log_gamma = -SOL_A_DEBYE
-            * f64::from(charge * charge)
-            * ((sqrt_i / (1.0 + sqrt_i)) - SOL_DAVIES_COEFF * ionic_strength);
-        return 10f64.powf(log_gamma);
+        return solubility_davies(ionic_strength, charge);
     }
     solubility_extended_debye_huckel(ionic_strength, charge, ion_size_nm)
 }
 
+/// SIT activity coefficient: `log10 γ = -z² A √I / (1 + 1.5 √I) + Σ ε·m`.
+fn sit_activity_coefficient(ionic_strength: f64, charge: i32, epsilon_sum: f64) -> f64 {
+    let sqrt_i = ionic_strength.max(0.0).sqrt();
+    let debye = SOL_A_DEBYE * sqrt_i / (1.0 + SIT_B_A * sqrt_i);
+    10f64.powf(-f64::from(charge * charge) * debye + epsilon_sum)
+}
+
+/// Activity coefficients `[Na+, H+, HCO3-, CO3^2-, OH-]` under the selected model.
+///
+/// The Pitzer branch evaluates its own ionic strength from the molalities and so
+/// ignores `ionic_strength` (and any cap applied to it).
+fn solubility_gammas(
+    activity_model: ActivityModel,
+    ionic_strength: f64,
+    na_conc: f64,
+    h_conc: f64,
+    hco3_conc: f64,
+    co3_conc: f64,
+    oh_conc: f64,
+) -> [f64; 5] {
+    match activity_model {
+        ActivityModel::Ideal => [1.0; 5],
+        ActivityModel::Davies => [
+            solubility_davies(ionic_strength, 1),
+            solubility_davies(ionic_strength, 1),
+            solubility_davies(ionic_strength, -1),
+            solubility_davies(ionic_strength, -2),
+            solubility_davies(ionic_strength, -1),
+        ],
+        ActivityModel::Guntelberg => [
+            solubility_guntelberg(ionic_strength, 1),
+            solubility_guntelberg(ionic_strength, 1),
+            solubility_guntelberg(ionic_strength, -1),
+            solubility_guntelberg(ionic_strength, -2),
+            solubility_guntelberg(ionic_strength, -1),
+        ],
+        ActivityModel::DebyeHuckel => [
+            solubility_extended_debye_huckel(ionic_strength, 1, 0.90),
+            solubility_extended_debye_huckel(ionic_strength, 1, 0.90),
+            solubility_extended_debye_huckel(ionic_strength, -1, 0.43),
+            solubility_extended_debye_huckel(ionic_strength, -2, 0.40),
+            solubility_extended_debye_huckel(ionic_strength, -1, 0.35),
+        ],
+        ActivityModel::Pitzer(params) => {
+            let (gammas, _) =
+                pitzer_gamma_set(na_conc, h_conc, oh_conc, hco3_conc, co3_conc, params);
+            [gammas[0], gammas[1], gammas[3], gammas[4], gammas[2]]
+        }
+        ActivityModel::Hybrid => [
+            solubility_activity_coefficient(ionic_strength, 1, 0.90),
+            solubility_activity_coefficient(ionic_strength, 1, 0.90),
+            solubility_activity_coefficient(ionic_strength, -1, 0.43),
+            solubility_activity_coefficient(ionic_strength, -2, 0.40),
+            solubility_activity_coefficient(ionic_strength, -1, 0.35),
+        ],
+        ActivityModel::Sit(eps) => [
+            sit_activity_coefficient(
+                ionic_strength,
+                1,
+                eps.eps_na_hco3 * hco3_conc + eps.eps_na_co3 * co3_conc + eps.eps_na_oh * oh_conc,
+            ),
+            sit_activity_coefficient(ionic_strength, 1, 0.0),
+            sit_activity_coefficient(ionic_strength, -1, eps.eps_na_hco3 * na_conc),
+            sit_activity_coefficient(ionic_strength, -2, eps.eps_na_co3 * na_conc),
+            sit_activity_coefficient(ionic_strength, -1, eps.eps_na_oh * na_conc),
+        ],
+    }
+}
+
+/// How [`solubility_ionic_state`] settles the ionic strength: a caller-fixed
+/// value that skips the loop, or the self-consistent iteration run for at most
+/// `max_iterations` passes until successive estimates agree within `tolerance`.
+#[derive(Clone, Copy)]
+struct IonicStrengthControl {
+    fixed: Option<f64>,
+    max_iterations: usize,
+    tolerance: f64,
+}
+
+impl Default for IonicStrengthControl {
+    fn default() -> Self {
+        Self {
+            fixed: None,
+            max_iterations: SOL_IONIC_STRENGTH_MAX_ITER,
+            tolerance: SOL_IONIC_STRENGTH_TOL,
+        }
+    }
+}
+
+/// Activity treatment shared by every speciation solve: the coefficient model,
+/// the optional ionic-strength cap and how the ionic strength itself is settled.
+#[derive(Clone, Copy)]
+struct ActivityTreatment {
+    model: ActivityModel,
+    ionic_strength_cap: Option<f64>,
+    ionic_strength: IonicStrengthControl,
+}
+
+impl ActivityTreatment {
+    /// `model` under `ionic_strength_cap` with the default self-consistent loop.
+    fn new(model: ActivityModel, ionic_strength_cap: Option<f64>) -> Self {
+        Self {
+            model,
+            ionic_strength_cap,
+            ionic_strength: IonicStrengthControl::default(),
+        }
+    }
+}
+
+/// Self-consistent ionic strength, activity coefficients and OH- for the given
+/// species. Returns `(ionic_strength, gammas, oh, converged)`; `converged` is
+/// false when the loop ran out of passes before the ionic strength settled.
 fn solubility_ionic_state(
     na_conc: f64,
     h_conc: f64,
     hco3_conc: f64,
     co3_conc: f64,
     kw_value: f64,
-    ionic_strength_cap: Option<f64>,
-) -> (f64, [f64; 5], f64) {
+    activity: ActivityTreatment,
+) -> (f64, [f64; 5], f64, bool) {
+    let ActivityTreatment {
+        model: activity_model,
+        ionic_strength_cap,
+        ionic_strength: ionic_strength_control,
+    } = activity;
+    if let ActivityModel::Ideal = activity_model {
+        // Unit coefficients need no ionic-strength loop; I is only reported.
+        let oh_conc = kw_value / h_conc.max(1e-18);
+        let mut ionic_strength = ionic_strength_control.fixed.unwrap_or(
+            0.5 * (na_conc + h_conc + hco3_conc + 4.0 * co3_conc + oh_conc),
+        );
+        if let Some(cap) = ionic_strength_cap {
+            ionic_strength = ionic_strength.min(cap);
+        }
+        return (ionic_strength, [1.0; 5], oh_conc, true);
+    }
+    if let Some(fixed) = ionic_strength_control.fixed {
+        // A known ionic strength (e.g. from conductivity) replaces the
+        // self-consistent loop; OH- is only refreshed so the species-dependent SIT
+        // and Pitzer terms see it.
+        let mut gammas = [1.0_f64; 5];
+        let mut oh_conc = 1e-7_f64;
+        for _ in 0..2 {
+            gammas = solubility_gammas(
+                activity_model,
+                fixed,
+                na_conc,
+                h_conc,
+                hco3_conc,
+                co3_conc,
+                oh_conc,
+            );
+            oh_conc = kw_value / (gammas[1] * gammas[4] * h_conc).max(1e-18);
+        }
+        return (fixed, gammas, oh_conc, true);
+    }
     let mut ionic_strength = (0.5 * (na_conc + h_conc + hco3_conc + 4.0 * co3_conc)).max(1e-12);
     if let Some(cap) = ionic_strength_cap {
         ionic_strength = ionic_strength.min(cap);
     }
     let mut gammas = [1.0_f64; 5];
     let mut oh_conc = 1e-7_f64;
-    for _ in 0..24 {
-        gammas = [
-            solubility_activity_coefficient(ionic_strength, 1, 0.90),
-            solubility_activity_coefficient(ionic_strength, 1, 0.90),
-            solubility_activity_coefficient(ionic_strength, -1, 0.43),
-            solubility_activity_coefficient(ionic_strength, -2, 0.40),
-            solubility_activity_coefficient(ionic_strength, -1, 0.35),
-        ];
+    let mut converged = false;
+    for _ in 0..ionic_strength_control.max_iterations {
+        gammas = solubility_gammas(
+            activity_model,
+            ionic_strength,
+            na_conc,
+            h_conc,
+            hco3_conc,
+            co3_conc,
+            oh_conc,
+        );
         oh_conc = kw_value / (gammas[1] * gammas[4] * h_conc).max(1e-18);
         let mut new_i = 0.5 * (na_conc + h_conc + hco3_conc + 4.0 * co3_conc + oh_conc);
         if let Some(cap) = ionic_strength_cap {
             new_i = new_i.min(cap);
         }
-        if (new_i - ionic_strength).abs() < 1e-12 {
+        if (new_i - ionic_strength).abs() < ionic_strength_control.tolerance {
             ionic_strength = new_i;
+            converged = true;
             break;
         }
         ionic_strength = new_i;
     }
-    (ionic_strength, gammas, oh_conc)
+    (ionic_strength, gammas, oh_conc, converged)
 }
 
 fn solve_linear_system(matrix: &[Vec<f64>], rhs: &[f64]) -> Result<Vec<f64>, String> {
@@ -250,4082 +830,9996 @@ where
     jacobian
 }
 
-fn newton_system_solve<F>(
-    func: &F,
-    mut x: Vec<f64>,
-    tol: f64,
-    max_iter: usize,
-) -> Result<Vec<f64>, String>
-where
-    F: Fn(&[f64]) -> Vec<f64>,
-{
-    for _ in 0..max_iter {
-        let residual = func(&x);
-        if residual.iter().any(|v| !v.is_finite()) {
-            return Err("Non-finite residual".to_string());
-        }
-        if residual.iter().fold(0.0_f64, |acc, v| acc.max(v.abs())) < tol {
-            return Ok(x);
-        }
-        let jacobian = numerical_jacobian(&func, &x, 1e-6);
-        let delta = solve_linear_system(
-            &jacobian,
-            &residual.iter().map(|value| -*value).collect::<Vec<f64>>(),
-        )?;
-        if delta.iter().any(|v| !v.is_finite()) {
-            return Err("Non-finite Newton increment".to_string());
-        }
-        x = x
-            .iter()
-            .zip(delta.iter())
-            .map(|(value, step)| (*value + *step).clamp(-25.0, 5.0))
-            .collect();
-        if delta.iter().fold(0.0_f64, |acc, v| acc.max(v.abs())) < tol {
-            if func(&x).iter().fold(0.0_f64, |acc, v| acc.max(v.abs())) < tol {
-                return Ok(x);
-            }
-        }
+/// Total ammonia (NH4+ plus NH3) co-buffering the carbonate system, with the
+/// thermodynamic NH4+ dissociation constant `ka`.
+#[derive(Clone, Copy)]
+struct AmmoniaBuffer {
+    total_m: f64,
+    ka: f64,
+}
+
+impl AmmoniaBuffer {
+    /// NH4+ at `[H+] = h`. NH3 is uncharged (gamma 1) and NH4+ borrows the Na+
+    /// coefficient; both monovalent cations also share Na+'s slot in the
+    /// ionic-strength state, so SIT/Pitzer treat NH4+ as extra sodium.
+    fn ammonium_m(&self, h: f64, gammas: &[f64; 5]) -> f64 {
+        let acid = gammas[1] * h;
+        self.total_m * acid / (acid + gammas[0] * self.ka).max(1e-300)
     }
-    Err("Newton solver did not converge".to_string())
 }
 
-fn solve_carbonate_state(
+/// pKa of NH4+ from the Emerson et al. (1975) fit, `0.09018 + 2729.92 / T(K)`.
+fn ammonium_pka(temperature_c: f64) -> f64 {
+    0.09018 + 2729.92 / (temperature_c + 273.15)
+}
+
+/// Total orthophosphate (H3PO4 through PO4^3-) co-buffering the carbonate system,
+/// with its three thermodynamic dissociation constants.
+#[derive(Clone, Copy)]
+struct PhosphateBuffer {
+    total_m: f64,
+    ka: [f64; 3],
+}
+
+impl PhosphateBuffer {
+    /// `[h3po4, h2po4, hpo4, po4]` at `[H+] = h`. H3PO4 is uncharged; H2PO4- and
+    /// HPO4^2- borrow the HCO3- and CO3^2- coefficients and PO4^3- takes
+    /// `γCO3^(9/4)`, the Davies-style `z²` scaling from the divalent ion.
+    fn species_m(&self, h: f64, gammas: &[f64; 5]) -> [f64; 4] {
+        let ion_gammas = phosphate_gammas(gammas);
+        let mut weights = [1.0_f64; 4];
+        for step in 0..3 {
+            let lower = if step == 0 { 1.0 } else { ion_gammas[step - 1] };
+            let conditional = self.ka[step] * lower / (gammas[1] * ion_gammas[step]);
+            weights[step + 1] = weights[step] * conditional / h.max(1e-300);
+        }
+        let sum: f64 = weights.iter().sum();
+        weights.map(|weight| self.total_m * weight / sum.max(1e-300))
+    }
+}
+
+/// Activity coefficients of `[H2PO4-, HPO4^2-, PO4^3-]` from the carbonate set.
+fn phosphate_gammas(gammas: &[f64; 5]) -> [f64; 3] {
+    [gammas[2], gammas[3], gammas[3].powf(2.25)]
+}
+
+/// Negative charge carried by phosphate species `[h3po4, h2po4, hpo4, po4]`, mol/L.
+fn phosphate_anion_charge(species: &[f64; 4]) -> f64 {
+    species[1] + 2.0 * species[2] + 3.0 * species[3]
+}
+
+/// Phosphate folded into the `(hco3, co3)` slots of [`solubility_ionic_state`]:
+/// H2PO4- as monovalent, HPO4^2- plus `9/4` PO4^3- as divalent, which reproduces
+/// their `½Σz²c` share of the ionic strength exactly. SIT/Pitzer therefore see
+/// phosphate as extra carbonate, as they see NH4+ as extra sodium.
+fn phosphate_ionic_slots(species: Option<[f64; 4]>) -> (f64, f64) {
+    species.map_or((0.0, 0.0), |species| (species[1], species[2] + 2.25 * species[3]))
+}
+
+/// Orthophosphate pKa1-3 at 25 °C.
+const PHOSPHATE_PKA_DEFAULT: (f64, f64, f64) = (2.148, 7.198, 12.35);
+
+/// Closed-carbon composition a speciation solve balances: total carbon and sodium
+/// (mol/L), the thermodynamic `Ka1`, `Ka2` and `Kw`, and the optional co-buffers.
+#[derive(Clone, Copy)]
+struct CarbonateSystem {
     total_carbon_m: f64,
     na_conc: f64,
     ka1: f64,
     ka2: f64,
     kw: f64,
-    ionic_strength_cap: Option<f64>,
-    initial_ph_guess: f64,
-) -> Result<(f64, f64, f64, f64, f64, [f64; 5], f64), String> {
-    let total_carbon_m = total_carbon_m.max(1e-16);
-    let na_conc = na_conc.max(0.0);
-    let residuals = |log_vars: &[f64]| -> Vec<f64> {
+    ammonia: Option<AmmoniaBuffer>,
+    phosphate: Option<PhosphateBuffer>,
+}
+
+impl CarbonateSystem {
+    /// Carbonate-only system with constants `(ka1, ka2, kw)`.
+    fn new(total_carbon_m: f64, na_conc: f64, (ka1, ka2, kw): (f64, f64, f64)) -> Self {
+        Self {
+            total_carbon_m,
+            na_conc,
+            ka1,
+            ka2,
+            kw,
+            ammonia: None,
+            phosphate: None,
+        }
+    }
+}
+
+/// Closed-carbon Newton residuals over `[log h, log hco3, log co3, log h2co3]`:
+/// the two mass-action rows in log space, then the carbon and charge balances.
+/// With `ammonia` a fifth unknown `log nh4` joins, NH4+ enters the charge balance
+/// and a fifth row holds NH4+ to its equilibrium share of total ammonia.
+/// `phosphate` appends `log h3po4, log h2po4, log hpo4, log po4` (after NH4+ when
+/// both are present) with their three mass-action rows and the phosphorus balance;
+/// the anions join the charge balance and ionic strength.
+fn closed_carbonate_residuals(
+    system: CarbonateSystem,
+    activity: ActivityTreatment,
+) -> impl Fn(&[f64]) -> Vec<f64> {
+    let CarbonateSystem {
+        total_carbon_m,
+        na_conc,
+        ka1,
+        ka2,
+        kw,
+        ammonia,
+        phosphate,
+    } = system;
+    let phosphate_offset = 4 + usize::from(ammonia.is_some());
+    move |log_vars: &[f64]| {
         let h = 10f64.powf(log_vars[0]);
         let hco3 = 10f64.powf(log_vars[1]);
         let co3 = 10f64.powf(log_vars[2]);
         let h2co3 = 10f64.powf(log_vars[3]);
-        let (_, gammas, oh) = solubility_ionic_state(na_conc, h, hco3, co3, kw, ionic_strength_cap);
-        let ka1_actual = (gammas[1] * gammas[2] * h * hco3) / h2co3.max(1e-16);
-        let ka2_actual = (gammas[1] * gammas[3] * h * co3) / (gammas[2] * hco3);
-        vec![
-            (ka1_actual / ka1).log10(),
-            (ka2_actual / ka2).log10(),
+        let nh4 = ammonia.map_or(0.0, |_| 10f64.powf(log_vars[4]));
+        let phosphate_species = phosphate.map(|_| {
+            [0, 1, 2, 3].map(|idx| 10f64.powf(log_vars[phosphate_offset + idx]))
+        });
+        let (mono_slot, di_slot) = phosphate_ionic_slots(phosphate_species);
+        let (_, gammas, oh, _) = solubility_ionic_state(
+            na_conc + nh4,
+            h,
+            hco3 + mono_slot,
+            co3 + di_slot,
+            kw,
+            activity,
+        );
+        // Written on the log unknowns directly so trace H2CO3 deep in the basic range
+        // keeps an exact mass-action row instead of dividing by a floored value.
+        let ka1_log_residual = (gammas[1] * gammas[2]).log10() + log_vars[0] + log_vars[1]
+            - log_vars[3]
+            - ka1.max(1e-30).log10();
+        let ka2_actual =
+            (gammas[1] * gammas[3] * h * co3) / (gammas[2] * hco3).max(SOL_LOG_RATIO_FLOOR);
+        let phosphate_charge = phosphate_species.as_ref().map_or(0.0, phosphate_anion_charge);
+        let mut rows = vec![
+            ka1_log_residual,
+            guarded_log10_ratio(ka2_actual, ka2),
             h2co3 + hco3 + co3 - total_carbon_m,
-            na_conc + h - hco3 - 2.0 * co3 - oh,
-        ]
-    };
-    let guesses = [
-        (initial_ph_guess, 0.85_f64, 0.12_f64),
-        (8.8_f64, 0.80_f64, 0.19_f64),
-        (7.5_f64, 0.95_f64, 0.03_f64),
-        (9.2_f64, 0.70_f64, 0.29_f64),
-    ];
-    for (ph_guess, hco3_frac, co3_frac) in guesses {
-        let h = 10f64.powf(-ph_guess);
-        let hco3 = (total_carbon_m * hco3_frac).max(1e-16);
-        let co3 = (total_carbon_m * co3_frac).max(1e-16);
-        let remainder = total_carbon_m - (hco3 + co3);
-        let h2co3 = if remainder > 0.0 {
-            remainder
-        } else {
-            total_carbon_m * 1e-3
-        }
-        .max(1e-16);
-        let guess = vec![h.log10(), hco3.log10(), co3.log10(), h2co3.log10()];
-        if let Ok(sol) = newton_system_solve(&residuals, guess, 1e-12, 60) {
-            let h = 10f64.powf(sol[0]);
-            let hco3 = 10f64.powf(sol[1]);
-            let co3 = 10f64.powf(sol[2]);
-            let h2co3 = 10f64.powf(sol[3]);
-            let (ionic_strength, gammas, oh) =
-                solubility_ionic_state(na_conc, h, hco3, co3, kw, ionic_strength_cap);
-            return Ok((h, hco3, co3, h2co3, oh, gammas, ionic_strength));
+            na_conc + nh4 + h - hco3 - 2.0 * co3 - oh - phosphate_charge,
+        ];
+        if let Some(buffer) = ammonia {
+            rows.push((nh4 - buffer.ammonium_m(h, &gammas)) / buffer.total_m);
+        }
+        if let (Some(buffer), Some(species)) = (phosphate, phosphate_species) {
+            let ion_gammas = phosphate_gammas(&gammas);
+            let lower_gammas = [1.0, ion_gammas[0], ion_gammas[1]];
+            for step in 0..3 {
+                let ka_actual = (gammas[1] * h * ion_gammas[step] * species[step + 1])
+                    / (lower_gammas[step] * species[step]).max(SOL_LOG_RATIO_FLOOR);
+                rows.push(guarded_log10_ratio(ka_actual, buffer.ka[step]));
+            }
+            rows.push((species.iter().sum::<f64>() - buffer.total_m) / buffer.total_m);
         }
+        rows
     }
-    Err("Equilibrium solver did not converge".to_string())
 }
 
-fn normalize_speciation_mode(mode: &str) -> &str {
-    let token = mode.trim().to_ascii_lowercase();
-    if token == SPEC_MODE_FIXED_PCO2 {
-        SPEC_MODE_FIXED_PCO2
-    } else {
-        SPEC_MODE_CLOSED
-    }
-}
-
-fn solve_carbonate_state_open(
+/// Fixed-H2CO3 Newton residuals over `[log h, log hco3, log co3]`: the two
+/// mass-action rows in log space and the charge balance.
+fn open_carbonate_residuals(
     na_conc: f64,
     ka1: f64,
     ka2: f64,
     kw: f64,
-    ionic_strength_cap: Option<f64>,
-    initial_ph_guess: f64,
+    activity: ActivityTreatment,
     fixed_h2co3: f64,
-) -> Result<(f64, f64, f64, f64, f64, [f64; 5], f64), String> {
-    let na_conc = na_conc.max(0.0);
-    let fixed_h2co3 = fixed_h2co3.max(1e-16);
-    let residuals = |log_vars: &[f64]| -> Vec<f64> {
+) -> impl Fn(&[f64]) -> Vec<f64> {
+    move |log_vars: &[f64]| {
         let h = 10f64.powf(log_vars[0]);
         let hco3 = 10f64.powf(log_vars[1]);
         let co3 = 10f64.powf(log_vars[2]);
-        let (_, gammas, oh) = solubility_ionic_state(na_conc, h, hco3, co3, kw, ionic_strength_cap);
-        let ka1_actual = (gammas[1] * gammas[2] * h * hco3) / fixed_h2co3.max(1e-16);
-        let ka2_actual = (gammas[1] * gammas[3] * h * co3) / (gammas[2] * hco3.max(1e-16));
-        vec![
-            (ka1_actual / ka1.max(1e-30)).log10(),
-            (ka2_actual / ka2.max(1e-30)).log10(),
+        let (_, gammas, oh, _) = solubility_ionic_state(
+            na_conc,
+            h,
+            hco3,
+            co3,
+            kw,
+            activity,
+        );
+        let ka1_log_residual = (gammas[1] * gammas[2]).log10() + log_vars[0] + log_vars[1]
+            - fixed_h2co3.log10()
+            - ka1.max(1e-30).log10();
+        let ka2_actual = (gammas[1] * gammas[3] * h * co3) / (gammas[2] * hco3.max(1e-16));
+        vec![
+            ka1_log_residual,
+            guarded_log10_ratio(ka2_actual, ka2.max(1e-30)),
             na_conc + h - hco3 - 2.0 * co3 - oh,
         ]
-    };
-    let guess_ph_values = [initial_ph_guess, 8.2, 7.8, 9.0];
-    for ph_guess in guess_ph_values {
-        let h = 10f64.powf(-ph_guess);
-        let hco3_guess = ((ka1 * fixed_h2co3) / h.max(1e-16)).max(1e-16);
-        let co3_guess = ((ka2 * hco3_guess) / h.max(1e-16)).max(1e-16);
-        let guess = vec![h.log10(), hco3_guess.log10(), co3_guess.log10()];
-        if let Ok(sol) = newton_system_solve(&residuals, guess, 1e-12, 60) {
-            let h = 10f64.powf(sol[0]);
-            let hco3 = 10f64.powf(sol[1]);
-            let co3 = 10f64.powf(sol[2]);
-            let (ionic_strength, gammas, oh) =
-                solubility_ionic_state(na_conc, h, hco3, co3, kw, ionic_strength_cap);
-            return Ok((h, hco3, co3, fixed_h2co3, oh, gammas, ionic_strength));
+    }
+}
+
+/// Ratio of the largest to the smallest pivot magnitude met while eliminating
+/// `matrix` with partial pivoting: a cheap lower-bound style estimate of its
+/// condition number (infinite when a pivot vanishes). Rows keep their own units,
+/// so the figure compares solves of the same system rather than being absolute.
+fn pivot_condition_estimate(matrix: &[Vec<f64>]) -> f64 {
+    let n = matrix.len();
+    let mut work = matrix.to_vec();
+    let mut largest = 0.0_f64;
+    let mut smallest = f64::INFINITY;
+    for col in 0..n {
+        let pivot_row = (col..n)
+            .max_by(|&a, &b| work[a][col].abs().total_cmp(&work[b][col].abs()))
+            .unwrap_or(col);
+        work.swap(col, pivot_row);
+        let pivot = work[col][col];
+        largest = largest.max(pivot.abs());
+        smallest = smallest.min(pivot.abs());
+        if pivot == 0.0 || !pivot.is_finite() {
+            return f64::INFINITY;
+        }
+        let pivot_values = work[col].clone();
+        for row in work.iter_mut().skip(col + 1) {
+            let factor = row[col] / pivot;
+            for (value, pivot_value) in row.iter_mut().zip(&pivot_values).skip(col) {
+                *value -= factor * pivot_value;
+            }
         }
     }
-    Err("Fixed-pCO2 equilibrium solver did not converge".to_string())
+    largest / smallest
 }
 
-fn solve_carbonate_state_with_mode(
-    total_carbon_m: f64,
-    na_conc: f64,
-    ka1: f64,
-    ka2: f64,
-    kw: f64,
-    ionic_strength_cap: Option<f64>,
-    initial_ph_guess: f64,
-    speciation_mode: &str,
+/// Pivot-ratio condition estimate of the speciation Newton Jacobian at a solved
+/// state, using the same system (closed or fixed-H2CO3) the solve ran on.
+fn carbonate_jacobian_condition(
+    solution: &CarbonateSolution,
+    system: CarbonateSystem,
+    activity: ActivityTreatment,
     fixed_h2co3: Option<f64>,
-) -> Result<(f64, f64, f64, f64, f64, [f64; 5], f64), String> {
-    let mode = normalize_speciation_mode(speciation_mode);
-    if mode == SPEC_MODE_FIXED_PCO2 {
-        return solve_carbonate_state_open(
-            na_conc,
-            ka1,
-            ka2,
-            kw,
-            ionic_strength_cap,
-            initial_ph_guess,
-            fixed_h2co3.unwrap_or(0.0),
-        );
-    }
-    solve_carbonate_state(
+) -> f64 {
+    let CarbonateSystem {
         total_carbon_m,
         na_conc,
         ka1,
         ka2,
         kw,
-        ionic_strength_cap,
-        initial_ph_guess,
-    )
+        ammonia,
+        phosphate,
+    } = system;
+    let (h, hco3, co3, h2co3, _, gammas, ..) = *solution;
+    let mut log_vars: Vec<f64> =
+        [h, hco3, co3, h2co3].iter().map(|value| value.max(1e-300).log10()).collect();
+    if let Some(buffer) = ammonia {
+        log_vars.push(buffer.ammonium_m(h, &gammas).max(1e-300).log10());
+    }
+    if let Some(buffer) = phosphate {
+        log_vars.extend(buffer.species_m(h, &gammas).map(|value| value.max(1e-300).log10()));
+    }
+    let jacobian = match fixed_h2co3 {
+        Some(fixed) => numerical_jacobian(
+            &open_carbonate_residuals(
+                na_conc.max(0.0),
+                ka1,
+                ka2,
+                kw,
+                activity,
+                fixed.max(1e-16),
+            ),
+            &log_vars[..3],
+            1e-6,
+        ),
+        None => numerical_jacobian(
+            &closed_carbonate_residuals(
+                CarbonateSystem {
+                    total_carbon_m: total_carbon_m.max(1e-16),
+                    na_conc: na_conc.max(0.0),
+                    ..system
+                },
+                activity,
+            ),
+            &log_vars,
+            1e-6,
+        ),
+    };
+    pivot_condition_estimate(&jacobian)
 }
 
-fn forced_ph_distribution_impl(
-    mut total_carbon_m: f64,
+/// Per-iteration Newton observer: `(iteration, log-space vector, residual max-norm)`.
+type NewtonIterationObserver<'a> = &'a dyn Fn(usize, &[f64], f64);
+type BoxedIterationObserver<'a> = Box<dyn Fn(usize, &[f64], f64) + 'a>;
+/// `(h, hco3, co3, h2co3, oh, gammas, ionic_strength, residual_norm)` from the
+/// Newton speciation solvers; `residual_norm` is the max-abs residual at acceptance.
+type CarbonateSolution = (f64, f64, f64, f64, f64, [f64; 5], f64, f64);
+
+// Thread safety: the solver core (`solve_carbonate_state*`, `estimate_ledger_ph*`,
+// `simulate_reaction_state_with_accounting_impl` and the helpers under them) is pure
+// over `Copy` inputs. The only process-wide state is the `log` facade's logger,
+// which is itself `Send + Sync`, so these functions may run concurrently on any
+// thread pool.
+// The one exception is the iteration observer, which is a plain `&dyn Fn` because
+// the Python adapter holds a `RefCell`; pass `None` when calling across threads.
+// A test in `mod tests` keeps the value types shared with such callers `Send + Sync`.
+
+// Upper edge of every log10 Newton unknown. Overshooting steps need the headroom to
+// come back from, so only the lower edges follow the composition.
+const SOL_NEWTON_LOG_UPPER: f64 = 5.0;
+// Decades below its composition scale a log10 unknown may still reach; chosen so
+// H2CO3 in strongly basic trace-carbon liquors is never pinned.
+const SOL_NEWTON_LOG_SPAN: f64 = 30.0;
+// Least room a composition-scaled floor keeps below `SOL_NEWTON_LOG_UPPER`, so a
+// non-physical concentration (e.g. moles over a vanishing volume) still leaves a
+// valid clamp range and the solve fails with an error instead of a panic.
+const SOL_NEWTON_LOG_FLOOR_HEADROOM: f64 = 1.0;
+// Decades from a clamp edge within which a converged log10 unknown counts as pinned:
+// the iteration hit the wall rather than a stationary point, so the root is rejected.
+const SOL_NEWTON_BOUND_PIN_EPS: f64 = 1e-6;
+// Perturbed restarts tried from the closest failed Newton point before the
+// bisection backstop, and the log10 nudge each pair of restarts adds.
+const SOL_NEWTON_RETRY_ATTEMPTS: usize = 3;
+const SOL_NEWTON_RETRY_STEP: f64 = 0.05;
+// Trust-region defaults: first radius (max-norm, log10 decades) and the minimum
+// actual/predicted residual reduction a step needs to be accepted. Radii shrink
+// 4x on rejection, double on a good boundary step, and give up below the floor.
+const SOL_TRUST_RADIUS_DEFAULT: f64 = 1.0;
+const SOL_TRUST_ACCEPTANCE_DEFAULT: f64 = 0.1;
+const SOL_TRUST_GROW_RATIO: f64 = 0.75;
+const SOL_TRUST_RADIUS_MAX: f64 = 8.0;
+const SOL_TRUST_RADIUS_MIN: f64 = 1e-10;
+
+/// Opt-in trust-region step control for [`newton_system_trajectory`].
+///
+/// Each Newton step is cut to `radius` (max-norm, log10 units) and only taken if it
+/// achieves at least `acceptance_ratio` of the residual reduction the linear model
+/// predicts (`‖r‖² - ‖r + J·s‖²`, sum of squares). Poor agreement shrinks the radius
+/// and retries from the same point; good agreement at the boundary grows it. Near
+/// equivalence, where the Jacobian is ill-conditioned, this replaces the bound
+/// clamp's overshoot-and-bounce with monotone residual decrease.
+#[derive(Clone, Copy)]
+struct NewtonTrustRegion {
+    initial_radius: f64,
+    acceptance_ratio: f64,
+}
+
+/// How a speciation Newton solve starts and steps: the caller's pH guess, the
+/// `(pH, hco3_frac, co3_frac)` seeds tried ahead of the built-in table, the
+/// per-iteration observer, the perturbed restarts allowed once every seed has
+/// failed, and the optional trust-region step control.
+#[derive(Clone, Copy)]
+struct NewtonOptions<'a> {
+    initial_ph_guess: f64,
+    extra_seeds: &'a [(f64, f64, f64)],
+    observer: Option<NewtonIterationObserver<'a>>,
+    retry_attempts: usize,
+    trust_region: Option<NewtonTrustRegion>,
+}
+
+impl<'a> NewtonOptions<'a> {
+    /// Built-in seeds around `initial_ph_guess`, the default retries, undamped steps
+    /// and no observer.
+    fn from_guess(initial_ph_guess: f64) -> Self {
+        Self {
+            initial_ph_guess,
+            extra_seeds: &[],
+            observer: None,
+            retry_attempts: SOL_NEWTON_RETRY_ATTEMPTS,
+            trust_region: None,
+        }
+    }
+}
+
+/// Lower clamp edge for a log10 unknown whose composition scale is `scale_log10`.
+fn newton_log_floor(scale_log10: f64) -> f64 {
+    (scale_log10 - SOL_NEWTON_LOG_SPAN).min(SOL_NEWTON_LOG_UPPER - SOL_NEWTON_LOG_FLOOR_HEADROOM)
+}
+
+/// Per-unknown log10 clamp ranges for the closed-carbon Newton system
+/// `[log h, log hco3, log co3, log h2co3]`. Carbon pools may fall
+/// `SOL_NEWTON_LOG_SPAN` decades below total carbon and `[H+]` as far below the
+/// free-OH- limit set by sodium, instead of all four sharing one fixed floor.
+fn closed_carbonate_log_bounds(total_carbon_m: f64, na_conc: f64, kw: f64) -> [(f64, f64); 4] {
+    let h_floor = newton_log_floor((kw / (na_conc + 1e-7)).log10());
+    let carbon_floor = newton_log_floor(total_carbon_m.max(1e-30).log10());
+    let carbon_bounds = (carbon_floor, SOL_NEWTON_LOG_UPPER);
+    [(h_floor, SOL_NEWTON_LOG_UPPER), carbon_bounds, carbon_bounds, carbon_bounds]
+}
+
+/// Per-unknown log10 clamp ranges for the fixed-H2CO3 Newton system
+/// `[log h, log hco3, log co3]`; the ionic pools are scaled by the sodium plus the
+/// acid dissociation the fixed H2CO3 can supply.
+fn open_carbonate_log_bounds(na_conc: f64, ka1: f64, kw: f64, fixed_h2co3: f64) -> [(f64, f64); 3] {
+    let acid_m = (ka1 * fixed_h2co3).max(0.0).sqrt();
+    let h_floor = newton_log_floor((kw / (na_conc + 1e-7)).log10());
+    let ionic_floor = newton_log_floor((na_conc + acid_m + 1e-7).log10());
+    [
+        (h_floor, SOL_NEWTON_LOG_UPPER),
+        (ionic_floor, SOL_NEWTON_LOG_UPPER),
+        (ionic_floor, SOL_NEWTON_LOG_UPPER),
+    ]
+}
+
+/// Newton iteration on `func`, undamped unless `trust_region` is given; every
+/// update is clamped into the matching `bounds` entry.
+fn newton_system_solve<F>(
+    func: &F,
+    x: Vec<f64>,
+    bounds: &[(f64, f64)],
+    tol: f64,
+    max_iter: usize,
+    observer: Option<NewtonIterationObserver<'_>>,
+    trust_region: Option<NewtonTrustRegion>,
+) -> Result<(Vec<f64>, f64), String>
+where
+    F: Fn(&[f64]) -> Vec<f64>,
+{
+    newton_system_trajectory(func, x, bounds, tol, max_iter, observer, trust_region)
+        .map_err(|failure| failure.message)
+}
+
+/// Why a Newton run failed, with the lowest-residual `(point, norm)` it visited.
+struct NewtonFailure {
+    message: String,
+    best: Option<(Vec<f64>, f64)>,
+}
+
+/// [`newton_system_solve`] that keeps its closest approach on failure, so a
+/// caller can restart from there instead of from the original seed.
+fn newton_system_trajectory<F>(
+    func: &F,
+    mut x: Vec<f64>,
+    bounds: &[(f64, f64)],
+    tol: f64,
+    max_iter: usize,
+    observer: Option<NewtonIterationObserver<'_>>,
+    trust_region: Option<NewtonTrustRegion>,
+) -> Result<(Vec<f64>, f64), NewtonFailure>
+where
+    F: Fn(&[f64]) -> Vec<f64>,
+{
+    let mut best: Option<(Vec<f64>, f64)> = None;
+    let mut radius = trust_region.map(|control| control.initial_radius);
+    for iteration in 0..max_iter {
+        let residual = func(&x);
+        if residual.iter().any(|v| !v.is_finite()) {
+            return Err(NewtonFailure {
+                message: "Non-finite residual".to_string(),
+                best,
+            });
+        }
+        let residual_norm = residual.iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+        solver_trace!("newton iteration {iteration}: residual {residual_norm:e} at {x:?}");
+        if let Some(observe) = observer {
+            observe(iteration, &x, residual_norm);
+        }
+        if residual_norm < tol {
+            return Ok((x, residual_norm));
+        }
+        if best.as_ref().is_none_or(|(_, norm)| residual_norm < *norm) {
+            best = Some((x.clone(), residual_norm));
+        }
+        let jacobian = numerical_jacobian(&func, &x, 1e-6);
+        let delta = match solve_linear_system(
+            &jacobian,
+            &residual.iter().map(|value| -*value).collect::<Vec<f64>>(),
+        ) {
+            Ok(delta) => delta,
+            Err(message) => return Err(NewtonFailure { message, best }),
+        };
+        if delta.iter().any(|v| !v.is_finite()) {
+            return Err(NewtonFailure {
+                message: "Non-finite Newton increment".to_string(),
+                best,
+            });
+        }
+        let delta = match (trust_region, radius.as_mut()) {
+            (Some(control), Some(radius)) => {
+                let step = trust_region_step(
+                    func,
+                    &x,
+                    (&residual, &jacobian),
+                    &delta,
+                    bounds,
+                    control,
+                    radius,
+                );
+                match step {
+                    Some(step) => step,
+                    None => {
+                        return Err(NewtonFailure {
+                            message: "Trust region collapsed".to_string(),
+                            best,
+                        });
+                    }
+                }
+            }
+            _ => delta,
+        };
+        x = x
+            .iter()
+            .zip(delta.iter())
+            .zip(bounds.iter())
+            .map(|((value, step), (low, high))| (*value + *step).clamp(*low, *high))
+            .collect();
+        if delta.iter().fold(0.0_f64, |acc, v| acc.max(v.abs())) < tol {
+            let final_norm = func(&x).iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+            if final_norm < tol {
+                return Ok((x, final_norm));
+            }
+        }
+    }
+    Err(NewtonFailure {
+        message: "Newton solver did not converge".to_string(),
+        best,
+    })
+}
+
+/// Indices of the unknowns of `point` lying within `SOL_NEWTON_BOUND_PIN_EPS`
+/// decades of their clamp edge in `bounds`.
+fn pinned_log_components(point: &[f64], bounds: &[(f64, f64)]) -> Vec<usize> {
+    point
+        .iter()
+        .zip(bounds)
+        .enumerate()
+        .filter(|(_, (value, (low, high)))| {
+            let (value, low, high) = (**value, *low, *high);
+            value - low <= SOL_NEWTON_BOUND_PIN_EPS || high - value <= SOL_NEWTON_BOUND_PIN_EPS
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Deterministic restart point for retry `attempt`: every unknown moves
+/// `SOL_NEWTON_RETRY_STEP` decades (growing every second attempt) with a sign that
+/// alternates across unknowns and flips between attempts, clamped into `bounds`.
+fn perturbed_log_point(point: &[f64], bounds: &[(f64, f64)], attempt: usize) -> Vec<f64> {
+    let magnitude = SOL_NEWTON_RETRY_STEP * (attempt / 2 + 1) as f64;
+    point
+        .iter()
+        .zip(bounds)
+        .enumerate()
+        .map(|(index, (value, (low, high)))| {
+            let sign = if (index + attempt).is_multiple_of(2) { 1.0 } else { -1.0 };
+            (value + sign * magnitude).clamp(*low, *high)
+        })
+        .collect()
+}
+
+/// Closed-form `(pH, hco3_frac, co3_frac)` seed from the Na:C stoichiometry.
+///
+/// Splits carbon by the sodium balance, applies Henderson-Hasselbalch on the
+/// governing pKa (or the free OH- past the carbonate point) with a first-pass
+/// Davies correction at the sodium-implied ionic strength, and reports the
+/// matching concentration-basis DIC fractions.
+fn analytical_seed(
+    total_carbon_m: f64,
     na_conc: f64,
-    forced_ph: f64,
     ka1: f64,
     ka2: f64,
     kw: f64,
-    ionic_strength_cap: Option<f64>,
-    fixed_h2co3: Option<f64>,
-    max_iter: usize,
-) -> Result<(f64, f64, f64, f64, f64, f64, f64, [f64; 5], f64), String> {
-    if !(0.0..14.5).contains(&forced_ph) {
-        return Err("Forced pH must be between 0 and 14.5".to_string());
-    }
-    total_carbon_m = total_carbon_m.max(1e-16);
+) -> (f64, f64, f64) {
+    let total_carbon_m = total_carbon_m.max(1e-16);
     let na_conc = na_conc.max(0.0);
-    let fixed_h2co3_value = fixed_h2co3.map(|value| value.max(1e-16));
-    let h = 10f64.powf(-forced_ph);
-    let mut hco3 = (total_carbon_m * 0.9).max(1e-16);
-    let mut co3 = (total_carbon_m * 0.05).max(1e-16);
-    let mut h2co3 = (total_carbon_m - hco3 - co3).max(0.0);
-    let mut charge_residual = 0.0_f64;
+    let ionic_strength = na_conc.min(SOL_DAVIES_LIMIT);
+    let gamma_1 = solubility_activity_coefficient(ionic_strength, 1, 0.90);
+    let gamma_2 = solubility_activity_coefficient(ionic_strength, -2, 0.40);
+    let ka1_c = ka1 / (gamma_1 * gamma_1);
+    let ka2_c = ka2 / gamma_2;
+    let floor = total_carbon_m * 1e-3;
+    let h = if na_conc >= 2.0 * total_carbon_m {
+        let free_oh = (na_conc - 2.0 * total_carbon_m).max(floor);
+        kw / (gamma_1 * gamma_1 * free_oh)
+    } else if na_conc >= total_carbon_m {
+        let co3 = (na_conc - total_carbon_m).max(floor);
+        let hco3 = (2.0 * total_carbon_m - na_conc).max(floor);
+        ka2_c * hco3 / co3
+    } else {
+        let hco3 = na_conc.max(floor);
+        let h2co3 = (total_carbon_m - na_conc).max(floor);
+        ka1_c * h2co3 / hco3
+    }
+    .max(1e-16);
+    let (_, hco3_frac, co3_frac) = aqion_alpha_fractions(h, ka1_c, ka2_c);
+    (-h.log10(), hco3_frac, co3_frac)
+}
+
+/// One-dimensional pH bisection for the closed-carbon speciation.
+///
+/// At each trial `[H+]` the carbon pools follow from the concentration-basis ratio
+/// equilibria, the charge excess is bisected on log10 `[H+]`, and the activity
+/// coefficients are refreshed to self-consistency. It serves as the dilute-limit
+/// path for trace carbon, where the 4-unknown Newton system would run through the
+/// `1e-16` guards, and as the last resort once every Newton seed has failed.
+/// Output matches [`CarbonateSolution`] minus the residual.
+fn solve_carbonate_state_bisection(
+    system: CarbonateSystem,
+    activity: ActivityTreatment,
+) -> (f64, f64, f64, f64, f64, [f64; 5], f64) {
+    let CarbonateSystem {
+        total_carbon_m,
+        na_conc,
+        ka1,
+        ka2,
+        kw,
+        ammonia,
+        phosphate,
+    } = system;
     let mut gammas = [1.0_f64; 5];
-    let mut ionic_strength = 0.0_f64;
-    let mut oh = 1e-7_f64;
-    for _ in 0..max_iter.max(1) {
-        let (next_i, next_gammas, next_oh) =
-            solubility_ionic_state(na_conc, h, hco3, co3, kw, ionic_strength_cap);
-        ionic_strength = next_i;
-        gammas = next_gammas;
-        oh = next_oh;
-        let coeff_co3 = (ka2 * gammas[2]) / (gammas[1] * gammas[3] * h.max(1e-18));
-        let coeff_h2co3 = (gammas[1] * gammas[2] * h) / ka1.max(1e-30);
-        let charge_basis = (na_conc + h - oh).max(1e-16);
-        hco3 = (charge_basis / (1.0 + (2.0 * coeff_co3)).max(1e-12)).max(1e-16);
-        co3 = (coeff_co3 * hco3).max(1e-16);
-        h2co3 = (coeff_h2co3 * hco3)
-            .max(fixed_h2co3_value.unwrap_or(0.0))
-            .max(1e-16);
-        charge_residual = na_conc + h - hco3 - 2.0 * co3 - oh;
-        let next_total = (h2co3 + hco3 + co3).max(1e-16);
-        let delta_ct = ((next_total - total_carbon_m) / total_carbon_m.max(1e-12)).abs();
-        total_carbon_m = next_total;
-        if delta_ct < 1e-8 && charge_residual.abs() < 1e-8 {
+    let mut h = 1e-7_f64;
+    let mut species = (0.0, 0.0, 0.0);
+    for _ in 0..100 {
+        let ka1_c = ka1 / (gammas[1] * gammas[2]);
+        let ka2_c = ka2 * gammas[2] / (gammas[1] * gammas[3]);
+        let kw_c = kw / (gammas[1] * gammas[4]);
+        let charge_excess = |log_h: f64| {
+            let h = 10f64.powf(log_h);
+            let (_, a1, a2) = aqion_alpha_fractions(h, ka1_c, ka2_c);
+            let nh4 = ammonia.map_or(0.0, |buffer| buffer.ammonium_m(h, &gammas));
+            let phosphate_charge = phosphate
+                .map_or(0.0, |buffer| phosphate_anion_charge(&buffer.species_m(h, &gammas)));
+            na_conc + nh4 + h - kw_c / h - total_carbon_m * (a1 + 2.0 * a2) - phosphate_charge
+        };
+        // Charge excess rises monotonically with [H+] (NH4+ and phosphate
+        // included), so bisect on log10 [H+].
+        let (mut lo, mut hi) = (-16.0_f64, 1.0_f64);
+        for _ in 0..200 {
+            let mid = 0.5 * (lo + hi);
+            if charge_excess(mid) > 0.0 {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+            if hi - lo < 1e-14 {
+                break;
+            }
+        }
+        h = 10f64.powf(0.5 * (lo + hi));
+        let (a0, a1, a2) = aqion_alpha_fractions(h, ka1_c, ka2_c);
+        species = (total_carbon_m * a0, total_carbon_m * a1, total_carbon_m * a2);
+        let nh4 = ammonia.map_or(0.0, |buffer| buffer.ammonium_m(h, &gammas));
+        let (mono_slot, di_slot) =
+            phosphate_ionic_slots(phosphate.map(|buffer| buffer.species_m(h, &gammas)));
+        let (_, next_gammas, _, _) = solubility_ionic_state(
+            na_conc + nh4,
+            h,
+            species.1 + mono_slot,
+            species.2 + di_slot,
+            kw,
+            activity,
+        );
+        let mut max_shift = 0.0_f64;
+        // Half-step damping keeps the refresh from two-cycling around the
+        // Davies/extended Debye-Hückel switch at I = 0.5.
+        for (gamma, next) in gammas.iter_mut().zip(next_gammas) {
+            max_shift = max_shift.max((next - *gamma).abs());
+            *gamma = 0.5 * (*gamma + next);
+        }
+        if max_shift < 1e-14 {
             break;
         }
     }
-    let (final_i, final_gammas, final_oh) =
-        solubility_ionic_state(na_conc, h, hco3, co3, kw, ionic_strength_cap);
-    let coeff_co3 =
-        (ka2 * final_gammas[2]) / (final_gammas[1] * final_gammas[3] * h.max(1e-18));
-    let coeff_h2co3 = (final_gammas[1] * final_gammas[2] * h) / ka1.max(1e-30);
-    let charge_basis = (na_conc + h - final_oh).max(1e-16);
-    hco3 = (charge_basis / (1.0 + (2.0 * coeff_co3)).max(1e-12)).max(1e-16);
-    co3 = (coeff_co3 * hco3).max(1e-16);
-    h2co3 = (coeff_h2co3 * hco3)
-        .max(fixed_h2co3_value.unwrap_or(0.0))
-        .max(1e-16);
-    total_carbon_m = (h2co3 + hco3 + co3).max(1e-16);
-    charge_residual = na_conc + h - hco3 - 2.0 * co3 - final_oh;
-    let (final_i, final_gammas, final_oh) =
-        solubility_ionic_state(na_conc, h, hco3, co3, kw, ionic_strength_cap);
-    charge_residual = na_conc + h - hco3 - 2.0 * co3 - final_oh;
-    Ok((
-        total_carbon_m,
+    let (h2co3, hco3, co3) = species;
+    let nh4 = ammonia.map_or(0.0, |buffer| buffer.ammonium_m(h, &gammas));
+    let (mono_slot, di_slot) =
+        phosphate_ionic_slots(phosphate.map(|buffer| buffer.species_m(h, &gammas)));
+    let (ionic_strength, gammas, oh, _) = solubility_ionic_state(
+        na_conc + nh4,
         h,
-        hco3,
-        co3,
-        h2co3,
-        final_oh,
-        charge_residual,
-        final_gammas,
-        final_i,
-    ))
+        hco3 + mono_slot,
+        co3 + di_slot,
+        kw,
+        activity,
+    );
+    (h, hco3, co3, h2co3, oh, gammas, ionic_strength)
 }
 
-fn aqion_alpha_fractions(h_conc: f64, ka1: f64, ka2: f64) -> (f64, f64, f64) {
-    let mut denom = (h_conc * h_conc) + (ka1 * h_conc) + (ka1 * ka2);
-    if denom <= 0.0 || !denom.is_finite() {
-        denom = 1e-30;
+/// Physical sanity check on a converged closed-carbon speciation: every species
+/// finite and non-negative, the carbon pools summing to the total and the charge
+/// balance closing, both to `SOL_PHYSICAL_REL_TOL` of their own scale. The
+/// log-space Newton clamp can otherwise settle on a "converged" point that
+/// violates the balances it was meant to enforce. `extra_anion_charge` is the
+/// non-carbonate anion charge (phosphate) in the balance, mol/L.
+fn carbonate_solution_is_physical(
+    total_carbon_m: f64,
+    na_conc: f64,
+    (h, hco3, co3, h2co3, oh): (f64, f64, f64, f64, f64),
+    extra_anion_charge: f64,
+) -> bool {
+    let species = [h, hco3, co3, h2co3, oh, extra_anion_charge];
+    if species.iter().any(|value| !value.is_finite() || *value < 0.0) {
+        return false;
     }
-    let a0 = (h_conc * h_conc) / denom;
-    let a1 = (ka1 * h_conc) / denom;
-    let a2 = (ka1 * ka2) / denom;
-    (a0, a1, a2)
-}
-
-fn aqion_species_from_ph(
-    ct: f64,
-    ph: f64,
-    ka1: f64,
-    ka2: f64,
-    kw: f64,
-) -> (f64, f64, f64, f64, f64, f64, f64, f64, f64) {
-    let h = 10f64.powf(-ph);
-    let (a0, a1, a2) = aqion_alpha_fractions(h, ka1, ka2);
-    let h2co3 = ct * a0;
-    let hco3 = ct * a1;
-    let co3 = ct * a2;
-    let oh = kw / h.max(1e-30);
-    let ionic_strength = 0.5 * (h + hco3 + 4.0 * co3 + oh);
-    (h, h2co3, hco3, co3, oh, a0, a1, a2, ionic_strength)
+    let carbon_sum = h2co3 + hco3 + co3;
+    let anion_charge = hco3 + 2.0 * co3 + oh + extra_anion_charge;
+    let charge_scale = na_conc + h + anion_charge;
+    (carbon_sum - total_carbon_m).abs() <= SOL_PHYSICAL_REL_TOL * total_carbon_m
+        && (na_conc + h - anion_charge).abs() <= SOL_PHYSICAL_REL_TOL * charge_scale
 }
 
-fn aqion_charge_balance_residual(ct: f64, ph: f64, ka1: f64, ka2: f64, kw: f64) -> f64 {
-    let (h, _h2co3, hco3, co3, oh, _a0, _a1, _a2, _ionic_strength) =
-        aqion_species_from_ph(ct, ph, ka1, ka2, kw);
-    h - hco3 - 2.0 * co3 - oh
+/// Solve the closed-carbon Na/CO2/H2O speciation by multi-seed Newton iteration.
+///
+/// Seeds are `(pH, hco3_frac, co3_frac)` tuples whose fractions partition total
+/// carbon, with H2CO3 taking the remainder. `extra_seeds` are tried first, in
+/// order, ahead of the built-in table, so a hand-chosen seed can rescue a stubborn
+/// composition without changing the default search. The built-in table opens
+/// with the closed-form [`analytical_seed`] and keeps the fixed guesses as
+/// fallbacks.
+///
+/// Below `SOL_DILUTE_CARBON_M` the Newton system is skipped for
+/// [`solve_carbonate_state_bisection`]; its residual covers the balances only.
+/// The same bisection, as [`solve_carbonate_state_proton_condition`], is the
+/// fallback when no seed converges, kept only if the full residual meets the
+/// relaxed tolerance.
+///
+/// Every seed is first held to the strict `1e-12` residual; only when none gets
+/// there are they retried against the relaxed `1e-8` tolerance. The achieved
+/// residual norm is returned last so batch callers can tell the two apart. A
+/// converged seed is only accepted if [`carbonate_solution_is_physical`] agrees.
+///
+/// When every seed fails, up to `retry_attempts` restarts from a
+/// [`perturbed_log_point`] around the lowest-residual point any seed reached are
+/// tried against the relaxed tolerance before the bisection fallback.
+///
+/// `ammonia` adds the NH4+/NH3 pair as a fifth unknown and equation (see
+/// [`closed_carbonate_residuals`]); the returned tuple keeps its carbonate shape
+/// and NH4+ follows from [`AmmoniaBuffer::ammonium_m`] at the solved state.
+/// `phosphate` likewise adds the four orthophosphate species; they follow from
+/// [`PhosphateBuffer::species_m`] at the solved state.
+fn solve_carbonate_state(
+    system: CarbonateSystem,
+    activity: ActivityTreatment,
+    newton: NewtonOptions<'_>,
+) -> Result<CarbonateSolution, String> {
+    solve_carbonate_state_traced(system, activity, newton).map(|(solution, _)| solution)
 }
 
-fn solve_aqion_closed_speciation(
-    total_inorganic_carbon_m: f64,
-    ka1: f64,
-    ka2: f64,
-    kw: f64,
-    ph_low: f64,
-    ph_high: f64,
-) -> Result<(f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, String), String> {
-    let ct = total_inorganic_carbon_m.max(0.0);
-    let low = ph_low.min(ph_high);
-    let high = ph_low.max(ph_high);
-    let mut f_low = aqion_charge_balance_residual(ct, low, ka1, ka2, kw);
-    let mut f_high = aqion_charge_balance_residual(ct, high, ka1, ka2, kw);
-    let mut solver = "bisection-charge-balance".to_string();
-    let ph_root = if f_low.abs() < 1e-14 {
-        solver = "bracket-bound".to_string();
-        low
-    } else if f_high.abs() < 1e-14 {
-        solver = "bracket-bound".to_string();
-        high
-    } else if f_low * f_high > 0.0 {
-        // Fall back to a dense residual scan when the bracket does not straddle.
-        solver = "scan-min-residual".to_string();
-        let mut best_ph = low;
-        let mut best_residual = f_low.abs();
-        let scan_steps = 480usize;
-        for idx in 0..=scan_steps {
-            let ph = low + (high - low) * (idx as f64 / scan_steps as f64);
-            let residual = aqion_charge_balance_residual(ct, ph, ka1, ka2, kw).abs();
-            if residual < best_residual {
-                best_residual = residual;
-                best_ph = ph;
-            }
+/// [`solve_carbonate_state`] plus the accepted Newton vector
+/// `[log10 h, log10 hco3, log10 co3, log10 h2co3(, log10 nh4)(, log10 phosphate x4)]`
+/// exactly as converged,
+/// before the `10^x` round-trip into concentrations. `None` when the state came from
+/// the dilute-carbon or proton-condition bisection, which have no such vector.
+/// `trust_region` switches the Newton runs to [`NewtonTrustRegion`] step control.
+fn solve_carbonate_state_traced(
+    system: CarbonateSystem,
+    activity: ActivityTreatment,
+    newton: NewtonOptions<'_>,
+) -> Result<(CarbonateSolution, Option<Vec<f64>>), String> {
+    let system = CarbonateSystem {
+        total_carbon_m: system.total_carbon_m.max(1e-16),
+        na_conc: system.na_conc.max(0.0),
+        ..system
+    };
+    let CarbonateSystem {
+        total_carbon_m,
+        na_conc,
+        ka1,
+        ka2,
+        kw,
+        ammonia,
+        phosphate,
+    } = system;
+    let NewtonOptions {
+        initial_ph_guess,
+        extra_seeds,
+        observer,
+        retry_attempts,
+        trust_region,
+    } = newton;
+    let residuals = closed_carbonate_residuals(system, activity);
+    if total_carbon_m < SOL_DILUTE_CARBON_M {
+        let (h, hco3, co3, h2co3, oh, gammas, ionic_strength) =
+            solve_carbonate_state_bisection(system, activity);
+        let nh4 = ammonia.map_or(0.0, |buffer| buffer.ammonium_m(h, &gammas));
+        let phosphate_charge = phosphate
+            .map_or(0.0, |buffer| phosphate_anion_charge(&buffer.species_m(h, &gammas)));
+        // The mass-action rows would hit the 1e-16 guards at these levels, so only
+        // the mass and charge balances are reported.
+        let residual_norm = (h2co3 + hco3 + co3 - total_carbon_m)
+            .abs()
+            .max((na_conc + nh4 + h - hco3 - 2.0 * co3 - oh - phosphate_charge).abs());
+        return Ok((
+            (h, hco3, co3, h2co3, oh, gammas, ionic_strength, residual_norm),
+            None,
+        ));
+    }
+    let builtin_guesses = [
+        analytical_seed(total_carbon_m, na_conc, ka1, ka2, kw),
+        (initial_ph_guess, 0.85_f64, 0.12_f64),
+        (8.8_f64, 0.80_f64, 0.19_f64),
+        (7.5_f64, 0.95_f64, 0.03_f64),
+        (9.2_f64, 0.70_f64, 0.29_f64),
+    ];
+    let mut log_bounds = closed_carbonate_log_bounds(total_carbon_m, na_conc, kw).to_vec();
+    if let Some(buffer) = ammonia {
+        let nh4_floor = newton_log_floor(buffer.total_m.log10());
+        log_bounds.push((nh4_floor, SOL_NEWTON_LOG_UPPER));
+    }
+    let phosphate_offset = log_bounds.len();
+    if let Some(buffer) = phosphate {
+        let phosphate_floor = newton_log_floor(buffer.total_m.log10());
+        log_bounds.extend([(phosphate_floor, SOL_NEWTON_LOG_UPPER); 4]);
+    }
+    let accept = |sol: &[f64], residual_norm: f64| {
+        let pinned = pinned_log_components(sol, &log_bounds);
+        if !pinned.is_empty() {
+            solver_debug!("converged with unknowns {pinned:?} pinned at their clamp bounds");
+            return None;
         }
-        best_ph
-    } else {
-        let mut lo = low;
-        let mut hi = high;
-        let mut mid = 0.5 * (lo + hi);
-        for _ in 0..220 {
-            mid = 0.5 * (lo + hi);
-            let f_mid = aqion_charge_balance_residual(ct, mid, ka1, ka2, kw);
-            if f_mid.abs() < 1e-14 || (hi - lo).abs() < 1e-6 {
-                break;
+        let h = 10f64.powf(sol[0]);
+        let hco3 = 10f64.powf(sol[1]);
+        let co3 = 10f64.powf(sol[2]);
+        let h2co3 = 10f64.powf(sol[3]);
+        let nh4 = ammonia.map_or(0.0, |_| 10f64.powf(sol[4]));
+        let phosphate_species = phosphate
+            .map(|_| [0, 1, 2, 3].map(|idx| 10f64.powf(sol[phosphate_offset + idx])));
+        let (mono_slot, di_slot) = phosphate_ionic_slots(phosphate_species);
+        let (ionic_strength, gammas, oh, _) = solubility_ionic_state(
+            na_conc + nh4,
+            h,
+            hco3 + mono_slot,
+            co3 + di_slot,
+            kw,
+            activity,
+        );
+        // NH4+ only enters the physical check's charge balance, so it rides along
+        // with the sodium.
+        let phosphate_charge = phosphate_species.as_ref().map_or(0.0, phosphate_anion_charge);
+        carbonate_solution_is_physical(
+            total_carbon_m,
+            na_conc + nh4,
+            (h, hco3, co3, h2co3, oh),
+            phosphate_charge,
+        )
+        .then_some((h, hco3, co3, h2co3, oh, gammas, ionic_strength, residual_norm))
+    };
+    let mut closest_miss: Option<(Vec<f64>, f64)> = None;
+    for tol in [SOL_NEWTON_STRICT_TOL, SOL_NEWTON_RELAXED_TOL] {
+        let seeds = extra_seeds.iter().copied().chain(builtin_guesses);
+        for (ph_guess, hco3_frac, co3_frac) in seeds {
+            let Some((h2co3_frac, hco3_frac, co3_frac)) =
+                normalized_seed_fractions(hco3_frac, co3_frac)
+            else {
+                continue;
+            };
+            let h = 10f64.powf(-ph_guess);
+            let hco3 = (total_carbon_m * hco3_frac).max(1e-16);
+            let co3 = (total_carbon_m * co3_frac).max(1e-16);
+            let h2co3 = (total_carbon_m * h2co3_frac).max(1e-16);
+            let mut guess = vec![h.log10(), hco3.log10(), co3.log10(), h2co3.log10()];
+            if let Some(buffer) = ammonia {
+                let nh4 = buffer.ammonium_m(h, &[1.0; 5]).max(1e-16);
+                guess.push(nh4.log10());
             }
-            if f_low * f_mid <= 0.0 {
-                hi = mid;
-                f_high = f_mid;
-            } else {
-                lo = mid;
-                f_low = f_mid;
+            if let Some(buffer) = phosphate {
+                let species = buffer.species_m(h, &[1.0; 5]);
+                guess.extend(species.map(|value| value.max(buffer.total_m * 1e-30).log10()));
+            }
+            solver_debug!(
+                "closed solve C_T={total_carbon_m:e} Na={na_conc:e}: seed pH {ph_guess} \
+                 (hco3 {hco3_frac}, co3 {co3_frac}) at tol {tol:e}"
+            );
+            let run = newton_system_trajectory(
+                &residuals,
+                guess,
+                &log_bounds,
+                tol,
+                60,
+                observer,
+                trust_region,
+            );
+            match run {
+                Ok((sol, residual_norm)) => {
+                    if let Some(solution) = accept(&sol, residual_norm) {
+                        solver_debug!("seed converged, residual {residual_norm:e}");
+                        return Ok((solution, Some(sol)));
+                    }
+                    solver_debug!("seed converged to a pinned or non-physical state; rejected");
+                }
+                Err(failure) => {
+                    solver_debug!("seed failed: {}", failure.message);
+                    if let Some((point, norm)) = failure.best
+                        && closest_miss.as_ref().is_none_or(|(_, best)| norm < *best)
+                    {
+                        closest_miss = Some((point, norm));
+                    }
+                }
             }
         }
-        mid
-    };
-    let (h, h2co3, hco3, co3, oh, a0, a1, a2, ionic_strength) =
-        aqion_species_from_ph(ct, ph_root, ka1, ka2, kw);
-    let residual = aqion_charge_balance_residual(ct, ph_root, ka1, ka2, kw);
-    Ok((
-        ph_root,
-        h,
-        oh,
-        h2co3,
-        hco3,
-        co3,
-        a0,
-        a1,
-        a2,
-        ionic_strength,
-        residual,
-        solver,
-    ))
-}
-
-#[derive(Clone, Copy)]
-struct PitzerParamsLite {
-    b0_na_oh: f64,
-    b1_na_oh: f64,
-    c0_na_oh: f64,
-    b0_na_hco3: f64,
-    b1_na_hco3: f64,
-    c0_na_hco3: f64,
-    b0_na_co3: f64,
-    b1_na_co3: f64,
-    c0_na_co3: f64,
-    theta_co3_oh: f64,
-    psi_co3_na_oh: f64,
-    psi_co3_hco3_na: f64,
+    }
+    if let Some((point, norm)) = closest_miss {
+        for attempt in 0..retry_attempts {
+            solver_debug!("retry {attempt} from the closest miss (residual {norm:e})");
+            let nudged = perturbed_log_point(&point, &log_bounds, attempt);
+            if let Ok((sol, residual_norm)) = newton_system_solve(
+                &residuals,
+                nudged,
+                &log_bounds,
+                SOL_NEWTON_RELAXED_TOL,
+                60,
+                observer,
+                trust_region,
+            ) && let Some(solution) = accept(&sol, residual_norm)
+            {
+                return Ok((solution, Some(sol)));
+            }
+        }
+    }
+    solver_debug!("Newton seeds exhausted; falling back to the proton-condition bisection");
+    solve_carbonate_state_proton_condition(system, activity)
+    .map(|solution| (solution, None))
+    .map_err(|_| "Equilibrium solver did not converge".to_string())
 }
 
-fn pitzer_g(x: f64) -> f64 {
-    if x.abs() <= 1e-18 {
-        return 0.0;
+/// Closed-carbon speciation from the proton condition alone: the charge balance
+/// as a scalar function of `[H+]`, bracketed and bisected by
+/// [`solve_carbonate_state_bisection`] under the activity refresh. The residual
+/// norm covers the same rows as the Newton system, and the state is only kept when
+/// it meets the relaxed tolerance and passes [`carbonate_solution_is_physical`].
+fn solve_carbonate_state_proton_condition(
+    system: CarbonateSystem,
+    activity: ActivityTreatment,
+) -> Result<CarbonateSolution, String> {
+    let system = CarbonateSystem {
+        total_carbon_m: system.total_carbon_m.max(1e-16),
+        na_conc: system.na_conc.max(0.0),
+        ..system
+    };
+    let CarbonateSystem {
+        total_carbon_m,
+        na_conc,
+        ka1,
+        ka2,
+        ammonia,
+        phosphate,
+        ..
+    } = system;
+    let (h, hco3, co3, h2co3, oh, gammas, ionic_strength) =
+        solve_carbonate_state_bisection(system, activity);
+    let nh4 = ammonia.map_or(0.0, |buffer| buffer.ammonium_m(h, &gammas));
+    let phosphate_charge = phosphate
+        .map_or(0.0, |buffer| phosphate_anion_charge(&buffer.species_m(h, &gammas)));
+    // Same rows as the Newton system, minus its `1e-16` H2CO3 guard: strongly
+    // basic fallbacks legitimately carry less H2CO3 than that. The bisection puts
+    // NH4+ and phosphate on their equilibrium shares exactly, so those rows are zero.
+    let ka1_actual = (gammas[1] * gammas[2] * h * hco3) / h2co3;
+    let ka2_actual = (gammas[1] * gammas[3] * h * co3) / (gammas[2] * hco3);
+    let residual_norm = [
+        (ka1_actual / ka1).log10(),
+        (ka2_actual / ka2).log10(),
+        h2co3 + hco3 + co3 - total_carbon_m,
+        na_conc + nh4 + h - hco3 - 2.0 * co3 - oh - phosphate_charge,
+    ]
+    .iter()
+    .fold(0.0_f64, |acc, v| acc.max(v.abs()));
+    if residual_norm.is_finite()
+        && residual_norm < SOL_NEWTON_RELAXED_TOL
+        && carbonate_solution_is_physical(
+            total_carbon_m,
+            na_conc + nh4,
+            (h, hco3, co3, h2co3, oh),
+            phosphate_charge,
+        )
+    {
+        return Ok((h, hco3, co3, h2co3, oh, gammas, ionic_strength, residual_norm));
     }
-    2.0 * (1.0 - (1.0 + x) * (-x).exp()) / (x * x)
+    Err("Proton-condition solver did not converge".to_string())
 }
 
-fn pitzer_g_prime(x: f64) -> f64 {
-    let h = 1e-6;
-    (pitzer_g(x + h) - pitzer_g(x - h)) / (2.0 * h)
+/// Seed carbon split as `(h2co3, hco3, co3)` fractions summing to one.
+///
+/// HCO3/CO3 fractions outside `[0, 1]` reject the seed; when they leave less than
+/// `SOL_SEED_MIN_H2CO3_FRACTION` for H2CO3 they are scaled down together so the
+/// explicit remainder never goes negative.
+fn normalized_seed_fractions(hco3_frac: f64, co3_frac: f64) -> Option<(f64, f64, f64)> {
+    if !(0.0..=1.0).contains(&hco3_frac) || !(0.0..=1.0).contains(&co3_frac) {
+        return None;
+    }
+    let charged_cap = 1.0 - SOL_SEED_MIN_H2CO3_FRACTION;
+    let charged = hco3_frac + co3_frac;
+    let scale = if charged > charged_cap {
+        charged_cap / charged
+    } else {
+        1.0
+    };
+    let (hco3_frac, co3_frac) = (hco3_frac * scale, co3_frac * scale);
+    Some((1.0 - hco3_frac - co3_frac, hco3_frac, co3_frac))
 }
 
-fn pitzer_b(beta0: f64, beta1: f64, ionic_strength: f64) -> f64 {
-    beta0 + beta1 * pitzer_g(PITZER_ALPHA_B1 * ionic_strength.max(0.0).sqrt())
+/// `log10(actual / reference)` with the ratio held inside
+/// `[SOL_LOG_RATIO_FLOOR, 1 / SOL_LOG_RATIO_FLOOR]`.
+///
+/// A species that underflows to zero mid-iteration would otherwise turn the
+/// mass-action row into ±inf and abort a Newton run that could still recover; NaN
+/// is passed through so genuinely broken states are still rejected.
+fn guarded_log10_ratio(actual: f64, reference: f64) -> f64 {
+    (actual / reference)
+        .clamp(SOL_LOG_RATIO_FLOOR, 1.0 / SOL_LOG_RATIO_FLOOR)
+        .log10()
 }
 
-fn pitzer_b_prime(beta1: f64, ionic_strength: f64) -> f64 {
-    if ionic_strength <= 0.0 {
-        return 0.0;
-    }
-    let x = PITZER_ALPHA_B1 * ionic_strength.sqrt();
-    beta1 * pitzer_g_prime(x) * PITZER_ALPHA_B1 / (2.0 * ionic_strength.sqrt())
+/// Dissolved CO2 (as H2CO3*) in equilibrium with a headspace of CO2 mole fraction
+/// `co2_gas_fraction` at `total_pressure_atm`.
+///
+/// Henry's law with the partial pressure taken as `y·P`, so the pressure enters as
+/// a plain multiplicative factor on the fixed-pCO2 solubility; non-ideal gas and
+/// Poynting corrections are ignored. The Henry constant is temperature-adjusted by
+/// van 't Hoff from its 25 °C value.
+fn henry_dissolved_co2(co2_gas_fraction: f64, total_pressure_atm: f64, temperature_c: f64) -> f64 {
+    let temperature_k = temperature_c + 273.15;
+    let kh = SOL_HENRY_KH_CO2_25C
+        * (SOL_HENRY_VANT_HOFF_K * (1.0 / temperature_k - 1.0 / 298.15)).exp();
+    kh * co2_gas_fraction * total_pressure_atm
 }
 
-fn pitzer_gamma_set(
-    na: f64,
-    h: f64,
-    oh: f64,
-    hco3: f64,
-    co3: f64,
-    params: PitzerParamsLite,
-) -> ([f64; 5], f64) {
-    let ionic_strength = 0.5 * (na + h + oh + hco3 + 4.0 * co3);
-    if ionic_strength <= 1e-30 {
-        return ([1.0_f64; 5], 0.0);
+/// Build the ionic-strength control from the Python arguments. A caller-fixed
+/// ionic strength must be a finite, positive molality; it replaces the
+/// self-consistent loop and takes precedence over `ionic_strength_cap`. The loop
+/// itself needs at least one pass and a finite, positive tolerance.
+fn ionic_strength_control_from_args(
+    fixed_ionic_strength: Option<f64>,
+    max_iterations: usize,
+    tolerance: f64,
+) -> PyResult<IonicStrengthControl> {
+    if let Some(value) = fixed_ionic_strength
+        && (!value.is_finite() || value <= 0.0)
+    {
+        return Err(PyValueError::new_err("fixed_ionic_strength must be finite and positive"));
     }
-    let sqrt_i = ionic_strength.sqrt();
-    let f = -PITZER_A_PHI_25C
-        * (sqrt_i / (1.0 + PITZER_B_DH * sqrt_i)
-            + (2.0 / PITZER_B_DH) * (1.0 + PITZER_B_DH * sqrt_i).ln());
-    let z_sum = na + h + oh + hco3 + 2.0 * co3;
-    let mut f_term = f;
-    let bprime_na_oh = pitzer_b_prime(params.b1_na_oh, ionic_strength);
-    let bprime_na_hco3 = pitzer_b_prime(params.b1_na_hco3, ionic_strength);
-    let bprime_na_co3 = pitzer_b_prime(params.b1_na_co3, ionic_strength);
-    f_term += na * oh * bprime_na_oh;
-    f_term += na * hco3 * bprime_na_hco3;
-    f_term += na * co3 * bprime_na_co3;
-    let mut ln_gamma = [
-        f_term,      // Na+ charge^2
-        f_term,      // H+ charge^2
-        f_term,      // OH- charge^2
-        f_term,      // HCO3- charge^2
-        4.0 * f_term, // CO3^2- charge^2
-    ];
-
-    let b_na_oh = pitzer_b(params.b0_na_oh, params.b1_na_oh, ionic_strength);
-    let b_na_hco3 = pitzer_b(params.b0_na_hco3, params.b1_na_hco3, ionic_strength);
-    let b_na_co3 = pitzer_b(params.b0_na_co3, params.b1_na_co3, ionic_strength);
-
-    ln_gamma[0] += oh * (2.0 * b_na_oh + z_sum * params.c0_na_oh);
-    ln_gamma[2] += na * (2.0 * b_na_oh + z_sum * params.c0_na_oh);
-    ln_gamma[0] += hco3 * (2.0 * b_na_hco3 + z_sum * params.c0_na_hco3);
-    ln_gamma[3] += na * (2.0 * b_na_hco3 + z_sum * params.c0_na_hco3);
-    ln_gamma[0] += co3 * (2.0 * b_na_co3 + z_sum * params.c0_na_co3);
-    ln_gamma[4] += na * (2.0 * b_na_co3 + z_sum * params.c0_na_co3);
-
-    ln_gamma[4] += oh * (2.0 * params.theta_co3_oh);
-    ln_gamma[2] += co3 * (2.0 * params.theta_co3_oh);
-
-    if na > 0.0 && co3 > 0.0 && oh > 0.0 {
-        ln_gamma[0] += co3 * oh * params.psi_co3_na_oh;
-        ln_gamma[4] += na * oh * params.psi_co3_na_oh;
-        ln_gamma[2] += na * co3 * params.psi_co3_na_oh;
+    if max_iterations == 0 {
+        return Err(PyValueError::new_err("ionic_strength_max_iterations must be at least 1"));
     }
-    if na > 0.0 && co3 > 0.0 && hco3 > 0.0 {
-        ln_gamma[0] += co3 * hco3 * params.psi_co3_hco3_na;
-        ln_gamma[4] += na * hco3 * params.psi_co3_hco3_na;
-        ln_gamma[3] += na * co3 * params.psi_co3_hco3_na;
+    if !tolerance.is_finite() || tolerance <= 0.0 {
+        return Err(PyValueError::new_err("ionic_strength_tolerance must be finite and positive"));
     }
+    Ok(IonicStrengthControl {
+        fixed: fixed_ionic_strength,
+        max_iterations,
+        tolerance,
+    })
+}
 
-    let mut gammas = [1.0_f64; 5];
-    for idx in 0..5 {
-        gammas[idx] = ln_gamma[idx].exp().max(1e-18);
+fn normalize_speciation_mode(mode: &str) -> &str {
+    let token = mode.trim().to_ascii_lowercase();
+    if token == SPEC_MODE_FIXED_PCO2 {
+        SPEC_MODE_FIXED_PCO2
+    } else {
+        SPEC_MODE_CLOSED
     }
-    (gammas, ionic_strength)
 }
 
-fn pitzer_find_root_bisect_on_log10<F>(
-    fun: F,
-    lo: f64,
-    hi: f64,
-    nscan: usize,
-) -> Result<f64, String>
+/// Accepted trust-region step from `x` for the full Newton increment `delta`, or
+/// `None` once `radius` falls below `SOL_TRUST_RADIUS_MIN` without an acceptable
+/// step. `(residual, jacobian)` is the linear model at `x` the predicted reduction
+/// comes from. The returned step is already clamped into `bounds`; `radius` is
+/// updated in place for the next iteration.
+fn trust_region_step<F>(
+    func: &F,
+    x: &[f64],
+    (residual, jacobian): (&[f64], &[Vec<f64>]),
+    delta: &[f64],
+    bounds: &[(f64, f64)],
+    control: NewtonTrustRegion,
+    radius: &mut f64,
+) -> Option<Vec<f64>>
 where
-    F: Fn(f64) -> f64,
+    F: Fn(&[f64]) -> Vec<f64>,
 {
-    let points = nscan.max(32);
-    let mut x_prev = lo;
-    let mut f_prev = fun(x_prev);
-    if f_prev.abs() < 1e-16 {
-        return Ok(x_prev);
-    }
-    for idx in 1..points {
-        let x_curr = lo + (hi - lo) * (idx as f64 / (points - 1) as f64);
-        let f_curr = fun(x_curr);
-        if f_curr.abs() < 1e-16 {
-            return Ok(x_curr);
-        }
-        if f_prev * f_curr < 0.0 {
-            let mut a = x_prev;
-            let mut b = x_curr;
-            let mut fa = f_prev;
-            for _ in 0..100 {
-                let mid = 0.5 * (a + b);
-                let f_mid = fun(mid);
-                if fa * f_mid <= 0.0 {
-                    b = mid;
-                } else {
-                    a = mid;
-                    fa = f_mid;
-                }
+    let sum_sq = |values: &[f64]| values.iter().map(|value| value * value).sum::<f64>();
+    let current = sum_sq(residual);
+    let full_length = delta.iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+    while *radius >= SOL_TRUST_RADIUS_MIN {
+        let scale = (*radius / full_length.max(1e-300)).min(1.0);
+        let step: Vec<f64> = x
+            .iter()
+            .zip(delta)
+            .zip(bounds)
+            .map(|((value, step), (low, high))| (value + scale * step).clamp(*low, *high) - value)
+            .collect();
+        let linearized: Vec<f64> = residual
+            .iter()
+            .zip(jacobian)
+            .map(|(value, row)| value + row.iter().zip(&step).map(|(j, s)| j * s).sum::<f64>())
+            .collect();
+        let predicted = current - sum_sq(&linearized);
+        let trial: Vec<f64> = x.iter().zip(&step).map(|(value, s)| value + s).collect();
+        let trial_residual = func(&trial);
+        let actual = if trial_residual.iter().all(|value| value.is_finite()) {
+            current - sum_sq(&trial_residual)
+        } else {
+            f64::NEG_INFINITY
+        };
+        let agreement = if predicted > 0.0 { actual / predicted } else { f64::NEG_INFINITY };
+        // A step the linear model expects no gain from is left to the caller's
+        // step-size convergence test rather than shrunk to nothing.
+        if agreement >= control.acceptance_ratio || (predicted <= 0.0 && actual >= 0.0) {
+            if agreement > SOL_TRUST_GROW_RATIO && scale < 1.0 {
+                *radius = (*radius * 2.0).min(SOL_TRUST_RADIUS_MAX);
             }
-            return Ok(0.5 * (a + b));
+            return Some(step);
         }
-        x_prev = x_curr;
-        f_prev = f_curr;
+        solver_trace!("trust region: rejected step (agreement {agreement:e}), radius {radius:e}");
+        *radius *= 0.25;
     }
-    Err("No sign change found while bracketing Pitzer root.".to_string())
+    None
 }
 
-/// Solve the focused NaOH-CO2 Pitzer equilibrium for charged carbon.
-///
-/// Purpose: returns pH and Na/H/OH/HCO3/CO3/CO2 molalities after resolving the
-/// immediately reactive aqueous-carbon inventory.
-/// Why: native planning calculations must match the finite gas/liquid endpoint
-/// transition used by the Python fallback instead of jumping to a hard carbon cap.
-/// Inputs: charged total carbon and sodium molalities, compact Pitzer parameters,
-/// and the maximum activity-coefficient iteration count.
-/// Output: `(pH, Na, H, OH, HCO3, CO3, CO2)` or an error message.
-/// Side effects: none.
-/// Errors: rejects non-positive sodium or an unbracketed charge-balance root.
-fn solve_pitzer_total_carbon_impl(
-    total_carbon_m: f64,
-    total_sodium_m: f64,
-    params: PitzerParamsLite,
-    max_iter: usize,
-) -> Result<(f64, f64, f64, f64, f64, f64, f64), String> {
-    if total_sodium_m <= 0.0 {
-        return Err("Total sodium molality must be positive.".to_string());
-    }
-    // Near bicarbonate capacity, only part of newly charged CO2 joins the
-    // immediately reactive liquid inventory; the remainder stays in finite-rate
-    // gas/liquid transport or headspace inventory.  This smooth asymptote avoids
-    // a nonphysical one-cycle jump into the dissolved-CO2 endpoint regime.
-    let transition = PITZER_REACTIVE_CARBON_TRANSITION_FRACTION * total_sodium_m;
-    let charged_ct = total_carbon_m.max(0.0);
-    let ct = if charged_ct <= transition {
-        charged_ct
-    } else {
-        let transition_span = (total_sodium_m - transition).max(1e-30);
-        let uptake_above_transition = charged_ct - transition;
-        transition
-            + transition_span * (1.0 - (-uptake_above_transition / transition_span).exp())
-    };
-    if ct <= 0.5 * total_sodium_m {
-        let m_oh = (total_sodium_m - 2.0 * ct).max(0.0);
-        let m_co3 = ct.max(0.0);
-        let (gammas, _ionic_strength) =
-            pitzer_gamma_set(total_sodium_m, 1e-16, m_oh, 0.0, m_co3, params);
-        let a_oh = if m_oh > 0.0 {
-            gammas[2] * m_oh
-        } else {
-            1e-30
-        };
-        let a_h = PITZER_KW / a_oh.max(1e-30);
-        let ph = -a_h.max(1e-30).log10();
-        let h = a_h / gammas[1].max(1e-30);
-        return Ok((ph, total_sodium_m, h, m_oh, 0.0, m_co3, 0.0));
-    }
-
-    let mut h = 1e-16_f64;
-    let mut oh = total_sodium_m.max(1e-12);
-    let mut hco3 = 0.0_f64;
-    let mut co3 = 0.0_f64;
-    let mut co2 = 0.0_f64;
-    let mut gammas = pitzer_gamma_set(total_sodium_m, h, oh, hco3, co3, params).0;
-    for _ in 0..max_iter.max(1) {
-        let root = pitzer_find_root_bisect_on_log10(
-            |log10_h| {
-                let m_h = 10f64.powf(log10_h);
-                let a_h = gammas[1] * m_h;
-                let m_oh = PITZER_KW / (a_h.max(1e-30) * gammas[2].max(1e-30));
-                let r1 = PITZER_KA1 / (gammas[1] * gammas[3] * m_h.max(1e-30));
-                let r23 = PITZER_KA2 * gammas[3] / (gammas[1] * gammas[4] * m_h.max(1e-30));
-                let r2 = r1 * r23;
-                let m_co2 = ct / (1.0 + r1 + r2);
-                let m_hco3 = r1 * m_co2;
-                let m_co3 = r2 * m_co2;
-                (total_sodium_m + m_h) - (m_oh + m_hco3 + 2.0 * m_co3)
-            },
-            -18.0,
-            0.0,
-            260,
-        )?;
-        h = 10f64.powf(root);
-        let a_h = gammas[1] * h;
-        oh = PITZER_KW / (a_h.max(1e-30) * gammas[2].max(1e-30));
-        let r1 = PITZER_KA1 / (gammas[1] * gammas[3] * h.max(1e-30));
-        let r23 = PITZER_KA2 * gammas[3] / (gammas[1] * gammas[4] * h.max(1e-30));
-        let r2 = r1 * r23;
-        co2 = ct / (1.0 + r1 + r2);
-        hco3 = r1 * co2;
-        co3 = r2 * co2;
-
-        let next_gammas = pitzer_gamma_set(total_sodium_m, h, oh, hco3, co3, params).0;
-        let mut max_delta = 0.0_f64;
-        for idx in 0..5 {
-            let delta = (next_gammas[idx] / gammas[idx].max(1e-30)).ln().abs();
-            if delta > max_delta {
-                max_delta = delta;
-            }
-        }
-        gammas = next_gammas;
-        if max_delta < 1e-6 {
-            break;
-        }
+/// `newton_system` keeps the multivariate mass-action/balance system;
+/// `proton_condition` collapses the closed system to one bracketed solve in [H+].
+fn parse_solver_formulation(token: &str) -> PyResult<&'static str> {
+    match token.trim().to_ascii_lowercase().as_str() {
+        FORMULATION_NEWTON_SYSTEM => Ok(FORMULATION_NEWTON_SYSTEM),
+        FORMULATION_PROTON_CONDITION => Ok(FORMULATION_PROTON_CONDITION),
+        other => Err(PyValueError::new_err(format!(
+            "Unknown formulation '{other}'; expected one of: {FORMULATION_NEWTON_SYSTEM}, \
+             {FORMULATION_PROTON_CONDITION}"
+        ))),
     }
-
-    let ph = -(gammas[1] * h).max(1e-30).log10();
-    Ok((ph, total_sodium_m, h, oh, hco3, co3, co2))
 }
 
-fn estimate_ledger_ph(
-    state: LedgerState,
-    pka2_value: f64,
-    solution_volume_l: Option<f64>,
-    temperature_c: Option<f64>,
-    ionic_strength_cap: Option<f64>,
-    use_temp_adjusted_constants: bool,
-    constants: Option<(f64, f64, f64)>,
-    initial_ph_guess: Option<f64>,
-) -> f64 {
-    let ratio = (state.na2co3_mol / state.nahco3_mol.max(1e-12)).max(1e-12);
-    let fallback_ph = clamp_ph_value(pka2_value + ratio.log10());
-    let volume = solution_volume_l.unwrap_or(0.0);
-    if volume <= 0.0 {
-        return fallback_ph;
-    }
-    let total_na = state.naoh_remaining_mol.max(0.0)
-        + state.nahco3_mol.max(0.0)
-        + 2.0 * state.na2co3_mol.max(0.0);
-    let total_carbon =
-        state.nahco3_mol.max(0.0) + state.na2co3_mol.max(0.0) + state.co2_excess_mol.max(0.0);
-    let total_na_conc = total_na / volume.max(1e-9);
-    let total_carbon_conc = total_carbon / volume.max(1e-9);
-    if total_na_conc <= 0.0 && total_carbon_conc <= 0.0 {
-        return fallback_ph;
-    }
-    let (ka1, ka2, kw) = constants
-        .unwrap_or_else(|| basic_carbonate_constants(temperature_c, use_temp_adjusted_constants));
-    let pkw = -kw.max(1e-30).log10();
-    let guess = initial_ph_guess.unwrap_or(fallback_ph);
-    let residual_naoh_ph_floor = if state.naoh_remaining_mol > 0.0 {
-        // Residual NaOH supplies one mole of free OH- per mole NaOH; this strong-base
-        // floor prevents carbonate charge balance from reporting acidic raw pH.
-        Some(clamp_ph_value(
-            pkw + (state.naoh_remaining_mol.max(0.0) / volume.max(1e-9)).max(1e-16).log10(),
-        ))
-    } else {
-        None
-    };
-    if total_carbon_conc <= 1e-12 {
-        if total_na_conc <= 0.0 {
-            return clamp_ph_value(pkw / 2.0);
-        }
-        let ph = pkw + total_na_conc.max(1e-16).log10();
-        return clamp_ph_value(match residual_naoh_ph_floor {
-            Some(floor) => ph.max(floor),
-            None => ph,
-        });
-    }
-    match solve_carbonate_state(
-        total_carbon_conc,
-        total_na_conc,
+/// Fixed-H2CO3 (open, fixed-pCO2) speciation by Newton iteration; total carbon and
+/// the co-buffers of `system` play no part, and neither do the extra seeds or the
+/// retries of `newton`.
+fn solve_carbonate_state_open(
+    system: CarbonateSystem,
+    activity: ActivityTreatment,
+    fixed_h2co3: f64,
+    newton: NewtonOptions<'_>,
+) -> Result<(CarbonateSolution, Vec<f64>), String> {
+    let CarbonateSystem { na_conc, ka1, ka2, kw, .. } = system;
+    let NewtonOptions {
+        initial_ph_guess,
+        observer,
+        trust_region,
+        ..
+    } = newton;
+    let na_conc = na_conc.max(0.0);
+    let fixed_h2co3 = fixed_h2co3.max(1e-16);
+    let residuals = open_carbonate_residuals(
+        na_conc,
         ka1,
         ka2,
         kw,
-        ionic_strength_cap,
-        guess,
-    ) {
-        Ok((h, _, _, _, _, _, _)) => {
-            let mut ph = clamp_ph_value(-h.max(1e-30).log10());
-            if let Some(floor) = residual_naoh_ph_floor {
-                ph = clamp_ph_value(ph.max(floor));
-            }
-            if ph < 6.0 && (state.nahco3_mol > 0.0 || state.co2_excess_mol > 0.0) {
-                let mut buffer_hint = fallback_ph.max(pka2_value - 2.2).max(8.0);
-                if let Some(floor) = residual_naoh_ph_floor {
-                    buffer_hint = buffer_hint.max(floor);
-                }
-                clamp_ph_value(buffer_hint)
-            } else {
-                ph
-            }
-        }
-        Err(_) => {
-            if state.nahco3_mol > 0.0 || state.co2_excess_mol > 0.0 {
-                let mut buffer_hint = fallback_ph.max(pka2_value - 2.2).max(8.0);
-                if let Some(floor) = residual_naoh_ph_floor {
-                    buffer_hint = buffer_hint.max(floor);
-                }
-                clamp_ph_value(buffer_hint)
-            } else if let Some(floor) = residual_naoh_ph_floor {
-                floor
-            } else {
-                fallback_ph
+        activity,
+        fixed_h2co3,
+    );
+    let log_bounds = open_carbonate_log_bounds(na_conc, ka1, kw, fixed_h2co3);
+    let guess_ph_values = [initial_ph_guess, 8.2, 7.8, 9.0];
+    for ph_guess in guess_ph_values {
+        let h = 10f64.powf(-ph_guess);
+        let hco3_guess = ((ka1 * fixed_h2co3) / h.max(1e-16)).max(1e-16);
+        let co3_guess = ((ka2 * hco3_guess) / h.max(1e-16)).max(1e-16);
+        let guess = vec![h.log10(), hco3_guess.log10(), co3_guess.log10()];
+        if let Ok((sol, residual_norm)) = newton_system_solve(
+            &residuals,
+            guess,
+            &log_bounds,
+            SOL_NEWTON_STRICT_TOL,
+            60,
+            observer,
+            trust_region,
+        ) {
+            let pinned = pinned_log_components(&sol, &log_bounds);
+            if !pinned.is_empty() {
+                solver_debug!(
+                    "fixed-pCO2 seed pH {ph_guess} converged with unknowns {pinned:?} pinned \
+                     at their clamp bounds; rejected"
+                );
+                continue;
             }
+            let h = 10f64.powf(sol[0]);
+            let hco3 = 10f64.powf(sol[1]);
+            let co3 = 10f64.powf(sol[2]);
+            let (ionic_strength, gammas, oh, _) = solubility_ionic_state(
+                na_conc,
+                h,
+                hco3,
+                co3,
+                kw,
+                activity,
+            );
+            let solution =
+                (h, hco3, co3, fixed_h2co3, oh, gammas, ionic_strength, residual_norm);
+            let mut log_species = sol;
+            log_species.push(fixed_h2co3.log10());
+            return Ok((solution, log_species));
         }
     }
+    Err("Fixed-pCO2 equilibrium solver did not converge".to_string())
 }
 
-fn estimate_ledger_ph_planning(
-    state: LedgerState,
-    pka2_value: f64,
-    solution_volume_l: Option<f64>,
-    temperature_c: Option<f64>,
-    ionic_strength_cap: Option<f64>,
-    use_temp_adjusted_constants: bool,
-    constants: Option<(f64, f64, f64)>,
-    initial_ph_guess: Option<f64>,
-) -> f64 {
-    let co3 = state.na2co3_mol.max(0.0);
-    let hco3 = state.nahco3_mol.max(0.0);
-    let excess = state.co2_excess_mol.max(0.0);
-    let carbonate_only_equivalence =
-        state.naoh_remaining_mol <= 1e-12 && co3 > 0.0 && hco3 <= 1e-12;
-    let carbon_pool = co3 + hco3;
-    let carbonate_depleted = co3 <= PLANNING_PLATEAU_CARBONATE_THRESHOLD
-        || (carbon_pool > 0.0
-            && co3 / carbon_pool.max(1e-12) <= PLANNING_PLATEAU_RELATIVE_THRESHOLD);
-    let mut ph_estimate = estimate_ledger_ph(
-        LedgerState {
-            co2_excess_mol: excess,
-            ..state
-        },
-        pka2_value,
-        solution_volume_l,
-        temperature_c,
-        ionic_strength_cap,
-        use_temp_adjusted_constants,
-        constants,
-        initial_ph_guess,
-    );
-    if carbonate_only_equivalence && ph_estimate.is_finite() {
-        let anchor = if pka2_value.is_finite() {
-            pka2_value
-        } else {
-            10.33
-        };
-        ph_estimate = clamp_ph_value(ph_estimate.max(anchor - 0.35).min(anchor + 0.35));
-    }
-    if carbonate_depleted && (hco3 > 0.0 || excess > 0.0) && ph_estimate.is_finite() {
-        ph_estimate = ph_estimate
-            .max(PLANNING_PLATEAU_PH_MIN)
-            .min(PLANNING_PLATEAU_PH_MAX);
+fn solve_carbonate_state_with_mode(
+    system: CarbonateSystem,
+    activity: ActivityTreatment,
+    speciation_mode: &str,
+    fixed_h2co3: Option<f64>,
+    newton: NewtonOptions<'_>,
+) -> Result<(CarbonateSolution, Option<Vec<f64>>), String> {
+    let mode = normalize_speciation_mode(speciation_mode);
+    if mode == SPEC_MODE_FIXED_PCO2 {
+        if system.ammonia.is_some() {
+            return Err("Ammonia buffering is only modelled in closed_carbon mode".to_string());
+        }
+        if system.phosphate.is_some() {
+            return Err("Phosphate buffering is only modelled in closed_carbon mode".to_string());
+        }
+        return solve_carbonate_state_open(system, activity, fixed_h2co3.unwrap_or(0.0), newton)
+            .map(|(solution, log_species)| (solution, Some(log_species)));
     }
-    ph_estimate
+    solve_carbonate_state_traced(system, activity, newton)
 }
 
-fn simulate_reaction_state_with_accounting_impl(
-    ledger: LedgerState,
-    delta_mol: f64,
-    pka2_value: f64,
-    solution_volume_l: Option<f64>,
-    temperature_c: Option<f64>,
-    ionic_strength_cap: Option<f64>,
-    use_temp_adjusted_constants: bool,
-    initial_ph_guess: Option<f64>,
-    constants: Option<(f64, f64, f64)>,
-    planning_mode: bool,
-) -> (LedgerState, AccountingState, f64) {
-    let mut extra = delta_mol.max(0.0);
-    let mut naoh_free = ledger.naoh_remaining_mol.max(0.0);
-    let mut co3 = ledger.na2co3_mol.max(0.0);
-    let mut hco3 = ledger.nahco3_mol.max(0.0);
-    let mut excess = ledger.co2_excess_mol.max(0.0);
-    let mut consumed_to_carbonate = 0.0;
-    let mut consumed_to_bicarbonate = 0.0;
-    if naoh_free > 0.0 {
-        let needed = naoh_free / 2.0;
-        let consume = extra.min(needed);
-        consumed_to_carbonate = consume;
-        naoh_free = (naoh_free - consume * 2.0).max(0.0);
-        co3 += consume;
-        extra -= consume;
+fn forced_ph_distribution_impl(
+    mut total_carbon_m: f64,
+    na_conc: f64,
+    forced_ph: f64,
+    ka1: f64,
+    ka2: f64,
+    kw: f64,
+    activity: ActivityTreatment,
+    fixed_h2co3: Option<f64>,
+    max_iter: usize,
+) -> Result<(f64, f64, f64, f64, f64, f64, f64, [f64; 5], f64), String> {
+    if !(0.0..14.5).contains(&forced_ph) {
+        return Err("Forced pH must be between 0 and 14.5".to_string());
     }
-    if co3 > 0.0 && extra > 0.0 {
-        let convert = extra.min(co3);
-        consumed_to_bicarbonate = convert;
-        co3 -= convert;
-        hco3 += convert * 2.0;
-        extra -= convert;
-    }
-    excess += extra;
-    let state = LedgerState {
-        naoh_remaining_mol: naoh_free,
-        na2co3_mol: co3,
-        nahco3_mol: hco3,
-        co2_excess_mol: excess,
-    };
-    let ratio_hint = co3 / hco3.max(1e-12);
-    let guess = Some(initial_ph_guess.unwrap_or(pka2_value + ratio_hint.max(1e-12).log10()));
-    let ph = if planning_mode {
-        estimate_ledger_ph_planning(
-            state,
-            pka2_value,
-            solution_volume_l,
-            temperature_c,
-            ionic_strength_cap,
-            use_temp_adjusted_constants,
-            constants,
-            guess,
-        )
-    } else {
-        estimate_ledger_ph(
-            state,
-            pka2_value,
-            solution_volume_l,
-            temperature_c,
-            ionic_strength_cap,
-            use_temp_adjusted_constants,
-            constants,
-            guess,
-        )
-    };
-    let accounting = AccountingState {
-        co2_consumed_to_carbonate_mol: consumed_to_carbonate,
-        co2_consumed_to_bicarbonate_mol: consumed_to_bicarbonate,
-        co2_consumed_total_mol: consumed_to_carbonate + consumed_to_bicarbonate,
-        co2_unconsumed_mol: extra.max(0.0),
-    };
-    (state, accounting, ph)
-}
-
-fn dict_float_value(dict: &Bound<'_, PyDict>, key: &str) -> f64 {
-    dict.get_item(key)
-        .ok()
-        .flatten()
-        .and_then(|value| value.extract::<f64>().ok())
-        .unwrap_or(0.0)
-}
-
-fn dict_optional_float_value(dict: &Bound<'_, PyDict>, key: &str) -> Option<f64> {
-    let value = dict.get_item(key).ok().flatten()?;
-    let parsed = value.extract::<f64>().ok()?;
-    if parsed.is_finite() {
-        Some(parsed)
-    } else {
-        None
+    total_carbon_m = total_carbon_m.max(1e-16);
+    let na_conc = na_conc.max(0.0);
+    let fixed_h2co3_value = fixed_h2co3.map(|value| value.max(1e-16));
+    let h = 10f64.powf(-forced_ph);
+    let mut hco3 = (total_carbon_m * 0.9).max(1e-16);
+    let mut co3 = (total_carbon_m * 0.05).max(1e-16);
+    let mut h2co3 = (total_carbon_m - hco3 - co3).max(0.0);
+    let mut charge_residual = 0.0_f64;
+    let mut gammas = [1.0_f64; 5];
+    let mut ionic_strength = 0.0_f64;
+    let mut oh = 1e-7_f64;
+    for _ in 0..max_iter.max(1) {
+        let (next_i, next_gammas, next_oh, _) = solubility_ionic_state(
+            na_conc,
+            h,
+            hco3,
+            co3,
+            kw,
+            activity,
+        );
+        ionic_strength = next_i;
+        gammas = next_gammas;
+        oh = next_oh;
+        let coeff_co3 = (ka2 * gammas[2]) / (gammas[1] * gammas[3] * h.max(1e-18));
+        let coeff_h2co3 = (gammas[1] * gammas[2] * h) / ka1.max(1e-30);
+        let charge_basis = (na_conc + h - oh).max(1e-16);
+        hco3 = (charge_basis / (1.0 + (2.0 * coeff_co3)).max(1e-12)).max(1e-16);
+        co3 = (coeff_co3 * hco3).max(1e-16);
+        h2co3 = (coeff_h2co3 * hco3)
+            .max(fixed_h2co3_value.unwrap_or(0.0))
+            .max(1e-16);
+        charge_residual = na_conc + h - hco3 - 2.0 * co3 - oh;
+        let next_total = (h2co3 + hco3 + co3).max(1e-16);
+        let delta_ct = ((next_total - total_carbon_m) / total_carbon_m.max(1e-12)).abs();
+        total_carbon_m = next_total;
+        if delta_ct < 1e-8 && charge_residual.abs() < 1e-8 {
+            break;
+        }
     }
+    let (final_i, final_gammas, final_oh, _) = solubility_ionic_state(
+        na_conc,
+        h,
+        hco3,
+        co3,
+        kw,
+        activity,
+    );
+    let coeff_co3 =
+        (ka2 * final_gammas[2]) / (final_gammas[1] * final_gammas[3] * h.max(1e-18));
+    let coeff_h2co3 = (final_gammas[1] * final_gammas[2] * h) / ka1.max(1e-30);
+    let charge_basis = (na_conc + h - final_oh).max(1e-16);
+    hco3 = (charge_basis / (1.0 + (2.0 * coeff_co3)).max(1e-12)).max(1e-16);
+    co3 = (coeff_co3 * hco3).max(1e-16);
+    h2co3 = (coeff_h2co3 * hco3)
+        .max(fixed_h2co3_value.unwrap_or(0.0))
+        .max(1e-16);
+    total_carbon_m = (h2co3 + hco3 + co3).max(1e-16);
+    charge_residual = na_conc + h - hco3 - 2.0 * co3 - final_oh;
+    let (final_i, final_gammas, final_oh, _) = solubility_ionic_state(
+        na_conc,
+        h,
+        hco3,
+        co3,
+        kw,
+        activity,
+    );
+    charge_residual = na_conc + h - hco3 - 2.0 * co3 - final_oh;
+    Ok((
+        total_carbon_m,
+        h,
+        hco3,
+        co3,
+        h2co3,
+        final_oh,
+        charge_residual,
+        final_gammas,
+        final_i,
+    ))
 }
 
-/// Return a truthy dictionary value or an empty Python string as an owned object.
-///
-/// This keeps report-table schema parity with the Python fallback while avoiding
-/// deprecated PyO3 conversion APIs that were removed from the current toolchain.
-fn dict_truthy_or_empty_pyobject(
-    py: Python<'_>,
-    dict: &Bound<'_, PyDict>,
-    key: &str,
-) -> Py<PyAny> {
-    let Some(value) = dict.get_item(key).ok().flatten() else {
-        return PyString::new(py, "").into_any().unbind();
-    };
-    if value.is_truthy().unwrap_or(false) {
-        value.unbind()
-    } else {
-        PyString::new(py, "").into_any().unbind()
+fn aqion_alpha_fractions(h_conc: f64, ka1: f64, ka2: f64) -> (f64, f64, f64) {
+    let mut denom = (h_conc * h_conc) + (ka1 * h_conc) + (ka1 * ka2);
+    if denom <= 0.0 || !denom.is_finite() {
+        denom = 1e-30;
     }
+    let a0 = (h_conc * h_conc) / denom;
+    let a1 = (ka1 * h_conc) / denom;
+    let a2 = (ka1 * ka2) / denom;
+    (a0, a1, a2)
 }
 
-fn format_optional_decimal(value: Option<f64>, precision: usize) -> String {
-    if let Some(parsed) = value {
-        format!("{parsed:.precision$}")
-    } else {
-        String::new()
-    }
+fn aqion_species_from_ph(
+    ct: f64,
+    ph: f64,
+    ka1: f64,
+    ka2: f64,
+    kw: f64,
+) -> (f64, f64, f64, f64, f64, f64, f64, f64, f64) {
+    let h = 10f64.powf(-ph);
+    let (a0, a1, a2) = aqion_alpha_fractions(h, ka1, ka2);
+    let h2co3 = ct * a0;
+    let hco3 = ct * a1;
+    let co3 = ct * a2;
+    let oh = kw / h.max(1e-30);
+    let ionic_strength = 0.5 * (h + hco3 + 4.0 * co3 + oh);
+    (h, h2co3, hco3, co3, oh, a0, a1, a2, ionic_strength)
 }
 
-/// Convert a Python object into a trimmed owned Rust string.
-///
-/// The helper materializes an owned `String` before trimming so no borrowed
-/// Python string data escapes temporary PyO3 bindings.
-fn py_any_to_trimmed_string(value: &Bound<'_, PyAny>) -> String {
-    if let Ok(text) = value.extract::<String>() {
-        return text.trim().to_string();
-    }
-    value
-        .str()
-        .ok()
-        .and_then(|text| text.extract::<String>().ok())
-        .map(|text| text.trim().to_string())
-        .unwrap_or_default()
+fn aqion_charge_balance_residual(ct: f64, ph: f64, ka1: f64, ka2: f64, kw: f64) -> f64 {
+    let (h, _h2co3, hco3, co3, oh, _a0, _a1, _a2, _ionic_strength) =
+        aqion_species_from_ph(ct, ph, ka1, ka2, kw);
+    h - hco3 - 2.0 * co3 - oh
 }
 
-fn dict_string_value(dict: &Bound<'_, PyDict>, key: &str) -> String {
-    let Some(value) = dict.get_item(key).ok().flatten() else {
-        return String::new();
+fn solve_aqion_closed_speciation(
+    total_inorganic_carbon_m: f64,
+    ka1: f64,
+    ka2: f64,
+    kw: f64,
+    ph_low: f64,
+    ph_high: f64,
+) -> Result<(f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, String), String> {
+    let ct = total_inorganic_carbon_m.max(0.0);
+    let low = ph_low.min(ph_high);
+    let high = ph_low.max(ph_high);
+    let mut f_low = aqion_charge_balance_residual(ct, low, ka1, ka2, kw);
+    let mut f_high = aqion_charge_balance_residual(ct, high, ka1, ka2, kw);
+    let mut solver = "bisection-charge-balance".to_string();
+    let ph_root = if f_low.abs() < 1e-14 {
+        solver = "bracket-bound".to_string();
+        low
+    } else if f_high.abs() < 1e-14 {
+        solver = "bracket-bound".to_string();
+        high
+    } else if f_low * f_high > 0.0 {
+        // Fall back to a dense residual scan when the bracket does not straddle.
+        solver = "scan-min-residual".to_string();
+        let mut best_ph = low;
+        let mut best_residual = f_low.abs();
+        let scan_steps = 480usize;
+        for idx in 0..=scan_steps {
+            let ph = low + (high - low) * (idx as f64 / scan_steps as f64);
+            let residual = aqion_charge_balance_residual(ct, ph, ka1, ka2, kw).abs();
+            if residual < best_residual {
+                best_residual = residual;
+                best_ph = ph;
+            }
+        }
+        best_ph
+    } else {
+        let mut lo = low;
+        let mut hi = high;
+        let mut mid = 0.5 * (lo + hi);
+        for _ in 0..220 {
+            mid = 0.5 * (lo + hi);
+            let f_mid = aqion_charge_balance_residual(ct, mid, ka1, ka2, kw);
+            if f_mid.abs() < 1e-14 || (hi - lo).abs() < 1e-6 {
+                break;
+            }
+            if f_low * f_mid <= 0.0 {
+                hi = mid;
+                f_high = f_mid;
+            } else {
+                lo = mid;
+                f_low = f_mid;
+            }
+        }
+        mid
     };
-    py_any_to_trimmed_string(&value)
+    let (h, h2co3, hco3, co3, oh, a0, a1, a2, ionic_strength) =
+        aqion_species_from_ph(ct, ph_root, ka1, ka2, kw);
+    let residual = aqion_charge_balance_residual(ct, ph_root, ka1, ka2, kw);
+    Ok((
+        ph_root,
+        h,
+        oh,
+        h2co3,
+        hco3,
+        co3,
+        a0,
+        a1,
+        a2,
+        ionic_strength,
+        residual,
+        solver,
+    ))
 }
 
-/// Resolve the optional custom-values map attached to a ledger row.
-///
-/// The explicit lifetime keeps the returned dictionary bound to the same Python
-/// interpreter scope as the source row, which PyO3 0.27 requires.
-fn dict_custom_map<'py>(dict: &Bound<'py, PyDict>) -> Option<Bound<'py, PyDict>> {
-    let custom_any = dict.get_item("custom_values").ok().flatten()?;
-    custom_any.cast_into::<PyDict>().ok()
+#[derive(Clone, Copy)]
+struct PitzerParamsLite {
+    b0_na_oh: f64,
+    b1_na_oh: f64,
+    c0_na_oh: f64,
+    b0_na_hco3: f64,
+    b1_na_hco3: f64,
+    c0_na_hco3: f64,
+    b0_na_co3: f64,
+    b1_na_co3: f64,
+    c0_na_co3: f64,
+    theta_co3_oh: f64,
+    psi_co3_na_oh: f64,
+    psi_co3_hco3_na: f64,
 }
 
-fn dict_custom_optional_float_value(dict: &Bound<'_, PyDict>, key: &str) -> Option<f64> {
-    let custom_map = dict_custom_map(dict)?;
-    let value = custom_map.get_item(key).ok().flatten()?;
-    let parsed = value.extract::<f64>().ok()?;
-    if parsed.is_finite() {
-        Some(parsed)
-    } else {
-        None
+fn pitzer_g(x: f64) -> f64 {
+    if x.abs() <= 1e-18 {
+        return 0.0;
     }
+    2.0 * (1.0 - (1.0 + x) * (-x).exp()) / (x * x)
 }
 
-fn dict_custom_string_value(dict: &Bound<'_, PyDict>, key: &str) -> String {
-    let Some(custom_map) = dict_custom_map(dict) else {
-        return String::new();
-    };
-    let Some(value) = custom_map.get_item(key).ok().flatten() else {
-        return String::new();
-    };
-    py_any_to_trimmed_string(&value)
+fn pitzer_g_prime(x: f64) -> f64 {
+    let h = 1e-6;
+    (pitzer_g(x + h) - pitzer_g(x - h)) / (2.0 * h)
 }
 
-fn is_ledger_builtin_numeric_key(key: &str) -> bool {
-    matches!(
-        key,
-        "final_mass_g"
-            | "final_pH"
-            | "cycles"
-            | "total_dp_uptake"
-            | "theoretical_yield_g"
-            | "actual_yield_pct"
-    )
+fn pitzer_b(beta0: f64, beta1: f64, ionic_strength: f64) -> f64 {
+    beta0 + beta1 * pitzer_g(PITZER_ALPHA_B1 * ionic_strength.max(0.0).sqrt())
 }
 
-fn is_ledger_builtin_date_key(key: &str) -> bool {
-    matches!(key, "run_date" | "updated_at")
+fn pitzer_b_prime(beta1: f64, ionic_strength: f64) -> f64 {
+    if ionic_strength <= 0.0 {
+        return 0.0;
+    }
+    let x = PITZER_ALPHA_B1 * ionic_strength.sqrt();
+    beta1 * pitzer_g_prime(x) * PITZER_ALPHA_B1 / (2.0 * ionic_strength.sqrt())
 }
 
-fn ledger_sort_numeric_value(dict: &Bound<'_, PyDict>, sort_key: &str) -> Option<f64> {
-    if is_ledger_builtin_numeric_key(sort_key) {
-        return dict_optional_float_value(dict, sort_key);
+fn pitzer_gamma_set(
+    na: f64,
+    h: f64,
+    oh: f64,
+    hco3: f64,
+    co3: f64,
+    params: PitzerParamsLite,
+) -> ([f64; 5], f64) {
+    let ionic_strength = 0.5 * (na + h + oh + hco3 + 4.0 * co3);
+    if ionic_strength <= 1e-30 {
+        return ([1.0_f64; 5], 0.0);
     }
-    dict_custom_optional_float_value(dict, sort_key)
-}
+    let sqrt_i = ionic_strength.sqrt();
+    let f = -PITZER_A_PHI_25C
+        * (sqrt_i / (1.0 + PITZER_B_DH * sqrt_i)
+            + (2.0 / PITZER_B_DH) * (1.0 + PITZER_B_DH * sqrt_i).ln());
+    let z_sum = na + h + oh + hco3 + 2.0 * co3;
+    let mut f_term = f;
+    let bprime_na_oh = pitzer_b_prime(params.b1_na_oh, ionic_strength);
+    let bprime_na_hco3 = pitzer_b_prime(params.b1_na_hco3, ionic_strength);
+    let bprime_na_co3 = pitzer_b_prime(params.b1_na_co3, ionic_strength);
+    f_term += na * oh * bprime_na_oh;
+    f_term += na * hco3 * bprime_na_hco3;
+    f_term += na * co3 * bprime_na_co3;
+    let mut ln_gamma = [
+        f_term,      // Na+ charge^2
+        f_term,      // H+ charge^2
+        f_term,      // OH- charge^2
+        f_term,      // HCO3- charge^2
+        4.0 * f_term, // CO3^2- charge^2
+    ];
 
-fn ledger_sort_text_value(dict: &Bound<'_, PyDict>, sort_key: &str) -> String {
-    if is_ledger_builtin_date_key(sort_key)
-        || is_ledger_builtin_numeric_key(sort_key)
-        || matches!(
-            sort_key,
-            "profile_name" | "project_number" | "batch_number" | "item_number" | "notes" | "id"
-        )
-    {
-        return dict_string_value(dict, sort_key);
+    let b_na_oh = pitzer_b(params.b0_na_oh, params.b1_na_oh, ionic_strength);
+    let b_na_hco3 = pitzer_b(params.b0_na_hco3, params.b1_na_hco3, ionic_strength);
+    let b_na_co3 = pitzer_b(params.b0_na_co3, params.b1_na_co3, ionic_strength);
+
+    ln_gamma[0] += oh * (2.0 * b_na_oh + z_sum * params.c0_na_oh);
+    ln_gamma[2] += na * (2.0 * b_na_oh + z_sum * params.c0_na_oh);
+    ln_gamma[0] += hco3 * (2.0 * b_na_hco3 + z_sum * params.c0_na_hco3);
+    ln_gamma[3] += na * (2.0 * b_na_hco3 + z_sum * params.c0_na_hco3);
+    ln_gamma[0] += co3 * (2.0 * b_na_co3 + z_sum * params.c0_na_co3);
+    ln_gamma[4] += na * (2.0 * b_na_co3 + z_sum * params.c0_na_co3);
+
+    ln_gamma[4] += oh * (2.0 * params.theta_co3_oh);
+    ln_gamma[2] += co3 * (2.0 * params.theta_co3_oh);
+
+    if na > 0.0 && co3 > 0.0 && oh > 0.0 {
+        ln_gamma[0] += co3 * oh * params.psi_co3_na_oh;
+        ln_gamma[4] += na * oh * params.psi_co3_na_oh;
+        ln_gamma[2] += na * co3 * params.psi_co3_na_oh;
     }
-    let custom_value = dict_custom_string_value(dict, sort_key);
-    if !custom_value.is_empty() {
-        return custom_value;
+    if na > 0.0 && co3 > 0.0 && hco3 > 0.0 {
+        ln_gamma[0] += co3 * hco3 * params.psi_co3_hco3_na;
+        ln_gamma[4] += na * hco3 * params.psi_co3_hco3_na;
+        ln_gamma[3] += na * co3 * params.psi_co3_hco3_na;
     }
-    dict_string_value(dict, sort_key)
-}
 
-fn parse_int_prefix(raw: &str) -> Option<i64> {
-    let digits: String = raw.chars().take_while(|ch| ch.is_ascii_digit()).collect();
-    if digits.is_empty() {
-        return None;
+    let mut gammas = [1.0_f64; 5];
+    for idx in 0..5 {
+        gammas[idx] = ln_gamma[idx].exp().max(1e-18);
     }
-    digits.parse::<i64>().ok()
+    (gammas, ionic_strength)
 }
 
-fn parse_iso_datetime_sort_token(text: &str) -> Option<i64> {
-    let trimmed = text.trim();
-    let bytes = trimmed.as_bytes();
-    if bytes.len() < 10 {
-        return None;
-    }
-    if bytes.get(4).copied() != Some(b'-') || bytes.get(7).copied() != Some(b'-') {
-        return None;
-    }
-    let date_part = std::str::from_utf8(&bytes[0..10]).ok()?;
-    let mut date_tokens = date_part.split('-');
-    let year = date_tokens.next()?.parse::<i64>().ok()?;
-    let month = date_tokens.next()?.parse::<i64>().ok()?;
-    let day = date_tokens.next()?.parse::<i64>().ok()?;
-    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
-        return None;
+fn pitzer_find_root_bisect_on_log10<F>(
+    fun: F,
+    lo: f64,
+    hi: f64,
+    nscan: usize,
+) -> Result<f64, String>
+where
+    F: Fn(f64) -> f64,
+{
+    let points = nscan.max(32);
+    let mut x_prev = lo;
+    let mut f_prev = fun(x_prev);
+    if f_prev.abs() < 1e-16 {
+        return Ok(x_prev);
     }
-    let mut hour = 0_i64;
-    let mut minute = 0_i64;
-    let mut second = 0_i64;
-    if bytes.len() > 10 {
-        let separator = bytes.get(10).copied()?;
-        if !matches!(separator, b'T' | b't' | b' ') {
-            return None;
-        }
-        let time_part = std::str::from_utf8(bytes.get(11..).unwrap_or_default()).ok()?;
-        let mut time_tokens = time_part.split(':');
-        if let Some(raw_hour) = time_tokens.next() {
-            hour = parse_int_prefix(raw_hour).unwrap_or(0);
-        }
-        if let Some(raw_minute) = time_tokens.next() {
-            minute = parse_int_prefix(raw_minute).unwrap_or(0);
+    for idx in 1..points {
+        let x_curr = lo + (hi - lo) * (idx as f64 / (points - 1) as f64);
+        let f_curr = fun(x_curr);
+        if f_curr.abs() < 1e-16 {
+            return Ok(x_curr);
         }
-        if let Some(raw_second) = time_tokens.next() {
-            second = parse_int_prefix(raw_second).unwrap_or(0);
+        if f_prev * f_curr < 0.0 {
+            let mut a = x_prev;
+            let mut b = x_curr;
+            let mut fa = f_prev;
+            for _ in 0..100 {
+                let mid = 0.5 * (a + b);
+                let f_mid = fun(mid);
+                if fa * f_mid <= 0.0 {
+                    b = mid;
+                } else {
+                    a = mid;
+                    fa = f_mid;
+                }
+            }
+            return Ok(0.5 * (a + b));
         }
-        hour = hour.clamp(0, 23);
-        minute = minute.clamp(0, 59);
-        second = second.clamp(0, 59);
+        x_prev = x_curr;
+        f_prev = f_curr;
     }
-    Some((((((year * 100) + month) * 100 + day) * 100 + hour) * 100 + minute) * 100 + second)
+    Err("No sign change found while bracketing Pitzer root.".to_string())
 }
 
-fn ledger_sort_date_value(dict: &Bound<'_, PyDict>, sort_key: &str) -> Option<i64> {
-    let raw_value = if is_ledger_builtin_date_key(sort_key) {
-        dict_string_value(dict, sort_key)
+/// Solve the focused NaOH-CO2 Pitzer equilibrium for charged carbon.
+///
+/// Purpose: returns pH and Na/H/OH/HCO3/CO3/CO2 molalities after resolving the
+/// immediately reactive aqueous-carbon inventory.
+/// Why: native planning calculations must match the finite gas/liquid endpoint
+/// transition used by the Python fallback instead of jumping to a hard carbon cap.
+/// Inputs: charged total carbon and sodium molalities, compact Pitzer parameters,
+/// and the maximum activity-coefficient iteration count.
+/// Output: `(pH, Na, H, OH, HCO3, CO3, CO2)` or an error message.
+/// Side effects: none.
+/// Errors: rejects non-positive sodium or an unbracketed charge-balance root.
+fn solve_pitzer_total_carbon_impl(
+    total_carbon_m: f64,
+    total_sodium_m: f64,
+    params: PitzerParamsLite,
+    max_iter: usize,
+) -> Result<(f64, f64, f64, f64, f64, f64, f64), String> {
+    if total_sodium_m <= 0.0 {
+        return Err("Total sodium molality must be positive.".to_string());
+    }
+    // Near bicarbonate capacity, only part of newly charged CO2 joins the
+    // immediately reactive liquid inventory; the remainder stays in finite-rate
+    // gas/liquid transport or headspace inventory.  This smooth asymptote avoids
+    // a nonphysical one-cycle jump into the dissolved-CO2 endpoint regime.
+    let transition = PITZER_REACTIVE_CARBON_TRANSITION_FRACTION * total_sodium_m;
+    let charged_ct = total_carbon_m.max(0.0);
+    let ct = if charged_ct <= transition {
+        charged_ct
     } else {
-        dict_custom_string_value(dict, sort_key)
+        let transition_span = (total_sodium_m - transition).max(1e-30);
+        let uptake_above_transition = charged_ct - transition;
+        transition
+            + transition_span * (1.0 - (-uptake_above_transition / transition_span).exp())
     };
-    if raw_value.is_empty() {
-        return None;
-    }
-    parse_iso_datetime_sort_token(&raw_value)
-}
-
-fn normalize_fraction_value(value: Option<f64>) -> f64 {
-    let mut normalized = value.unwrap_or(0.0);
-    if normalized > 1.0 {
-        normalized /= 100.0;
-    }
-    if !normalized.is_finite() {
-        return 0.0;
+    if ct <= 0.5 * total_sodium_m {
+        let m_oh = (total_sodium_m - 2.0 * ct).max(0.0);
+        let m_co3 = ct.max(0.0);
+        let (gammas, _ionic_strength) =
+            pitzer_gamma_set(total_sodium_m, 1e-16, m_oh, 0.0, m_co3, params);
+        let a_oh = if m_oh > 0.0 {
+            gammas[2] * m_oh
+        } else {
+            1e-30
+        };
+        let a_h = PITZER_KW / a_oh.max(1e-30);
+        let ph = -a_h.max(1e-30).log10();
+        let h = a_h / gammas[1].max(1e-30);
+        return Ok((ph, total_sodium_m, h, m_oh, 0.0, m_co3, 0.0));
     }
-    normalized.max(0.0)
-}
 
-/// Extract a carbonate fraction value from a nested `fractions` mapping.
-///
-/// Missing nested dictionaries or non-finite values are treated as absent so
-/// Rust-side report builders match the Python fallback behavior.
-fn extract_fraction_field(dict: &Bound<'_, PyDict>, key: &str) -> Option<f64> {
-    let fractions_any = dict.get_item("fractions").ok().flatten()?;
-    let fractions = fractions_any.cast_into::<PyDict>().ok()?;
-    dict_optional_float_value(&fractions, key)
-}
+    let mut h = 1e-16_f64;
+    let mut oh = total_sodium_m.max(1e-12);
+    let mut hco3 = 0.0_f64;
+    let mut co3 = 0.0_f64;
+    let mut co2 = 0.0_f64;
+    let mut gammas = pitzer_gamma_set(total_sodium_m, h, oh, hco3, co3, params).0;
+    for _ in 0..max_iter.max(1) {
+        let root = pitzer_find_root_bisect_on_log10(
+            |log10_h| {
+                let m_h = 10f64.powf(log10_h);
+                let a_h = gammas[1] * m_h;
+                let m_oh = PITZER_KW / (a_h.max(1e-30) * gammas[2].max(1e-30));
+                let r1 = PITZER_KA1 / (gammas[1] * gammas[3] * m_h.max(1e-30));
+                let r23 = PITZER_KA2 * gammas[3] / (gammas[1] * gammas[4] * m_h.max(1e-30));
+                let r2 = r1 * r23;
+                let m_co2 = ct / (1.0 + r1 + r2);
+                let m_hco3 = r1 * m_co2;
+                let m_co3 = r2 * m_co2;
+                (total_sodium_m + m_h) - (m_oh + m_hco3 + 2.0 * m_co3)
+            },
+            -18.0,
+            0.0,
+            260,
+        )?;
+        h = 10f64.powf(root);
+        let a_h = gammas[1] * h;
+        oh = PITZER_KW / (a_h.max(1e-30) * gammas[2].max(1e-30));
+        let r1 = PITZER_KA1 / (gammas[1] * gammas[3] * h.max(1e-30));
+        let r23 = PITZER_KA2 * gammas[3] / (gammas[1] * gammas[4] * h.max(1e-30));
+        let r2 = r1 * r23;
+        co2 = ct / (1.0 + r1 + r2);
+        hco3 = r1 * co2;
+        co3 = r2 * co2;
 
-fn dict_optional_finite_by_keys(dict: &Bound<'_, PyDict>, keys: &[&str]) -> Option<f64> {
-    for key in keys {
-        let Some(any_value) = dict.get_item(*key).ok().flatten() else {
-            continue;
-        };
-        let Ok(parsed) = any_value.extract::<f64>() else {
-            continue;
-        };
-        if parsed.is_finite() {
-            return Some(parsed);
+        let next_gammas = pitzer_gamma_set(total_sodium_m, h, oh, hco3, co3, params).0;
+        let mut max_delta = 0.0_f64;
+        for idx in 0..5 {
+            let delta = (next_gammas[idx] / gammas[idx].max(1e-30)).ln().abs();
+            if delta > max_delta {
+                max_delta = delta;
+            }
+        }
+        gammas = next_gammas;
+        if max_delta < 1e-6 {
+            break;
         }
     }
-    None
+
+    let ph = -(gammas[1] * h).max(1e-30).log10();
+    Ok((ph, total_sodium_m, h, oh, hco3, co3, co2))
 }
 
-/// Parse compact NaOH-CO2 Pitzer coefficients from a Python mapping payload.
-///
-/// Returns `None` when any required coefficient is missing or non-finite.
-fn parse_pitzer_params_map(pitzer_params: Option<&Bound<'_, PyDict>>) -> Option<PitzerParamsLite> {
-    let params_map = pitzer_params?;
-    let parse = |key: &str| -> Option<f64> {
-        let value = params_map.get_item(key).ok().flatten()?;
-        let parsed = value.extract::<f64>().ok()?;
-        if parsed.is_finite() {
-            Some(parsed)
-        } else {
-            None
-        }
-    };
-    Some(PitzerParamsLite {
-        b0_na_oh: parse("b0_na_oh")?,
-        b1_na_oh: parse("b1_na_oh")?,
-        c0_na_oh: parse("c0_na_oh")?,
-        b0_na_hco3: parse("b0_na_hco3")?,
-        b1_na_hco3: parse("b1_na_hco3")?,
-        c0_na_hco3: parse("c0_na_hco3")?,
-        b0_na_co3: parse("b0_na_co3")?,
-        b1_na_co3: parse("b1_na_co3")?,
-        c0_na_co3: parse("c0_na_co3")?,
-        theta_co3_oh: parse("theta_co3_oh")?,
-        psi_co3_na_oh: parse("psi_co3_na_oh")?,
-        psi_co3_hco3_na: parse("psi_co3_hco3_na")?,
-    })
+/// Equilibrium and activity settings a ledger solve shares with its caller: the
+/// temperature and whether the constants follow it, explicit `(Ka1, Ka2, Kw)` that
+/// take precedence over both, the activity treatment, the clamp ranges, and whether
+/// the pH < 6 buffer rescue is skipped in favour of the raw charge-balance root.
+#[derive(Clone, Copy)]
+struct SolverSettings {
+    temperature_c: Option<f64>,
+    use_temp_adjusted_constants: bool,
+    constants: Option<(f64, f64, f64)>,
+    ionic_strength_cap: Option<f64>,
+    activity_model: ActivityModel,
+    clamp_bounds: ClampBounds,
+    raw_solver_ph: bool,
 }
 
-fn compare_cycle_row_index(dict: &Bound<'_, PyDict>, fallback_index: usize) -> usize {
-    for key in ["cycle_id", "cycle", "cycle_number", "cycle_index", "index"] {
-        let Some(any_value) = dict.get_item(key).ok().flatten() else {
-            continue;
-        };
-        let Ok(parsed) = any_value.extract::<isize>() else {
-            continue;
-        };
-        if parsed >= 1 {
-            if let Ok(cycle_id) = usize::try_from(parsed) {
-                return cycle_id;
-            }
+impl Default for SolverSettings {
+    /// Built-in 25 C constants under the hybrid activity model.
+    fn default() -> Self {
+        Self {
+            temperature_c: None,
+            use_temp_adjusted_constants: false,
+            constants: None,
+            ionic_strength_cap: None,
+            activity_model: ActivityModel::Hybrid,
+            clamp_bounds: ClampBounds::default(),
+            raw_solver_ph: false,
         }
     }
-    fallback_index.max(1)
 }
 
-fn py_any_optional_finite_float(value: &Bound<'_, PyAny>) -> Option<f64> {
-    if value.is_none() {
-        return None;
-    }
-    let parsed = value.extract::<f64>().ok()?;
-    if parsed.is_finite() {
-        Some(parsed)
-    } else {
-        None
+impl SolverSettings {
+    /// `(Ka1, Ka2, Kw)`: the explicit constants, else the built-in set at the
+    /// clamped temperature.
+    fn equilibrium_constants(&self) -> (f64, f64, f64) {
+        self.constants.unwrap_or_else(|| {
+            basic_carbonate_constants_within(
+                self.temperature_c,
+                self.use_temp_adjusted_constants,
+                self.clamp_bounds,
+            )
+        })
     }
-}
 
-fn interpolate_reference_series_impl(
-    x_value: Option<f64>,
-    x_series: &[f64],
-    y_series: &[Option<f64>],
-) -> (Option<f64>, &'static str) {
-    let Some(current_x) = x_value else {
-        return (None, "out_of_range");
-    };
-    if !current_x.is_finite() {
-        return (None, "out_of_range");
-    }
-    let mut pairs: Vec<(f64, Option<f64>)> = Vec::new();
-    for (x_raw, y_raw) in x_series.iter().zip(y_series.iter()) {
-        if !x_raw.is_finite() {
-            continue;
-        }
-        pairs.push((*x_raw, *y_raw));
-    }
-    if pairs.is_empty() {
-        return (None, "sparse_reference");
-    }
-    pairs.sort_by(|left, right| left.0.partial_cmp(&right.0).unwrap_or(Ordering::Equal));
-    if pairs.len() < 2 {
-        return (pairs[0].1, "sparse_reference");
-    }
-    if current_x < pairs[0].0 || current_x > pairs[pairs.len() - 1].0 {
-        return (None, "out_of_range");
+    /// Built-in pKa2 at the clamped temperature.
+    fn pka2_value(&self) -> f64 {
+        resolve_pka2_value_within(
+            self.temperature_c,
+            self.use_temp_adjusted_constants,
+            self.clamp_bounds,
+        )
     }
 
-    let mut right_idx: usize = 0;
-    let mut found_right = false;
-    for (idx, (x_pair, _)) in pairs.iter().enumerate() {
-        if *x_pair >= current_x {
-            right_idx = idx;
-            found_right = true;
-            break;
-        }
+    fn activity(&self) -> ActivityTreatment {
+        ActivityTreatment::new(self.activity_model, self.ionic_strength_cap)
     }
-    if !found_right {
-        right_idx = pairs.len() - 1;
-    }
-    let left_idx = right_idx.saturating_sub(1);
-    let (x_left, y_left) = pairs[left_idx];
-    let (x_right, y_right) = pairs[right_idx];
 
-    if let (Some(left_val), Some(right_val)) = (y_left, y_right) {
-        if left_val.is_finite() && right_val.is_finite() && x_right != x_left {
-            let ratio = (current_x - x_left) / (x_right - x_left);
-            let mapped = left_val + ratio * (right_val - left_val);
-            if mapped.is_finite() {
-                return (Some(mapped), "ok");
-            }
-        }
+    /// Read the shared keywords of a ledger kernel's `**options`: those of
+    /// [`Self::take_equilibrium_from`] plus `raw_solver_ph`.
+    fn take_from(kwargs: &KernelKwargs<'_>) -> PyResult<Self> {
+        Ok(Self {
+            raw_solver_ph: kwargs.take_or("raw_solver_ph", false)?,
+            ..Self::take_equilibrium_from(kwargs)?
+        })
     }
 
-    let nearest_idx = if (current_x - x_right).abs() < (current_x - x_left).abs() {
-        right_idx
-    } else {
-        left_idx
-    };
-    let nearest = pairs[nearest_idx].1;
-    if nearest.is_some() {
-        (nearest, "ok")
-    } else {
-        (None, "sparse_reference")
+    /// Read the shared keywords of a speciation kernel's `**options`:
+    /// `temperature_c`, `use_temp_adjusted_constants`, `ionic_strength_cap`,
+    /// `constants` or `pka_constants`, `clamp_bounds` and the activity model. These
+    /// kernels have no ledger rescue to skip, so `raw_solver_ph` stays off.
+    fn take_equilibrium_from(kwargs: &KernelKwargs<'_>) -> PyResult<Self> {
+        Ok(Self {
+            temperature_c: kwargs.take("temperature_c")?,
+            use_temp_adjusted_constants: kwargs.take_or("use_temp_adjusted_constants", false)?,
+            constants: kwargs.take_constants()?,
+            ionic_strength_cap: kwargs.take("ionic_strength_cap")?,
+            activity_model: kwargs.take_activity_model()?,
+            clamp_bounds: kwargs.take_clamp_bounds()?,
+            raw_solver_ph: false,
+        })
     }
 }
 
-#[pyfunction]
-#[pyo3(signature = (x_value, x_series, y_series))]
-fn analysis_interpolate_reference_series_core(
-    py: Python<'_>,
-    x_value: Option<f64>,
-    x_series: &Bound<'_, PyList>,
-    y_series: &Bound<'_, PyList>,
-) -> PyResult<Py<PyDict>> {
-    // Interpolate one reference series point with the same out-of-range/sparse
-    // flags used by the Python forensic/dashboard alignment path.
-    let mut safe_x: Vec<f64> = Vec::new();
-    let mut safe_y: Vec<Option<f64>> = Vec::new();
-    for (x_any, y_any) in x_series.iter().zip(y_series.iter()) {
-        let Some(x_val) = py_any_optional_finite_float(&x_any) else {
-            continue;
-        };
-        safe_x.push(x_val);
-        safe_y.push(py_any_optional_finite_float(&y_any));
-    }
-    let (mapped_value, flag) = interpolate_reference_series_impl(x_value, &safe_x, &safe_y);
-    let response = PyDict::new(py);
-    if let Some(value) = mapped_value {
-        response.set_item("value", value)?;
-    } else {
-        response.set_item("value", py.None())?;
+/// One ledger pH solve: the shared settings plus the pKa2 of the
+/// Henderson-Hasselbalch estimates, the liquor volume (`None` keeps the closed-form
+/// estimates), whether the planning heuristics apply and the pool-fraction width
+/// they fade in over, and where CO2 dosed past the bicarbonate point ends up.
+#[derive(Clone, Copy)]
+struct LedgerSolve {
+    settings: SolverSettings,
+    pka2_value: f64,
+    solution_volume_l: Option<f64>,
+    planning_mode: bool,
+    planning_transition_width: f64,
+    excess_co2_fate: ExcessCo2Fate,
+}
+
+impl LedgerSolve {
+    /// Actual-mode solve with excess CO2 kept dissolved.
+    fn new(settings: SolverSettings, pka2_value: f64, solution_volume_l: Option<f64>) -> Self {
+        Self {
+            settings,
+            pka2_value,
+            solution_volume_l,
+            planning_mode: false,
+            planning_transition_width: PLANNING_TRANSITION_WIDTH,
+            excess_co2_fate: ExcessCo2Fate::Dissolved,
+        }
     }
-    response.set_item("flag", flag)?;
-    Ok(response.unbind())
 }
 
-#[pyfunction]
-#[pyo3(signature = (
-    actual_cycle_series,
-    reference_cycle_series,
-    target_ph,
-    reaction_naoh_mass,
-    initial_naoh_mol,
-    co2_mw,
-    naoh_mw
-))]
-/// Build the Rust-backed analysis dashboard payload from cycle series inputs.
+/// Estimate the liquor pH of a ledger state.
 ///
-/// The function mirrors the Python dashboard assembly path so backend switching
-/// does not change interpolation, fraction extraction, or summary table shape.
-fn analysis_dashboard_core(
-    py: Python<'_>,
-    actual_cycle_series: &Bound<'_, PyList>,
-    reference_cycle_series: &Bound<'_, PyList>,
-    target_ph: f64,
-    reaction_naoh_mass: Option<f64>,
-    initial_naoh_mol: Option<f64>,
-    co2_mw: f64,
-    naoh_mw: f64,
-) -> PyResult<Py<PyDict>> {
-    // Build comparison rows + summary metrics for the Analysis dashboard using
-    // the same deterministic interpolation and fail-closed value handling.
-    #[derive(Clone, Copy)]
-    struct ActualRow {
-        cycle_id: usize,
-        cycle_co2_g: Option<f64>,
-        cumulative_co2_g: Option<f64>,
-        cumulative_co2_mol: Option<f64>,
-        duration_x: Option<f64>,
-        ph: Option<f64>,
-        hco3_fraction: f64,
-        co3_fraction: f64,
+/// Returns `(ph, residual_norm, rescue)`; the residual is the Newton solver's
+/// achieved norm and is `None` whenever the pH came from a closed-form or fallback
+/// branch, and `rescue` records the pH < 6 buffer rescue when it replaced the root.
+/// A `warm_seed` `(pH, hco3_frac, co3_frac)` from an earlier solve is tried
+/// before the built-in Newton seeds.
+fn estimate_ledger_ph(
+    state: LedgerState,
+    solve: &LedgerSolve,
+    initial_ph_guess: Option<f64>,
+    observer: Option<NewtonIterationObserver<'_>>,
+    warm_seed: Option<(f64, f64, f64)>,
+) -> (f64, Option<f64>, Option<f64>, Option<PlanningAdjustment>) {
+    let LedgerSolve {
+        settings,
+        pka2_value,
+        solution_volume_l,
+        ..
+    } = *solve;
+    let clamp_bounds = settings.clamp_bounds;
+    let volume = solution_volume_l.unwrap_or(0.0);
+    let fallback_ph = if state.nahco3_mol <= 0.0 && state.na2co3_mol > 0.0 {
+        // No bicarbonate means no HCO3-/CO3^2- pair to take a ratio of; the liquor
+        // is set by carbonate hydrolysis. Without a volume the pools read per litre.
+        let fallback_volume = if volume > 0.0 { volume } else { 1.0 };
+        let (_, _, kw) = settings.equilibrium_constants();
+        clamp_bounds.ph(carbonate_hydrolysis_ph(
+            state.na2co3_mol / fallback_volume,
+            state.naoh_remaining_mol / fallback_volume,
+            10f64.powf(-pka2_value),
+            kw,
+        ))
+    } else {
+        let ratio = (state.na2co3_mol / state.nahco3_mol.max(1e-12)).max(1e-12);
+        clamp_bounds.ph(hh_ph_from_ratio(ratio, pka2_value))
+    };
+    if volume <= 0.0 {
+        return (fallback_ph, None, None, None);
     }
-    #[derive(Clone, Copy)]
-    struct ReferenceRow {
-        cycle_id: usize,
-        cumulative_co2_g: Option<f64>,
-        ph: Option<f64>,
-        equivalence_reached: bool,
+    let total_na = state.naoh_remaining_mol.max(0.0)
+        + state.nahco3_mol.max(0.0)
+        + 2.0 * state.na2co3_mol.max(0.0);
+    let total_carbon =
+        state.nahco3_mol.max(0.0) + state.na2co3_mol.max(0.0) + state.co2_excess_mol.max(0.0);
+    let total_na_conc = total_na / volume.max(1e-9);
+    let total_carbon_conc = total_carbon / volume.max(1e-9);
+    if total_na_conc <= 0.0 && total_carbon_conc <= 0.0 {
+        return (fallback_ph, None, None, None);
     }
-    let mut actual_rows: Vec<ActualRow> = Vec::new();
-    let mut actual_alignment_override: Vec<String> = Vec::new();
-    let mut inferred_time_label = String::new();
-    for (idx, item) in actual_cycle_series.iter().enumerate() {
-        let Ok(entry) = item.cast_into::<PyDict>() else {
-            continue;
-        };
-        let cycle_id = compare_cycle_row_index(&entry, idx + 1);
-        let cycle_co2_g = dict_optional_finite_by_keys(
-            &entry,
-            &["co2_cycle_g", "cycle_co2_g", "co2_mass_g"],
-        );
-        let cumulative_co2_g = dict_optional_finite_by_keys(
-            &entry,
-            &["cumulative_co2_g", "co2_g", "cumulative_co2_added_mass_g", "co2_mass_g"],
-        );
-        let cumulative_co2_mol = dict_optional_finite_by_keys(
-            &entry,
-            &[
-                "cumulative_co2_mol",
-                "cumulative_co2_added_moles",
-                "co2_moles",
-                "co2_added_moles",
-            ],
-        );
-        let duration_x = dict_optional_finite_by_keys(&entry, &["duration_x"]);
-        if inferred_time_label.is_empty() {
-            inferred_time_label = dict_string_value(&entry, "x_label");
+    let (ka1, ka2, kw) = settings.equilibrium_constants();
+    let pkw = -kw.max(1e-30).log10();
+    let guess = initial_ph_guess.unwrap_or(fallback_ph);
+    let residual_naoh_ph_floor = if state.naoh_remaining_mol > 0.0 {
+        // Residual NaOH supplies one mole of free OH- per mole NaOH; this strong-base
+        // floor prevents carbonate charge balance from reporting acidic raw pH.
+        Some(clamp_bounds.ph(
+            pkw + (state.naoh_remaining_mol.max(0.0) / volume.max(1e-9)).max(1e-16).log10(),
+        ))
+    } else {
+        None
+    };
+    if total_carbon_conc <= 1e-12 {
+        if total_na_conc <= 0.0 {
+            return (clamp_bounds.ph(pkw / 2.0), None, None, None);
         }
-        let ph = dict_optional_finite_by_keys(&entry, &["ph", "actual_ph", "solution_ph"]);
-        let mut hco3_fraction = 0.0_f64;
-        let mut co3_fraction = 0.0_f64;
-        if let Some(fractions_any) = entry.get_item("fractions").ok().flatten() {
-            if let Ok(fractions) = fractions_any.cast_into::<PyDict>() {
-                hco3_fraction = dict_optional_float_value(&fractions, "HCO3-")
-                    .unwrap_or(0.0)
-                    .max(0.0);
-                co3_fraction = dict_optional_float_value(&fractions, "CO3^2-")
-                    .unwrap_or(0.0)
-                    .max(0.0);
+        let ph = pkw + total_na_conc.max(1e-16).log10();
+        let ph = match residual_naoh_ph_floor {
+            Some(floor) => ph.max(floor),
+            None => ph,
+        };
+        return (clamp_bounds.ph(ph), None, None, None);
+    }
+    // Without sodium there is no carbonate/bicarbonate buffer: dissolved CO2 alone
+    // is carbonic-acid dominated, so solve the pure-water charge balance directly
+    // and never apply the alkaline plateau floor below.
+    let has_sodium_buffer = total_na_conc > 1e-12;
+    if !has_sodium_buffer
+        && let Ok(solved) = solve_aqion_closed_speciation(
+            total_carbon_conc,
+            ka1,
+            ka2,
+            kw,
+            AQION_DEFAULT_PH_LOW,
+            AQION_DEFAULT_PH_HIGH,
+        )
+    {
+        return (clamp_bounds.ph(solved.0), None, None, None);
+    }
+    let buffered_carbon =
+        has_sodium_buffer && (state.nahco3_mol > 0.0 || state.co2_excess_mol > 0.0);
+    // Past the bicarbonate point every sodium is HCO3- and further CO2 only piles
+    // up as dissolved carbonic acid: the liquor is genuinely on the H2CO3/HCO3-
+    // buffer heading toward pH ~4, so the alkaline rescue floor must not engage.
+    let over_carbonated = state.naoh_remaining_mol <= 0.0
+        && state.na2co3_mol <= 0.0
+        && state.co2_excess_mol > 0.0;
+    match solve_carbonate_state(
+        CarbonateSystem::new(total_carbon_conc, total_na_conc, (ka1, ka2, kw)),
+        settings.activity(),
+        NewtonOptions {
+            extra_seeds: warm_seed.as_slice(),
+            observer,
+            ..NewtonOptions::from_guess(guess)
+        },
+    ) {
+        Ok((h, _, _, _, _, _, ionic_strength, residual_norm)) => {
+            let mut ph = clamp_bounds.ph(-h.max(1e-30).log10());
+            if let Some(floor) = residual_naoh_ph_floor {
+                ph = clamp_bounds.ph(ph.max(floor));
+            }
+            // The pH < 6 rescue masks solver trouble in buffered liquors; callers
+            // debugging the curve can opt out and keep the raw charge-balance root.
+            if ph < 6.0 && buffered_carbon && !over_carbonated && !settings.raw_solver_ph {
+                let mut buffer_hint = fallback_ph.max(pka2_value - 2.2).max(8.0);
+                if let Some(floor) = residual_naoh_ph_floor {
+                    buffer_hint = buffer_hint.max(floor);
+                }
+                let rescued = clamp_bounds.ph(buffer_hint);
+                let adjustment = PlanningAdjustment {
+                    kind: PLANNING_ADJUSTMENT_LOW_PH_RESCUE,
+                    ph_before: ph,
+                    ph_after: rescued,
+                    weight: 1.0,
+                };
+                (rescued, Some(residual_norm), Some(ionic_strength), Some(adjustment))
+            } else {
+                (ph, Some(residual_norm), Some(ionic_strength), None)
             }
         }
-        actual_rows.push(ActualRow {
-            cycle_id,
-            cycle_co2_g,
-            cumulative_co2_g,
-            cumulative_co2_mol,
-            duration_x,
-            ph,
-            hco3_fraction,
-            co3_fraction,
-        });
-        actual_alignment_override.push(dict_string_value(&entry, "alignment_quality_flag"));
+        Err(_) => {
+            let ph = if over_carbonated && has_sodium_buffer {
+                // First-dissociation Henderson-Hasselbalch on the HCO3-/H2CO3 pair.
+                let ratio = (state.nahco3_mol.max(1e-12) / state.co2_excess_mol).max(1e-12);
+                clamp_bounds.ph(hh_ph_from_ratio(ratio, -ka1.max(1e-30).log10()))
+            } else if buffered_carbon {
+                let mut buffer_hint = fallback_ph.max(pka2_value - 2.2).max(8.0);
+                if let Some(floor) = residual_naoh_ph_floor {
+                    buffer_hint = buffer_hint.max(floor);
+                }
+                clamp_bounds.ph(buffer_hint)
+            } else if let Some(floor) = residual_naoh_ph_floor {
+                floor
+            } else {
+                fallback_ph
+            };
+            (ph, None, None, None)
+        }
     }
+}
 
-    let mut reference_rows: Vec<ReferenceRow> = Vec::new();
-    let mut reference_x: Vec<f64> = Vec::new();
-    let mut reference_ph: Vec<Option<f64>> = Vec::new();
-    let mut reference_hco3: Vec<Option<f64>> = Vec::new();
-    let mut reference_co3: Vec<Option<f64>> = Vec::new();
-    for (idx, item) in reference_cycle_series.iter().enumerate() {
-        let Ok(entry) = item.cast_into::<PyDict>() else {
-            continue;
+/// Weight of a planning clamp: 1 while `fraction` is at or below `threshold`,
+/// ramping linearly to 0 across `width` above it (a hard switch when `width` is 0).
+fn planning_clamp_weight(fraction: f64, threshold: f64, width: f64) -> f64 {
+    if width <= 0.0 {
+        return if fraction <= threshold { 1.0 } else { 0.0 };
+    }
+    ((threshold + width - fraction) / width).clamp(0.0, 1.0)
+}
+
+/// Planning-mode ledger pH: the solver estimate clamped into the pKa2 ± 0.35 anchor
+/// band at carbonate-only equivalence and pulled onto the bicarbonate plateau once
+/// carbonate is depleted. Both are blended in by [`planning_clamp_weight`] across
+/// `solve.planning_transition_width` so the curve stays continuous where they
+/// engage. The band's ceiling fades in over the residual-NaOH share and then holds
+/// past equivalence, while its floor holds before equivalence and fades out over the
+/// bicarbonate share: neither pull ever grows with dose in a way that lifts the pH,
+/// so the curve never climbs back after equivalence. The plateau fades out on the
+/// excess-CO2 share, so CO2 dosed past the bicarbonate point carries the curve down
+/// into the carbonic-acid regime instead of holding it at 8.
+fn estimate_ledger_ph_planning(
+    state: LedgerState,
+    solve: &LedgerSolve,
+    initial_ph_guess: Option<f64>,
+    observer: Option<NewtonIterationObserver<'_>>,
+    warm_seed: Option<(f64, f64, f64)>,
+) -> (f64, Option<f64>, Option<f64>, Vec<PlanningAdjustment>) {
+    let (pka2_value, clamp_bounds) = (solve.pka2_value, solve.settings.clamp_bounds);
+    let co3 = state.na2co3_mol.max(0.0);
+    let hco3 = state.nahco3_mol.max(0.0);
+    let excess = state.co2_excess_mol.max(0.0);
+    let carbon_pool = co3 + hco3;
+    let has_sodium_buffer = state.naoh_remaining_mol > 0.0 || carbon_pool > 0.0;
+    let width = solve.planning_transition_width;
+    let naoh = state.naoh_remaining_mol.max(0.0);
+    let anchor_band = (co3 > 0.0).then(|| {
+        let anchor = if pka2_value.is_finite() {
+            pka2_value
+        } else {
+            10.33
         };
-        let cycle_id = compare_cycle_row_index(&entry, idx + 1);
-        let cumulative_co2_g = dict_optional_finite_by_keys(
-            &entry,
-            &["cumulative_co2_g", "co2_g", "cumulative_co2_added_mass_g", "co2_mass_g"],
-        );
-        if let Some(x_value) = cumulative_co2_g {
-            if x_value.is_finite() {
-                reference_x.push(x_value);
-            }
-        }
-        let ph = dict_optional_finite_by_keys(&entry, &["ph", "reference_ph", "solution_ph"]);
-        let mut hco3_fraction = None;
-        let mut co3_fraction = None;
-        if let Some(fractions_any) = entry.get_item("fractions").ok().flatten() {
-            if let Ok(fractions) = fractions_any.cast_into::<PyDict>() {
-                hco3_fraction = dict_optional_float_value(&fractions, "HCO3-");
-                co3_fraction = dict_optional_float_value(&fractions, "CO3^2-");
-            }
+        (
+            anchor - 0.35,
+            anchor + 0.35,
+            planning_clamp_weight(hco3 / carbon_pool, 0.0, width),
+            planning_clamp_weight(naoh / (naoh + co3), 0.0, width),
+        )
+    });
+    let depleted_weight = if co3 <= PLANNING_PLATEAU_CARBONATE_THRESHOLD {
+        planning_clamp_weight(excess / (hco3 + excess).max(1e-12), 0.0, width)
+    } else if carbon_pool > 0.0 {
+        planning_clamp_weight(
+            co3 / carbon_pool.max(1e-12),
+            PLANNING_PLATEAU_RELATIVE_THRESHOLD,
+            width,
+        )
+    } else {
+        0.0
+    };
+    let (mut ph_estimate, residual_norm, ionic_strength, rescue) = estimate_ledger_ph(
+        LedgerState {
+            co2_excess_mol: excess,
+            ..state
+        },
+        solve,
+        initial_ph_guess,
+        observer,
+        warm_seed,
+    );
+    let mut adjustments: Vec<PlanningAdjustment> = rescue.into_iter().collect();
+    if let Some((floor, ceiling, floor_weight, ceiling_weight)) = anchor_band
+        && ph_estimate.is_finite()
+    {
+        let (edge, weight) = if ph_estimate > ceiling {
+            (ceiling, ceiling_weight)
+        } else {
+            (floor, floor_weight)
+        };
+        if weight > 0.0 && !(floor..=ceiling).contains(&ph_estimate) {
+            let ph_before = ph_estimate;
+            ph_estimate = clamp_bounds.ph(ph_estimate + weight * (edge - ph_estimate));
+            adjustments.push(PlanningAdjustment {
+                kind: PLANNING_ADJUSTMENT_EQUIVALENCE_ANCHOR,
+                ph_before,
+                ph_after: ph_estimate,
+                weight,
+            });
         }
-        reference_ph.push(ph);
-        reference_hco3.push(hco3_fraction);
-        reference_co3.push(co3_fraction);
-        let equivalence_reached = entry
-            .get_item("equivalence_reached")
-            .ok()
-            .flatten()
-            .and_then(|value| value.is_truthy().ok())
-            .unwrap_or(false);
-        reference_rows.push(ReferenceRow {
-            cycle_id,
-            cumulative_co2_g,
-            ph,
-            equivalence_reached,
+    }
+    if depleted_weight > 0.0
+        && has_sodium_buffer
+        && (hco3 > 0.0 || excess > 0.0)
+        && ph_estimate.is_finite()
+    {
+        let plateau = ph_estimate
+            .max(PLANNING_PLATEAU_PH_MIN)
+            .min(PLANNING_PLATEAU_PH_MAX);
+        let ph_before = ph_estimate;
+        ph_estimate += depleted_weight * (plateau - ph_estimate);
+        adjustments.push(PlanningAdjustment {
+            kind: PLANNING_ADJUSTMENT_DEPLETED_PLATEAU,
+            ph_before,
+            ph_after: ph_estimate,
+            weight: depleted_weight,
         });
     }
+    (ph_estimate, residual_norm, ionic_strength, adjustments)
+}
 
-    let comparison_series = PyList::empty(py);
-    for (idx, row) in actual_rows.iter().enumerate() {
-        let ref_cycle = reference_rows.get(idx).copied();
-        let reference_cycle_ph = ref_cycle.and_then(|item| item.ph);
-        let delta_cycle_ph = match (row.ph, reference_cycle_ph) {
-            (Some(actual), Some(reference)) => Some(actual - reference),
-            _ => None,
+/// Most and second-most abundant carbon species of `[H2CO3, HCO3-, CO3^2-]`
+/// amounts (molar or fractional); `None` when no carbon is present.
+fn dominant_carbon_species(amounts: [f64; 3]) -> Option<(&'static str, &'static str)> {
+    const NAMES: [&str; 3] = ["H2CO3", "HCO3", "CO3"];
+    if !amounts.iter().all(|value| value.is_finite()) || amounts.iter().sum::<f64>() <= 0.0 {
+        return None;
+    }
+    let mut order = [0usize, 1, 2];
+    order.sort_by(|&a, &b| amounts[b].total_cmp(&amounts[a]));
+    Some((NAMES[order[0]], NAMES[order[1]]))
+}
+
+/// Write `dominant_species`/`secondary_species` next to a speciation's fractions.
+fn set_dominant_species(out: &Bound<'_, PyDict>, amounts: [f64; 3]) -> PyResult<()> {
+    let ranked = dominant_carbon_species(amounts);
+    out.set_item("dominant_species", ranked.map(|pair| pair.0))?;
+    out.set_item("secondary_species", ranked.map(|pair| pair.1))?;
+    Ok(())
+}
+
+/// Split the dissolved inorganic carbon of a ledger state into
+/// `[H2CO3, HCO3-, CO3^2-]` fractions of DIC.
+///
+/// Uses the converged activity-corrected speciation when a solution volume is
+/// known and falls back to the stoichiometric ledger split otherwise (or when the
+/// solver fails). A carbon-free ledger reports all-zero fractions.
+fn ledger_carbon_fractions(
+    state: LedgerState,
+    solution_volume_l: Option<f64>,
+    settings: &SolverSettings,
+    ph_guess: f64,
+) -> [f64; 3] {
+    let co3 = state.na2co3_mol.max(0.0);
+    let hco3 = state.nahco3_mol.max(0.0);
+    let excess = state.co2_excess_mol.max(0.0);
+    let ledger_total = co3 + hco3 + excess;
+    if ledger_total <= 1e-12 {
+        return [0.0, 0.0, 0.0];
+    }
+    let ledger_split = [
+        excess / ledger_total,
+        hco3 / ledger_total,
+        co3 / ledger_total,
+    ];
+    let Some(volume) = solution_volume_l.filter(|value| *value > 0.0) else {
+        return ledger_split;
+    };
+    let (ka1, ka2, kw) = settings.equilibrium_constants();
+    let total_carbon_conc = ledger_total / volume;
+    let total_na_conc = (state.naoh_remaining_mol.max(0.0) + hco3 + 2.0 * co3) / volume;
+    if total_na_conc <= 1e-12 {
+        return match solve_aqion_closed_speciation(
+            total_carbon_conc,
+            ka1,
+            ka2,
+            kw,
+            AQION_DEFAULT_PH_LOW,
+            AQION_DEFAULT_PH_HIGH,
+        ) {
+            Ok((_, _, _, _, _, _, a0, a1, a2, _, _, _)) => [a0, a1, a2],
+            Err(_) => ledger_split,
         };
-        let (reference_co2_aligned_ph, ph_alignment) = interpolate_reference_series_impl(
-            row.cumulative_co2_g,
-            &reference_x,
-            &reference_ph,
-        );
-        let (co2_aligned_hco3, hco3_alignment) = interpolate_reference_series_impl(
-            row.cumulative_co2_g,
-            &reference_x,
-            &reference_hco3,
-        );
-        let (co2_aligned_co3, co3_alignment) = interpolate_reference_series_impl(
-            row.cumulative_co2_g,
-            &reference_x,
-            &reference_co3,
-        );
-        let delta_co2_ph = match (row.ph, reference_co2_aligned_ph) {
-            (Some(actual), Some(reference)) => Some(actual - reference),
-            _ => None,
-        };
-        let actual_hco3_pct = row.hco3_fraction.max(0.0) * 100.0;
-        let actual_co3_pct = row.co3_fraction.max(0.0) * 100.0;
-        let reference_hco3_pct = co2_aligned_hco3.map(|value| value * 100.0);
-        let reference_co3_pct = co2_aligned_co3.map(|value| value * 100.0);
-        let delta_hco3_pct = reference_hco3_pct.map(|reference| actual_hco3_pct - reference);
-        let delta_co3_pct = reference_co3_pct.map(|reference| actual_co3_pct - reference);
-        let mut alignment_quality = if [ph_alignment, hco3_alignment, co3_alignment]
-            .iter()
-            .any(|flag| *flag == "out_of_range")
-        {
-            "out_of_range".to_string()
-        } else if [ph_alignment, hco3_alignment, co3_alignment]
-            .iter()
-            .any(|flag| *flag == "sparse_reference")
-        {
-            "sparse_reference".to_string()
-        } else {
-            "ok".to_string()
-        };
-        if let Some(override_flag) = actual_alignment_override.get(idx) {
-            let trimmed = override_flag.trim();
-            if !trimmed.is_empty() {
-                alignment_quality = trimmed.to_string();
-            }
+    }
+    match solve_carbonate_state(
+        CarbonateSystem::new(total_carbon_conc, total_na_conc, (ka1, ka2, kw)),
+        settings.activity(),
+        NewtonOptions::from_guess(ph_guess),
+    ) {
+        Ok((_, hco3_m, co3_m, h2co3_m, _, _, _, _)) => {
+            let total = (h2co3_m + hco3_m + co3_m).max(1e-30);
+            [h2co3_m / total, hco3_m / total, co3_m / total]
         }
+        Err(_) => ledger_split,
+    }
+}
 
-        let comparison_row = PyDict::new(py);
-        comparison_row.set_item("cycle_id", row.cycle_id)?;
-        if let Some(value) = row.ph {
-            comparison_row.set_item("actual_ph", value)?;
-        } else {
-            comparison_row.set_item("actual_ph", py.None())?;
-        }
-        if let Some(value) = reference_cycle_ph {
-            comparison_row.set_item("reference_cycle_ph", value)?;
-        } else {
-            comparison_row.set_item("reference_cycle_ph", py.None())?;
-        }
-        if let Some(value) = reference_co2_aligned_ph {
-            comparison_row.set_item("reference_co2_aligned_ph", value)?;
-        } else {
-            comparison_row.set_item("reference_co2_aligned_ph", py.None())?;
-        }
-        if let Some(value) = delta_cycle_ph {
-            comparison_row.set_item("delta_cycle_ph", value)?;
-        } else {
-            comparison_row.set_item("delta_cycle_ph", py.None())?;
-        }
-        if let Some(value) = delta_co2_ph {
-            comparison_row.set_item("delta_co2_ph", value)?;
-        } else {
-            comparison_row.set_item("delta_co2_ph", py.None())?;
-        }
-        comparison_row.set_item("actual_hco3_pct", actual_hco3_pct)?;
-        comparison_row.set_item("actual_co3_pct", actual_co3_pct)?;
-        if let Some(value) = reference_hco3_pct {
-            comparison_row.set_item("reference_hco3_pct", value)?;
-        } else {
-            comparison_row.set_item("reference_hco3_pct", py.None())?;
-        }
-        if let Some(value) = reference_co3_pct {
-            comparison_row.set_item("reference_co3_pct", value)?;
-        } else {
-            comparison_row.set_item("reference_co3_pct", py.None())?;
-        }
-        if let Some(value) = delta_hco3_pct {
-            comparison_row.set_item("delta_hco3_pct", value)?;
-        } else {
-            comparison_row.set_item("delta_hco3_pct", py.None())?;
-        }
-        if let Some(value) = delta_co3_pct {
-            comparison_row.set_item("delta_co3_pct", value)?;
-        } else {
-            comparison_row.set_item("delta_co3_pct", py.None())?;
-        }
-        comparison_row.set_item("alignment_quality_flag", alignment_quality)?;
-        comparison_series.append(comparison_row)?;
+/// Closed-carbon speciation of a ledger dissolved in `volume_l` under `settings`,
+/// seeded at `ph_guess`, as `(total_carbon_m, na_conc, solution)`.
+///
+/// `None` for a carbon- or sodium-free ledger or when the solver fails; those
+/// states carry no activity correction worth reporting.
+fn ledger_speciation(
+    state: LedgerState,
+    volume_l: f64,
+    settings: &SolverSettings,
+    ph_guess: f64,
+) -> Option<(f64, f64, CarbonateSolution)> {
+    let co3 = state.na2co3_mol.max(0.0);
+    let hco3 = state.nahco3_mol.max(0.0);
+    let total_carbon = co3 + hco3 + state.co2_excess_mol.max(0.0);
+    let total_na = state.naoh_remaining_mol.max(0.0) + hco3 + 2.0 * co3;
+    if total_carbon <= 1e-12 || total_na <= 1e-12 {
+        return None;
     }
+    let (total_carbon_m, na_conc) = (total_carbon / volume_l, total_na / volume_l);
+    solve_carbonate_state(
+        CarbonateSystem::new(total_carbon_m, na_conc, settings.equilibrium_constants()),
+        settings.activity(),
+        NewtonOptions::from_guess(ph_guess),
+    )
+    .ok()
+    .map(|solution| (total_carbon_m, na_conc, solution))
+}
 
-    let total_uptake_g = actual_rows.last().and_then(|row| row.cumulative_co2_g);
-    let total_uptake_mol = actual_rows.last().and_then(|row| row.cumulative_co2_mol);
-    let co2_mw_value = if co2_mw.is_finite() && co2_mw > 0.0 {
-        co2_mw
-    } else {
-        SOL_MW_CO2
+/// Ionic strength and activity coefficients from [`ledger_speciation`].
+fn ledger_ionic_state(
+    state: LedgerState,
+    volume_l: f64,
+    settings: &SolverSettings,
+    ph_guess: f64,
+) -> Option<(f64, [f64; 5])> {
+    ledger_speciation(state, volume_l, settings, ph_guess)
+        .map(|(_, _, (_, _, _, _, _, gammas, ionic_strength, _))| (ionic_strength, gammas))
+}
+
+/// Exact local slope `d pH / d C_T` (pH per mol/L of added carbon, sodium fixed)
+/// of a solved closed-carbon state, by implicit differentiation.
+///
+/// Only the carbon-balance row depends on `C_T` directly (`∂F/∂C_T = -1`), so
+/// `J·dx/dC_T = e_carbon` over the log-space unknowns and the slope is minus the
+/// `log10 h` component. `None` when the Jacobian is singular at the state.
+fn carbonate_ph_sensitivity(
+    solution: &CarbonateSolution,
+    total_carbon_m: f64,
+    na_conc: f64,
+    settings: &SolverSettings,
+) -> Option<f64> {
+    let (h, hco3, co3, h2co3, ..) = *solution;
+    let log_vars: Vec<f64> =
+        [h, hco3, co3, h2co3].iter().map(|value| value.max(1e-300).log10()).collect();
+    let residuals = closed_carbonate_residuals(
+        CarbonateSystem::new(
+            total_carbon_m.max(1e-16),
+            na_conc.max(0.0),
+            settings.equilibrium_constants(),
+        ),
+        settings.activity(),
+    );
+    let jacobian = numerical_jacobian(&residuals, &log_vars, 1e-6);
+    let tangent = solve_linear_system(&jacobian, &[0.0, 0.0, 1.0, 0.0]).ok()?;
+    Some(-tangent[0]).filter(|slope| slope.is_finite())
+}
+
+/// Apply one CO2 dose to a ledger and estimate the resulting pH.
+///
+/// A positive `delta_mol` stages through caustic, then carbonate, into excess
+/// CO2. A negative one degasses: it strips dissolved excess CO2 only, since the
+/// carbonate and bicarbonate pools do not give CO2 back on their own. Callers
+/// reject removals larger than the excess pool (see [`validate_co2_removal`]);
+/// here they stop at an empty pool.
+fn simulate_reaction_state_with_accounting_impl(
+    ledger: LedgerState,
+    delta_mol: f64,
+    solve: &LedgerSolve,
+    initial_ph_guess: Option<f64>,
+    observer: Option<NewtonIterationObserver<'_>>,
+    warm_seed: Option<(f64, f64, f64)>,
+) -> (LedgerState, AccountingState, f64, Option<f64>, Option<f64>, Vec<PlanningAdjustment>) {
+    let mut extra = delta_mol.max(0.0);
+    let mut naoh_free = ledger.naoh_remaining_mol.max(0.0);
+    let mut co3 = ledger.na2co3_mol.max(0.0);
+    let mut hco3 = ledger.nahco3_mol.max(0.0);
+    let degassed = (-delta_mol).max(0.0).min(ledger.co2_excess_mol.max(0.0));
+    let mut excess = ledger.co2_excess_mol.max(0.0) - degassed;
+    let mut consumed_to_carbonate = 0.0;
+    let mut consumed_to_bicarbonate = 0.0;
+    if naoh_free > 0.0 {
+        let needed = naoh_free / 2.0;
+        let consume = extra.min(needed);
+        consumed_to_carbonate = consume;
+        naoh_free = (naoh_free - consume * 2.0).max(0.0);
+        co3 += consume;
+        extra -= consume;
+    }
+    if co3 > 0.0 && extra > 0.0 {
+        let convert = extra.min(co3);
+        consumed_to_bicarbonate = convert;
+        co3 -= convert;
+        hco3 += convert * 2.0;
+        extra -= convert;
+    }
+    excess += extra;
+    let state = LedgerState {
+        naoh_remaining_mol: naoh_free,
+        na2co3_mol: co3,
+        nahco3_mol: hco3,
+        co2_excess_mol: excess,
     };
-    let naoh_mw_value = if naoh_mw.is_finite() && naoh_mw > 0.0 {
-        naoh_mw
+    let ratio_hint = co3 / hco3.max(1e-12);
+    let hint_ph = hh_ph_from_ratio(ratio_hint.max(1e-12), solve.pka2_value);
+    let guess = Some(initial_ph_guess.unwrap_or(hint_ph));
+    let solve_state = solve.excess_co2_fate.solve_basis(state);
+    let (ph, residual_norm, ionic_strength, adjustments) = if solve.planning_mode {
+        estimate_ledger_ph_planning(solve_state, solve, guess, observer, warm_seed)
     } else {
-        SOL_MW_NAOH
+        let (ph, residual_norm, ionic_strength, rescue) =
+            estimate_ledger_ph(solve_state, solve, guess, observer, warm_seed);
+        (ph, residual_norm, ionic_strength, rescue.into_iter().collect())
     };
-    let theoretical_yield_g = if let Some(start_mass_g) = reaction_naoh_mass {
-        if start_mass_g.is_finite() && start_mass_g > 0.0 && naoh_mw_value > 0.0 {
-            Some((start_mass_g / naoh_mw_value) * co2_mw_value)
-        } else {
-            None
-        }
-    } else {
-        None
+    let accounting = AccountingState {
+        co2_consumed_to_carbonate_mol: consumed_to_carbonate,
+        co2_consumed_to_bicarbonate_mol: consumed_to_bicarbonate,
+        co2_consumed_total_mol: consumed_to_carbonate + consumed_to_bicarbonate,
+        co2_unconsumed_mol: extra.max(0.0),
+        co2_degassed_mol: degassed,
+        naoh_consumed_mol: (ledger.naoh_remaining_mol.max(0.0) - naoh_free).max(0.0),
+        free_caustic_m: solve
+            .solution_volume_l
+            .filter(|volume| *volume > 0.0)
+            .map(|volume| naoh_free / volume),
     };
-    let actual_yield_pct = match (total_uptake_g, theoretical_yield_g) {
-        (Some(total), Some(theoretical)) if theoretical > 1e-12 => Some((total / theoretical) * 100.0),
-        _ => None,
+    (state, accounting, ph, residual_norm, ionic_strength, adjustments)
+}
+
+/// A negative CO2 dose is a degassing step and may only remove the dissolved
+/// excess CO2 the ledger holds; anything more would have to come out of the
+/// sodium carbonate pools, which the staging does not reverse.
+fn validate_co2_removal(ledger: LedgerState, delta_mol: f64) -> PyResult<()> {
+    if !delta_mol.is_finite() {
+        return Err(PyValueError::new_err("delta_mol must be finite"));
+    }
+    let available = ledger.co2_excess_mol.max(0.0);
+    if -delta_mol > available * (1.0 + STOICHIOMETRY_AUDIT_REL_TOL) {
+        return Err(PyValueError::new_err(format!(
+            "delta_mol {delta_mol} removes more CO2 than the {available} mol of dissolved \
+             excess CO2 the ledger holds; only excess CO2 can be degassed"
+        )));
+    }
+    Ok(())
+}
+
+/// Spike a ledger with solid Na2CO3/NaHCO3 and re-settle the pools.
+///
+/// Added bicarbonate first neutralizes any free NaOH to carbonate, and any excess
+/// dissolved CO2 then converts carbonate to bicarbonate, mirroring the staging
+/// order used for CO2 doses.
+fn add_salt_impl(ledger: LedgerState, na2co3_mol: f64, nahco3_mol: f64) -> LedgerState {
+    let mut naoh = ledger.naoh_remaining_mol.max(0.0);
+    let mut co3 = ledger.na2co3_mol.max(0.0) + na2co3_mol.max(0.0);
+    let mut hco3 = ledger.nahco3_mol.max(0.0) + nahco3_mol.max(0.0);
+    let mut excess = ledger.co2_excess_mol.max(0.0);
+    let neutralized = naoh.min(hco3);
+    naoh -= neutralized;
+    hco3 -= neutralized;
+    co3 += neutralized;
+    let converted = excess.min(co3);
+    excess -= converted;
+    co3 -= converted;
+    hco3 += converted * 2.0;
+    LedgerState {
+        naoh_remaining_mol: naoh,
+        na2co3_mol: co3,
+        nahco3_mol: hco3,
+        co2_excess_mol: excess,
+    }
+}
+
+/// Neutralize a ledger with a strong monoprotic acid, strongest base first: free
+/// NaOH, then carbonate to bicarbonate, then bicarbonate to dissolved CO2.
+///
+/// The sodium each proton frees pairs with the spectator anion and drops out of
+/// the ledger, so the pools keep only the alkalinity-carrying sodium; the salt's
+/// own ionic strength is not tracked. Acid beyond the ledger's alkalinity (see
+/// [`ledger_alkalinity_mol`]) has nothing left to react with and is ignored.
+fn add_strong_acid_impl(ledger: LedgerState, acid_mol: f64) -> LedgerState {
+    let mut acid = acid_mol.max(0.0);
+    let mut naoh = ledger.naoh_remaining_mol.max(0.0);
+    let mut co3 = ledger.na2co3_mol.max(0.0);
+    let mut hco3 = ledger.nahco3_mol.max(0.0);
+    let mut excess = ledger.co2_excess_mol.max(0.0);
+    let neutralized = naoh.min(acid);
+    naoh -= neutralized;
+    acid -= neutralized;
+    let protonated = co3.min(acid);
+    co3 -= protonated;
+    hco3 += protonated;
+    acid -= protonated;
+    let released = hco3.min(acid);
+    hco3 -= released;
+    excess += released;
+    LedgerState {
+        naoh_remaining_mol: naoh,
+        na2co3_mol: co3,
+        nahco3_mol: hco3,
+        co2_excess_mol: excess,
+    }
+}
+
+/// Strip `removed_mol` of CO2 from a ledger, reversing the dose staging: dissolved
+/// excess CO2 goes first, then bicarbonate gives CO2 back as
+/// `2 NaHCO3 -> Na2CO3 + CO2 + H2O`. Removal beyond `excess + NaHCO3/2` has no
+/// source and stops there. Returns the new ledger and the CO2 taken from the
+/// excess and bicarbonate pools.
+fn degas_ledger_impl(ledger: LedgerState, removed_mol: f64) -> (LedgerState, f64, f64) {
+    let excess = ledger.co2_excess_mol.max(0.0);
+    let hco3 = ledger.nahco3_mol.max(0.0);
+    let from_excess = removed_mol.max(0.0).min(excess);
+    let from_bicarbonate = (removed_mol.max(0.0) - from_excess).min(hco3 / 2.0);
+    let state = LedgerState {
+        naoh_remaining_mol: ledger.naoh_remaining_mol.max(0.0),
+        na2co3_mol: ledger.na2co3_mol.max(0.0) + from_bicarbonate,
+        nahco3_mol: hco3 - 2.0 * from_bicarbonate,
+        co2_excess_mol: excess - from_excess,
     };
-    let reference_final_g = reference_rows.last().and_then(|row| row.cumulative_co2_g);
-    let planning_completion_pct = match (reference_final_g, total_uptake_g) {
-        (Some(reference), Some(total)) if reference > 1e-12 => {
-            Some((total / reference).clamp(0.0, 1.0) * 100.0)
-        }
-        _ => None,
+    (state, from_excess, from_bicarbonate)
+}
+
+/// Strong acid a ledger can take up: `NaOH + 2·Na2CO3 + NaHCO3` in mol.
+fn ledger_alkalinity_mol(ledger: LedgerState) -> f64 {
+    ledger.naoh_remaining_mol.max(0.0)
+        + 2.0 * ledger.na2co3_mol.max(0.0)
+        + ledger.nahco3_mol.max(0.0)
+}
+
+/// Combine two ledgers mole-for-mole and re-settle the pools.
+///
+/// Dissolved CO2 from one batch first carbonates free NaOH from the other
+/// (stage 1); whatever is left then reacts as in [`add_salt_impl`], so the blend
+/// never carries free NaOH alongside bicarbonate or excess CO2.
+fn blend_ledgers_impl(a: LedgerState, b: LedgerState) -> LedgerState {
+    let mut naoh = a.naoh_remaining_mol.max(0.0) + b.naoh_remaining_mol.max(0.0);
+    let mut co3 = a.na2co3_mol.max(0.0) + b.na2co3_mol.max(0.0);
+    let mut excess = a.co2_excess_mol.max(0.0) + b.co2_excess_mol.max(0.0);
+    let carbonated = excess.min(naoh / 2.0);
+    excess -= carbonated;
+    naoh -= carbonated * 2.0;
+    co3 += carbonated;
+    let combined = LedgerState {
+        naoh_remaining_mol: naoh,
+        na2co3_mol: co3,
+        nahco3_mol: a.nahco3_mol.max(0.0) + b.nahco3_mol.max(0.0),
+        co2_excess_mol: excess,
     };
-    let equivalence_co2_g = match initial_naoh_mol {
-        Some(moles) if moles.is_finite() && moles > 0.0 => Some((moles / 2.0) * co2_mw_value),
-        _ => None,
+    add_salt_impl(combined, 0.0, 0.0)
+}
+
+/// Worst of two optional solver residuals; `None` only when neither side solved.
+fn max_optional_residual(current: Option<f64>, next: Option<f64>) -> Option<f64> {
+    match (current, next) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Back-solve the CO2 absorption efficiency that reproduces a measured pH.
+///
+/// The pH of a fresh NaOH charge falls monotonically as more of the charged CO2
+/// reacts, so the efficiency is bracketed on `[0, 1]` and bisected. When even the
+/// endpoints cannot reach the measurement, the nearer endpoint is returned with
+/// the clamped flag set.
+/// Output: `(efficiency, simulated_ph, residual, clamped, iterations)` where the
+/// residual is simulated minus measured pH.
+fn fit_absorption_efficiency_impl(
+    naoh_mol: f64,
+    co2_mol: f64,
+    measured_ph: f64,
+    solve: &LedgerSolve,
+    tolerance: f64,
+    max_iter: usize,
+) -> (f64, f64, f64, bool, usize) {
+    let simulate_ph = |efficiency: f64| -> f64 {
+        let charge = LedgerState {
+            naoh_remaining_mol: naoh_mol,
+            na2co3_mol: 0.0,
+            nahco3_mol: 0.0,
+            co2_excess_mol: 0.0,
+        };
+        simulate_reaction_state_with_accounting_impl(
+            charge,
+            co2_mol * efficiency,
+            solve,
+            None,
+            None,
+            None,
+        )
+        .2
     };
-    let equivalence_completion_pct = match (equivalence_co2_g, total_uptake_g) {
-        (Some(reference), Some(total)) if reference > 1e-12 => {
-            Some((total / reference).clamp(0.0, 1.0) * 100.0)
+    let ph_none = simulate_ph(0.0);
+    if ph_none - measured_ph <= tolerance {
+        return (0.0, ph_none, ph_none - measured_ph, ph_none < measured_ph - tolerance, 0);
+    }
+    let ph_full = simulate_ph(1.0);
+    if ph_full - measured_ph >= -tolerance {
+        return (1.0, ph_full, ph_full - measured_ph, ph_full > measured_ph + tolerance, 0);
+    }
+    let mut lo = 0.0_f64;
+    let mut hi = 1.0_f64;
+    let mut best = (1.0_f64, ph_full);
+    let mut iterations = 0usize;
+    for _ in 0..max_iter.max(1) {
+        iterations += 1;
+        let mid = 0.5 * (lo + hi);
+        let ph_mid = simulate_ph(mid);
+        best = (mid, ph_mid);
+        let residual = ph_mid - measured_ph;
+        if residual.abs() <= tolerance || (hi - lo) < 1e-12 {
+            break;
         }
-        _ => None,
+        if residual > 0.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    (best.0, best.1, best.1 - measured_ph, false, iterations)
+}
+
+/// Reverse-solve the CO2 dose that brings a ledger down to a target pH.
+///
+/// The simulated pH falls monotonically with added CO2, so the dose is bracketed
+/// by doubling an upper bound from the ledger's sodium inventory and then bisected.
+/// Returns `Some(0.0)` when the ledger already sits at or below the target and
+/// `None` when no bounded dose reaches it.
+fn co2_dose_for_target_ph_impl(
+    base: LedgerState,
+    target_ph: f64,
+    solve: &LedgerSolve,
+) -> Option<f64> {
+    let simulate_ph = |dose_mol: f64| -> f64 {
+        simulate_reaction_state_with_accounting_impl(base, dose_mol, solve, None, None, None).2
     };
-    let mut equivalence_cycle_actual: Option<usize> = None;
-    if let Some(eq_target_g) = equivalence_co2_g {
-        for row in &actual_rows {
-            if let Some(cumulative_g) = row.cumulative_co2_g {
-                if cumulative_g >= eq_target_g {
-                    equivalence_cycle_actual = Some(row.cycle_id);
-                    break;
-                }
-            }
+    if !target_ph.is_finite() {
+        return None;
+    }
+    if simulate_ph(0.0) <= target_ph {
+        return Some(0.0);
+    }
+    let sodium_mol = base.naoh_remaining_mol.max(0.0)
+        + base.nahco3_mol.max(0.0)
+        + 2.0 * base.na2co3_mol.max(0.0);
+    let mut hi = sodium_mol.max(1e-9);
+    let mut bracketed = false;
+    for _ in 0..60 {
+        if simulate_ph(hi) <= target_ph {
+            bracketed = true;
+            break;
         }
+        hi *= 2.0;
     }
-    let mut equivalence_cycle_reference: Option<usize> = None;
-    for row in &reference_rows {
-        if row.equivalence_reached {
-            equivalence_cycle_reference = Some(row.cycle_id);
+    if !bracketed {
+        return None;
+    }
+    let mut lo = 0.0_f64;
+    for _ in 0..100 {
+        let mid = 0.5 * (lo + hi);
+        if simulate_ph(mid) > target_ph {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+        if (hi - lo) <= 1e-12 * hi.max(1e-12) {
             break;
         }
     }
-    let additional_co2_required_g = match (reference_final_g, total_uptake_g) {
-        (Some(reference), Some(total)) if reference.is_finite() && total.is_finite() => {
-            Some((reference - total).max(0.0))
-        }
-        _ => None,
+    Some(0.5 * (lo + hi))
+}
+
+/// Infer the total inorganic carbon that yields a measured pH at known sodium.
+///
+/// The solved pH falls monotonically with carbon at fixed sodium, so log10 of the
+/// carbon concentration is bisected from a trace level (a thousandth of the
+/// sodium) to well past the bicarbonate point. Strongly caustic, carbon-poor
+/// liquors that the speciation solver cannot converge are stepped past, raising
+/// the lower edge. Returns `None` when the measurement lies outside the bracket;
+/// otherwise `(total_carbon_m, solution, iterations)`.
+fn infer_total_carbon_impl(
+    na_conc: f64,
+    measured_ph: f64,
+    settings: &SolverSettings,
+    tolerance: f64,
+    max_iter: usize,
+) -> Option<(f64, CarbonateSolution, usize)> {
+    if !measured_ph.is_finite() {
+        return None;
+    }
+    let eq_constants = settings.equilibrium_constants();
+    let solve_at = |log_ct: f64, guess: f64| {
+        solve_carbonate_state(
+            CarbonateSystem::new(10f64.powf(log_ct), na_conc, eq_constants),
+            settings.activity(),
+            NewtonOptions::from_guess(guess),
+        )
+        .ok()
     };
-    let additional_co2_required_mol = match additional_co2_required_g {
-        Some(value) if co2_mw_value > 0.0 => Some(value / co2_mw_value),
-        _ => None,
+    let solved_ph = |solution: &CarbonateSolution| -solution.0.max(1e-30).log10();
+    let mut lo = (na_conc * 1e-3).max(1e-10).log10();
+    let mut hi = (4.0 * na_conc).max(1.0).log10() + 1.0;
+    let high_carbon = solve_at(hi, measured_ph)?;
+    let mut low_carbon = None;
+    while lo < hi {
+        low_carbon = solve_at(lo, measured_ph);
+        if low_carbon.is_some() {
+            break;
+        }
+        lo += 0.25;
+    }
+    let low_carbon = low_carbon?;
+    if measured_ph > solved_ph(&low_carbon) || measured_ph < solved_ph(&high_carbon) {
+        return None;
+    }
+    let mut best = low_carbon;
+    let mut guess = measured_ph;
+    let mut iterations = 0;
+    while iterations < max_iter {
+        iterations += 1;
+        let mid = 0.5 * (lo + hi);
+        let Some(solution) = solve_at(mid, guess) else {
+            lo = mid;
+            continue;
+        };
+        let ph = solved_ph(&solution);
+        guess = ph;
+        best = solution;
+        if (ph - measured_ph).abs() <= tolerance {
+            return Some((10f64.powf(mid), best, iterations));
+        }
+        if ph > measured_ph {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Some((10f64.powf(0.5 * (lo + hi)), best, iterations))
+}
+
+/// Sodium concentration that brings `total_carbon_m` to `target_ph`, by bisection.
+///
+/// The solved pH rises monotonically with sodium, so the bracket runs from the
+/// sodium-free carbonic-acid liquor up to full carbonate plus the ideal free OH-
+/// of the target, doubled a few times when activity corrections leave it short.
+/// `None` when the target lies outside that bracket.
+fn design_sodium_impl(
+    total_carbon_m: f64,
+    target_ph: f64,
+    settings: &SolverSettings,
+    tolerance: f64,
+    max_iter: usize,
+) -> Option<(f64, CarbonateSolution, usize)> {
+    if !target_ph.is_finite() {
+        return None;
+    }
+    let (ka1, ka2, kw) = settings.equilibrium_constants();
+    let solve_at = |na_conc: f64, guess: f64| {
+        solve_carbonate_state(
+            CarbonateSystem::new(total_carbon_m, na_conc, (ka1, ka2, kw)),
+            settings.activity(),
+            NewtonOptions::from_guess(guess),
+        )
+        .ok()
     };
-    let mut forecast_confidence = "low".to_string();
-    let mut forecast_slowdown_detected = false;
-    let mut forecast_lookback_cycles: usize = 0;
-    let mut forecast_knee_cycle: Option<usize> = None;
-    let mut forecast_pre_knee_rate_g_per_cycle: Option<f64> = None;
-    let mut forecast_post_knee_rate_g_per_cycle: Option<f64> = None;
-    let mut forecast_tail_rate_g_per_cycle: Option<f64> = None;
-    let mut forecast_remaining_cycles: Option<f64> = None;
-    let mut forecast_remaining_time_x: Option<f64> = None;
-    let mut forecast_time_basis = "cycles_only".to_string();
-    let remaining_target_g = additional_co2_required_g.unwrap_or(0.0);
-    if remaining_target_g <= 1e-12 {
-        forecast_confidence = "high".to_string();
-        forecast_remaining_cycles = Some(0.0);
-        forecast_remaining_time_x = Some(0.0);
+    let solved_ph = |solution: &CarbonateSolution| -solution.0.max(1e-30).log10();
+    let free_oh = 10f64.powf(target_ph + kw.max(1e-30).log10());
+    let mut lo = 0.0_f64;
+    let mut hi = 2.0 * total_carbon_m + free_oh;
+    let mut high_sodium = solve_at(hi, target_ph)?;
+    for _ in 0..8 {
+        if solved_ph(&high_sodium) >= target_ph {
+            break;
+        }
+        hi *= 2.0;
+        high_sodium = solve_at(hi, target_ph)?;
+    }
+    let low_sodium = solve_at(lo, target_ph)?;
+    if target_ph < solved_ph(&low_sodium) || target_ph > solved_ph(&high_sodium) {
+        return None;
+    }
+    let mut best = high_sodium;
+    let mut guess = target_ph;
+    let mut iterations = 0;
+    while iterations < max_iter {
+        iterations += 1;
+        let mid = 0.5 * (lo + hi);
+        let Some(solution) = solve_at(mid, guess) else {
+            hi = mid;
+            continue;
+        };
+        let ph = solved_ph(&solution);
+        guess = ph;
+        best = solution;
+        if (ph - target_ph).abs() <= tolerance {
+            return Some((mid, best, iterations));
+        }
+        if ph < target_ph {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Some((0.5 * (lo + hi), best, iterations))
+}
+
+fn dict_float_value(dict: &Bound<'_, PyDict>, key: &str) -> f64 {
+    dict.get_item(key)
+        .ok()
+        .flatten()
+        .and_then(|value| value.extract::<f64>().ok())
+        .unwrap_or(0.0)
+}
+
+fn dict_optional_float_value(dict: &Bound<'_, PyDict>, key: &str) -> Option<f64> {
+    let value = dict.get_item(key).ok().flatten()?;
+    let parsed = value.extract::<f64>().ok()?;
+    if parsed.is_finite() {
+        Some(parsed)
     } else {
-        let mut cycle_uptakes_g: Vec<f64> = Vec::new();
-        let mut durations_x: Vec<Option<f64>> = Vec::new();
-        let mut cumulative_known = false;
-        let mut last_cumulative = 0.0_f64;
-        for row in &actual_rows {
-            let mut cycle_mass = row.cycle_co2_g;
-            if cycle_mass.is_none() {
-                if let Some(cumulative) = row.cumulative_co2_g {
-                    if cumulative_known {
-                        cycle_mass = Some((cumulative - last_cumulative).max(0.0));
-                    } else {
-                        cycle_mass = Some(cumulative.max(0.0));
-                    }
-                    cumulative_known = true;
-                    last_cumulative = cumulative;
-                }
-            }
-            cycle_uptakes_g.push(cycle_mass.unwrap_or(0.0).max(0.0));
-            let duration = row.duration_x.and_then(|value| {
-                if value.is_finite() && value > 0.0 {
-                    Some(value)
-                } else {
-                    None
+        None
+    }
+}
+
+/// Resolve an activity-model token plus optional SIT ε overrides.
+///
+/// Unknown tokens raise `ValueError` listing the accepted names. SIT overrides are
+/// read from `eps_na_hco3`/`eps_na_co3`/`eps_na_oh`; missing keys keep the NEA-TDB
+/// defaults.
+fn parse_activity_model(
+    token: &str,
+    sit_coefficients: Option<&Bound<'_, PyDict>>,
+    pitzer_params: Option<&Bound<'_, PyDict>>,
+) -> PyResult<ActivityModel> {
+    match token.trim().to_ascii_lowercase().as_str() {
+        ACTIVITY_MODEL_HYBRID => Ok(ActivityModel::Hybrid),
+        ACTIVITY_MODEL_IDEAL => Ok(ActivityModel::Ideal),
+        ACTIVITY_MODEL_DAVIES => Ok(ActivityModel::Davies),
+        ACTIVITY_MODEL_GUNTELBERG => Ok(ActivityModel::Guntelberg),
+        ACTIVITY_MODEL_DEBYE_HUCKEL => Ok(ActivityModel::DebyeHuckel),
+        ACTIVITY_MODEL_PITZER => parse_pitzer_params_map(pitzer_params)
+            .map(ActivityModel::Pitzer)
+            .ok_or_else(|| {
+                PyValueError::new_err(
+                    "activity_model 'pitzer' requires a complete pitzer_params mapping",
+                )
+            }),
+        ACTIVITY_MODEL_SIT => {
+            let mut eps = SitCoefficients::default();
+            if let Some(overrides) = sit_coefficients {
+                if let Some(value) = dict_optional_float_value(overrides, "eps_na_hco3") {
+                    eps.eps_na_hco3 = value;
                 }
-            });
-            durations_x.push(duration);
-        }
-        let detected_cycles = cycle_uptakes_g.len();
-        if detected_cycles >= 4 {
-            let mut lookback = ((detected_cycles as f64) * 0.2_f64).round() as usize;
-            lookback = lookback.clamp(4, 12).min(detected_cycles);
-            forecast_lookback_cycles = lookback;
-            let recent = &cycle_uptakes_g[detected_cycles - lookback..];
-            if recent.len() >= 4 {
-                let mut best_split: Option<usize> = None;
-                let mut best_sse = f64::INFINITY;
-                for split_idx in 2..(recent.len() - 1) {
-                    let pre = &recent[..split_idx];
-                    let post = &recent[split_idx..];
-                    let pre_mean = pre.iter().sum::<f64>() / pre.len() as f64;
-                    let post_mean = post.iter().sum::<f64>() / post.len() as f64;
-                    let pre_sse = pre
-                        .iter()
-                        .map(|value| (value - pre_mean) * (value - pre_mean))
-                        .sum::<f64>();
-                    let post_sse = post
-                        .iter()
-                        .map(|value| (value - post_mean) * (value - post_mean))
-                        .sum::<f64>();
-                    let sse = pre_sse + post_sse;
-                    if sse < best_sse {
-                        best_sse = sse;
-                        best_split = Some(split_idx);
-                    }
+                if let Some(value) = dict_optional_float_value(overrides, "eps_na_co3") {
+                    eps.eps_na_co3 = value;
                 }
-                if let Some(split_idx) = best_split {
-                    let pre = &recent[..split_idx];
-                    let post = &recent[split_idx..];
-                    let pre_rate = pre.iter().sum::<f64>() / pre.len() as f64;
-                    let post_rate = post.iter().sum::<f64>() / post.len() as f64;
-                    forecast_pre_knee_rate_g_per_cycle = Some(pre_rate);
-                    forecast_post_knee_rate_g_per_cycle = Some(post_rate);
-                    forecast_knee_cycle = Some(detected_cycles - lookback + split_idx);
-                    forecast_slowdown_detected =
-                        pre_rate > 1e-12 && post_rate <= (pre_rate * 0.92_f64);
-                    let tail_window = recent.len().clamp(3, 5);
-                    let tail_rate = if forecast_slowdown_detected && post_rate > 1e-12 {
-                        post_rate
-                    } else {
-                        recent[recent.len() - tail_window..].iter().sum::<f64>()
-                            / tail_window as f64
-                    };
-                    if tail_rate > 1e-12 && tail_rate.is_finite() {
-                        forecast_tail_rate_g_per_cycle = Some(tail_rate);
-                        let remaining_cycles = (remaining_target_g / tail_rate).max(0.0);
-                        forecast_remaining_cycles = Some(remaining_cycles);
-                        let post_variance = post
-                            .iter()
-                            .map(|value| (value - post_rate) * (value - post_rate))
-                            .sum::<f64>()
-                            / post.len().max(1) as f64;
-                        let post_cv = if post_rate.abs() > 1e-12 {
-                            post_variance.sqrt() / post_rate.abs()
-                        } else {
-                            f64::INFINITY
-                        };
-                        if detected_cycles >= 8 && forecast_slowdown_detected && post_cv <= 0.25 {
-                            forecast_confidence = "high".to_string();
-                        } else if detected_cycles >= 6 && post_cv <= 0.45 {
-                            forecast_confidence = "medium".to_string();
-                        }
-                        let time_label_lower = inferred_time_label.trim().to_lowercase();
-                        let is_time_basis = ["elapsed", "time", "sec", "second", "min", "hour", "day"]
-                            .iter()
-                            .any(|token| time_label_lower.contains(token));
-                        if is_time_basis {
-                            let finite_durations: Vec<f64> = durations_x
-                                [durations_x.len().saturating_sub(lookback)..]
-                                .iter()
-                                .filter_map(|value| *value)
-                                .collect();
-                            if !finite_durations.is_empty() {
-                                let avg_duration =
-                                    finite_durations.iter().sum::<f64>() / finite_durations.len() as f64;
-                                forecast_remaining_time_x = Some(remaining_cycles * avg_duration);
-                                forecast_time_basis = if inferred_time_label.trim().is_empty() {
-                                    "elapsed_time".to_string()
-                                } else {
-                                    inferred_time_label.trim().to_string()
-                                };
-                            }
-                        }
-                    }
+                if let Some(value) = dict_optional_float_value(overrides, "eps_na_oh") {
+                    eps.eps_na_oh = value;
                 }
             }
+            Ok(ActivityModel::Sit(eps))
         }
+        other => Err(PyValueError::new_err(format!(
+            "Unknown activity_model '{other}'; expected one of: {ACTIVITY_MODEL_HYBRID}, \
+             {ACTIVITY_MODEL_DAVIES}, {ACTIVITY_MODEL_GUNTELBERG}, {ACTIVITY_MODEL_DEBYE_HUCKEL}, \
+             {ACTIVITY_MODEL_PITZER}, {ACTIVITY_MODEL_SIT}, {ACTIVITY_MODEL_IDEAL}"
+        ))),
     }
-    let warning_rows = PyList::empty(py);
-    if reference_rows.is_empty() && !actual_rows.is_empty() {
-        warning_rows.append(
-            "Reference simulation unavailable; verify per-cycle CO2 uptake fields.",
-        )?;
+}
+
+/// Resolve the equilibrium-constant inputs of a kernel. A raw `(ka1, ka2, kw)`
+/// tuple takes precedence; otherwise `(pKa1, pKa2, pKw)` is converted with
+/// `K = 10^-pK`. `None` from both leaves the kernel on its built-in constants.
+/// Raw constants must be finite and strictly positive; a zero or negative entry
+/// (usually a pK passed as a K) would otherwise surface as NaN deep in the solver.
+fn resolve_equilibrium_constants(
+    constants: Option<(f64, f64, f64)>,
+    pka_constants: Option<(f64, f64, f64)>,
+) -> PyResult<Option<(f64, f64, f64)>> {
+    if let Some((ka1, ka2, kw)) = constants {
+        for (name, value) in [("ka1", ka1), ("ka2", ka2), ("kw", kw)] {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(PyValueError::new_err(format!(
+                    "{name} must be positive, got {value}; pass pK values via pka_constants"
+                )));
+            }
+        }
+        return Ok(constants);
+    }
+    let Some((pka1, pka2, pkw)) = pka_constants else {
+        return Ok(None);
+    };
+    if !(pka1.is_finite() && pka2.is_finite() && pkw.is_finite()) {
+        return Err(PyValueError::new_err("pka_constants entries must be finite"));
     }
+    Ok(Some((10f64.powf(-pka1), 10f64.powf(-pka2), 10f64.powf(-pkw))))
+}
 
-    let summary = PyDict::new(py);
-    summary.set_item("detected_cycles", actual_rows.len())?;
-    summary.set_item("reference_cycles", reference_rows.len())?;
-    summary.set_item("target_ph", target_ph)?;
-    if let Some(value) = total_uptake_g {
-        summary.set_item("total_uptake_g", value)?;
-    } else {
-        summary.set_item("total_uptake_g", py.None())?;
+fn parse_excess_co2_fate(token: &str) -> PyResult<ExcessCo2Fate> {
+    match token.trim().to_ascii_lowercase().as_str() {
+        EXCESS_CO2_FATE_DISSOLVED => Ok(ExcessCo2Fate::Dissolved),
+        EXCESS_CO2_FATE_OUTGASSED => Ok(ExcessCo2Fate::Outgassed),
+        other => Err(PyValueError::new_err(format!(
+            "Unknown excess_co2_fate '{other}'; expected one of: {EXCESS_CO2_FATE_DISSOLVED}, \
+             {EXCESS_CO2_FATE_OUTGASSED}"
+        ))),
     }
-    if let Some(value) = total_uptake_mol {
-        summary.set_item("total_uptake_mol", value)?;
-    } else {
-        summary.set_item("total_uptake_mol", py.None())?;
+}
+
+/// Gas-volume convention from its token; an explicit `molar_volume_l` overrides it.
+fn parse_gas_volume_basis(token: &str, molar_volume_l: Option<f64>) -> PyResult<GasVolumeBasis> {
+    if let Some(volume) = molar_volume_l {
+        if !volume.is_finite() || volume <= 0.0 {
+            return Err(PyValueError::new_err("molar_volume_l must be positive and finite"));
+        }
+        return Ok(GasVolumeBasis::Custom(volume));
     }
-    if let Some(value) = theoretical_yield_g {
-        summary.set_item("theoretical_yield_g", value)?;
-    } else {
-        summary.set_item("theoretical_yield_g", py.None())?;
+    match token.trim().to_ascii_lowercase().as_str() {
+        GAS_VOLUME_BASIS_STP => Ok(GasVolumeBasis::Stp),
+        GAS_VOLUME_BASIS_NTP => Ok(GasVolumeBasis::Ntp),
+        other => Err(PyValueError::new_err(format!(
+            "Unknown gas_volume_basis '{other}'; expected one of: {GAS_VOLUME_BASIS_STP}, \
+             {GAS_VOLUME_BASIS_NTP}"
+        ))),
     }
-    if let Some(value) = actual_yield_pct {
-        summary.set_item("actual_yield_pct", value)?;
-    } else {
-        summary.set_item("actual_yield_pct", py.None())?;
+}
+
+fn parse_reagent(token: &str) -> PyResult<Reagent> {
+    match token.trim().to_ascii_lowercase().as_str() {
+        REAGENT_CO2 => Ok(Reagent::Co2),
+        REAGENT_STRONG_BASE => Ok(Reagent::StrongBase),
+        REAGENT_STRONG_ACID => Ok(Reagent::StrongAcid),
+        REAGENT_NA2CO3 => Ok(Reagent::Na2co3),
+        REAGENT_NAHCO3 => Ok(Reagent::Nahco3),
+        other => Err(PyValueError::new_err(format!(
+            "Unknown reagent '{other}'; expected one of: {REAGENT_CO2}, {REAGENT_STRONG_BASE}, \
+             {REAGENT_STRONG_ACID}, {REAGENT_NA2CO3}, {REAGENT_NAHCO3}"
+        ))),
     }
-    if let Some(value) = planning_completion_pct {
-        summary.set_item("planning_completion_pct", value)?;
-    } else {
-        summary.set_item("planning_completion_pct", py.None())?;
+}
+
+fn reagent_name(reagent: Reagent) -> &'static str {
+    match reagent {
+        Reagent::Co2 => REAGENT_CO2,
+        Reagent::StrongBase => REAGENT_STRONG_BASE,
+        Reagent::StrongAcid => REAGENT_STRONG_ACID,
+        Reagent::Na2co3 => REAGENT_NA2CO3,
+        Reagent::Nahco3 => REAGENT_NAHCO3,
     }
-    if let Some(value) = equivalence_completion_pct {
-        summary.set_item("equivalence_completion_pct", value)?;
-    } else {
-        summary.set_item("equivalence_completion_pct", py.None())?;
+}
+
+/// Read optional `ph_min`/`ph_max`/`temperature_min_c`/`temperature_max_c`
+/// overrides on top of the default clamp ranges. A `None` value keeps the default;
+/// unknown keys and non-numeric or non-finite values raise `ValueError`.
+fn parse_clamp_bounds(overrides: Option<&Bound<'_, PyDict>>) -> PyResult<ClampBounds> {
+    let mut bounds = ClampBounds::default();
+    for (key, value) in overrides.into_iter().flat_map(|overrides| overrides.iter()) {
+        let key = key.extract::<String>().unwrap_or_else(|_| key.to_string());
+        let slot = match key.as_str() {
+            "ph_min" => &mut bounds.ph_min,
+            "ph_max" => &mut bounds.ph_max,
+            "temperature_min_c" => &mut bounds.temperature_min_c,
+            "temperature_max_c" => &mut bounds.temperature_max_c,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown clamp_bounds key '{key}'; expected ph_min, ph_max, \
+                     temperature_min_c or temperature_max_c"
+                )));
+            }
+        };
+        if value.is_none() {
+            continue;
+        }
+        match value.extract::<f64>() {
+            Ok(parsed) if parsed.is_finite() => *slot = parsed,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "clamp_bounds {key} must be a finite number"
+                )));
+            }
+        }
     }
-    if let Some(value) = equivalence_cycle_actual {
-        summary.set_item("equivalence_cycle_actual", value)?;
-    } else {
-        summary.set_item("equivalence_cycle_actual", py.None())?;
+    if bounds.ph_min >= bounds.ph_max || bounds.temperature_min_c >= bounds.temperature_max_c {
+        return Err(PyValueError::new_err("clamp_bounds minimums must be below their maximums"));
     }
-    if let Some(value) = equivalence_cycle_reference {
-        summary.set_item("equivalence_cycle_reference", value)?;
-    } else {
-        summary.set_item("equivalence_cycle_reference", py.None())?;
+    Ok(bounds)
+}
+
+/// The `**options` keywords of a kernel, read key by key into its options struct.
+///
+/// Each read consumes its key, so `finish` can reject whatever is left the way a
+/// fixed signature rejects an unknown keyword. `None` reads as "use the default";
+/// a value of the wrong type raises `TypeError` naming the argument.
+struct KernelKwargs<'py> {
+    kernel: &'static str,
+    kwargs: Option<Bound<'py, PyDict>>,
+}
+
+impl<'py> KernelKwargs<'py> {
+    fn new(kernel: &'static str, kwargs: Option<&Bound<'py, PyDict>>) -> PyResult<Self> {
+        // Consume a copy: Rust callers may pass a dict they keep using.
+        let kwargs = kwargs.map(|kwargs| kwargs.copy()).transpose()?;
+        Ok(Self { kernel, kwargs })
     }
-    if let Some(value) = additional_co2_required_g {
-        summary.set_item("additional_co2_required_g", value)?;
-    } else {
-        summary.set_item("additional_co2_required_g", py.None())?;
+
+    fn take_any(&self, key: &str) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let Some(kwargs) = &self.kwargs else {
+            return Ok(None);
+        };
+        let value = kwargs.get_item(key)?;
+        if value.is_some() {
+            kwargs.del_item(key)?;
+        }
+        Ok(value.filter(|value| !value.is_none()))
     }
-    if let Some(value) = additional_co2_required_mol {
-        summary.set_item("additional_co2_required_mol", value)?;
-    } else {
-        summary.set_item("additional_co2_required_mol", py.None())?;
+
+    fn take<T>(&self, key: &str) -> PyResult<Option<T>>
+    where
+        T: for<'a> FromPyObject<'a, 'py, Error = PyErr>,
+    {
+        self.take_any(key)?
+            .map(|value| {
+                value.extract::<T>().map_err(|err| {
+                    PyTypeError::new_err(format!(
+                        "{}() argument '{key}': {}",
+                        self.kernel,
+                        err.value(value.py())
+                    ))
+                })
+            })
+            .transpose()
     }
-    summary.set_item("forecast_model", "two_phase_trend")?;
-    summary.set_item("forecast_confidence", forecast_confidence)?;
-    summary.set_item("forecast_slowdown_detected", forecast_slowdown_detected)?;
-    summary.set_item("forecast_lookback_cycles", forecast_lookback_cycles)?;
-    if let Some(value) = forecast_knee_cycle {
-        summary.set_item("forecast_knee_cycle", value)?;
-    } else {
-        summary.set_item("forecast_knee_cycle", py.None())?;
+
+    fn take_or<T>(&self, key: &str, default: T) -> PyResult<T>
+    where
+        T: for<'a> FromPyObject<'a, 'py, Error = PyErr>,
+    {
+        Ok(self.take(key)?.unwrap_or(default))
     }
-    if let Some(value) = forecast_pre_knee_rate_g_per_cycle {
-        summary.set_item("forecast_pre_knee_rate_g_per_cycle", value)?;
-    } else {
-        summary.set_item("forecast_pre_knee_rate_g_per_cycle", py.None())?;
+
+    fn take_dict(&self, key: &str) -> PyResult<Option<Bound<'py, PyDict>>> {
+        self.take_any(key)?
+            .map(|value| {
+                value.cast_into::<PyDict>().map_err(|_| {
+                    let message = format!("{}() argument '{key}' must be a dict", self.kernel);
+                    PyTypeError::new_err(message)
+                })
+            })
+            .transpose()
     }
-    if let Some(value) = forecast_post_knee_rate_g_per_cycle {
-        summary.set_item("forecast_post_knee_rate_g_per_cycle", value)?;
-    } else {
-        summary.set_item("forecast_post_knee_rate_g_per_cycle", py.None())?;
+
+    /// `constants` `(Ka1, Ka2, Kw)` or `pka_constants`; at most one may be given.
+    fn take_constants(&self) -> PyResult<Option<(f64, f64, f64)>> {
+        resolve_equilibrium_constants(self.take("constants")?, self.take("pka_constants")?)
     }
-    if let Some(value) = forecast_tail_rate_g_per_cycle {
-        summary.set_item("forecast_tail_rate_g_per_cycle", value)?;
-    } else {
-        summary.set_item("forecast_tail_rate_g_per_cycle", py.None())?;
+
+    /// `planning_transition_width` (default `PLANNING_TRANSITION_WIDTH`).
+    fn take_planning_transition_width(&self) -> PyResult<f64> {
+        let width = self.take_or("planning_transition_width", PLANNING_TRANSITION_WIDTH)?;
+        if !width.is_finite() || width < 0.0 {
+            return Err(PyValueError::new_err(
+                "planning_transition_width must be finite and non-negative",
+            ));
+        }
+        Ok(width)
     }
-    if let Some(value) = forecast_remaining_cycles {
-        summary.set_item("forecast_remaining_cycles", value)?;
-    } else {
-        summary.set_item("forecast_remaining_cycles", py.None())?;
+
+    /// `clamp_bounds` overrides on top of the default ranges.
+    fn take_clamp_bounds(&self) -> PyResult<ClampBounds> {
+        parse_clamp_bounds(self.take_dict("clamp_bounds")?.as_ref())
     }
-    if let Some(value) = forecast_remaining_time_x {
-        summary.set_item("forecast_remaining_time_x", value)?;
-    } else {
-        summary.set_item("forecast_remaining_time_x", py.None())?;
+
+    /// `activity_model` (default hybrid) with its `sit_coefficients` and
+    /// `pitzer_params` tables.
+    fn take_activity_model(&self) -> PyResult<ActivityModel> {
+        parse_activity_model(
+            &self.take_or("activity_model", ACTIVITY_MODEL_HYBRID.to_string())?,
+            self.take_dict("sit_coefficients")?.as_ref(),
+            self.take_dict("pitzer_params")?.as_ref(),
+        )
     }
-    summary.set_item("forecast_time_basis", forecast_time_basis)?;
-    summary.set_item("warnings", warning_rows)?;
 
-    let response = PyDict::new(py);
-    response.set_item("comparison_series", comparison_series)?;
-    response.set_item("summary", summary)?;
-    Ok(response.unbind())
+    fn finish(self) -> PyResult<()> {
+        if let Some(key) = self.kwargs.and_then(|kwargs| kwargs.keys().iter().next()) {
+            return Err(PyTypeError::new_err(format!(
+                "{}() got an unexpected keyword argument '{key}'",
+                self.kernel
+            )));
+        }
+        Ok(())
+    }
 }
 
-#[pyfunction]
-#[pyo3(signature = (
-    model_key,
-    cycle_uptake_mol_series,
-    anchor_cycle_index=None,
-    measured_ph=None,
-    equivalence_cycle_index=None,
-    target_ph=None,
-    initial_naoh_mol=None,
-    solution_volume_l=None,
-    temperature_c=None,
-    planning_reference_total_mol=None,
-    equivalence_co2_mol=None,
-    pitzer_params=None,
-    anchors=None,
-    prior_cycle_factor_series=None,
-    prior_sample_count=0,
-    learning_enabled=true,
-    terminal_ph_range_low=8.0,
-    terminal_ph_range_high=8.3,
-    terminal_ph_weight=1.0,
-    regularization_strength=0.02,
-    smoothness_strength=0.01,
-    min_factor=0.5,
-    max_factor=1.5
-))]
-/// Calibrate piecewise uptake factors against one or more measured pH anchors.
+/// True when the supplied temperature was pulled back into the clamp range. Every
+/// temperature-dependent term (the constants, Henry solubility, the NH4+ pKa and the
+/// saturation check) reads the clamped value, so this holds whichever of them ran.
+fn temperature_was_clamped(temperature_c: Option<f64>, clamp_bounds: ClampBounds) -> bool {
+    temperature_c.is_some_and(|value| clamp_bounds.temperature(value) != value)
+}
+
+/// Named view of a solver gamma array (`[Na+, H+, HCO3-, CO3^2-, OH-]`).
 ///
-/// The solver mirrors the Python fallback contract: bounded deterministic
-/// segment fitting with regularization and split-factor smoothness.
-fn measured_ph_uptake_calibration_core(
-    py: Python<'_>,
-    model_key: &str,
-    cycle_uptake_mol_series: &Bound<'_, PyList>,
-    anchor_cycle_index: Option<usize>,
-    measured_ph: Option<f64>,
-    equivalence_cycle_index: Option<usize>,
-    target_ph: Option<f64>,
-    initial_naoh_mol: Option<f64>,
-    solution_volume_l: Option<f64>,
-    temperature_c: Option<f64>,
-    planning_reference_total_mol: Option<f64>,
-    equivalence_co2_mol: Option<f64>,
-    pitzer_params: Option<Bound<'_, PyDict>>,
-    anchors: Option<Bound<'_, PyList>>,
-    prior_cycle_factor_series: Option<Bound<'_, PyList>>,
-    prior_sample_count: usize,
-    learning_enabled: bool,
-    terminal_ph_range_low: f64,
-    terminal_ph_range_high: f64,
-    terminal_ph_weight: f64,
-    regularization_strength: f64,
-    smoothness_strength: f64,
-    min_factor: f64,
-    max_factor: f64,
-) -> PyResult<Py<PyDict>> {
-    #[derive(Clone)]
-    struct SimulationPayload {
-        corrected_cycle: Vec<f64>,
-        corrected_cumulative: Vec<f64>,
-        corrected_ph: Vec<Option<f64>>,
-        corrected_fractions: Vec<[f64; 3]>,
-    }
+/// The Na+ and H+ entries share one value under the Debye-Hückel-family models
+/// (SIT and Pitzer split them); the explicit keys keep callers off the wrong slot.
+fn activity_coefficients_dict<'py>(
+    py: Python<'py>,
+    gammas: [f64; 5],
+) -> PyResult<Bound<'py, PyDict>> {
+    let named = PyDict::new(py);
+    named.set_item("gamma_na", gammas[0])?;
+    named.set_item("gamma_h", gammas[1])?;
+    named.set_item("gamma_hco3", gammas[2])?;
+    named.set_item("gamma_co3", gammas[3])?;
+    named.set_item("gamma_oh", gammas[4])?;
+    Ok(named)
+}
 
-    #[derive(Clone)]
-    struct AnchorPoint {
-        cycle_index: usize,
-        measured_ph: f64,
-        source: String,
+/// pH of a solved state on each reporting scale.
+///
+/// - `concentration`: `-log10[H+]`, the scale the mass/charge balance is solved on.
+/// - `free_activity`: `-log10(gamma_H·[H+])`, the thermodynamic free-hydrogen scale.
+/// - `nbs`: the free-activity value shifted by a caller-supplied residual
+///   liquid-junction offset, approximating an NBS-calibrated meter; `None` without
+///   an offset, since the junction term depends on the electrode and buffer set.
+fn ph_scales_dict<'py>(
+    py: Python<'py>,
+    h: f64,
+    gamma_h: f64,
+    liquid_junction_offset: Option<f64>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let concentration_ph = -h.max(1e-30).log10();
+    let free_activity_ph = -(gamma_h * h).max(1e-30).log10();
+    let scales = PyDict::new(py);
+    scales.set_item("concentration", concentration_ph)?;
+    scales.set_item("free_activity", free_activity_ph)?;
+    scales.set_item("nbs", liquid_junction_offset.map(|offset| free_activity_ph + offset))?;
+    scales.set_item("liquid_junction_offset", liquid_junction_offset)?;
+    Ok(scales)
+}
+
+fn activity_model_name(activity_model: ActivityModel) -> &'static str {
+    match activity_model {
+        ActivityModel::Ideal => ACTIVITY_MODEL_IDEAL,
+        ActivityModel::Hybrid => ACTIVITY_MODEL_HYBRID,
+        ActivityModel::Davies => ACTIVITY_MODEL_DAVIES,
+        ActivityModel::Guntelberg => ACTIVITY_MODEL_GUNTELBERG,
+        ActivityModel::DebyeHuckel => ACTIVITY_MODEL_DEBYE_HUCKEL,
+        ActivityModel::Sit(_) => ACTIVITY_MODEL_SIT,
+        ActivityModel::Pitzer(_) => ACTIVITY_MODEL_PITZER,
     }
+}
 
-    let build_error = |message: &str| -> PyResult<Py<PyDict>> {
-        let payload = PyDict::new(py);
-        payload.set_item("solver_status", "invalid_input")?;
-        payload.set_item("solver_message", message)?;
-        Ok(payload.unbind())
+/// Adapt an optional Python callable into a Newton iteration observer.
+///
+/// The callable receives `(iteration, log_vars, residual_norm)` for every Newton
+/// step, including restarts from later seeds. The first exception it raises is
+/// parked in `callback_error` and later iterations skip the call, so the solver
+/// keeps its own control flow and the caller re-raises once solving finishes.
+fn python_iteration_observer<'a, 'py: 'a>(
+    callback: Option<&'a Bound<'py, PyAny>>,
+    callback_error: &'a RefCell<Option<PyErr>>,
+) -> PyResult<Option<BoxedIterationObserver<'a>>> {
+    let Some(callable) = callback.filter(|value| !value.is_none()) else {
+        return Ok(None);
     };
-
-    let mut cycle_moles: Vec<f64> = Vec::new();
-    for item in cycle_uptake_mol_series.iter() {
-        let parsed = item.extract::<f64>().ok().unwrap_or(0.0);
-        if parsed.is_finite() && parsed >= 0.0 {
-            cycle_moles.push(parsed);
-        } else {
-            cycle_moles.push(0.0);
-        }
-    }
-    if cycle_moles.is_empty() {
-        return build_error("No cycle uptake values were provided for calibration.");
+    if !callable.is_callable() {
+        return Err(PyTypeError::new_err("iteration_callback must be callable"));
     }
+    Ok(Some(Box::new(
+        move |iteration: usize, log_vars: &[f64], residual_norm: f64| {
+            if callback_error.borrow().is_some() {
+                return;
+            }
+            if let Err(err) = callable.call1((iteration, log_vars.to_vec(), residual_norm)) {
+                *callback_error.borrow_mut() = Some(err);
+            }
+        },
+    )))
+}
 
-    let mut lower_bound = min_factor.min(max_factor);
-    let mut upper_bound = min_factor.max(max_factor);
-    if !lower_bound.is_finite() || !upper_bound.is_finite() {
-        lower_bound = 0.5;
-        upper_bound = 1.5;
-    }
-    if lower_bound <= 0.0 {
-        lower_bound = 0.1;
+/// Note on `warnings` when temperature adjustment was requested without a
+/// temperature, since the constants then silently fall back to 25 C.
+fn missing_temperature_warning(
+    temperature_c: Option<f64>,
+    use_temp_adjusted_constants: bool,
+    warnings: &Bound<'_, PyList>,
+) -> PyResult<()> {
+    if use_temp_adjusted_constants && temperature_c.is_none() {
+        warnings.append(
+            "use_temp_adjusted_constants is set but temperature_c is missing; using 25 C.",
+        )?;
     }
-    if upper_bound <= lower_bound {
-        upper_bound = lower_bound + 0.1;
+    Ok(())
+}
+
+/// Record the temperature the correlations actually saw: `effective_temperature_c`
+/// and the [`temperature_was_clamped`] flag on `out`, plus a `warnings` note
+/// whenever that flag is set.
+fn temperature_clamp_report(
+    temperature_c: Option<f64>,
+    clamp_bounds: ClampBounds,
+    out: &Bound<'_, PyDict>,
+    warnings: &Bound<'_, PyList>,
+) -> PyResult<()> {
+    let effective = temperature_c.map(|raw| clamp_bounds.temperature(raw));
+    let clamped = temperature_was_clamped(temperature_c, clamp_bounds);
+    if let (true, Some(raw), Some(effective)) = (clamped, temperature_c, effective) {
+        warnings.append(format!(
+            "temperature_c {raw} C is outside [{}, {}] C; temperature-dependent terms use \
+             {effective} C.",
+            clamp_bounds.temperature_min_c, clamp_bounds.temperature_max_c
+        ))?;
+    }
+    out.set_item("temperature_clamped", clamped)?;
+    out.set_item("effective_temperature_c", effective)?;
+    Ok(())
+}
+
+/// Dissolved CO2 (mol/L) in equilibrium with pure CO2 at 1 atm at `temperature_c`
+/// (25 °C when unknown): the ceiling above which excess CO2 would bubble off.
+fn co2_saturation_m(temperature_c: Option<f64>, clamp_bounds: ClampBounds) -> f64 {
+    henry_dissolved_co2(1.0, 1.0, clamp_bounds.temperature(temperature_c.unwrap_or(25.0)))
+}
+
+/// Flag a ledger whose dissolved excess CO2 exceeds [`co2_saturation_m`] and
+/// warn that its low predicted pH would not survive off-gassing; `None` without a
+/// positive volume.
+fn co2_supersaturation_warning(
+    state: LedgerState,
+    solution_volume_l: Option<f64>,
+    temperature_c: Option<f64>,
+    clamp_bounds: ClampBounds,
+    warnings: &Bound<'_, PyList>,
+) -> PyResult<Option<bool>> {
+    let Some(volume) = solution_volume_l.filter(|volume| *volume > 0.0) else {
+        return Ok(None);
+    };
+    let supersaturated =
+        state.co2_excess_mol.max(0.0) / volume > co2_saturation_m(temperature_c, clamp_bounds);
+    if supersaturated {
+        warnings.append(
+            "Dissolved excess CO2 exceeds 1 atm saturation; it would off-gas and the \
+             predicted pH is likely too low.",
+        )?;
     }
-    let mut terminal_low = if terminal_ph_range_low.is_finite() {
-        terminal_ph_range_low
-    } else {
-        8.0
+    Ok(Some(supersaturated))
+}
+
+/// Return a truthy dictionary value or an empty Python string as an owned object.
+///
+/// This keeps report-table schema parity with the Python fallback while avoiding
+/// deprecated PyO3 conversion APIs that were removed from the current toolchain.
+fn dict_truthy_or_empty_pyobject(
+    py: Python<'_>,
+    dict: &Bound<'_, PyDict>,
+    key: &str,
+) -> Py<PyAny> {
+    let Some(value) = dict.get_item(key).ok().flatten() else {
+        return PyString::new(py, "").into_any().unbind();
     };
-    let mut terminal_high = if terminal_ph_range_high.is_finite() {
-        terminal_ph_range_high
+    if value.is_truthy().unwrap_or(false) {
+        value.unbind()
     } else {
-        8.3
-    };
-    terminal_low = terminal_low.clamp(0.0, 14.5);
-    terminal_high = terminal_high.clamp(0.0, 14.5);
-    if terminal_high < terminal_low {
-        std::mem::swap(&mut terminal_low, &mut terminal_high);
+        PyString::new(py, "").into_any().unbind()
     }
-    let terminal_weight = if terminal_ph_weight.is_finite() {
-        terminal_ph_weight.clamp(0.0, 20.0)
-    } else {
-        1.0
-    };
+}
 
-    let source_priority = |source: &str| -> i32 {
-        match source.trim().to_ascii_lowercase().as_str() {
-            "manual" | "form" => 5,
-            "legacy" => 4,
-            "profile" => 3,
-            "auto_final_ph" => 1,
-            _ => 2,
-        }
-    };
+fn format_optional_decimal(value: Option<f64>, precision: usize) -> String {
+    if let Some(parsed) = value {
+        format!("{parsed:.precision$}")
+    } else {
+        String::new()
+    }
+}
 
-    let mut anchors_by_cycle: BTreeMap<usize, (i32, AnchorPoint)> = BTreeMap::new();
-    if let Some(anchor_rows) = anchors {
-        for item in anchor_rows.iter() {
-            let Ok(row) = item.cast_into::<PyDict>() else {
-                continue;
-            };
-            let cycle_any = row
-                .get_item("cycle_index")
-                .ok()
-                .flatten()
-                .or_else(|| row.get_item("measured_ph_cycle_index").ok().flatten());
-            let ph_any = row
-                .get_item("measured_ph")
-                .ok()
-                .flatten()
-                .or_else(|| row.get_item("measured_ph_value").ok().flatten());
-            let source = row
-                .get_item("source")
-                .ok()
-                .flatten()
-                .and_then(|value| value.extract::<String>().ok())
-                .unwrap_or_else(|| "manual".to_string());
-            let Some(cycle_raw) = cycle_any.and_then(|value| value.extract::<f64>().ok()) else {
-                continue;
-            };
-            let Some(ph_value) = ph_any.and_then(|value| value.extract::<f64>().ok()) else {
-                continue;
-            };
-            if !cycle_raw.is_finite() || !ph_value.is_finite() {
-                continue;
-            }
-            let rounded_cycle = cycle_raw.round();
-            if (cycle_raw - rounded_cycle).abs() > 1e-6 || rounded_cycle <= 0.0 {
-                continue;
-            }
-            let cycle_index = (rounded_cycle as usize).max(1).min(cycle_moles.len());
-            if !(0.0 < ph_value && ph_value < 14.0) {
-                continue;
-            }
-            let anchor = AnchorPoint {
-                cycle_index,
-                measured_ph: ph_value,
-                source: source.clone(),
-            };
-            let incoming_priority = source_priority(&source);
-            if let Some((existing_priority, _existing_anchor)) =
-                anchors_by_cycle.get(&cycle_index)
-            {
-                if incoming_priority < *existing_priority {
-                    continue;
-                }
-            }
-            anchors_by_cycle.insert(cycle_index, (incoming_priority, anchor));
-        }
-    }
-    if anchors_by_cycle.is_empty() {
-        let Some(legacy_ph) = measured_ph.filter(|value| value.is_finite()) else {
-            return build_error("Measured pH anchors are required for calibration.");
-        };
-        let anchor_idx = anchor_cycle_index
-            .unwrap_or(1)
-            .max(1)
-            .min(cycle_moles.len());
-        anchors_by_cycle.insert(
-            anchor_idx,
-            (
-                source_priority("legacy"),
-                AnchorPoint {
-                    cycle_index: anchor_idx,
-                    measured_ph: legacy_ph,
-                    source: "legacy".to_string(),
-                },
-            ),
-        );
+/// Convert a Python object into a trimmed owned Rust string.
+///
+/// The helper materializes an owned `String` before trimming so no borrowed
+/// Python string data escapes temporary PyO3 bindings.
+fn py_any_to_trimmed_string(value: &Bound<'_, PyAny>) -> String {
+    if let Ok(text) = value.extract::<String>() {
+        return text.trim().to_string();
     }
-    let normalized_anchors: Vec<AnchorPoint> = anchors_by_cycle
-        .values()
-        .map(|(_priority, anchor)| anchor.clone())
-        .collect();
-    let primary_anchor = normalized_anchors
-        .last()
-        .cloned()
-        .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Missing anchor payload"))?;
-    let split_idx = equivalence_cycle_index
-        .unwrap_or(primary_anchor.cycle_index)
-        .max(1)
-        .min(cycle_moles.len());
+    value
+        .str()
+        .ok()
+        .and_then(|text| text.extract::<String>().ok())
+        .map(|text| text.trim().to_string())
+        .unwrap_or_default()
+}
 
-    let temp_c = temperature_c
-        .filter(|value| value.is_finite())
-        .unwrap_or(25.0);
-    let use_temp_constants = true;
-    let pka2_value = resolve_pka2_value(Some(temp_c), use_temp_constants);
-    let is_pitzer_model = model_key.trim().eq_ignore_ascii_case("naoh_co2_pitzer_hmw");
-    let pitzer_coeffs = if is_pitzer_model {
-        parse_pitzer_params_map(pitzer_params.as_ref())
+fn dict_string_value(dict: &Bound<'_, PyDict>, key: &str) -> String {
+    let Some(value) = dict.get_item(key).ok().flatten() else {
+        return String::new();
+    };
+    py_any_to_trimmed_string(&value)
+}
+
+/// Resolve the optional custom-values map attached to a ledger row.
+///
+/// The explicit lifetime keeps the returned dictionary bound to the same Python
+/// interpreter scope as the source row, which PyO3 0.27 requires.
+fn dict_custom_map<'py>(dict: &Bound<'py, PyDict>) -> Option<Bound<'py, PyDict>> {
+    let custom_any = dict.get_item("custom_values").ok().flatten()?;
+    custom_any.cast_into::<PyDict>().ok()
+}
+
+fn dict_custom_optional_float_value(dict: &Bound<'_, PyDict>, key: &str) -> Option<f64> {
+    let custom_map = dict_custom_map(dict)?;
+    let value = custom_map.get_item(key).ok().flatten()?;
+    let parsed = value.extract::<f64>().ok()?;
+    if parsed.is_finite() {
+        Some(parsed)
     } else {
         None
+    }
+}
+
+fn dict_custom_string_value(dict: &Bound<'_, PyDict>, key: &str) -> String {
+    let Some(custom_map) = dict_custom_map(dict) else {
+        return String::new();
     };
-    let available_naoh_mol = initial_naoh_mol
-        .filter(|value| value.is_finite() && *value > 0.0)
-        .map(|value| value.max(0.0));
-    let available_volume_l = solution_volume_l
-        .filter(|value| value.is_finite() && *value > 0.0)
-        .map(|value| value.max(1e-9));
+    let Some(value) = custom_map.get_item(key).ok().flatten() else {
+        return String::new();
+    };
+    py_any_to_trimmed_string(&value)
+}
 
-    let predict_from_cumulative =
-        |cumulative_mol: f64, _cycle_index: usize| -> (Option<f64>, [f64; 3]) {
-            let cumulative = cumulative_mol.max(0.0);
+fn is_ledger_builtin_numeric_key(key: &str) -> bool {
+    matches!(
+        key,
+        "final_mass_g"
+            | "final_pH"
+            | "cycles"
+            | "total_dp_uptake"
+            | "theoretical_yield_g"
+            | "actual_yield_pct"
+    )
+}
 
-            // Prefer the Pitzer mapping when the selected model is NaOH-CO2 Pitzer
-            // and compact parameter coefficients are available.
-            if let (Some(params), Some(naoh_mol), Some(volume_l)) =
-                (pitzer_coeffs, available_naoh_mol, available_volume_l)
-            {
-                let total_sodium_m = naoh_mol / volume_l;
-                let total_carbon_m = cumulative / volume_l;
-                if total_sodium_m.is_finite() && total_sodium_m > 0.0 {
-                    if let Ok((ph, _na, _h, _oh, hco3, co3, co2)) =
-                        solve_pitzer_total_carbon_impl(total_carbon_m, total_sodium_m, params, 60)
-                    {
-                        if ph.is_finite() {
-                            let total = (co2.max(0.0) + hco3.max(0.0) + co3.max(0.0)).max(1e-12);
-                            return (
-                                Some(clamp_ph_value(ph)),
-                                [co2.max(0.0) / total, hco3.max(0.0) / total, co3.max(0.0) / total],
-                            );
-                        }
-                    }
-                }
-            }
+fn is_ledger_builtin_date_key(key: &str) -> bool {
+    matches!(key, "run_date" | "updated_at")
+}
 
-            if let (Some(naoh_mol), Some(volume_l)) = (available_naoh_mol, available_volume_l) {
-                let input_ledger = LedgerState {
-                    naoh_remaining_mol: naoh_mol,
-                    na2co3_mol: 0.0,
-                    nahco3_mol: 0.0,
-                    co2_excess_mol: 0.0,
-                };
-                let (state, _accounting, ph_value) = simulate_reaction_state_with_accounting_impl(
-                    input_ledger,
-                    cumulative,
-                    pka2_value,
-                    Some(volume_l),
-                    Some(temp_c),
-                    None,
-                    use_temp_constants,
-                    None,
-                    None,
-                    true,
-                );
-                let total_carbon = (state.co2_excess_mol.max(0.0)
-                    + state.nahco3_mol.max(0.0)
-                    + state.na2co3_mol.max(0.0))
-                .max(1e-12);
-                return (
-                    Some(clamp_ph_value(ph_value)),
-                    [
-                        state.co2_excess_mol.max(0.0) / total_carbon,
-                        state.nahco3_mol.max(0.0) / total_carbon,
-                        state.na2co3_mol.max(0.0) / total_carbon,
-                    ],
-                );
-            }
+fn ledger_sort_numeric_value(dict: &Bound<'_, PyDict>, sort_key: &str) -> Option<f64> {
+    if is_ledger_builtin_numeric_key(sort_key) {
+        return dict_optional_float_value(dict, sort_key);
+    }
+    dict_custom_optional_float_value(dict, sort_key)
+}
 
-            // Corrected pH must remain chemistry-backed. Returning no pH here
-            // fails this Rust candidate and lets the Python chemistry fallback
-            // handle incomplete contexts instead of drawing a pseudo high-pH line.
-            (None, [0.0, 0.0, 0.0])
-        };
+fn ledger_sort_text_value(dict: &Bound<'_, PyDict>, sort_key: &str) -> String {
+    if is_ledger_builtin_date_key(sort_key)
+        || is_ledger_builtin_numeric_key(sort_key)
+        || matches!(
+            sort_key,
+            "profile_name" | "project_number" | "batch_number" | "item_number" | "notes" | "id"
+        )
+    {
+        return dict_string_value(dict, sort_key);
+    }
+    let custom_value = dict_custom_string_value(dict, sort_key);
+    if !custom_value.is_empty() {
+        return custom_value;
+    }
+    dict_string_value(dict, sort_key)
+}
 
-    let simulate_for_factor_series = |factor_series: &[f64]| -> SimulationPayload {
-        let mut corrected_cycle: Vec<f64> = Vec::with_capacity(cycle_moles.len());
-        let mut corrected_cumulative: Vec<f64> = Vec::with_capacity(cycle_moles.len());
-        let mut corrected_ph: Vec<Option<f64>> = Vec::with_capacity(cycle_moles.len());
-        let mut corrected_fractions: Vec<[f64; 3]> = Vec::with_capacity(cycle_moles.len());
-        let mut cumulative = 0.0_f64;
-        for (idx, raw_cycle_mol) in cycle_moles.iter().enumerate() {
-            let cycle_index = idx + 1;
-            let factor = if idx < factor_series.len() {
-                factor_series[idx]
-            } else {
-                1.0
-            };
-            let corrected = (raw_cycle_mol * factor).max(0.0);
-            cumulative += corrected;
-            let (ph_value, fractions) = predict_from_cumulative(cumulative, cycle_index);
-            corrected_cycle.push(corrected);
-            corrected_cumulative.push(cumulative);
-            corrected_ph.push(ph_value.filter(|value| value.is_finite()));
-            corrected_fractions.push([
-                fractions[0].max(0.0),
-                fractions[1].max(0.0),
-                fractions[2].max(0.0),
-            ]);
-        }
-        let mut previous_ph: Option<f64> = None;
-        let mut previous_cumulative: Option<f64> = None;
-        for (idx, value) in corrected_ph.iter_mut().enumerate() {
-            let Some(current_ph) = *value else {
-                previous_cumulative = corrected_cumulative.get(idx).copied();
-                continue;
-            };
-            if !current_ph.is_finite() {
-                previous_cumulative = corrected_cumulative.get(idx).copied();
-                continue;
-            }
-            if let Some(previous) = previous_ph {
-                let cycle_uptake = corrected_cycle.get(idx).copied().unwrap_or(0.0);
-                let cumulative = corrected_cumulative.get(idx).copied();
-                let cumulative_increased = match (cumulative, previous_cumulative) {
-                    (Some(current), Some(prior)) => current > prior + 1e-12,
-                    _ => false,
-                };
-                // Positive corrected CO2 movement must display as acidification;
-                // this guard only lowers solver jitter/plateaus after the
-                // chemistry-backed pH calculation has already run.
-                let uptake_occurred = cycle_uptake > 1e-12 || cumulative_increased;
-                let required_drop = if uptake_occurred { 0.003 } else { 0.0 };
-                let ceiling = previous - required_drop;
-                if current_ph > ceiling {
-                    let adjusted = ceiling.max(0.0);
-                    *value = Some(adjusted);
-                    previous_ph = Some(adjusted);
-                    previous_cumulative = cumulative;
-                    continue;
-                }
-            }
-            previous_ph = Some(current_ph);
-            previous_cumulative = corrected_cumulative.get(idx).copied();
+fn parse_int_prefix(raw: &str) -> Option<i64> {
+    let digits: String = raw.chars().take_while(|ch| ch.is_ascii_digit()).collect();
+    if digits.is_empty() {
+        return None;
+    }
+    digits.parse::<i64>().ok()
+}
+
+fn parse_iso_datetime_sort_token(text: &str) -> Option<i64> {
+    let trimmed = text.trim();
+    let bytes = trimmed.as_bytes();
+    if bytes.len() < 10 {
+        return None;
+    }
+    if bytes.get(4).copied() != Some(b'-') || bytes.get(7).copied() != Some(b'-') {
+        return None;
+    }
+    let date_part = std::str::from_utf8(&bytes[0..10]).ok()?;
+    let mut date_tokens = date_part.split('-');
+    let year = date_tokens.next()?.parse::<i64>().ok()?;
+    let month = date_tokens.next()?.parse::<i64>().ok()?;
+    let day = date_tokens.next()?.parse::<i64>().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let mut hour = 0_i64;
+    let mut minute = 0_i64;
+    let mut second = 0_i64;
+    if bytes.len() > 10 {
+        let separator = bytes.get(10).copied()?;
+        if !matches!(separator, b'T' | b't' | b' ') {
+            return None;
         }
-        SimulationPayload {
-            corrected_cycle,
-            corrected_cumulative,
-            corrected_ph,
-            corrected_fractions,
+        let time_part = std::str::from_utf8(bytes.get(11..).unwrap_or_default()).ok()?;
+        let mut time_tokens = time_part.split(':');
+        if let Some(raw_hour) = time_tokens.next() {
+            hour = parse_int_prefix(raw_hour).unwrap_or(0);
+        }
+        if let Some(raw_minute) = time_tokens.next() {
+            minute = parse_int_prefix(raw_minute).unwrap_or(0);
+        }
+        if let Some(raw_second) = time_tokens.next() {
+            second = parse_int_prefix(raw_second).unwrap_or(0);
         }
+        hour = hour.clamp(0, 23);
+        minute = minute.clamp(0, 59);
+        second = second.clamp(0, 59);
+    }
+    Some((((((year * 100) + month) * 100 + day) * 100 + hour) * 100 + minute) * 100 + second)
+}
+
+fn ledger_sort_date_value(dict: &Bound<'_, PyDict>, sort_key: &str) -> Option<i64> {
+    let raw_value = if is_ledger_builtin_date_key(sort_key) {
+        dict_string_value(dict, sort_key)
+    } else {
+        dict_custom_string_value(dict, sort_key)
     };
-    let terminal_penalty_raw = |endpoint_ph: Option<f64>| -> f64 {
-        let Some(ph_value) = endpoint_ph else {
-            return 0.0;
+    if raw_value.is_empty() {
+        return None;
+    }
+    parse_iso_datetime_sort_token(&raw_value)
+}
+
+fn normalize_fraction_value(value: Option<f64>) -> f64 {
+    let mut normalized = value.unwrap_or(0.0);
+    if normalized > 1.0 {
+        normalized /= 100.0;
+    }
+    if !normalized.is_finite() {
+        return 0.0;
+    }
+    normalized.max(0.0)
+}
+
+/// Extract a carbonate fraction value from a nested `fractions` mapping.
+///
+/// Missing nested dictionaries or non-finite values are treated as absent so
+/// Rust-side report builders match the Python fallback behavior.
+fn extract_fraction_field(dict: &Bound<'_, PyDict>, key: &str) -> Option<f64> {
+    let fractions_any = dict.get_item("fractions").ok().flatten()?;
+    let fractions = fractions_any.cast_into::<PyDict>().ok()?;
+    dict_optional_float_value(&fractions, key)
+}
+
+fn dict_optional_finite_by_keys(dict: &Bound<'_, PyDict>, keys: &[&str]) -> Option<f64> {
+    for key in keys {
+        let Some(any_value) = dict.get_item(*key).ok().flatten() else {
+            continue;
         };
-        if !ph_value.is_finite() {
-            return 0.0;
-        }
-        if ph_value < terminal_low {
-            return (terminal_low - ph_value).powi(2);
+        let Ok(parsed) = any_value.extract::<f64>() else {
+            continue;
+        };
+        if parsed.is_finite() {
+            return Some(parsed);
         }
-        if ph_value > terminal_high {
-            return (ph_value - terminal_high).powi(2);
+    }
+    None
+}
+
+/// Parse compact NaOH-CO2 Pitzer coefficients from a Python mapping payload.
+///
+/// Returns `None` when any required coefficient is missing or non-finite.
+fn parse_pitzer_params_map(pitzer_params: Option<&Bound<'_, PyDict>>) -> Option<PitzerParamsLite> {
+    let params_map = pitzer_params?;
+    let parse = |key: &str| -> Option<f64> {
+        let value = params_map.get_item(key).ok().flatten()?;
+        let parsed = value.extract::<f64>().ok()?;
+        if parsed.is_finite() {
+            Some(parsed)
+        } else {
+            None
         }
-        0.0
     };
+    Some(PitzerParamsLite {
+        b0_na_oh: parse("b0_na_oh")?,
+        b1_na_oh: parse("b1_na_oh")?,
+        c0_na_oh: parse("c0_na_oh")?,
+        b0_na_hco3: parse("b0_na_hco3")?,
+        b1_na_hco3: parse("b1_na_hco3")?,
+        c0_na_hco3: parse("c0_na_hco3")?,
+        b0_na_co3: parse("b0_na_co3")?,
+        b1_na_co3: parse("b1_na_co3")?,
+        c0_na_co3: parse("c0_na_co3")?,
+        theta_co3_oh: parse("theta_co3_oh")?,
+        psi_co3_na_oh: parse("psi_co3_na_oh")?,
+        psi_co3_hco3_na: parse("psi_co3_hco3_na")?,
+    })
+}
 
-    let mut seed_factors: Vec<f64> = vec![1.0; cycle_moles.len()];
-    let mut prior_applied = false;
-    let mut prior_points_used = 0_usize;
-    if learning_enabled {
-        if let Some(prior_rows) = prior_cycle_factor_series {
-            for (idx, item) in prior_rows.iter().enumerate() {
-                if idx >= seed_factors.len() {
-                    break;
-                }
-                let parsed = item.extract::<f64>().ok().unwrap_or(1.0);
-                if parsed.is_finite() {
-                    seed_factors[idx] = parsed.clamp(lower_bound, upper_bound);
-                    prior_applied = true;
-                    prior_points_used += 1;
-                }
+fn compare_cycle_row_index(dict: &Bound<'_, PyDict>, fallback_index: usize) -> usize {
+    for key in ["cycle_id", "cycle", "cycle_number", "cycle_index", "index"] {
+        let Some(any_value) = dict.get_item(key).ok().flatten() else {
+            continue;
+        };
+        let Ok(parsed) = any_value.extract::<isize>() else {
+            continue;
+        };
+        if parsed >= 1 {
+            if let Ok(cycle_id) = usize::try_from(parsed) {
+                return cycle_id;
             }
         }
     }
-    let prefit_baseline = simulate_for_factor_series(&seed_factors);
-    let mut working_factors = seed_factors.clone();
-    let mut segment_rows: Vec<(usize, usize, f64, bool)> = Vec::new();
-    let mut objective_total = 0.0_f64;
-    let mut prev_anchor_idx = 0_usize;
-    let mut previous_segment_scale = 1.0_f64;
+    fallback_index.max(1)
+}
 
-    let mut grid_values: Vec<f64> = Vec::new();
-    let mut current = lower_bound;
-    while current <= upper_bound + 1e-12 {
-        grid_values.push((current * 1_000_000.0).round() / 1_000_000.0);
-        current += 0.05;
+fn py_any_optional_finite_float(value: &Bound<'_, PyAny>) -> Option<f64> {
+    if value.is_none() {
+        return None;
     }
-    if grid_values.is_empty() {
-        grid_values.push(1.0);
+    let parsed = value.extract::<f64>().ok()?;
+    if parsed.is_finite() {
+        Some(parsed)
+    } else {
+        None
     }
+}
 
-    for anchor in &normalized_anchors {
-        let anchor_idx = anchor.cycle_index.max(1).min(cycle_moles.len());
-        let measured_anchor_ph = anchor.measured_ph;
-        let segment_start = prev_anchor_idx + 1;
-        let segment_end = anchor_idx;
-        if segment_end < segment_start {
+fn interpolate_reference_series_impl(
+    x_value: Option<f64>,
+    x_series: &[f64],
+    y_series: &[Option<f64>],
+) -> (Option<f64>, &'static str) {
+    let Some(current_x) = x_value else {
+        return (None, "out_of_range");
+    };
+    if !current_x.is_finite() {
+        return (None, "out_of_range");
+    }
+    let mut pairs: Vec<(f64, Option<f64>)> = Vec::new();
+    for (x_raw, y_raw) in x_series.iter().zip(y_series.iter()) {
+        if !x_raw.is_finite() {
             continue;
         }
-        let segment_base = working_factors.clone();
-        let segment_objective = |segment_scale: f64| -> (f64, SimulationPayload) {
-            let mut candidate_factors = working_factors.clone();
-            for cycle_pos in (segment_start - 1)..segment_end {
-                let candidate_value =
-                    (segment_base[cycle_pos] * segment_scale).clamp(lower_bound, upper_bound);
-                candidate_factors[cycle_pos] = candidate_value;
-            }
-            let sim_payload = simulate_for_factor_series(&candidate_factors);
-            let anchor_pred = sim_payload
-                .corrected_ph
-                .get(anchor_idx.saturating_sub(1))
-                .copied()
-                .flatten();
-            let Some(anchor_predicted) = anchor_pred else {
-                return (f64::INFINITY, sim_payload);
-            };
-            if !anchor_predicted.is_finite() {
-                return (f64::INFINITY, sim_payload);
-            }
-            let residual = anchor_predicted - measured_anchor_ph;
-            let endpoint_ph = sim_payload.corrected_ph.last().copied().flatten();
-            let terminal_penalty = terminal_penalty_raw(endpoint_ph);
-            let objective = residual * residual
-                + regularization_strength * (segment_scale - 1.0).powi(2)
-                + smoothness_strength * (segment_scale - previous_segment_scale).powi(2)
-                + (terminal_penalty * terminal_weight);
-            (objective, sim_payload)
-        };
-        let mut best_scale = 1.0_f64;
-        let mut best_objective = f64::INFINITY;
-        let mut best_sim = simulate_for_factor_series(&working_factors);
-        for candidate in &grid_values {
-            let (objective_value, sim_payload) = segment_objective(*candidate);
-            if objective_value < best_objective {
-                best_objective = objective_value;
-                best_scale = *candidate;
-                best_sim = sim_payload;
-            }
-        }
-        let mut local_step = 0.025_f64;
-        for _ in 0..4 {
-            for candidate in [
-                best_scale - local_step,
-                best_scale,
-                best_scale + local_step,
-            ] {
-                let clipped = candidate.clamp(lower_bound, upper_bound);
-                let (objective_value, sim_payload) = segment_objective(clipped);
-                if objective_value < best_objective {
-                    best_objective = objective_value;
-                    best_scale = clipped;
-                    best_sim = sim_payload;
-                }
-            }
-            local_step *= 0.5;
-        }
-        for cycle_pos in (segment_start - 1)..segment_end {
-            let candidate_value = (segment_base[cycle_pos] * best_scale).clamp(lower_bound, upper_bound);
-            working_factors[cycle_pos] = candidate_value;
-        }
-        prev_anchor_idx = anchor_idx;
-        previous_segment_scale = best_scale;
-        if best_objective.is_finite() {
-            objective_total += best_objective;
-        }
-        segment_rows.push((segment_start, segment_end, best_scale, false));
-        let _ = best_sim;
+        pairs.push((*x_raw, *y_raw));
     }
-    if prev_anchor_idx < cycle_moles.len() {
-        let tail_start = prev_anchor_idx + 1;
-        for cycle_pos in prev_anchor_idx..cycle_moles.len() {
-            let candidate_value =
-                (working_factors[cycle_pos] * previous_segment_scale).clamp(lower_bound, upper_bound);
-            working_factors[cycle_pos] = candidate_value;
-        }
-        segment_rows.push((tail_start, cycle_moles.len(), previous_segment_scale, true));
+    if pairs.is_empty() {
+        return (None, "sparse_reference");
+    }
+    pairs.sort_by(|left, right| left.0.partial_cmp(&right.0).unwrap_or(Ordering::Equal));
+    if pairs.len() < 2 {
+        return (pairs[0].1, "sparse_reference");
+    }
+    if current_x < pairs[0].0 || current_x > pairs[pairs.len() - 1].0 {
+        return (None, "out_of_range");
     }
 
-    let final_sim = simulate_for_factor_series(&working_factors);
-    let corrected_cycle_series = final_sim.corrected_cycle.clone();
-    let corrected_cumulative_series = final_sim.corrected_cumulative.clone();
-    let corrected_ph_series = final_sim.corrected_ph.clone();
-    let corrected_fractions_series = final_sim.corrected_fractions.clone();
-    let anchor_after_ph = corrected_ph_series
-        .get(primary_anchor.cycle_index.saturating_sub(1))
-        .copied()
-        .flatten();
-    let anchor_before_ph = prefit_baseline
-        .corrected_ph
-        .get(primary_anchor.cycle_index.saturating_sub(1))
-        .copied()
-        .flatten();
-    let anchor_residual_before = anchor_before_ph.map(|value| value - primary_anchor.measured_ph);
-    let anchor_residual_after = anchor_after_ph.map(|value| value - primary_anchor.measured_ph);
-    let corrected_latest_total_mol = corrected_cumulative_series
-        .last()
-        .copied()
-        .unwrap_or(0.0);
+    let mut right_idx: usize = 0;
+    let mut found_right = false;
+    for (idx, (x_pair, _)) in pairs.iter().enumerate() {
+        if *x_pair >= current_x {
+            right_idx = idx;
+            found_right = true;
+            break;
+        }
+    }
+    if !found_right {
+        right_idx = pairs.len() - 1;
+    }
+    let left_idx = right_idx.saturating_sub(1);
+    let (x_left, y_left) = pairs[left_idx];
+    let (x_right, y_right) = pairs[right_idx];
 
-    let latest_fraction = corrected_fractions_series
-        .last()
-        .copied()
-        .unwrap_or([0.0, 0.0, 0.0]);
-    let latest_ph = corrected_ph_series.last().copied().flatten();
+    if let (Some(left_val), Some(right_val)) = (y_left, y_right) {
+        if left_val.is_finite() && right_val.is_finite() && x_right != x_left {
+            let ratio = (current_x - x_left) / (x_right - x_left);
+            let mapped = left_val + ratio * (right_val - left_val);
+            if mapped.is_finite() {
+                return (Some(mapped), "ok");
+            }
+        }
+    }
 
-    let pre_slice = &working_factors[..split_idx];
-    let alpha_pre = if pre_slice.is_empty() {
-        1.0
-    } else {
-        pre_slice.iter().sum::<f64>() / (pre_slice.len() as f64)
-    };
-    let post_slice = &working_factors[split_idx..];
-    let alpha_post = if post_slice.is_empty() {
-        alpha_pre
+    let nearest_idx = if (current_x - x_right).abs() < (current_x - x_left).abs() {
+        right_idx
     } else {
-        post_slice.iter().sum::<f64>() / (post_slice.len() as f64)
+        left_idx
     };
-    let reference_total = planning_reference_total_mol
-        .filter(|value| value.is_finite() && *value > 0.0);
-    let eq_reference = equivalence_co2_mol.filter(|value| value.is_finite() && *value > 0.0);
-    let co2_required_target_mol = if let Some(reference_total_value) = reference_total {
-        let pre_reference = if let Some(eq_reference_value) = eq_reference {
-            reference_total_value.min(eq_reference_value)
-        } else {
-            reference_total_value
-        };
-        let post_reference = (reference_total_value - pre_reference).max(0.0);
-        Some(pre_reference * alpha_pre + post_reference * alpha_post)
-    } else if let Some(eq_reference_value) = eq_reference {
-        Some(eq_reference_value * alpha_pre)
+    let nearest = pairs[nearest_idx].1;
+    if nearest.is_some() {
+        (nearest, "ok")
     } else {
-        None
-    };
-    let completion_pct = co2_required_target_mol.and_then(|required| {
-        if required <= 1e-12 {
-            None
-        } else {
-            Some((corrected_latest_total_mol / required).clamp(0.0, 1.0) * 100.0)
-        }
-    });
-    let factor_regularization_term = regularization_strength
-        * working_factors
-            .iter()
-            .map(|value| (value - 1.0).powi(2))
-            .sum::<f64>();
-    let factor_smoothness_term = smoothness_strength
-        * working_factors
-            .windows(2)
-            .map(|window| (window[1] - window[0]).powi(2))
-            .sum::<f64>();
-    let anchor_residual_squared_sum = normalized_anchors
-        .iter()
-        .filter_map(|anchor| {
-            corrected_ph_series
-                .get(anchor.cycle_index.saturating_sub(1))
-                .copied()
-                .flatten()
-                .map(|value| (value - anchor.measured_ph).powi(2))
-        })
-        .sum::<f64>();
-    let terminal_penalty_raw_value = terminal_penalty_raw(latest_ph);
-    let terminal_penalty_weighted_value = terminal_penalty_raw_value * terminal_weight;
-    let objective_total_components = anchor_residual_squared_sum
-        + factor_regularization_term
-        + factor_smoothness_term
-        + terminal_penalty_weighted_value;
-    let endpoint_distance = latest_ph.map(|value| {
-        if value < terminal_low {
-            terminal_low - value
-        } else if value > terminal_high {
-            value - terminal_high
-        } else {
+        (None, "sparse_reference")
+    }
+}
+
+/// Fritsch-Carlson node slopes for monotone cubic (PCHIP) interpolation.
+///
+/// Interior slopes use the weighted harmonic mean of adjacent secants and drop to
+/// zero at local extrema; endpoint slopes use the shape-preserving three-point rule.
+fn pchip_slopes(xs: &[f64], ys: &[f64]) -> Vec<f64> {
+    let n = xs.len();
+    if n < 2 {
+        return vec![0.0; n];
+    }
+    let h: Vec<f64> = xs.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    let delta: Vec<f64> = (0..n - 1).map(|k| (ys[k + 1] - ys[k]) / h[k]).collect();
+    if n == 2 {
+        return vec![delta[0], delta[0]];
+    }
+    let mut slopes = vec![0.0_f64; n];
+    for k in 1..n - 1 {
+        if delta[k - 1] * delta[k] > 0.0 {
+            let w1 = 2.0 * h[k] + h[k - 1];
+            let w2 = h[k] + 2.0 * h[k - 1];
+            slopes[k] = (w1 + w2) / (w1 / delta[k - 1] + w2 / delta[k]);
+        }
+    }
+    let endpoint = |h0: f64, h1: f64, d0: f64, d1: f64| -> f64 {
+        let slope = ((2.0 * h0 + h1) * d0 - h0 * d1) / (h0 + h1);
+        if slope * d0 <= 0.0 {
             0.0
-        }
-    });
-    let mut anchor_source_counts: BTreeMap<String, usize> = BTreeMap::new();
-    for anchor in &normalized_anchors {
-        let key = if anchor.source.trim().is_empty() {
-            "unknown".to_string()
+        } else if d0 * d1 < 0.0 && slope.abs() > 3.0 * d0.abs() {
+            3.0 * d0
         } else {
-            anchor.source.trim().to_string()
-        };
-        let next = anchor_source_counts.get(&key).copied().unwrap_or(0) + 1;
-        anchor_source_counts.insert(key, next);
-    }
+            slope
+        }
+    };
+    slopes[0] = endpoint(h[0], h[1], delta[0], delta[1]);
+    slopes[n - 1] = endpoint(h[n - 2], h[n - 3], delta[n - 2], delta[n - 3]);
+    slopes
+}
 
-    let corrected_cycle_g_series: Vec<f64> = corrected_cycle_series
-        .iter()
-        .map(|value| value * SOL_MW_CO2)
-        .collect();
-    let corrected_cumulative_g_series: Vec<f64> = corrected_cumulative_series
-        .iter()
-        .map(|value| value * SOL_MW_CO2)
-        .collect();
+/// Evaluate a PCHIP interpolant at `x`, clamping to the end nodes outside the
+/// sampled range. Returns `(value, extrapolated)`; `xs` must be strictly increasing.
+fn pchip_interpolate(xs: &[f64], ys: &[f64], x: f64) -> (f64, bool) {
+    let n = xs.len();
+    if x <= xs[0] || n == 1 {
+        return (ys[0], x < xs[0]);
+    }
+    if x >= xs[n - 1] {
+        return (ys[n - 1], x > xs[n - 1]);
+    }
+    let slopes = pchip_slopes(xs, ys);
+    let k = xs.partition_point(|value| *value <= x).saturating_sub(1).min(n - 2);
+    let h = xs[k + 1] - xs[k];
+    let t = (x - xs[k]) / h;
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let value = (2.0 * t3 - 3.0 * t2 + 1.0) * ys[k]
+        + (t3 - 2.0 * t2 + t) * h * slopes[k]
+        + (-2.0 * t3 + 3.0 * t2) * ys[k + 1]
+        + (t3 - t2) * h * slopes[k + 1];
+    (value, false)
+}
 
-    let set_optional_f64 =
-        |dict: &Bound<'_, PyDict>, key: &str, value: Option<f64>| -> PyResult<()> {
-            if let Some(parsed) = value.filter(|item| item.is_finite()) {
-                dict.set_item(key, parsed)?;
-            } else {
-                dict.set_item(key, py.None())?;
-            }
-            Ok(())
+#[pyfunction]
+#[pyo3(signature = (rows, query_delta_g))]
+/// Look up pH on a simulated titration curve with monotone cubic interpolation.
+///
+/// `rows` are `simulation_curve` dictionaries carrying `delta_g` and `ph`; the
+/// interpolant is exact at the nodes and never overshoots between them. Queries
+/// outside the sampled range return the nearest endpoint with `extrapolated` set.
+fn interpolate_curve(
+    py: Python<'_>,
+    rows: &Bound<'_, PyList>,
+    query_delta_g: f64,
+) -> PyResult<Py<PyDict>> {
+    let mut pairs: Vec<(f64, f64)> = Vec::new();
+    for item in rows.iter() {
+        let Ok(row) = item.cast_into::<PyDict>() else {
+            continue;
         };
-
-    let response = PyDict::new(py);
-    response.set_item("solver_status", "ok")?;
-    response.set_item("solver_message", "")?;
-    response.set_item("split_cycle_index", split_idx)?;
-    response.set_item("anchor_cycle_index", primary_anchor.cycle_index)?;
-    response.set_item("anchor_measured_ph", primary_anchor.measured_ph)?;
-    set_optional_f64(&response, "anchor_predicted_ph_before", anchor_before_ph)?;
-    set_optional_f64(&response, "anchor_predicted_ph_after", anchor_after_ph)?;
-    set_optional_f64(&response, "anchor_residual_before", anchor_residual_before)?;
-    set_optional_f64(&response, "anchor_residual_after", anchor_residual_after)?;
-    if objective_total_components.is_finite() {
-        response.set_item("objective", objective_total_components)?;
-    } else if objective_total.is_finite() {
-        response.set_item("objective", objective_total)?;
-    } else {
-        response.set_item("objective", py.None())?;
+        let (Some(x_val), Some(y_val)) = (
+            dict_optional_float_value(&row, "delta_g"),
+            dict_optional_float_value(&row, "ph"),
+        ) else {
+            continue;
+        };
+        pairs.push((x_val, y_val));
     }
-    response.set_item("alpha_pre", alpha_pre)?;
-    response.set_item("alpha_post", alpha_post)?;
-    response.set_item("cycle_factor_series", working_factors.clone())?;
-    response.set_item("anchor_count", normalized_anchors.len())?;
-    let anchor_source_count_map = PyDict::new(py);
-    for (source, count) in &anchor_source_counts {
-        anchor_source_count_map.set_item(source, *count)?;
+    pairs.sort_by(|left, right| left.0.partial_cmp(&right.0).unwrap_or(Ordering::Equal));
+    pairs.dedup_by(|next, kept| next.0 == kept.0);
+    let response = PyDict::new(py);
+    if pairs.is_empty() || !query_delta_g.is_finite() {
+        response.set_item("ph", py.None())?;
+        response.set_item("extrapolated", false)?;
+        response.set_item("flag", "empty_curve")?;
+        return Ok(response.unbind());
     }
-    response.set_item("anchor_count_by_source", anchor_source_count_map)?;
-    response.set_item(
-        "anchor_sources",
-        anchor_source_counts.keys().cloned().collect::<Vec<String>>(),
-    )?;
-    response.set_item("learning_enabled", learning_enabled)?;
-    response.set_item("learning_prior_applied", prior_applied)?;
-    response.set_item("prior_sample_count", prior_sample_count)?;
-    response.set_item(
-        "prior_cycle_factor_points_used",
-        prior_points_used,
-    )?;
-    response.set_item("prior_cycle_factor_series", seed_factors.clone())?;
-    response.set_item("terminal_ph_range_low", terminal_low)?;
-    response.set_item("terminal_ph_range_high", terminal_high)?;
-    response.set_item("terminal_ph_weight", terminal_weight)?;
-    response.set_item("ph_prediction_basis", "chemistry_context")?;
-    response.set_item(
-        "terminal_objective_enabled",
-        terminal_weight > 0.0 && !normalized_anchors.is_empty(),
-    )?;
-    let objective_components = PyDict::new(py);
-    objective_components.set_item(
-        "anchor_residual_squared_sum",
-        anchor_residual_squared_sum,
-    )?;
-    objective_components.set_item(
-        "factor_regularization_term",
-        factor_regularization_term,
-    )?;
-    objective_components.set_item("factor_smoothness_term", factor_smoothness_term)?;
-    objective_components.set_item("terminal_penalty_raw", terminal_penalty_raw_value)?;
-    objective_components.set_item(
-        "terminal_penalty_weighted",
-        terminal_penalty_weighted_value,
-    )?;
-    objective_components.set_item("objective_total", objective_total_components)?;
-    response.set_item("objective_components", objective_components)?;
-    let endpoint_diagnostics = PyDict::new(py);
-    set_optional_f64(&endpoint_diagnostics, "endpoint_ph", latest_ph)?;
-    endpoint_diagnostics.set_item(
-        "in_terminal_band",
-        latest_ph
-            .map(|value| value >= terminal_low && value <= terminal_high)
-            .unwrap_or(false),
-    )?;
-    set_optional_f64(
-        &endpoint_diagnostics,
-        "distance_to_terminal_band",
-        endpoint_distance,
-    )?;
-    endpoint_diagnostics.set_item("terminal_penalty_raw", terminal_penalty_raw_value)?;
-    endpoint_diagnostics.set_item(
-        "terminal_penalty_weighted",
-        terminal_penalty_weighted_value,
-    )?;
-    response.set_item("endpoint_diagnostics", endpoint_diagnostics)?;
-    let segment_list = PyList::empty(py);
-    for (start_cycle, end_cycle, scale, tail_flag) in &segment_rows {
-        let row = PyDict::new(py);
-        row.set_item("start_cycle_index", *start_cycle)?;
-        row.set_item("end_cycle_index", *end_cycle)?;
-        row.set_item("scale", *scale)?;
-        if *tail_flag {
-            row.set_item("tail_extrapolation", true)?;
-        }
-        segment_list.append(row)?;
-    }
-    response.set_item("segment_scales", segment_list)?;
-    let anchors_list = PyList::empty(py);
-    for anchor in &normalized_anchors {
-        let before_ph = prefit_baseline
-            .corrected_ph
-            .get(anchor.cycle_index.saturating_sub(1))
-            .copied()
-            .flatten();
-        let after_ph = corrected_ph_series
-            .get(anchor.cycle_index.saturating_sub(1))
-            .copied()
-            .flatten();
-        let row = PyDict::new(py);
-        row.set_item("cycle_index", anchor.cycle_index)?;
-        row.set_item("measured_ph", anchor.measured_ph)?;
-        row.set_item("source", anchor.source.clone())?;
-        set_optional_f64(&row, "predicted_ph_before", before_ph)?;
-        set_optional_f64(&row, "predicted_ph_after", after_ph)?;
-        set_optional_f64(
-            &row,
-            "residual_before",
-            before_ph.map(|value| value - anchor.measured_ph),
-        )?;
-        set_optional_f64(
-            &row,
-            "residual_after",
-            after_ph.map(|value| value - anchor.measured_ph),
-        )?;
-        anchors_list.append(row)?;
-    }
-    response.set_item("anchors", anchors_list)?;
-    response.set_item(
-        "corrected_cycle_uptake_mol_series",
-        corrected_cycle_series.clone(),
-    )?;
-    response.set_item(
-        "corrected_cycle_uptake_g_series",
-        corrected_cycle_g_series.clone(),
-    )?;
-    response.set_item(
-        "corrected_cumulative_co2_mol_series",
-        corrected_cumulative_series.clone(),
-    )?;
-    response.set_item(
-        "corrected_cumulative_co2_g_series",
-        corrected_cumulative_g_series.clone(),
-    )?;
-
-    let corrected_ph_list = PyList::empty(py);
-    for value in corrected_ph_series {
-        if let Some(parsed) = value.filter(|item| item.is_finite()) {
-            corrected_ph_list.append(parsed)?;
-        } else {
-            corrected_ph_list.append(py.None())?;
-        }
-    }
-    response.set_item("corrected_ph_series", corrected_ph_list)?;
-
-    let fractions_list = PyList::empty(py);
-    for fraction_row in corrected_fractions_series {
-        let fraction_map = PyDict::new(py);
-        fraction_map.set_item("H2CO3", fraction_row[0].max(0.0))?;
-        fraction_map.set_item("HCO3-", fraction_row[1].max(0.0))?;
-        fraction_map.set_item("CO3^2-", fraction_row[2].max(0.0))?;
-        fractions_list.append(fraction_map)?;
-    }
-    response.set_item("corrected_fraction_series", fractions_list)?;
-
-    let latest_speciation = PyDict::new(py);
-    set_optional_f64(&latest_speciation, "ph", latest_ph)?;
-    let latest_fractions_map = PyDict::new(py);
-    latest_fractions_map.set_item("H2CO3", latest_fraction[0].max(0.0))?;
-    latest_fractions_map.set_item("HCO3-", latest_fraction[1].max(0.0))?;
-    latest_fractions_map.set_item("CO3^2-", latest_fraction[2].max(0.0))?;
-    latest_speciation.set_item("fractions", latest_fractions_map)?;
-    response.set_item("latest_corrected_speciation", latest_speciation)?;
-
-    set_optional_f64(
-        &response,
-        "co2_required_for_target_ph_mol",
-        co2_required_target_mol,
-    )?;
-    set_optional_f64(
-        &response,
-        "co2_required_for_target_ph_g",
-        co2_required_target_mol.map(|value| value * SOL_MW_CO2),
-    )?;
-    response.set_item(
-        "corrected_cumulative_uptake_latest_mol",
-        corrected_latest_total_mol,
-    )?;
-    response.set_item(
-        "corrected_cumulative_uptake_latest_g",
-        corrected_latest_total_mol * SOL_MW_CO2,
-    )?;
-    set_optional_f64(
-        &response,
-        "completion_from_measured_ph_pct",
-        completion_pct,
-    )?;
-    set_optional_f64(
-        &response,
-        "target_ph",
-        target_ph.filter(|value| value.is_finite()),
-    )?;
+    let xs: Vec<f64> = pairs.iter().map(|pair| pair.0).collect();
+    let ys: Vec<f64> = pairs.iter().map(|pair| pair.1).collect();
+    let (ph, extrapolated) = pchip_interpolate(&xs, &ys, query_delta_g);
+    response.set_item("ph", ph)?;
+    response.set_item("extrapolated", extrapolated)?;
+    response.set_item("flag", if extrapolated { "extrapolated" } else { "ok" })?;
     Ok(response.unbind())
 }
 
 #[pyfunction]
-#[pyo3(signature = (rows_a, rows_b))]
-/// Compare aligned cycle rows from two datasets while preserving Python parity.
-///
-/// The output ordering and lookup rules intentionally mirror the Python
-/// implementation so Compare tables remain backend-agnostic.
-fn compare_aligned_cycle_rows_core(
+#[pyo3(signature = (x_value, x_series, y_series))]
+fn analysis_interpolate_reference_series_core(
     py: Python<'_>,
-    rows_a: &Bound<'_, PyList>,
-    rows_b: &Bound<'_, PyList>,
-) -> PyResult<Py<PyList>> {
-    // Keep Rust/Python parity for Compare cycle alignment and delta aggregation
-    // so table math remains identical across backend selection.
-    let uptake_keys = [
-        "selected_mass_g",
-        "selected_mass",
-        "co2_added_g",
-        "co2_added_mass_g",
-        "co2_mass_g",
-        "co2_g",
-        "cycle_mass_g",
-        "cycle_co2_mass_g",
-    ];
-    let cumulative_keys = [
-        "cumulative_co2_mass_g",
-        "cumulative_co2_added_mass_g",
-        "cumulative_co2_added_g",
-        "cumulative_co2_g",
-        "cumulative_added_g",
-        "cumulative_mass_g",
-    ];
-
-    let mut map_a: BTreeMap<usize, (Option<f64>, Option<f64>)> = BTreeMap::new();
-    let mut map_b: BTreeMap<usize, (Option<f64>, Option<f64>)> = BTreeMap::new();
-
-    for (idx, item) in rows_a.iter().enumerate() {
-        let Ok(entry) = item.cast_into::<PyDict>() else {
+    x_value: Option<f64>,
+    x_series: &Bound<'_, PyList>,
+    y_series: &Bound<'_, PyList>,
+) -> PyResult<Py<PyDict>> {
+    // Interpolate one reference series point with the same out-of-range/sparse
+    // flags used by the Python forensic/dashboard alignment path.
+    let mut safe_x: Vec<f64> = Vec::new();
+    let mut safe_y: Vec<Option<f64>> = Vec::new();
+    for (x_any, y_any) in x_series.iter().zip(y_series.iter()) {
+        let Some(x_val) = py_any_optional_finite_float(&x_any) else {
             continue;
         };
-        let cycle_id = compare_cycle_row_index(&entry, idx + 1);
-        if map_a.contains_key(&cycle_id) {
-            continue;
-        }
-        let uptake = dict_optional_finite_by_keys(&entry, &uptake_keys);
-        let cumulative = dict_optional_finite_by_keys(&entry, &cumulative_keys);
-        map_a.insert(cycle_id, (uptake, cumulative));
+        safe_x.push(x_val);
+        safe_y.push(py_any_optional_finite_float(&y_any));
     }
-    for (idx, item) in rows_b.iter().enumerate() {
-        let Ok(entry) = item.cast_into::<PyDict>() else {
-            continue;
-        };
-        let cycle_id = compare_cycle_row_index(&entry, idx + 1);
-        if map_b.contains_key(&cycle_id) {
-            continue;
-        }
-        let uptake = dict_optional_finite_by_keys(&entry, &uptake_keys);
-        let cumulative = dict_optional_finite_by_keys(&entry, &cumulative_keys);
-        map_b.insert(cycle_id, (uptake, cumulative));
+    let (mapped_value, flag) = interpolate_reference_series_impl(x_value, &safe_x, &safe_y);
+    let response = PyDict::new(py);
+    if let Some(value) = mapped_value {
+        response.set_item("value", value)?;
+    } else {
+        response.set_item("value", py.None())?;
     }
+    response.set_item("flag", flag)?;
+    Ok(response.unbind())
+}
 
-    let all_cycles: BTreeSet<usize> = map_a.keys().chain(map_b.keys()).copied().collect();
-    let rows = PyList::empty(py);
-    let mut last_cum_a = 0.0_f64;
-    let mut last_cum_b = 0.0_f64;
-    let mut valid_a = false;
-    let mut valid_b = false;
-
-    for cycle_id in all_cycles {
-        let (mut uptake_a, mut cum_a) = map_a.get(&cycle_id).copied().unwrap_or((None, None));
-        let (mut uptake_b, mut cum_b) = map_b.get(&cycle_id).copied().unwrap_or((None, None));
-        let prior_cum_a = if valid_a { last_cum_a } else { 0.0 };
-        let prior_cum_b = if valid_b { last_cum_b } else { 0.0 };
-
-        if uptake_a.is_none() {
-            if let Some(cum_val) = cum_a {
-                uptake_a = Some(cum_val - prior_cum_a);
-            }
+#[pyfunction]
+#[pyo3(signature = (
+    actual_cycle_series,
+    reference_cycle_series,
+    target_ph,
+    reaction_naoh_mass,
+    initial_naoh_mol,
+    co2_mw,
+    naoh_mw
+))]
+/// Build the Rust-backed analysis dashboard payload from cycle series inputs.
+///
+/// The function mirrors the Python dashboard assembly path so backend switching
+/// does not change interpolation, fraction extraction, or summary table shape.
+fn analysis_dashboard_core(
+    py: Python<'_>,
+    actual_cycle_series: &Bound<'_, PyList>,
+    reference_cycle_series: &Bound<'_, PyList>,
+    target_ph: f64,
+    reaction_naoh_mass: Option<f64>,
+    initial_naoh_mol: Option<f64>,
+    co2_mw: f64,
+    naoh_mw: f64,
+) -> PyResult<Py<PyDict>> {
+    // Build comparison rows + summary metrics for the Analysis dashboard using
+    // the same deterministic interpolation and fail-closed value handling.
+    #[derive(Clone, Copy)]
+    struct ActualRow {
+        cycle_id: usize,
+        cycle_co2_g: Option<f64>,
+        cumulative_co2_g: Option<f64>,
+        cumulative_co2_mol: Option<f64>,
+        duration_x: Option<f64>,
+        ph: Option<f64>,
+        hco3_fraction: f64,
+        co3_fraction: f64,
+    }
+    #[derive(Clone, Copy)]
+    struct ReferenceRow {
+        cycle_id: usize,
+        cumulative_co2_g: Option<f64>,
+        ph: Option<f64>,
+        equivalence_reached: bool,
+    }
+    let mut actual_rows: Vec<ActualRow> = Vec::new();
+    let mut actual_alignment_override: Vec<String> = Vec::new();
+    let mut inferred_time_label = String::new();
+    for (idx, item) in actual_cycle_series.iter().enumerate() {
+        let Ok(entry) = item.cast_into::<PyDict>() else {
+            continue;
+        };
+        let cycle_id = compare_cycle_row_index(&entry, idx + 1);
+        let cycle_co2_g = dict_optional_finite_by_keys(
+            &entry,
+            &["co2_cycle_g", "cycle_co2_g", "co2_mass_g"],
+        );
+        let cumulative_co2_g = dict_optional_finite_by_keys(
+            &entry,
+            &["cumulative_co2_g", "co2_g", "cumulative_co2_added_mass_g", "co2_mass_g"],
+        );
+        let cumulative_co2_mol = dict_optional_finite_by_keys(
+            &entry,
+            &[
+                "cumulative_co2_mol",
+                "cumulative_co2_added_moles",
+                "co2_moles",
+                "co2_added_moles",
+            ],
+        );
+        let duration_x = dict_optional_finite_by_keys(&entry, &["duration_x"]);
+        if inferred_time_label.is_empty() {
+            inferred_time_label = dict_string_value(&entry, "x_label");
         }
-        if uptake_b.is_none() {
-            if let Some(cum_val) = cum_b {
-                uptake_b = Some(cum_val - prior_cum_b);
+        let ph = dict_optional_finite_by_keys(&entry, &["ph", "actual_ph", "solution_ph"]);
+        let mut hco3_fraction = 0.0_f64;
+        let mut co3_fraction = 0.0_f64;
+        if let Some(fractions_any) = entry.get_item("fractions").ok().flatten() {
+            if let Ok(fractions) = fractions_any.cast_into::<PyDict>() {
+                hco3_fraction = dict_optional_float_value(&fractions, "HCO3-")
+                    .unwrap_or(0.0)
+                    .max(0.0);
+                co3_fraction = dict_optional_float_value(&fractions, "CO3^2-")
+                    .unwrap_or(0.0)
+                    .max(0.0);
             }
         }
-        if cum_a.is_none() {
-            if let Some(uptake_val) = uptake_a {
-                cum_a = Some(last_cum_a + uptake_val);
+        actual_rows.push(ActualRow {
+            cycle_id,
+            cycle_co2_g,
+            cumulative_co2_g,
+            cumulative_co2_mol,
+            duration_x,
+            ph,
+            hco3_fraction,
+            co3_fraction,
+        });
+        actual_alignment_override.push(dict_string_value(&entry, "alignment_quality_flag"));
+    }
+
+    let mut reference_rows: Vec<ReferenceRow> = Vec::new();
+    let mut reference_x: Vec<f64> = Vec::new();
+    let mut reference_ph: Vec<Option<f64>> = Vec::new();
+    let mut reference_hco3: Vec<Option<f64>> = Vec::new();
+    let mut reference_co3: Vec<Option<f64>> = Vec::new();
+    for (idx, item) in reference_cycle_series.iter().enumerate() {
+        let Ok(entry) = item.cast_into::<PyDict>() else {
+            continue;
+        };
+        let cycle_id = compare_cycle_row_index(&entry, idx + 1);
+        let cumulative_co2_g = dict_optional_finite_by_keys(
+            &entry,
+            &["cumulative_co2_g", "co2_g", "cumulative_co2_added_mass_g", "co2_mass_g"],
+        );
+        if let Some(x_value) = cumulative_co2_g {
+            if x_value.is_finite() {
+                reference_x.push(x_value);
             }
         }
-        if cum_b.is_none() {
-            if let Some(uptake_val) = uptake_b {
-                cum_b = Some(last_cum_b + uptake_val);
+        let ph = dict_optional_finite_by_keys(&entry, &["ph", "reference_ph", "solution_ph"]);
+        let mut hco3_fraction = None;
+        let mut co3_fraction = None;
+        if let Some(fractions_any) = entry.get_item("fractions").ok().flatten() {
+            if let Ok(fractions) = fractions_any.cast_into::<PyDict>() {
+                hco3_fraction = dict_optional_float_value(&fractions, "HCO3-");
+                co3_fraction = dict_optional_float_value(&fractions, "CO3^2-");
             }
         }
-        if let Some(cum_val) = cum_a {
-            last_cum_a = cum_val;
-            valid_a = true;
-        }
-        if let Some(cum_val) = cum_b {
-            last_cum_b = cum_val;
-            valid_b = true;
-        }
-        let delta_g = match (uptake_a, uptake_b) {
-            (Some(a), Some(b)) => Some(b - a),
+        reference_ph.push(ph);
+        reference_hco3.push(hco3_fraction);
+        reference_co3.push(co3_fraction);
+        let equivalence_reached = entry
+            .get_item("equivalence_reached")
+            .ok()
+            .flatten()
+            .and_then(|value| value.is_truthy().ok())
+            .unwrap_or(false);
+        reference_rows.push(ReferenceRow {
+            cycle_id,
+            cumulative_co2_g,
+            ph,
+            equivalence_reached,
+        });
+    }
+
+    let comparison_series = PyList::empty(py);
+    for (idx, row) in actual_rows.iter().enumerate() {
+        let ref_cycle = reference_rows.get(idx).copied();
+        let reference_cycle_ph = ref_cycle.and_then(|item| item.ph);
+        let delta_cycle_ph = match (row.ph, reference_cycle_ph) {
+            (Some(actual), Some(reference)) => Some(actual - reference),
             _ => None,
         };
-        let cum_delta_g = match (cum_a, cum_b) {
-            (Some(a), Some(b)) => Some(b - a),
+        let (reference_co2_aligned_ph, ph_alignment) = interpolate_reference_series_impl(
+            row.cumulative_co2_g,
+            &reference_x,
+            &reference_ph,
+        );
+        let (co2_aligned_hco3, hco3_alignment) = interpolate_reference_series_impl(
+            row.cumulative_co2_g,
+            &reference_x,
+            &reference_hco3,
+        );
+        let (co2_aligned_co3, co3_alignment) = interpolate_reference_series_impl(
+            row.cumulative_co2_g,
+            &reference_x,
+            &reference_co3,
+        );
+        let delta_co2_ph = match (row.ph, reference_co2_aligned_ph) {
+            (Some(actual), Some(reference)) => Some(actual - reference),
             _ => None,
         };
+        let actual_hco3_pct = row.hco3_fraction.max(0.0) * 100.0;
+        let actual_co3_pct = row.co3_fraction.max(0.0) * 100.0;
+        let reference_hco3_pct = co2_aligned_hco3.map(|value| value * 100.0);
+        let reference_co3_pct = co2_aligned_co3.map(|value| value * 100.0);
+        let delta_hco3_pct = reference_hco3_pct.map(|reference| actual_hco3_pct - reference);
+        let delta_co3_pct = reference_co3_pct.map(|reference| actual_co3_pct - reference);
+        let mut alignment_quality = if [ph_alignment, hco3_alignment, co3_alignment]
+            .iter()
+            .any(|flag| *flag == "out_of_range")
+        {
+            "out_of_range".to_string()
+        } else if [ph_alignment, hco3_alignment, co3_alignment]
+            .iter()
+            .any(|flag| *flag == "sparse_reference")
+        {
+            "sparse_reference".to_string()
+        } else {
+            "ok".to_string()
+        };
+        if let Some(override_flag) = actual_alignment_override.get(idx) {
+            let trimmed = override_flag.trim();
+            if !trimmed.is_empty() {
+                alignment_quality = trimmed.to_string();
+            }
+        }
 
-        let row = PyDict::new(py);
-        row.set_item("cycle", cycle_id)?;
-        if let Some(value) = uptake_a {
-            row.set_item("uptake_a_g", value)?;
+        let comparison_row = PyDict::new(py);
+        comparison_row.set_item("cycle_id", row.cycle_id)?;
+        if let Some(value) = row.ph {
+            comparison_row.set_item("actual_ph", value)?;
         } else {
-            row.set_item("uptake_a_g", py.None())?;
+            comparison_row.set_item("actual_ph", py.None())?;
         }
-        if let Some(value) = uptake_b {
-            row.set_item("uptake_b_g", value)?;
+        if let Some(value) = reference_cycle_ph {
+            comparison_row.set_item("reference_cycle_ph", value)?;
         } else {
-            row.set_item("uptake_b_g", py.None())?;
+            comparison_row.set_item("reference_cycle_ph", py.None())?;
         }
-        if let Some(value) = delta_g {
-            row.set_item("delta_g", value)?;
+        if let Some(value) = reference_co2_aligned_ph {
+            comparison_row.set_item("reference_co2_aligned_ph", value)?;
         } else {
-            row.set_item("delta_g", py.None())?;
+            comparison_row.set_item("reference_co2_aligned_ph", py.None())?;
         }
-        if let Some(value) = cum_a {
-            row.set_item("cum_a_g", value)?;
+        if let Some(value) = delta_cycle_ph {
+            comparison_row.set_item("delta_cycle_ph", value)?;
         } else {
-            row.set_item("cum_a_g", py.None())?;
+            comparison_row.set_item("delta_cycle_ph", py.None())?;
         }
-        if let Some(value) = cum_b {
-            row.set_item("cum_b_g", value)?;
+        if let Some(value) = delta_co2_ph {
+            comparison_row.set_item("delta_co2_ph", value)?;
         } else {
-            row.set_item("cum_b_g", py.None())?;
+            comparison_row.set_item("delta_co2_ph", py.None())?;
         }
-        if let Some(value) = cum_delta_g {
-            row.set_item("cum_delta_g", value)?;
+        comparison_row.set_item("actual_hco3_pct", actual_hco3_pct)?;
+        comparison_row.set_item("actual_co3_pct", actual_co3_pct)?;
+        if let Some(value) = reference_hco3_pct {
+            comparison_row.set_item("reference_hco3_pct", value)?;
         } else {
-            row.set_item("cum_delta_g", py.None())?;
+            comparison_row.set_item("reference_hco3_pct", py.None())?;
         }
-        rows.append(row)?;
-    }
-    Ok(rows.unbind())
-}
-
-#[pyfunction]
-#[pyo3(signature = (entries, filter_value, sort_mode, sort_key, sort_desc, sort_column_type))]
-/// Compute Ledger row indices after applying filter and sort rules.
-///
-/// Returning indices instead of rebuilt rows preserves the existing Python UI
-/// update flow while allowing Rust to accelerate the expensive comparisons.
-fn ledger_sort_filter_indices_core(
-    entries: &Bound<'_, PyList>,
-    filter_value: &str,
-    sort_mode: &str,
-    sort_key: &str,
-    sort_desc: bool,
-    sort_column_type: &str,
-) -> PyResult<Option<Vec<usize>>> {
-    // Prepare filtered/sorted row indices for Ledger table refresh while keeping
-    // schema handling deterministic and fallback-safe.
-    #[derive(Clone)]
-    struct RowSortData {
-        idx: usize,
-        display_order: i64,
-        updated_at: String,
-        row_id: String,
-        number_value: Option<f64>,
-        date_value: Option<i64>,
-        text_value: String,
+        if let Some(value) = reference_co3_pct {
+            comparison_row.set_item("reference_co3_pct", value)?;
+        } else {
+            comparison_row.set_item("reference_co3_pct", py.None())?;
+        }
+        if let Some(value) = delta_hco3_pct {
+            comparison_row.set_item("delta_hco3_pct", value)?;
+        } else {
+            comparison_row.set_item("delta_hco3_pct", py.None())?;
+        }
+        if let Some(value) = delta_co3_pct {
+            comparison_row.set_item("delta_co3_pct", value)?;
+        } else {
+            comparison_row.set_item("delta_co3_pct", py.None())?;
+        }
+        comparison_row.set_item("alignment_quality_flag", alignment_quality)?;
+        comparison_series.append(comparison_row)?;
     }
 
-    let mode_token = match sort_mode.trim().to_ascii_lowercase().as_str() {
-        "column" | "column sort" => "column",
-        _ => "manual",
+    let total_uptake_g = actual_rows.last().and_then(|row| row.cumulative_co2_g);
+    let total_uptake_mol = actual_rows.last().and_then(|row| row.cumulative_co2_mol);
+    let co2_mw_value = if co2_mw.is_finite() && co2_mw > 0.0 {
+        co2_mw
+    } else {
+        SOL_MW_CO2
     };
-    let column_type = sort_column_type.trim().to_ascii_lowercase();
-    if mode_token == "column" && column_type == "formula" {
-        return Ok(None);
-    }
-    let normalized_sort_key = {
-        let candidate = sort_key.trim();
-        if candidate.is_empty() {
-            "updated_at"
+    let naoh_mw_value = if naoh_mw.is_finite() && naoh_mw > 0.0 {
+        naoh_mw
+    } else {
+        SOL_MW_NAOH
+    };
+    let theoretical_yield_g = if let Some(start_mass_g) = reaction_naoh_mass {
+        if start_mass_g.is_finite() && start_mass_g > 0.0 && naoh_mw_value > 0.0 {
+            Some((start_mass_g / naoh_mw_value) * co2_mw_value)
         } else {
-            candidate
+            None
         }
+    } else {
+        None
     };
-    let filter_token = filter_value.trim().to_ascii_lowercase();
-    let apply_filter = !filter_token.is_empty() && filter_token != "all profiles";
-    let mut rows: Vec<RowSortData> = Vec::new();
-    for (idx, item) in entries.iter().enumerate() {
-        let Ok(entry) = item.cast_into::<PyDict>() else {
-            continue;
-        };
-        let profile_lower = dict_string_value(&entry, "profile_name").to_ascii_lowercase();
-        if apply_filter && profile_lower != filter_token {
-            continue;
+    let actual_yield_pct = match (total_uptake_g, theoretical_yield_g) {
+        (Some(total), Some(theoretical)) if theoretical > 1e-12 => Some((total / theoretical) * 100.0),
+        _ => None,
+    };
+    let reference_final_g = reference_rows.last().and_then(|row| row.cumulative_co2_g);
+    let planning_completion_pct = match (reference_final_g, total_uptake_g) {
+        (Some(reference), Some(total)) if reference > 1e-12 => {
+            Some((total / reference).clamp(0.0, 1.0) * 100.0)
+        }
+        _ => None,
+    };
+    let equivalence_co2_g = match initial_naoh_mol {
+        Some(moles) if moles.is_finite() && moles > 0.0 => Some((moles / 2.0) * co2_mw_value),
+        _ => None,
+    };
+    let equivalence_completion_pct = match (equivalence_co2_g, total_uptake_g) {
+        (Some(reference), Some(total)) if reference > 1e-12 => {
+            Some((total / reference).clamp(0.0, 1.0) * 100.0)
+        }
+        _ => None,
+    };
+    let mut equivalence_cycle_actual: Option<usize> = None;
+    if let Some(eq_target_g) = equivalence_co2_g {
+        for row in &actual_rows {
+            if let Some(cumulative_g) = row.cumulative_co2_g {
+                if cumulative_g >= eq_target_g {
+                    equivalence_cycle_actual = Some(row.cycle_id);
+                    break;
+                }
+            }
         }
-        let display_order = entry
-            .get_item("display_order")
-            .ok()
-            .flatten()
-            .and_then(|value| value.extract::<i64>().ok())
-            .unwrap_or(0);
-        let updated_at = dict_string_value(&entry, "updated_at");
-        let row_id = dict_string_value(&entry, "id");
-        let number_value = if column_type == "number" {
-            ledger_sort_numeric_value(&entry, normalized_sort_key)
-        } else {
-            None
-        };
-        let date_value = if column_type == "date" {
-            ledger_sort_date_value(&entry, normalized_sort_key)
-        } else {
-            None
-        };
-        let text_value = if column_type == "text" {
-            ledger_sort_text_value(&entry, normalized_sort_key).to_ascii_lowercase()
-        } else {
-            String::new()
-        };
-        rows.push(RowSortData {
-            idx,
-            display_order,
-            updated_at,
-            row_id,
-            number_value,
-            date_value,
-            text_value,
-        });
     }
-
-    if mode_token == "manual" {
-        rows.sort_by(|left, right| {
-            (
-                left.display_order,
-                left.updated_at.as_str(),
-                left.row_id.as_str(),
-            )
-                .cmp(&(
-                    right.display_order,
-                    right.updated_at.as_str(),
-                    right.row_id.as_str(),
-                ))
-        });
-        return Ok(Some(rows.into_iter().map(|row| row.idx).collect()));
+    let mut equivalence_cycle_reference: Option<usize> = None;
+    for row in &reference_rows {
+        if row.equivalence_reached {
+            equivalence_cycle_reference = Some(row.cycle_id);
+            break;
+        }
     }
-
-    match column_type.as_str() {
-        "number" => {
-            rows.sort_by(|left, right| {
-                let left_missing = left.number_value.is_none();
-                let right_missing = right.number_value.is_none();
-                if left_missing != right_missing {
-                    return if sort_desc {
-                        if left_missing {
-                            Ordering::Less
-                        } else {
-                            Ordering::Greater
-                        }
-                    } else if left_missing {
-                        Ordering::Greater
+    let additional_co2_required_g = match (reference_final_g, total_uptake_g) {
+        (Some(reference), Some(total)) if reference.is_finite() && total.is_finite() => {
+            Some((reference - total).max(0.0))
+        }
+        _ => None,
+    };
+    let additional_co2_required_mol = match additional_co2_required_g {
+        Some(value) if co2_mw_value > 0.0 => Some(value / co2_mw_value),
+        _ => None,
+    };
+    let mut forecast_confidence = "low".to_string();
+    let mut forecast_slowdown_detected = false;
+    let mut forecast_lookback_cycles: usize = 0;
+    let mut forecast_knee_cycle: Option<usize> = None;
+    let mut forecast_pre_knee_rate_g_per_cycle: Option<f64> = None;
+    let mut forecast_post_knee_rate_g_per_cycle: Option<f64> = None;
+    let mut forecast_tail_rate_g_per_cycle: Option<f64> = None;
+    let mut forecast_remaining_cycles: Option<f64> = None;
+    let mut forecast_remaining_time_x: Option<f64> = None;
+    let mut forecast_time_basis = "cycles_only".to_string();
+    let remaining_target_g = additional_co2_required_g.unwrap_or(0.0);
+    if remaining_target_g <= 1e-12 {
+        forecast_confidence = "high".to_string();
+        forecast_remaining_cycles = Some(0.0);
+        forecast_remaining_time_x = Some(0.0);
+    } else {
+        let mut cycle_uptakes_g: Vec<f64> = Vec::new();
+        let mut durations_x: Vec<Option<f64>> = Vec::new();
+        let mut cumulative_known = false;
+        let mut last_cumulative = 0.0_f64;
+        for row in &actual_rows {
+            let mut cycle_mass = row.cycle_co2_g;
+            if cycle_mass.is_none() {
+                if let Some(cumulative) = row.cumulative_co2_g {
+                    if cumulative_known {
+                        cycle_mass = Some((cumulative - last_cumulative).max(0.0));
                     } else {
-                        Ordering::Less
-                    };
-                }
-                match (left.number_value, right.number_value) {
-                    (Some(a), Some(b)) => {
-                        if sort_desc {
-                            b.partial_cmp(&a).unwrap_or(Ordering::Equal)
-                        } else {
-                            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
-                        }
+                        cycle_mass = Some(cumulative.max(0.0));
                     }
-                    _ => Ordering::Equal,
+                    cumulative_known = true;
+                    last_cumulative = cumulative;
+                }
+            }
+            cycle_uptakes_g.push(cycle_mass.unwrap_or(0.0).max(0.0));
+            let duration = row.duration_x.and_then(|value| {
+                if value.is_finite() && value > 0.0 {
+                    Some(value)
+                } else {
+                    None
                 }
             });
+            durations_x.push(duration);
         }
-        "date" => {
-            rows.sort_by(|left, right| {
-                let left_missing = left.date_value.is_none();
-                let right_missing = right.date_value.is_none();
-                if left_missing != right_missing {
-                    return if sort_desc {
-                        if left_missing {
-                            Ordering::Less
-                        } else {
-                            Ordering::Greater
-                        }
-                    } else if left_missing {
-                        Ordering::Greater
+        let detected_cycles = cycle_uptakes_g.len();
+        if detected_cycles >= 4 {
+            let mut lookback = ((detected_cycles as f64) * 0.2_f64).round() as usize;
+            lookback = lookback.clamp(4, 12).min(detected_cycles);
+            forecast_lookback_cycles = lookback;
+            let recent = &cycle_uptakes_g[detected_cycles - lookback..];
+            if recent.len() >= 4 {
+                let mut best_split: Option<usize> = None;
+                let mut best_sse = f64::INFINITY;
+                for split_idx in 2..(recent.len() - 1) {
+                    let pre = &recent[..split_idx];
+                    let post = &recent[split_idx..];
+                    let pre_mean = pre.iter().sum::<f64>() / pre.len() as f64;
+                    let post_mean = post.iter().sum::<f64>() / post.len() as f64;
+                    let pre_sse = pre
+                        .iter()
+                        .map(|value| (value - pre_mean) * (value - pre_mean))
+                        .sum::<f64>();
+                    let post_sse = post
+                        .iter()
+                        .map(|value| (value - post_mean) * (value - post_mean))
+                        .sum::<f64>();
+                    let sse = pre_sse + post_sse;
+                    if sse < best_sse {
+                        best_sse = sse;
+                        best_split = Some(split_idx);
+                    }
+                }
+                if let Some(split_idx) = best_split {
+                    let pre = &recent[..split_idx];
+                    let post = &recent[split_idx..];
+                    let pre_rate = pre.iter().sum::<f64>() / pre.len() as f64;
+                    let post_rate = post.iter().sum::<f64>() / post.len() as f64;
+                    forecast_pre_knee_rate_g_per_cycle = Some(pre_rate);
+                    forecast_post_knee_rate_g_per_cycle = Some(post_rate);
+                    forecast_knee_cycle = Some(detected_cycles - lookback + split_idx);
+                    forecast_slowdown_detected =
+                        pre_rate > 1e-12 && post_rate <= (pre_rate * 0.92_f64);
+                    let tail_window = recent.len().clamp(3, 5);
+                    let tail_rate = if forecast_slowdown_detected && post_rate > 1e-12 {
+                        post_rate
                     } else {
-                        Ordering::Less
+                        recent[recent.len() - tail_window..].iter().sum::<f64>()
+                            / tail_window as f64
                     };
-                }
-                match (left.date_value, right.date_value) {
-                    (Some(a), Some(b)) => {
-                        if sort_desc {
-                            b.cmp(&a)
+                    if tail_rate > 1e-12 && tail_rate.is_finite() {
+                        forecast_tail_rate_g_per_cycle = Some(tail_rate);
+                        let remaining_cycles = (remaining_target_g / tail_rate).max(0.0);
+                        forecast_remaining_cycles = Some(remaining_cycles);
+                        let post_variance = post
+                            .iter()
+                            .map(|value| (value - post_rate) * (value - post_rate))
+                            .sum::<f64>()
+                            / post.len().max(1) as f64;
+                        let post_cv = if post_rate.abs() > 1e-12 {
+                            post_variance.sqrt() / post_rate.abs()
                         } else {
-                            a.cmp(&b)
+                            f64::INFINITY
+                        };
+                        if detected_cycles >= 8 && forecast_slowdown_detected && post_cv <= 0.25 {
+                            forecast_confidence = "high".to_string();
+                        } else if detected_cycles >= 6 && post_cv <= 0.45 {
+                            forecast_confidence = "medium".to_string();
+                        }
+                        let time_label_lower = inferred_time_label.trim().to_lowercase();
+                        let is_time_basis = ["elapsed", "time", "sec", "second", "min", "hour", "day"]
+                            .iter()
+                            .any(|token| time_label_lower.contains(token));
+                        if is_time_basis {
+                            let finite_durations: Vec<f64> = durations_x
+                                [durations_x.len().saturating_sub(lookback)..]
+                                .iter()
+                                .filter_map(|value| *value)
+                                .collect();
+                            if !finite_durations.is_empty() {
+                                let avg_duration =
+                                    finite_durations.iter().sum::<f64>() / finite_durations.len() as f64;
+                                forecast_remaining_time_x = Some(remaining_cycles * avg_duration);
+                                forecast_time_basis = if inferred_time_label.trim().is_empty() {
+                                    "elapsed_time".to_string()
+                                } else {
+                                    inferred_time_label.trim().to_string()
+                                };
+                            }
                         }
                     }
-                    _ => Ordering::Equal,
-                }
-            });
-        }
-        _ => {
-            rows.sort_by(|left, right| {
-                if sort_desc {
-                    right.text_value.cmp(&left.text_value)
-                } else {
-                    left.text_value.cmp(&right.text_value)
                 }
-            });
+            }
         }
     }
-    Ok(Some(rows.into_iter().map(|row| row.idx).collect()))
-}
+    let warning_rows = PyList::empty(py);
+    if reference_rows.is_empty() && !actual_rows.is_empty() {
+        warning_rows.append(
+            "Reference simulation unavailable; verify per-cycle CO2 uptake fields.",
+        )?;
+    }
 
-#[pyfunction]
-#[pyo3(signature = (cycle_rows, total_drop_value=None, reaction_basis=None))]
-/// Derive Ledger add-from-profile metrics from cycle rows and reaction inputs.
-///
-/// The function preserves the Python fallback schema while normalizing PyO3
-/// ownership so row dictionaries can be retained safely across the loop.
-fn ledger_prefill_metrics_core(
-    py: Python<'_>,
-    cycle_rows: &Bound<'_, PyList>,
-    total_drop_value: Option<f64>,
-    reaction_basis: Option<Bound<'_, PyDict>>,
-) -> PyResult<Py<PyDict>> {
-    // Extract Ledger add-from-profile metrics in Rust while preserving Python
-    // fallback parity and schema expectations.
-    let cumulative_keys = [
-        "cumulative_co2_mass_g",
-        "cumulative_co2_added_mass_g",
-        "cumulative_co2_added_g",
-        "cumulative_co2_g",
-        "cumulative_added_g",
-        "cumulative_mass_g",
-    ];
-    let mut normalized_rows: Vec<Bound<'_, PyDict>> = Vec::new();
-    for item in cycle_rows.iter() {
-        let Ok(entry) = item.cast_into::<PyDict>() else {
-            continue;
-        };
-        normalized_rows.push(entry);
+    let summary = PyDict::new(py);
+    summary.set_item("detected_cycles", actual_rows.len())?;
+    summary.set_item("reference_cycles", reference_rows.len())?;
+    summary.set_item("target_ph", target_ph)?;
+    if let Some(value) = total_uptake_g {
+        summary.set_item("total_uptake_g", value)?;
+    } else {
+        summary.set_item("total_uptake_g", py.None())?;
     }
-    let mut total_drop = total_drop_value.filter(|value| value.is_finite() && *value >= 0.0);
-    if total_drop.is_none() {
-        if let Some(last_row) = normalized_rows.last() {
-            total_drop = dict_optional_finite_by_keys(last_row, &cumulative_keys)
-                .filter(|value| value.is_finite() && *value >= 0.0);
-        }
+    if let Some(value) = total_uptake_mol {
+        summary.set_item("total_uptake_mol", value)?;
+    } else {
+        summary.set_item("total_uptake_mol", py.None())?;
     }
-    let basis = reaction_basis.as_ref();
-    let start_mass = basis.and_then(|dict| dict_optional_float_value(dict, "starting_mass_g"));
-    let start_mw =
-        basis.and_then(|dict| dict_optional_float_value(dict, "starting_material_mw_g_mol"));
-    let stoich =
-        basis.and_then(|dict| dict_optional_float_value(dict, "stoich_mol_gas_per_mol_starting"));
-    let gas_mw = basis.and_then(|dict| dict_optional_float_value(dict, "gas_molar_mass"));
-    let theoretical = match (start_mass, start_mw, stoich, gas_mw) {
-        (Some(mass), Some(mw), Some(st), Some(gas))
-            if mw > 0.0 && st > 0.0 && gas > 0.0 && mass >= 0.0 =>
-        {
-            Some((mass / mw) * st * gas)
-        }
-        _ => None,
-    };
-    let out = PyDict::new(py);
-    if normalized_rows.is_empty() {
-        out.set_item("cycles", py.None())?;
+    if let Some(value) = theoretical_yield_g {
+        summary.set_item("theoretical_yield_g", value)?;
     } else {
-        out.set_item("cycles", normalized_rows.len())?;
+        summary.set_item("theoretical_yield_g", py.None())?;
     }
-    if let Some(value) = total_drop {
-        out.set_item("total_dp_uptake", value)?;
+    if let Some(value) = actual_yield_pct {
+        summary.set_item("actual_yield_pct", value)?;
     } else {
-        out.set_item("total_dp_uptake", py.None())?;
+        summary.set_item("actual_yield_pct", py.None())?;
     }
-    if let Some(value) = theoretical {
-        out.set_item("theoretical_yield_g", value)?;
+    if let Some(value) = planning_completion_pct {
+        summary.set_item("planning_completion_pct", value)?;
     } else {
-        out.set_item("theoretical_yield_g", py.None())?;
+        summary.set_item("planning_completion_pct", py.None())?;
     }
-    Ok(out.unbind())
+    if let Some(value) = equivalence_completion_pct {
+        summary.set_item("equivalence_completion_pct", value)?;
+    } else {
+        summary.set_item("equivalence_completion_pct", py.None())?;
+    }
+    if let Some(value) = equivalence_cycle_actual {
+        summary.set_item("equivalence_cycle_actual", value)?;
+    } else {
+        summary.set_item("equivalence_cycle_actual", py.None())?;
+    }
+    if let Some(value) = equivalence_cycle_reference {
+        summary.set_item("equivalence_cycle_reference", value)?;
+    } else {
+        summary.set_item("equivalence_cycle_reference", py.None())?;
+    }
+    if let Some(value) = additional_co2_required_g {
+        summary.set_item("additional_co2_required_g", value)?;
+    } else {
+        summary.set_item("additional_co2_required_g", py.None())?;
+    }
+    if let Some(value) = additional_co2_required_mol {
+        summary.set_item("additional_co2_required_mol", value)?;
+    } else {
+        summary.set_item("additional_co2_required_mol", py.None())?;
+    }
+    summary.set_item("forecast_model", "two_phase_trend")?;
+    summary.set_item("forecast_confidence", forecast_confidence)?;
+    summary.set_item("forecast_slowdown_detected", forecast_slowdown_detected)?;
+    summary.set_item("forecast_lookback_cycles", forecast_lookback_cycles)?;
+    if let Some(value) = forecast_knee_cycle {
+        summary.set_item("forecast_knee_cycle", value)?;
+    } else {
+        summary.set_item("forecast_knee_cycle", py.None())?;
+    }
+    if let Some(value) = forecast_pre_knee_rate_g_per_cycle {
+        summary.set_item("forecast_pre_knee_rate_g_per_cycle", value)?;
+    } else {
+        summary.set_item("forecast_pre_knee_rate_g_per_cycle", py.None())?;
+    }
+    if let Some(value) = forecast_post_knee_rate_g_per_cycle {
+        summary.set_item("forecast_post_knee_rate_g_per_cycle", value)?;
+    } else {
+        summary.set_item("forecast_post_knee_rate_g_per_cycle", py.None())?;
+    }
+    if let Some(value) = forecast_tail_rate_g_per_cycle {
+        summary.set_item("forecast_tail_rate_g_per_cycle", value)?;
+    } else {
+        summary.set_item("forecast_tail_rate_g_per_cycle", py.None())?;
+    }
+    if let Some(value) = forecast_remaining_cycles {
+        summary.set_item("forecast_remaining_cycles", value)?;
+    } else {
+        summary.set_item("forecast_remaining_cycles", py.None())?;
+    }
+    if let Some(value) = forecast_remaining_time_x {
+        summary.set_item("forecast_remaining_time_x", value)?;
+    } else {
+        summary.set_item("forecast_remaining_time_x", py.None())?;
+    }
+    summary.set_item("forecast_time_basis", forecast_time_basis)?;
+    summary.set_item("warnings", warning_rows)?;
+
+    let response = PyDict::new(py);
+    response.set_item("comparison_series", comparison_series)?;
+    response.set_item("summary", summary)?;
+    Ok(response.unbind())
 }
 
 #[pyfunction]
-#[pyo3(signature = (x_values))]
-fn array_signature_core(x_values: PyReadonlyArray1<'_, f64>) -> PyResult<(usize, String, i64)> {
-    // Compute deterministic signed 64-bit FNV-1a hash for contiguous float bytes
-    // so Python/Rust parity checks remain stable across process restarts.
-    let view = x_values.as_array();
-    let mut hash_value: u64 = 0xCBF29CE484222325;
-    for value in view.iter() {
-        let bytes = value.to_le_bytes();
-        for byte_value in bytes {
-            hash_value ^= u64::from(byte_value);
-            hash_value = hash_value.wrapping_mul(0x100000001B3);
-        }
-    }
-    let hash_i64 = i64::from_ne_bytes(hash_value.to_ne_bytes());
-    Ok((view.len(), "float64".to_string(), hash_i64))
-}
-
-#[pyfunction]
-#[pyo3(signature = (cycle_transfer_rows, duration_header))]
-/// Format Final Report cycle-stat rows using the shared table schema.
+#[pyo3(signature = (
+    model_key,
+    cycle_uptake_mol_series,
+    anchor_cycle_index=None,
+    measured_ph=None,
+    equivalence_cycle_index=None,
+    target_ph=None,
+    initial_naoh_mol=None,
+    solution_volume_l=None,
+    temperature_c=None,
+    planning_reference_total_mol=None,
+    equivalence_co2_mol=None,
+    pitzer_params=None,
+    anchors=None,
+    prior_cycle_factor_series=None,
+    prior_sample_count=0,
+    learning_enabled=true,
+    terminal_ph_range_low=8.0,
+    terminal_ph_range_high=8.3,
+    terminal_ph_weight=1.0,
+    regularization_strength=0.02,
+    smoothness_strength=0.01,
+    min_factor=0.5,
+    max_factor=1.5,
+    **options
+))]
+/// Calibrate piecewise uptake factors against one or more measured pH anchors.
 ///
-/// Rust keeps the same column values and empty-string behavior expected by the
-/// Python report builders, so exports remain identical across backends.
-fn final_report_cycle_stats_rows_core(
+/// The solver mirrors the Python fallback contract: bounded deterministic
+/// segment fitting with regularization and split-factor smoothness.
+fn measured_ph_uptake_calibration_core(
     py: Python<'_>,
-    cycle_transfer_rows: &Bound<'_, PyList>,
-    duration_header: &str,
-) -> PyResult<Py<PyList>> {
-    // Format cycle-stat rows with the same schema expected by Python report
-    // builders while keeping Rust wrappers strictly fallback-safe.
-    let rows = PyList::empty(py);
-    for item in cycle_transfer_rows.iter() {
-        let Ok(entry) = item.cast_into::<PyDict>() else {
-            continue;
-        };
-        let delta = dict_optional_float_value(&entry, "delta_pressure_psi");
-        let duration = dict_optional_float_value(&entry, "duration_x")
-            .or_else(|| dict_optional_float_value(&entry, "duration"));
-        let peak_psi = dict_optional_float_value(&entry, "peak_pressure_psi");
-        let trough_psi = dict_optional_float_value(&entry, "trough_pressure_psi");
-        let mean_temp = dict_optional_float_value(&entry, "mean_temperature_c");
-        let co2_mass = dict_optional_float_value(&entry, "cumulative_co2_mass_g");
-        let row = PyDict::new(py);
-        row.set_item("Cycle", dict_truthy_or_empty_pyobject(py, &entry, "cycle_id"))?;
-        row.set_item("\u{0394}P (PSI)", format_optional_decimal(delta, 2))?;
-        row.set_item(duration_header, format_optional_decimal(duration, 3))?;
-        row.set_item("Peak PSI", format_optional_decimal(peak_psi, 2))?;
-        row.set_item("Trough PSI", format_optional_decimal(trough_psi, 2))?;
-        row.set_item("Mean T (\u{00B0}C)", format_optional_decimal(mean_temp, 2))?;
-        row.set_item("CO\u{2082} (g)", format_optional_decimal(co2_mass, 2))?;
-        rows.append(row)?;
+    model_key: &str,
+    cycle_uptake_mol_series: &Bound<'_, PyList>,
+    anchor_cycle_index: Option<usize>,
+    measured_ph: Option<f64>,
+    equivalence_cycle_index: Option<usize>,
+    target_ph: Option<f64>,
+    initial_naoh_mol: Option<f64>,
+    solution_volume_l: Option<f64>,
+    temperature_c: Option<f64>,
+    planning_reference_total_mol: Option<f64>,
+    equivalence_co2_mol: Option<f64>,
+    pitzer_params: Option<Bound<'_, PyDict>>,
+    anchors: Option<Bound<'_, PyList>>,
+    prior_cycle_factor_series: Option<Bound<'_, PyList>>,
+    prior_sample_count: usize,
+    learning_enabled: bool,
+    terminal_ph_range_low: f64,
+    terminal_ph_range_high: f64,
+    terminal_ph_weight: f64,
+    regularization_strength: f64,
+    smoothness_strength: f64,
+    min_factor: f64,
+    max_factor: f64,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    #[derive(Clone)]
+    struct SimulationPayload {
+        corrected_cycle: Vec<f64>,
+        corrected_cumulative: Vec<f64>,
+        corrected_ph: Vec<Option<f64>>,
+        corrected_fractions: Vec<[f64; 3]>,
     }
-    Ok(rows.unbind())
-}
 
-const REACTION_SODIUM_MOLAR_MASS_G_MOL: f64 = 22.989_769;
-const REACTION_WATER_MOLAR_MASS_G_MOL: f64 = 18.015_28;
-const REACTION_METHANOL_MOLAR_MASS_G_MOL: f64 = 32.042;
-const REACTION_NAOH_MOLAR_MASS_G_MOL: f64 = 39.997;
+    #[derive(Clone)]
+    struct AnchorPoint {
+        cycle_index: usize,
+        measured_ph: f64,
+        source: String,
+    }
 
-fn nonnegative_charge_value(
-    value: Option<f64>,
-    label: &str,
-    warnings: &Bound<'_, PyList>,
-) -> PyResult<f64> {
-    let Some(raw) = value else {
-        warnings.append(format!("{label} is missing or invalid; using 0."))?;
-        return Ok(0.0);
+    let build_error = |message: &str| -> PyResult<Py<PyDict>> {
+        let payload = PyDict::new(py);
+        payload.set_item("solver_status", "invalid_input")?;
+        payload.set_item("solver_message", message)?;
+        Ok(payload.unbind())
     };
-    if !raw.is_finite() {
-        warnings.append(format!("{label} is missing or invalid; using 0."))?;
-        return Ok(0.0);
+
+    let mut cycle_moles: Vec<f64> = Vec::new();
+    for item in cycle_uptake_mol_series.iter() {
+        let parsed = item.extract::<f64>().ok().unwrap_or(0.0);
+        if parsed.is_finite() && parsed >= 0.0 {
+            cycle_moles.push(parsed);
+        } else {
+            cycle_moles.push(0.0);
+        }
     }
-    if raw < 0.0 {
-        warnings.append(format!("{label} was negative; using 0."))?;
-        return Ok(0.0);
+    if cycle_moles.is_empty() {
+        return build_error("No cycle uptake values were provided for calibration.");
     }
-    Ok(raw)
-}
-
-#[pyfunction]
-#[pyo3(signature = (sodium_metal_mass_g=None, methanol_mass_g=None, water_ppm_by_mass=None, explicit_naoh_mass_g=None))]
-/// Compute sodium/methanol charge availability for the Reaction Dashboard.
-///
-/// Water consumes sodium first and produces NaOH; remaining sodium forms sodium
-/// methoxide from dry methanol. The result schema mirrors the Python fallback.
-fn reaction_solution_charge_core(
-    py: Python<'_>,
-    sodium_metal_mass_g: Option<f64>,
-    methanol_mass_g: Option<f64>,
-    water_ppm_by_mass: Option<f64>,
-    explicit_naoh_mass_g: Option<f64>,
-) -> PyResult<Py<PyDict>> {
-    let warnings = PyList::empty(py);
-    let sodium_mass =
-        nonnegative_charge_value(sodium_metal_mass_g, "Sodium metal mass", &warnings)?;
-    let wet_methanol_mass =
-        nonnegative_charge_value(methanol_mass_g, "Methanol mass", &warnings)?;
-    let water_ppm =
-        nonnegative_charge_value(water_ppm_by_mass, "Methanol water ppm", &warnings)?;
-    let explicit_naoh_mass =
-        nonnegative_charge_value(explicit_naoh_mass_g, "Explicit NaOH mass", &warnings)?;
+    let kwargs = KernelKwargs::new("measured_ph_uptake_calibration_core", options)?;
+    let clamp_bounds = kwargs.take_clamp_bounds();
+    let planning_transition_width = kwargs.take_planning_transition_width();
+    kwargs.finish()?;
+    let planning = clamp_bounds.and_then(|bounds| Ok((bounds, planning_transition_width?)));
+    let (clamp_bounds, planning_transition_width) = match planning {
+        Ok(planning) => planning,
+        Err(err) => return build_error(&err.value(py).to_string()),
+    };
 
-    let water_mass_g = wet_methanol_mass * water_ppm / 1_000_000.0;
-    let dry_methanol_mass_g = (wet_methanol_mass - water_mass_g).max(0.0);
-    let sodium_mol = sodium_mass / REACTION_SODIUM_MOLAR_MASS_G_MOL;
-    let water_mol = water_mass_g / REACTION_WATER_MOLAR_MASS_G_MOL;
-    let methanol_mol = dry_methanol_mass_g / REACTION_METHANOL_MOLAR_MASS_G_MOL;
-    let explicit_naoh_mol = explicit_naoh_mass / REACTION_NAOH_MOLAR_MASS_G_MOL;
-    let sodium_consumed_by_water_mol = sodium_mol.min(water_mol);
-    let generated_naoh_mol = sodium_consumed_by_water_mol;
-    let sodium_after_water_mol = (sodium_mol - sodium_consumed_by_water_mol).max(0.0);
-    let sodium_methoxide_mol = sodium_after_water_mol.min(methanol_mol);
-    let remaining_methanol_mol = (methanol_mol - sodium_methoxide_mol).max(0.0);
-    let unreacted_sodium_mol = (sodium_after_water_mol - sodium_methoxide_mol).max(0.0);
-    let excess_water_mol = (water_mol - sodium_consumed_by_water_mol).max(0.0);
-    if excess_water_mol > 1e-12 {
-        warnings.append("Water exceeds sodium charge; sodium methoxide availability is zero.")?;
+    let mut lower_bound = min_factor.min(max_factor);
+    let mut upper_bound = min_factor.max(max_factor);
+    if !lower_bound.is_finite() || !upper_bound.is_finite() {
+        lower_bound = 0.5;
+        upper_bound = 1.5;
     }
-    if unreacted_sodium_mol > 1e-12 {
-        warnings.append("Sodium exceeds available dry methanol; sodium remains unused.")?;
+    if lower_bound <= 0.0 {
+        lower_bound = 0.1;
     }
-    let total_naoh_mol = explicit_naoh_mol + generated_naoh_mol;
+    if upper_bound <= lower_bound {
+        upper_bound = lower_bound + 0.1;
+    }
+    let mut terminal_low = if terminal_ph_range_low.is_finite() {
+        terminal_ph_range_low
+    } else {
+        8.0
+    };
+    let mut terminal_high = if terminal_ph_range_high.is_finite() {
+        terminal_ph_range_high
+    } else {
+        8.3
+    };
+    terminal_low = terminal_low.clamp(0.0, 14.5);
+    terminal_high = terminal_high.clamp(0.0, 14.5);
+    if terminal_high < terminal_low {
+        std::mem::swap(&mut terminal_low, &mut terminal_high);
+    }
+    let terminal_weight = if terminal_ph_weight.is_finite() {
+        terminal_ph_weight.clamp(0.0, 20.0)
+    } else {
+        1.0
+    };
 
-    let out = PyDict::new(py);
-    out.set_item("backend", "rust")?;
-    out.set_item("sodium_metal_mass_g", sodium_mass)?;
-    out.set_item("methanol_mass_g", wet_methanol_mass)?;
-    out.set_item("water_ppm_by_mass", water_ppm)?;
-    out.set_item("water_mass_g", water_mass_g)?;
-    out.set_item("dry_methanol_mass_g", dry_methanol_mass_g)?;
-    out.set_item("sodium_mol", sodium_mol)?;
-    out.set_item("water_mol", water_mol)?;
-    out.set_item("methanol_initial_mol", methanol_mol)?;
-    out.set_item("sodium_consumed_by_water_mol", sodium_consumed_by_water_mol)?;
-    out.set_item("generated_naoh_mol", generated_naoh_mol)?;
-    out.set_item("explicit_naoh_mol", explicit_naoh_mol)?;
-    out.set_item("total_naoh_mol", total_naoh_mol)?;
-    out.set_item("sodium_methoxide_mol", sodium_methoxide_mol)?;
-    out.set_item("remaining_methanol_mol", remaining_methanol_mol)?;
-    out.set_item("unreacted_sodium_mol", unreacted_sodium_mol)?;
-    out.set_item("excess_water_mol", excess_water_mol)?;
-    out.set_item("warnings", warnings)?;
-    Ok(out.unbind())
-}
+    let source_priority = |source: &str| -> i32 {
+        match source.trim().to_ascii_lowercase().as_str() {
+            "manual" | "form" => 5,
+            "legacy" => 4,
+            "profile" => 3,
+            "auto_final_ph" => 1,
+            _ => 2,
+        }
+    };
 
-#[pyfunction]
-#[pyo3(signature = (species_rows, step_rows, gas_uptake_mol, gas_species_id, yield_species_id, target_reactant_species_id=None, actual_yield_mass_g=None))]
-/// Compute template-neutral Reaction Dashboard stoichiometry and yield metrics.
-///
-/// The kernel mirrors the Python fallback schema while keeping pH/ChemPy
-/// behavior in Python. Species rows provide initial moles and molar masses;
-/// step rows provide signed stoichiometry maps with negative reactants and
-/// positive products.
-fn reaction_dashboard_core(
-    py: Python<'_>,
-    species_rows: &Bound<'_, PyList>,
-    step_rows: &Bound<'_, PyList>,
-    gas_uptake_mol: f64,
-    gas_species_id: &str,
-    yield_species_id: &str,
-    target_reactant_species_id: Option<&str>,
-    actual_yield_mass_g: Option<f64>,
-) -> PyResult<Py<PyDict>> {
-    let mut inventory: BTreeMap<String, f64> = BTreeMap::new();
-    let mut initial_inventory: BTreeMap<String, f64> = BTreeMap::new();
-    let mut molar_masses: BTreeMap<String, f64> = BTreeMap::new();
-    let mut species_names: BTreeMap<String, String> = BTreeMap::new();
-    let warnings = PyList::empty(py);
-
-    for item in species_rows.iter() {
-        let Ok(row) = item.cast_into::<PyDict>() else {
-            continue;
-        };
-        let mut species_id = dict_string_value(&row, "species_id");
-        if species_id.is_empty() {
-            species_id = dict_string_value(&row, "id");
-        }
-        if species_id.is_empty() {
-            continue;
-        }
-        let initial = dict_optional_float_value(&row, "initial_moles")
-            .unwrap_or(0.0)
-            .max(0.0);
-        let molar_mass = dict_optional_float_value(&row, "molar_mass_g_mol")
-            .unwrap_or(0.0)
-            .max(0.0);
-        inventory.insert(species_id.clone(), initial);
-        initial_inventory.insert(species_id.clone(), initial);
-        molar_masses.insert(species_id.clone(), molar_mass);
-        let name = dict_string_value(&row, "name");
-        species_names.insert(
-            species_id.clone(),
-            if name.is_empty() {
-                species_id.clone()
-            } else {
-                name
-            },
-        );
-    }
-
-    let gas_id = gas_species_id.trim().to_string();
-    let gas_uptake = gas_uptake_mol.max(0.0);
-    if gas_id.is_empty() {
-        warnings.append("Template does not define a gas species.")?;
-    } else {
-        let next_value = inventory.get(&gas_id).copied().unwrap_or(0.0) + gas_uptake;
-        inventory.insert(gas_id.clone(), next_value);
-        let next_initial = initial_inventory.get(&gas_id).copied().unwrap_or(0.0) + gas_uptake;
-        initial_inventory.insert(gas_id.clone(), next_initial);
-    }
-
-    let step_results = PyList::empty(py);
-    for (idx, item) in step_rows.iter().enumerate() {
-        let Ok(step) = item.cast_into::<PyDict>() else {
-            continue;
-        };
-        let stoich_any = step.get_item("stoichiometry").ok().flatten();
-        let Some(stoich_dict) = stoich_any.and_then(|value| value.cast_into::<PyDict>().ok())
-        else {
-            warnings.append(format!(
-                "{} has no stoichiometry.",
-                dict_string_value(&step, "name")
-            ))?;
-            continue;
-        };
-        let mut stoich: BTreeMap<String, f64> = BTreeMap::new();
-        for (key, value) in stoich_dict.iter() {
-            let species_id = py_any_to_trimmed_string(&key);
-            if species_id.is_empty() {
+    let mut anchors_by_cycle: BTreeMap<usize, (i32, AnchorPoint)> = BTreeMap::new();
+    if let Some(anchor_rows) = anchors {
+        for item in anchor_rows.iter() {
+            let Ok(row) = item.cast_into::<PyDict>() else {
+                continue;
+            };
+            let cycle_any = row
+                .get_item("cycle_index")
+                .ok()
+                .flatten()
+                .or_else(|| row.get_item("measured_ph_cycle_index").ok().flatten());
+            let ph_any = row
+                .get_item("measured_ph")
+                .ok()
+                .flatten()
+                .or_else(|| row.get_item("measured_ph_value").ok().flatten());
+            let source = row
+                .get_item("source")
+                .ok()
+                .flatten()
+                .and_then(|value| value.extract::<String>().ok())
+                .unwrap_or_else(|| "manual".to_string());
+            let Some(cycle_raw) = cycle_any.and_then(|value| value.extract::<f64>().ok()) else {
+                continue;
+            };
+            let Some(ph_value) = ph_any.and_then(|value| value.extract::<f64>().ok()) else {
+                continue;
+            };
+            if !cycle_raw.is_finite() || !ph_value.is_finite() {
                 continue;
             }
-            let coeff = value.extract::<f64>().unwrap_or(0.0);
-            if coeff.is_finite() && coeff.abs() > 0.0 {
-                stoich.insert(species_id, coeff);
+            let rounded_cycle = cycle_raw.round();
+            if (cycle_raw - rounded_cycle).abs() > 1e-6 || rounded_cycle <= 0.0 {
+                continue;
             }
-        }
-        let mut limiting_species: Option<String> = None;
-        let mut extent = f64::INFINITY;
-        for (species_id, coeff) in stoich.iter() {
-            if *coeff >= 0.0 {
+            let cycle_index = (rounded_cycle as usize).max(1).min(cycle_moles.len());
+            if !(0.0 < ph_value && ph_value < 14.0) {
                 continue;
             }
-            let candidate =
-                inventory.get(species_id).copied().unwrap_or(0.0) / coeff.abs().max(1e-30);
-            if candidate < extent {
-                extent = candidate.max(0.0);
-                limiting_species = Some(species_id.clone());
+            let anchor = AnchorPoint {
+                cycle_index,
+                measured_ph: ph_value,
+                source: source.clone(),
+            };
+            let incoming_priority = source_priority(&source);
+            if let Some((existing_priority, _existing_anchor)) =
+                anchors_by_cycle.get(&cycle_index)
+            {
+                if incoming_priority < *existing_priority {
+                    continue;
+                }
             }
+            anchors_by_cycle.insert(cycle_index, (incoming_priority, anchor));
         }
-        if !extent.is_finite() {
-            extent = 0.0;
-            warnings.append(format!(
-                "{} has no consumed species.",
-                dict_string_value(&step, "name")
-            ))?;
-        }
-        for (species_id, coeff) in stoich.iter() {
-            let current = inventory.get(species_id).copied().unwrap_or(0.0);
-            inventory.insert(species_id.clone(), (current + coeff * extent).max(0.0));
-        }
-        let step_row = PyDict::new(py);
-        let step_id = {
-            let value = dict_string_value(&step, "step_id");
-            if value.is_empty() {
-                dict_string_value(&step, "id")
-            } else {
-                value
-            }
+    }
+    if anchors_by_cycle.is_empty() {
+        let Some(legacy_ph) = measured_ph.filter(|value| value.is_finite()) else {
+            return build_error("Measured pH anchors are required for calibration.");
         };
-        step_row.set_item(
-            "step_id",
-            if step_id.is_empty() {
-                format!("{}", idx + 1)
-            } else {
-                step_id
-            },
-        )?;
-        let step_name = dict_string_value(&step, "name");
-        step_row.set_item(
-            "name",
-            if step_name.is_empty() {
-                format!("Step {}", idx + 1)
-            } else {
-                step_name
-            },
-        )?;
-        step_row.set_item("extent_mol", extent)?;
-        if let Some(limiting_id) = limiting_species {
-            step_row.set_item("limiting_species_id", limiting_id.clone())?;
-            step_row.set_item(
-                "limiting_species",
-                species_names.get(&limiting_id).cloned().unwrap_or_default(),
-            )?;
-        } else {
-            step_row.set_item("limiting_species_id", py.None())?;
-            step_row.set_item("limiting_species", "")?;
-        }
-        step_results.append(step_row)?;
+        let anchor_idx = anchor_cycle_index
+            .unwrap_or(1)
+            .max(1)
+            .min(cycle_moles.len());
+        anchors_by_cycle.insert(
+            anchor_idx,
+            (
+                source_priority("legacy"),
+                AnchorPoint {
+                    cycle_index: anchor_idx,
+                    measured_ph: legacy_ph,
+                    source: "legacy".to_string(),
+                },
+            ),
+        );
     }
+    let normalized_anchors: Vec<AnchorPoint> = anchors_by_cycle
+        .values()
+        .map(|(_priority, anchor)| anchor.clone())
+        .collect();
+    let primary_anchor = normalized_anchors
+        .last()
+        .cloned()
+        .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Missing anchor payload"))?;
+    let split_idx = equivalence_cycle_index
+        .unwrap_or(primary_anchor.cycle_index)
+        .max(1)
+        .min(cycle_moles.len());
 
-    let product_id = yield_species_id.trim().to_string();
-    let product_mol = inventory.get(&product_id).copied().unwrap_or(0.0);
-    let product_mw = molar_masses.get(&product_id).copied().unwrap_or(0.0);
-    let theoretical_yield_g = if product_mol >= 0.0 && product_mw > 0.0 {
-        Some(product_mol * product_mw)
+    let temp_c = temperature_c
+        .filter(|value| value.is_finite())
+        .unwrap_or(25.0);
+    let use_temp_constants = true;
+    let pka2_value = resolve_pka2_value_within(Some(temp_c), use_temp_constants, clamp_bounds);
+    let is_pitzer_model = model_key.trim().eq_ignore_ascii_case("naoh_co2_pitzer_hmw");
+    let pitzer_coeffs = if is_pitzer_model {
+        parse_pitzer_params_map(pitzer_params.as_ref())
     } else {
         None
     };
-    let actual_yield_pct = match (actual_yield_mass_g, theoretical_yield_g) {
-        (Some(actual), Some(theoretical)) if actual.is_finite() && theoretical > 1e-12 => {
-            Some((actual.max(0.0) / theoretical) * 100.0)
-        }
-        _ => None,
-    };
-    let target_id = target_reactant_species_id.unwrap_or("").trim().to_string();
-    let target_initial = if target_id.is_empty() {
-        0.0
-    } else {
-        initial_inventory.get(&target_id).copied().unwrap_or(0.0)
+    let available_naoh_mol = initial_naoh_mol
+        .filter(|value| value.is_finite() && *value > 0.0)
+        .map(|value| value.max(0.0));
+    let available_volume_l = solution_volume_l
+        .filter(|value| value.is_finite() && *value > 0.0)
+        .map(|value| value.max(1e-9));
+    let settings = SolverSettings {
+        temperature_c: Some(temp_c),
+        use_temp_adjusted_constants: use_temp_constants,
+        constants: None,
+        ionic_strength_cap: None,
+        activity_model: ActivityModel::Hybrid,
+        clamp_bounds,
+        raw_solver_ph: false,
     };
-    let completion_pct = if target_initial > 1e-12 {
-        Some(((product_mol / target_initial) * 100.0).clamp(0.0, 100.0))
-    } else if let Some(actual_pct) = actual_yield_pct {
-        Some(actual_pct.clamp(0.0, 100.0))
-    } else if product_mol > 1e-12 {
-        Some(100.0)
-    } else {
-        None
+
+    let predict_from_cumulative =
+        |cumulative_mol: f64, _cycle_index: usize| -> (Option<f64>, [f64; 3]) {
+            let cumulative = cumulative_mol.max(0.0);
+
+            // Prefer the Pitzer mapping when the selected model is NaOH-CO2 Pitzer
+            // and compact parameter coefficients are available.
+            if let (Some(params), Some(naoh_mol), Some(volume_l)) =
+                (pitzer_coeffs, available_naoh_mol, available_volume_l)
+            {
+                let total_sodium_m = naoh_mol / volume_l;
+                let total_carbon_m = cumulative / volume_l;
+                if total_sodium_m.is_finite() && total_sodium_m > 0.0 {
+                    if let Ok((ph, _na, _h, _oh, hco3, co3, co2)) =
+                        solve_pitzer_total_carbon_impl(total_carbon_m, total_sodium_m, params, 60)
+                    {
+                        if ph.is_finite() {
+                            let total = (co2.max(0.0) + hco3.max(0.0) + co3.max(0.0)).max(1e-12);
+                            return (
+                                Some(clamp_bounds.ph(ph)),
+                                [co2.max(0.0) / total, hco3.max(0.0) / total, co3.max(0.0) / total],
+                            );
+                        }
+                    }
+                }
+            }
+
+            if let (Some(naoh_mol), Some(volume_l)) = (available_naoh_mol, available_volume_l) {
+                let input_ledger = LedgerState {
+                    naoh_remaining_mol: naoh_mol,
+                    na2co3_mol: 0.0,
+                    nahco3_mol: 0.0,
+                    co2_excess_mol: 0.0,
+                };
+                let solve = LedgerSolve {
+                    planning_mode: true,
+                    planning_transition_width,
+                    ..LedgerSolve::new(settings, pka2_value, Some(volume_l))
+                };
+                let (state, _accounting, ph_value, _, _, _) =
+                    simulate_reaction_state_with_accounting_impl(
+                        input_ledger,
+                        cumulative,
+                        &solve,
+                        None,
+                        None,
+                        None,
+                    );
+                let total_carbon = (state.co2_excess_mol.max(0.0)
+                    + state.nahco3_mol.max(0.0)
+                    + state.na2co3_mol.max(0.0))
+                .max(1e-12);
+                return (
+                    Some(clamp_bounds.ph(ph_value)),
+                    [
+                        state.co2_excess_mol.max(0.0) / total_carbon,
+                        state.nahco3_mol.max(0.0) / total_carbon,
+                        state.na2co3_mol.max(0.0) / total_carbon,
+                    ],
+                );
+            }
+
+            // Corrected pH must remain chemistry-backed. Returning no pH here
+            // fails this Rust candidate and lets the Python chemistry fallback
+            // handle incomplete contexts instead of drawing a pseudo high-pH line.
+            (None, [0.0, 0.0, 0.0])
+        };
+
+    let simulate_for_factor_series = |factor_series: &[f64]| -> SimulationPayload {
+        let mut corrected_cycle: Vec<f64> = Vec::with_capacity(cycle_moles.len());
+        let mut corrected_cumulative: Vec<f64> = Vec::with_capacity(cycle_moles.len());
+        let mut corrected_ph: Vec<Option<f64>> = Vec::with_capacity(cycle_moles.len());
+        let mut corrected_fractions: Vec<[f64; 3]> = Vec::with_capacity(cycle_moles.len());
+        let mut cumulative = 0.0_f64;
+        for (idx, raw_cycle_mol) in cycle_moles.iter().enumerate() {
+            let cycle_index = idx + 1;
+            let factor = if idx < factor_series.len() {
+                factor_series[idx]
+            } else {
+                1.0
+            };
+            let corrected = (raw_cycle_mol * factor).max(0.0);
+            cumulative += corrected;
+            let (ph_value, fractions) = predict_from_cumulative(cumulative, cycle_index);
+            corrected_cycle.push(corrected);
+            corrected_cumulative.push(cumulative);
+            corrected_ph.push(ph_value.filter(|value| value.is_finite()));
+            corrected_fractions.push([
+                fractions[0].max(0.0),
+                fractions[1].max(0.0),
+                fractions[2].max(0.0),
+            ]);
+        }
+        let mut previous_ph: Option<f64> = None;
+        let mut previous_cumulative: Option<f64> = None;
+        for (idx, value) in corrected_ph.iter_mut().enumerate() {
+            let Some(current_ph) = *value else {
+                previous_cumulative = corrected_cumulative.get(idx).copied();
+                continue;
+            };
+            if !current_ph.is_finite() {
+                previous_cumulative = corrected_cumulative.get(idx).copied();
+                continue;
+            }
+            if let Some(previous) = previous_ph {
+                let cycle_uptake = corrected_cycle.get(idx).copied().unwrap_or(0.0);
+                let cumulative = corrected_cumulative.get(idx).copied();
+                let cumulative_increased = match (cumulative, previous_cumulative) {
+                    (Some(current), Some(prior)) => current > prior + 1e-12,
+                    _ => false,
+                };
+                // Positive corrected CO2 movement must display as acidification;
+                // this guard only lowers solver jitter/plateaus after the
+                // chemistry-backed pH calculation has already run.
+                let uptake_occurred = cycle_uptake > 1e-12 || cumulative_increased;
+                let required_drop = if uptake_occurred { 0.003 } else { 0.0 };
+                let ceiling = previous - required_drop;
+                if current_ph > ceiling {
+                    let adjusted = ceiling.max(0.0);
+                    *value = Some(adjusted);
+                    previous_ph = Some(adjusted);
+                    previous_cumulative = cumulative;
+                    continue;
+                }
+            }
+            previous_ph = Some(current_ph);
+            previous_cumulative = corrected_cumulative.get(idx).copied();
+        }
+        SimulationPayload {
+            corrected_cycle,
+            corrected_cumulative,
+            corrected_ph,
+            corrected_fractions,
+        }
+    };
+    let terminal_penalty_raw = |endpoint_ph: Option<f64>| -> f64 {
+        let Some(ph_value) = endpoint_ph else {
+            return 0.0;
+        };
+        if !ph_value.is_finite() {
+            return 0.0;
+        }
+        if ph_value < terminal_low {
+            return (terminal_low - ph_value).powi(2);
+        }
+        if ph_value > terminal_high {
+            return (ph_value - terminal_high).powi(2);
+        }
+        0.0
+    };
+
+    let mut seed_factors: Vec<f64> = vec![1.0; cycle_moles.len()];
+    let mut prior_applied = false;
+    let mut prior_points_used = 0_usize;
+    if learning_enabled {
+        if let Some(prior_rows) = prior_cycle_factor_series {
+            for (idx, item) in prior_rows.iter().enumerate() {
+                if idx >= seed_factors.len() {
+                    break;
+                }
+                let parsed = item.extract::<f64>().ok().unwrap_or(1.0);
+                if parsed.is_finite() {
+                    seed_factors[idx] = parsed.clamp(lower_bound, upper_bound);
+                    prior_applied = true;
+                    prior_points_used += 1;
+                }
+            }
+        }
+    }
+    let prefit_baseline = simulate_for_factor_series(&seed_factors);
+    let mut working_factors = seed_factors.clone();
+    let mut segment_rows: Vec<(usize, usize, f64, bool)> = Vec::new();
+    let mut objective_total = 0.0_f64;
+    let mut prev_anchor_idx = 0_usize;
+    let mut previous_segment_scale = 1.0_f64;
+
+    let mut grid_values: Vec<f64> = Vec::new();
+    let mut current = lower_bound;
+    while current <= upper_bound + 1e-12 {
+        grid_values.push((current * 1_000_000.0).round() / 1_000_000.0);
+        current += 0.05;
+    }
+    if grid_values.is_empty() {
+        grid_values.push(1.0);
+    }
+
+    for anchor in &normalized_anchors {
+        let anchor_idx = anchor.cycle_index.max(1).min(cycle_moles.len());
+        let measured_anchor_ph = anchor.measured_ph;
+        let segment_start = prev_anchor_idx + 1;
+        let segment_end = anchor_idx;
+        if segment_end < segment_start {
+            continue;
+        }
+        let segment_base = working_factors.clone();
+        let segment_objective = |segment_scale: f64| -> (f64, SimulationPayload) {
+            let mut candidate_factors = working_factors.clone();
+            for cycle_pos in (segment_start - 1)..segment_end {
+                let candidate_value =
+                    (segment_base[cycle_pos] * segment_scale).clamp(lower_bound, upper_bound);
+                candidate_factors[cycle_pos] = candidate_value;
+            }
+            let sim_payload = simulate_for_factor_series(&candidate_factors);
+            let anchor_pred = sim_payload
+                .corrected_ph
+                .get(anchor_idx.saturating_sub(1))
+                .copied()
+                .flatten();
+            let Some(anchor_predicted) = anchor_pred else {
+                return (f64::INFINITY, sim_payload);
+            };
+            if !anchor_predicted.is_finite() {
+                return (f64::INFINITY, sim_payload);
+            }
+            let residual = anchor_predicted - measured_anchor_ph;
+            let endpoint_ph = sim_payload.corrected_ph.last().copied().flatten();
+            let terminal_penalty = terminal_penalty_raw(endpoint_ph);
+            let objective = residual * residual
+                + regularization_strength * (segment_scale - 1.0).powi(2)
+                + smoothness_strength * (segment_scale - previous_segment_scale).powi(2)
+                + (terminal_penalty * terminal_weight);
+            (objective, sim_payload)
+        };
+        let mut best_scale = 1.0_f64;
+        let mut best_objective = f64::INFINITY;
+        let mut best_sim = simulate_for_factor_series(&working_factors);
+        for candidate in &grid_values {
+            let (objective_value, sim_payload) = segment_objective(*candidate);
+            if objective_value < best_objective {
+                best_objective = objective_value;
+                best_scale = *candidate;
+                best_sim = sim_payload;
+            }
+        }
+        let mut local_step = 0.025_f64;
+        for _ in 0..4 {
+            for candidate in [
+                best_scale - local_step,
+                best_scale,
+                best_scale + local_step,
+            ] {
+                let clipped = candidate.clamp(lower_bound, upper_bound);
+                let (objective_value, sim_payload) = segment_objective(clipped);
+                if objective_value < best_objective {
+                    best_objective = objective_value;
+                    best_scale = clipped;
+                    best_sim = sim_payload;
+                }
+            }
+            local_step *= 0.5;
+        }
+        for cycle_pos in (segment_start - 1)..segment_end {
+            let candidate_value = (segment_base[cycle_pos] * best_scale).clamp(lower_bound, upper_bound);
+            working_factors[cycle_pos] = candidate_value;
+        }
+        prev_anchor_idx = anchor_idx;
+        previous_segment_scale = best_scale;
+        if best_objective.is_finite() {
+            objective_total += best_objective;
+        }
+        segment_rows.push((segment_start, segment_end, best_scale, false));
+        let _ = best_sim;
+    }
+    if prev_anchor_idx < cycle_moles.len() {
+        let tail_start = prev_anchor_idx + 1;
+        for cycle_pos in prev_anchor_idx..cycle_moles.len() {
+            let candidate_value =
+                (working_factors[cycle_pos] * previous_segment_scale).clamp(lower_bound, upper_bound);
+            working_factors[cycle_pos] = candidate_value;
+        }
+        segment_rows.push((tail_start, cycle_moles.len(), previous_segment_scale, true));
+    }
+
+    let final_sim = simulate_for_factor_series(&working_factors);
+    let corrected_cycle_series = final_sim.corrected_cycle.clone();
+    let corrected_cumulative_series = final_sim.corrected_cumulative.clone();
+    let corrected_ph_series = final_sim.corrected_ph.clone();
+    let corrected_fractions_series = final_sim.corrected_fractions.clone();
+    let anchor_after_ph = corrected_ph_series
+        .get(primary_anchor.cycle_index.saturating_sub(1))
+        .copied()
+        .flatten();
+    let anchor_before_ph = prefit_baseline
+        .corrected_ph
+        .get(primary_anchor.cycle_index.saturating_sub(1))
+        .copied()
+        .flatten();
+    let anchor_residual_before = anchor_before_ph.map(|value| value - primary_anchor.measured_ph);
+    let anchor_residual_after = anchor_after_ph.map(|value| value - primary_anchor.measured_ph);
+    let corrected_latest_total_mol = corrected_cumulative_series
+        .last()
+        .copied()
+        .unwrap_or(0.0);
+
+    let latest_fraction = corrected_fractions_series
+        .last()
+        .copied()
+        .unwrap_or([0.0, 0.0, 0.0]);
+    let latest_ph = corrected_ph_series.last().copied().flatten();
+
+    let pre_slice = &working_factors[..split_idx];
+    let alpha_pre = if pre_slice.is_empty() {
+        1.0
+    } else {
+        pre_slice.iter().sum::<f64>() / (pre_slice.len() as f64)
+    };
+    let post_slice = &working_factors[split_idx..];
+    let alpha_post = if post_slice.is_empty() {
+        alpha_pre
+    } else {
+        post_slice.iter().sum::<f64>() / (post_slice.len() as f64)
+    };
+    let reference_total = planning_reference_total_mol
+        .filter(|value| value.is_finite() && *value > 0.0);
+    let eq_reference = equivalence_co2_mol.filter(|value| value.is_finite() && *value > 0.0);
+    let co2_required_target_mol = if let Some(reference_total_value) = reference_total {
+        let pre_reference = if let Some(eq_reference_value) = eq_reference {
+            reference_total_value.min(eq_reference_value)
+        } else {
+            reference_total_value
+        };
+        let post_reference = (reference_total_value - pre_reference).max(0.0);
+        Some(pre_reference * alpha_pre + post_reference * alpha_post)
+    } else if let Some(eq_reference_value) = eq_reference {
+        Some(eq_reference_value * alpha_pre)
+    } else {
+        None
+    };
+    let completion_pct = co2_required_target_mol.and_then(|required| {
+        if required <= 1e-12 {
+            None
+        } else {
+            Some((corrected_latest_total_mol / required).clamp(0.0, 1.0) * 100.0)
+        }
+    });
+    let factor_regularization_term = regularization_strength
+        * working_factors
+            .iter()
+            .map(|value| (value - 1.0).powi(2))
+            .sum::<f64>();
+    let factor_smoothness_term = smoothness_strength
+        * working_factors
+            .windows(2)
+            .map(|window| (window[1] - window[0]).powi(2))
+            .sum::<f64>();
+    let anchor_residual_squared_sum = normalized_anchors
+        .iter()
+        .filter_map(|anchor| {
+            corrected_ph_series
+                .get(anchor.cycle_index.saturating_sub(1))
+                .copied()
+                .flatten()
+                .map(|value| (value - anchor.measured_ph).powi(2))
+        })
+        .sum::<f64>();
+    let terminal_penalty_raw_value = terminal_penalty_raw(latest_ph);
+    let terminal_penalty_weighted_value = terminal_penalty_raw_value * terminal_weight;
+    let objective_total_components = anchor_residual_squared_sum
+        + factor_regularization_term
+        + factor_smoothness_term
+        + terminal_penalty_weighted_value;
+    let endpoint_distance = latest_ph.map(|value| {
+        if value < terminal_low {
+            terminal_low - value
+        } else if value > terminal_high {
+            value - terminal_high
+        } else {
+            0.0
+        }
+    });
+    let mut anchor_source_counts: BTreeMap<String, usize> = BTreeMap::new();
+    for anchor in &normalized_anchors {
+        let key = if anchor.source.trim().is_empty() {
+            "unknown".to_string()
+        } else {
+            anchor.source.trim().to_string()
+        };
+        let next = anchor_source_counts.get(&key).copied().unwrap_or(0) + 1;
+        anchor_source_counts.insert(key, next);
+    }
+
+    let corrected_cycle_g_series: Vec<f64> = corrected_cycle_series
+        .iter()
+        .map(|value| value * SOL_MW_CO2)
+        .collect();
+    let corrected_cumulative_g_series: Vec<f64> = corrected_cumulative_series
+        .iter()
+        .map(|value| value * SOL_MW_CO2)
+        .collect();
+
+    let set_optional_f64 =
+        |dict: &Bound<'_, PyDict>, key: &str, value: Option<f64>| -> PyResult<()> {
+            if let Some(parsed) = value.filter(|item| item.is_finite()) {
+                dict.set_item(key, parsed)?;
+            } else {
+                dict.set_item(key, py.None())?;
+            }
+            Ok(())
+        };
+
+    let response = PyDict::new(py);
+    response.set_item("solver_status", "ok")?;
+    response.set_item("solver_message", "")?;
+    let warnings = PyList::empty(py);
+    temperature_clamp_report(Some(temp_c), clamp_bounds, &response, &warnings)?;
+    response.set_item("warnings", warnings)?;
+    response.set_item("split_cycle_index", split_idx)?;
+    response.set_item("anchor_cycle_index", primary_anchor.cycle_index)?;
+    response.set_item("anchor_measured_ph", primary_anchor.measured_ph)?;
+    set_optional_f64(&response, "anchor_predicted_ph_before", anchor_before_ph)?;
+    set_optional_f64(&response, "anchor_predicted_ph_after", anchor_after_ph)?;
+    set_optional_f64(&response, "anchor_residual_before", anchor_residual_before)?;
+    set_optional_f64(&response, "anchor_residual_after", anchor_residual_after)?;
+    if objective_total_components.is_finite() {
+        response.set_item("objective", objective_total_components)?;
+    } else if objective_total.is_finite() {
+        response.set_item("objective", objective_total)?;
+    } else {
+        response.set_item("objective", py.None())?;
+    }
+    response.set_item("alpha_pre", alpha_pre)?;
+    response.set_item("alpha_post", alpha_post)?;
+    response.set_item("cycle_factor_series", working_factors.clone())?;
+    response.set_item("anchor_count", normalized_anchors.len())?;
+    let anchor_source_count_map = PyDict::new(py);
+    for (source, count) in &anchor_source_counts {
+        anchor_source_count_map.set_item(source, *count)?;
+    }
+    response.set_item("anchor_count_by_source", anchor_source_count_map)?;
+    response.set_item(
+        "anchor_sources",
+        anchor_source_counts.keys().cloned().collect::<Vec<String>>(),
+    )?;
+    response.set_item("learning_enabled", learning_enabled)?;
+    response.set_item("learning_prior_applied", prior_applied)?;
+    response.set_item("prior_sample_count", prior_sample_count)?;
+    response.set_item(
+        "prior_cycle_factor_points_used",
+        prior_points_used,
+    )?;
+    response.set_item("prior_cycle_factor_series", seed_factors.clone())?;
+    response.set_item("terminal_ph_range_low", terminal_low)?;
+    response.set_item("terminal_ph_range_high", terminal_high)?;
+    response.set_item("terminal_ph_weight", terminal_weight)?;
+    response.set_item("ph_prediction_basis", "chemistry_context")?;
+    response.set_item(
+        "terminal_objective_enabled",
+        terminal_weight > 0.0 && !normalized_anchors.is_empty(),
+    )?;
+    let objective_components = PyDict::new(py);
+    objective_components.set_item(
+        "anchor_residual_squared_sum",
+        anchor_residual_squared_sum,
+    )?;
+    objective_components.set_item(
+        "factor_regularization_term",
+        factor_regularization_term,
+    )?;
+    objective_components.set_item("factor_smoothness_term", factor_smoothness_term)?;
+    objective_components.set_item("terminal_penalty_raw", terminal_penalty_raw_value)?;
+    objective_components.set_item(
+        "terminal_penalty_weighted",
+        terminal_penalty_weighted_value,
+    )?;
+    objective_components.set_item("objective_total", objective_total_components)?;
+    response.set_item("objective_components", objective_components)?;
+    let endpoint_diagnostics = PyDict::new(py);
+    set_optional_f64(&endpoint_diagnostics, "endpoint_ph", latest_ph)?;
+    endpoint_diagnostics.set_item(
+        "in_terminal_band",
+        latest_ph
+            .map(|value| value >= terminal_low && value <= terminal_high)
+            .unwrap_or(false),
+    )?;
+    set_optional_f64(
+        &endpoint_diagnostics,
+        "distance_to_terminal_band",
+        endpoint_distance,
+    )?;
+    endpoint_diagnostics.set_item("terminal_penalty_raw", terminal_penalty_raw_value)?;
+    endpoint_diagnostics.set_item(
+        "terminal_penalty_weighted",
+        terminal_penalty_weighted_value,
+    )?;
+    response.set_item("endpoint_diagnostics", endpoint_diagnostics)?;
+    let segment_list = PyList::empty(py);
+    for (start_cycle, end_cycle, scale, tail_flag) in &segment_rows {
+        let row = PyDict::new(py);
+        row.set_item("start_cycle_index", *start_cycle)?;
+        row.set_item("end_cycle_index", *end_cycle)?;
+        row.set_item("scale", *scale)?;
+        if *tail_flag {
+            row.set_item("tail_extrapolation", true)?;
+        }
+        segment_list.append(row)?;
+    }
+    response.set_item("segment_scales", segment_list)?;
+    let anchors_list = PyList::empty(py);
+    for anchor in &normalized_anchors {
+        let before_ph = prefit_baseline
+            .corrected_ph
+            .get(anchor.cycle_index.saturating_sub(1))
+            .copied()
+            .flatten();
+        let after_ph = corrected_ph_series
+            .get(anchor.cycle_index.saturating_sub(1))
+            .copied()
+            .flatten();
+        let row = PyDict::new(py);
+        row.set_item("cycle_index", anchor.cycle_index)?;
+        row.set_item("measured_ph", anchor.measured_ph)?;
+        row.set_item("source", anchor.source.clone())?;
+        set_optional_f64(&row, "predicted_ph_before", before_ph)?;
+        set_optional_f64(&row, "predicted_ph_after", after_ph)?;
+        set_optional_f64(
+            &row,
+            "residual_before",
+            before_ph.map(|value| value - anchor.measured_ph),
+        )?;
+        set_optional_f64(
+            &row,
+            "residual_after",
+            after_ph.map(|value| value - anchor.measured_ph),
+        )?;
+        anchors_list.append(row)?;
+    }
+    response.set_item("anchors", anchors_list)?;
+    response.set_item(
+        "corrected_cycle_uptake_mol_series",
+        corrected_cycle_series.clone(),
+    )?;
+    response.set_item(
+        "corrected_cycle_uptake_g_series",
+        corrected_cycle_g_series.clone(),
+    )?;
+    response.set_item(
+        "corrected_cumulative_co2_mol_series",
+        corrected_cumulative_series.clone(),
+    )?;
+    response.set_item(
+        "corrected_cumulative_co2_g_series",
+        corrected_cumulative_g_series.clone(),
+    )?;
+
+    let corrected_ph_list = PyList::empty(py);
+    for value in corrected_ph_series {
+        if let Some(parsed) = value.filter(|item| item.is_finite()) {
+            corrected_ph_list.append(parsed)?;
+        } else {
+            corrected_ph_list.append(py.None())?;
+        }
+    }
+    response.set_item("corrected_ph_series", corrected_ph_list)?;
+
+    let fractions_list = PyList::empty(py);
+    for fraction_row in corrected_fractions_series {
+        let fraction_map = PyDict::new(py);
+        fraction_map.set_item("H2CO3", fraction_row[0].max(0.0))?;
+        fraction_map.set_item("HCO3-", fraction_row[1].max(0.0))?;
+        fraction_map.set_item("CO3^2-", fraction_row[2].max(0.0))?;
+        fractions_list.append(fraction_map)?;
+    }
+    response.set_item("corrected_fraction_series", fractions_list)?;
+
+    let latest_speciation = PyDict::new(py);
+    set_optional_f64(&latest_speciation, "ph", latest_ph)?;
+    let latest_fractions_map = PyDict::new(py);
+    latest_fractions_map.set_item("H2CO3", latest_fraction[0].max(0.0))?;
+    latest_fractions_map.set_item("HCO3-", latest_fraction[1].max(0.0))?;
+    latest_fractions_map.set_item("CO3^2-", latest_fraction[2].max(0.0))?;
+    latest_speciation.set_item("fractions", latest_fractions_map)?;
+    response.set_item("latest_corrected_speciation", latest_speciation)?;
+
+    set_optional_f64(
+        &response,
+        "co2_required_for_target_ph_mol",
+        co2_required_target_mol,
+    )?;
+    set_optional_f64(
+        &response,
+        "co2_required_for_target_ph_g",
+        co2_required_target_mol.map(|value| value * SOL_MW_CO2),
+    )?;
+    response.set_item(
+        "corrected_cumulative_uptake_latest_mol",
+        corrected_latest_total_mol,
+    )?;
+    response.set_item(
+        "corrected_cumulative_uptake_latest_g",
+        corrected_latest_total_mol * SOL_MW_CO2,
+    )?;
+    set_optional_f64(
+        &response,
+        "completion_from_measured_ph_pct",
+        completion_pct,
+    )?;
+    set_optional_f64(
+        &response,
+        "target_ph",
+        target_ph.filter(|value| value.is_finite()),
+    )?;
+    Ok(response.unbind())
+}
+
+#[pyfunction]
+#[pyo3(signature = (rows_a, rows_b))]
+/// Compare aligned cycle rows from two datasets while preserving Python parity.
+///
+/// The output ordering and lookup rules intentionally mirror the Python
+/// implementation so Compare tables remain backend-agnostic.
+fn compare_aligned_cycle_rows_core(
+    py: Python<'_>,
+    rows_a: &Bound<'_, PyList>,
+    rows_b: &Bound<'_, PyList>,
+) -> PyResult<Py<PyList>> {
+    // Keep Rust/Python parity for Compare cycle alignment and delta aggregation
+    // so table math remains identical across backend selection.
+    let uptake_keys = [
+        "selected_mass_g",
+        "selected_mass",
+        "co2_added_g",
+        "co2_added_mass_g",
+        "co2_mass_g",
+        "co2_g",
+        "cycle_mass_g",
+        "cycle_co2_mass_g",
+    ];
+    let cumulative_keys = [
+        "cumulative_co2_mass_g",
+        "cumulative_co2_added_mass_g",
+        "cumulative_co2_added_g",
+        "cumulative_co2_g",
+        "cumulative_added_g",
+        "cumulative_mass_g",
+    ];
+
+    let mut map_a: BTreeMap<usize, (Option<f64>, Option<f64>)> = BTreeMap::new();
+    let mut map_b: BTreeMap<usize, (Option<f64>, Option<f64>)> = BTreeMap::new();
+
+    for (idx, item) in rows_a.iter().enumerate() {
+        let Ok(entry) = item.cast_into::<PyDict>() else {
+            continue;
+        };
+        let cycle_id = compare_cycle_row_index(&entry, idx + 1);
+        if map_a.contains_key(&cycle_id) {
+            continue;
+        }
+        let uptake = dict_optional_finite_by_keys(&entry, &uptake_keys);
+        let cumulative = dict_optional_finite_by_keys(&entry, &cumulative_keys);
+        map_a.insert(cycle_id, (uptake, cumulative));
+    }
+    for (idx, item) in rows_b.iter().enumerate() {
+        let Ok(entry) = item.cast_into::<PyDict>() else {
+            continue;
+        };
+        let cycle_id = compare_cycle_row_index(&entry, idx + 1);
+        if map_b.contains_key(&cycle_id) {
+            continue;
+        }
+        let uptake = dict_optional_finite_by_keys(&entry, &uptake_keys);
+        let cumulative = dict_optional_finite_by_keys(&entry, &cumulative_keys);
+        map_b.insert(cycle_id, (uptake, cumulative));
+    }
+
+    let all_cycles: BTreeSet<usize> = map_a.keys().chain(map_b.keys()).copied().collect();
+    let rows = PyList::empty(py);
+    let mut last_cum_a = 0.0_f64;
+    let mut last_cum_b = 0.0_f64;
+    let mut valid_a = false;
+    let mut valid_b = false;
+
+    for cycle_id in all_cycles {
+        let (mut uptake_a, mut cum_a) = map_a.get(&cycle_id).copied().unwrap_or((None, None));
+        let (mut uptake_b, mut cum_b) = map_b.get(&cycle_id).copied().unwrap_or((None, None));
+        let prior_cum_a = if valid_a { last_cum_a } else { 0.0 };
+        let prior_cum_b = if valid_b { last_cum_b } else { 0.0 };
+
+        if uptake_a.is_none() {
+            if let Some(cum_val) = cum_a {
+                uptake_a = Some(cum_val - prior_cum_a);
+            }
+        }
+        if uptake_b.is_none() {
+            if let Some(cum_val) = cum_b {
+                uptake_b = Some(cum_val - prior_cum_b);
+            }
+        }
+        if cum_a.is_none() {
+            if let Some(uptake_val) = uptake_a {
+                cum_a = Some(last_cum_a + uptake_val);
+            }
+        }
+        if cum_b.is_none() {
+            if let Some(uptake_val) = uptake_b {
+                cum_b = Some(last_cum_b + uptake_val);
+            }
+        }
+        if let Some(cum_val) = cum_a {
+            last_cum_a = cum_val;
+            valid_a = true;
+        }
+        if let Some(cum_val) = cum_b {
+            last_cum_b = cum_val;
+            valid_b = true;
+        }
+        let delta_g = match (uptake_a, uptake_b) {
+            (Some(a), Some(b)) => Some(b - a),
+            _ => None,
+        };
+        let cum_delta_g = match (cum_a, cum_b) {
+            (Some(a), Some(b)) => Some(b - a),
+            _ => None,
+        };
+
+        let row = PyDict::new(py);
+        row.set_item("cycle", cycle_id)?;
+        if let Some(value) = uptake_a {
+            row.set_item("uptake_a_g", value)?;
+        } else {
+            row.set_item("uptake_a_g", py.None())?;
+        }
+        if let Some(value) = uptake_b {
+            row.set_item("uptake_b_g", value)?;
+        } else {
+            row.set_item("uptake_b_g", py.None())?;
+        }
+        if let Some(value) = delta_g {
+            row.set_item("delta_g", value)?;
+        } else {
+            row.set_item("delta_g", py.None())?;
+        }
+        if let Some(value) = cum_a {
+            row.set_item("cum_a_g", value)?;
+        } else {
+            row.set_item("cum_a_g", py.None())?;
+        }
+        if let Some(value) = cum_b {
+            row.set_item("cum_b_g", value)?;
+        } else {
+            row.set_item("cum_b_g", py.None())?;
+        }
+        if let Some(value) = cum_delta_g {
+            row.set_item("cum_delta_g", value)?;
+        } else {
+            row.set_item("cum_delta_g", py.None())?;
+        }
+        rows.append(row)?;
+    }
+    Ok(rows.unbind())
+}
+
+#[pyfunction]
+#[pyo3(signature = (entries, filter_value, sort_mode, sort_key, sort_desc, sort_column_type))]
+/// Compute Ledger row indices after applying filter and sort rules.
+///
+/// Returning indices instead of rebuilt rows preserves the existing Python UI
+/// update flow while allowing Rust to accelerate the expensive comparisons.
+fn ledger_sort_filter_indices_core(
+    entries: &Bound<'_, PyList>,
+    filter_value: &str,
+    sort_mode: &str,
+    sort_key: &str,
+    sort_desc: bool,
+    sort_column_type: &str,
+) -> PyResult<Option<Vec<usize>>> {
+    // Prepare filtered/sorted row indices for Ledger table refresh while keeping
+    // schema handling deterministic and fallback-safe.
+    #[derive(Clone)]
+    struct RowSortData {
+        idx: usize,
+        display_order: i64,
+        updated_at: String,
+        row_id: String,
+        number_value: Option<f64>,
+        date_value: Option<i64>,
+        text_value: String,
+    }
+
+    let mode_token = match sort_mode.trim().to_ascii_lowercase().as_str() {
+        "column" | "column sort" => "column",
+        _ => "manual",
+    };
+    let column_type = sort_column_type.trim().to_ascii_lowercase();
+    if mode_token == "column" && column_type == "formula" {
+        return Ok(None);
+    }
+    let normalized_sort_key = {
+        let candidate = sort_key.trim();
+        if candidate.is_empty() {
+            "updated_at"
+        } else {
+            candidate
+        }
+    };
+    let filter_token = filter_value.trim().to_ascii_lowercase();
+    let apply_filter = !filter_token.is_empty() && filter_token != "all profiles";
+    let mut rows: Vec<RowSortData> = Vec::new();
+    for (idx, item) in entries.iter().enumerate() {
+        let Ok(entry) = item.cast_into::<PyDict>() else {
+            continue;
+        };
+        let profile_lower = dict_string_value(&entry, "profile_name").to_ascii_lowercase();
+        if apply_filter && profile_lower != filter_token {
+            continue;
+        }
+        let display_order = entry
+            .get_item("display_order")
+            .ok()
+            .flatten()
+            .and_then(|value| value.extract::<i64>().ok())
+            .unwrap_or(0);
+        let updated_at = dict_string_value(&entry, "updated_at");
+        let row_id = dict_string_value(&entry, "id");
+        let number_value = if column_type == "number" {
+            ledger_sort_numeric_value(&entry, normalized_sort_key)
+        } else {
+            None
+        };
+        let date_value = if column_type == "date" {
+            ledger_sort_date_value(&entry, normalized_sort_key)
+        } else {
+            None
+        };
+        let text_value = if column_type == "text" {
+            ledger_sort_text_value(&entry, normalized_sort_key).to_ascii_lowercase()
+        } else {
+            String::new()
+        };
+        rows.push(RowSortData {
+            idx,
+            display_order,
+            updated_at,
+            row_id,
+            number_value,
+            date_value,
+            text_value,
+        });
+    }
+
+    if mode_token == "manual" {
+        rows.sort_by(|left, right| {
+            (
+                left.display_order,
+                left.updated_at.as_str(),
+                left.row_id.as_str(),
+            )
+                .cmp(&(
+                    right.display_order,
+                    right.updated_at.as_str(),
+                    right.row_id.as_str(),
+                ))
+        });
+        return Ok(Some(rows.into_iter().map(|row| row.idx).collect()));
+    }
+
+    match column_type.as_str() {
+        "number" => {
+            rows.sort_by(|left, right| {
+                let left_missing = left.number_value.is_none();
+                let right_missing = right.number_value.is_none();
+                if left_missing != right_missing {
+                    return if sort_desc {
+                        if left_missing {
+                            Ordering::Less
+                        } else {
+                            Ordering::Greater
+                        }
+                    } else if left_missing {
+                        Ordering::Greater
+                    } else {
+                        Ordering::Less
+                    };
+                }
+                match (left.number_value, right.number_value) {
+                    (Some(a), Some(b)) => {
+                        if sort_desc {
+                            b.partial_cmp(&a).unwrap_or(Ordering::Equal)
+                        } else {
+                            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+                        }
+                    }
+                    _ => Ordering::Equal,
+                }
+            });
+        }
+        "date" => {
+            rows.sort_by(|left, right| {
+                let left_missing = left.date_value.is_none();
+                let right_missing = right.date_value.is_none();
+                if left_missing != right_missing {
+                    return if sort_desc {
+                        if left_missing {
+                            Ordering::Less
+                        } else {
+                            Ordering::Greater
+                        }
+                    } else if left_missing {
+                        Ordering::Greater
+                    } else {
+                        Ordering::Less
+                    };
+                }
+                match (left.date_value, right.date_value) {
+                    (Some(a), Some(b)) => {
+                        if sort_desc {
+                            b.cmp(&a)
+                        } else {
+                            a.cmp(&b)
+                        }
+                    }
+                    _ => Ordering::Equal,
+                }
+            });
+        }
+        _ => {
+            rows.sort_by(|left, right| {
+                if sort_desc {
+                    right.text_value.cmp(&left.text_value)
+                } else {
+                    left.text_value.cmp(&right.text_value)
+                }
+            });
+        }
+    }
+    Ok(Some(rows.into_iter().map(|row| row.idx).collect()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (cycle_rows, total_drop_value=None, reaction_basis=None))]
+/// Derive Ledger add-from-profile metrics from cycle rows and reaction inputs.
+///
+/// The function preserves the Python fallback schema while normalizing PyO3
+/// ownership so row dictionaries can be retained safely across the loop.
+fn ledger_prefill_metrics_core(
+    py: Python<'_>,
+    cycle_rows: &Bound<'_, PyList>,
+    total_drop_value: Option<f64>,
+    reaction_basis: Option<Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    // Extract Ledger add-from-profile metrics in Rust while preserving Python
+    // fallback parity and schema expectations.
+    let cumulative_keys = [
+        "cumulative_co2_mass_g",
+        "cumulative_co2_added_mass_g",
+        "cumulative_co2_added_g",
+        "cumulative_co2_g",
+        "cumulative_added_g",
+        "cumulative_mass_g",
+    ];
+    let mut normalized_rows: Vec<Bound<'_, PyDict>> = Vec::new();
+    for item in cycle_rows.iter() {
+        let Ok(entry) = item.cast_into::<PyDict>() else {
+            continue;
+        };
+        normalized_rows.push(entry);
+    }
+    let mut total_drop = total_drop_value.filter(|value| value.is_finite() && *value >= 0.0);
+    if total_drop.is_none() {
+        if let Some(last_row) = normalized_rows.last() {
+            total_drop = dict_optional_finite_by_keys(last_row, &cumulative_keys)
+                .filter(|value| value.is_finite() && *value >= 0.0);
+        }
+    }
+    let basis = reaction_basis.as_ref();
+    let start_mass = basis.and_then(|dict| dict_optional_float_value(dict, "starting_mass_g"));
+    let start_mw =
+        basis.and_then(|dict| dict_optional_float_value(dict, "starting_material_mw_g_mol"));
+    let stoich =
+        basis.and_then(|dict| dict_optional_float_value(dict, "stoich_mol_gas_per_mol_starting"));
+    let gas_mw = basis.and_then(|dict| dict_optional_float_value(dict, "gas_molar_mass"));
+    let theoretical = match (start_mass, start_mw, stoich, gas_mw) {
+        (Some(mass), Some(mw), Some(st), Some(gas))
+            if mw > 0.0 && st > 0.0 && gas > 0.0 && mass >= 0.0 =>
+        {
+            Some((mass / mw) * st * gas)
+        }
+        _ => None,
+    };
+    let out = PyDict::new(py);
+    if normalized_rows.is_empty() {
+        out.set_item("cycles", py.None())?;
+    } else {
+        out.set_item("cycles", normalized_rows.len())?;
+    }
+    if let Some(value) = total_drop {
+        out.set_item("total_dp_uptake", value)?;
+    } else {
+        out.set_item("total_dp_uptake", py.None())?;
+    }
+    if let Some(value) = theoretical {
+        out.set_item("theoretical_yield_g", value)?;
+    } else {
+        out.set_item("theoretical_yield_g", py.None())?;
+    }
+    Ok(out.unbind())
+}
+
+#[pyfunction]
+#[pyo3(signature = (x_values))]
+fn array_signature_core(x_values: PyReadonlyArray1<'_, f64>) -> PyResult<(usize, String, i64)> {
+    // Compute deterministic signed 64-bit FNV-1a hash for contiguous float bytes
+    // so Python/Rust parity checks remain stable across process restarts.
+    let view = x_values.as_array();
+    let mut hash_value: u64 = 0xCBF29CE484222325;
+    for value in view.iter() {
+        let bytes = value.to_le_bytes();
+        for byte_value in bytes {
+            hash_value ^= u64::from(byte_value);
+            hash_value = hash_value.wrapping_mul(0x100000001B3);
+        }
+    }
+    let hash_i64 = i64::from_ne_bytes(hash_value.to_ne_bytes());
+    Ok((view.len(), "float64".to_string(), hash_i64))
+}
+
+#[pyfunction]
+#[pyo3(signature = (cycle_transfer_rows, duration_header))]
+/// Format Final Report cycle-stat rows using the shared table schema.
+///
+/// Rust keeps the same column values and empty-string behavior expected by the
+/// Python report builders, so exports remain identical across backends.
+fn final_report_cycle_stats_rows_core(
+    py: Python<'_>,
+    cycle_transfer_rows: &Bound<'_, PyList>,
+    duration_header: &str,
+) -> PyResult<Py<PyList>> {
+    // Format cycle-stat rows with the same schema expected by Python report
+    // builders while keeping Rust wrappers strictly fallback-safe.
+    let rows = PyList::empty(py);
+    for item in cycle_transfer_rows.iter() {
+        let Ok(entry) = item.cast_into::<PyDict>() else {
+            continue;
+        };
+        let delta = dict_optional_float_value(&entry, "delta_pressure_psi");
+        let duration = dict_optional_float_value(&entry, "duration_x")
+            .or_else(|| dict_optional_float_value(&entry, "duration"));
+        let peak_psi = dict_optional_float_value(&entry, "peak_pressure_psi");
+        let trough_psi = dict_optional_float_value(&entry, "trough_pressure_psi");
+        let mean_temp = dict_optional_float_value(&entry, "mean_temperature_c");
+        let co2_mass = dict_optional_float_value(&entry, "cumulative_co2_mass_g");
+        let row = PyDict::new(py);
+        row.set_item("Cycle", dict_truthy_or_empty_pyobject(py, &entry, "cycle_id"))?;
+        row.set_item("\u{0394}P (PSI)", format_optional_decimal(delta, 2))?;
+        row.set_item(duration_header, format_optional_decimal(duration, 3))?;
+        row.set_item("Peak PSI", format_optional_decimal(peak_psi, 2))?;
+        row.set_item("Trough PSI", format_optional_decimal(trough_psi, 2))?;
+        row.set_item("Mean T (\u{00B0}C)", format_optional_decimal(mean_temp, 2))?;
+        row.set_item("CO\u{2082} (g)", format_optional_decimal(co2_mass, 2))?;
+        rows.append(row)?;
+    }
+    Ok(rows.unbind())
+}
+
+const REACTION_SODIUM_MOLAR_MASS_G_MOL: f64 = 22.989_769;
+const REACTION_WATER_MOLAR_MASS_G_MOL: f64 = 18.015_28;
+const REACTION_METHANOL_MOLAR_MASS_G_MOL: f64 = 32.042;
+const REACTION_NAOH_MOLAR_MASS_G_MOL: f64 = 39.997;
+
+fn nonnegative_charge_value(
+    value: Option<f64>,
+    label: &str,
+    warnings: &Bound<'_, PyList>,
+) -> PyResult<f64> {
+    let Some(raw) = value else {
+        warnings.append(format!("{label} is missing or invalid; using 0."))?;
+        return Ok(0.0);
+    };
+    if !raw.is_finite() {
+        warnings.append(format!("{label} is missing or invalid; using 0."))?;
+        return Ok(0.0);
+    }
+    if raw < 0.0 {
+        warnings.append(format!("{label} was negative; using 0."))?;
+        return Ok(0.0);
+    }
+    Ok(raw)
+}
+
+#[pyfunction]
+#[pyo3(signature = (sodium_metal_mass_g=None, methanol_mass_g=None, water_ppm_by_mass=None, explicit_naoh_mass_g=None))]
+/// Compute sodium/methanol charge availability for the Reaction Dashboard.
+///
+/// Water consumes sodium first and produces NaOH; remaining sodium forms sodium
+/// methoxide from dry methanol. The result schema mirrors the Python fallback.
+fn reaction_solution_charge_core(
+    py: Python<'_>,
+    sodium_metal_mass_g: Option<f64>,
+    methanol_mass_g: Option<f64>,
+    water_ppm_by_mass: Option<f64>,
+    explicit_naoh_mass_g: Option<f64>,
+) -> PyResult<Py<PyDict>> {
+    let warnings = PyList::empty(py);
+    let sodium_mass =
+        nonnegative_charge_value(sodium_metal_mass_g, "Sodium metal mass", &warnings)?;
+    let wet_methanol_mass =
+        nonnegative_charge_value(methanol_mass_g, "Methanol mass", &warnings)?;
+    let water_ppm =
+        nonnegative_charge_value(water_ppm_by_mass, "Methanol water ppm", &warnings)?;
+    let explicit_naoh_mass =
+        nonnegative_charge_value(explicit_naoh_mass_g, "Explicit NaOH mass", &warnings)?;
+
+    let water_mass_g = wet_methanol_mass * water_ppm / 1_000_000.0;
+    let dry_methanol_mass_g = (wet_methanol_mass - water_mass_g).max(0.0);
+    let sodium_mol = sodium_mass / REACTION_SODIUM_MOLAR_MASS_G_MOL;
+    let water_mol = water_mass_g / REACTION_WATER_MOLAR_MASS_G_MOL;
+    let methanol_mol = dry_methanol_mass_g / REACTION_METHANOL_MOLAR_MASS_G_MOL;
+    let explicit_naoh_mol = explicit_naoh_mass / REACTION_NAOH_MOLAR_MASS_G_MOL;
+    let sodium_consumed_by_water_mol = sodium_mol.min(water_mol);
+    let generated_naoh_mol = sodium_consumed_by_water_mol;
+    let sodium_after_water_mol = (sodium_mol - sodium_consumed_by_water_mol).max(0.0);
+    let sodium_methoxide_mol = sodium_after_water_mol.min(methanol_mol);
+    let remaining_methanol_mol = (methanol_mol - sodium_methoxide_mol).max(0.0);
+    let unreacted_sodium_mol = (sodium_after_water_mol - sodium_methoxide_mol).max(0.0);
+    let excess_water_mol = (water_mol - sodium_consumed_by_water_mol).max(0.0);
+    if excess_water_mol > 1e-12 {
+        warnings.append("Water exceeds sodium charge; sodium methoxide availability is zero.")?;
+    }
+    if unreacted_sodium_mol > 1e-12 {
+        warnings.append("Sodium exceeds available dry methanol; sodium remains unused.")?;
+    }
+    let total_naoh_mol = explicit_naoh_mol + generated_naoh_mol;
+
+    let out = PyDict::new(py);
+    out.set_item("backend", "rust")?;
+    out.set_item("sodium_metal_mass_g", sodium_mass)?;
+    out.set_item("methanol_mass_g", wet_methanol_mass)?;
+    out.set_item("water_ppm_by_mass", water_ppm)?;
+    out.set_item("water_mass_g", water_mass_g)?;
+    out.set_item("dry_methanol_mass_g", dry_methanol_mass_g)?;
+    out.set_item("sodium_mol", sodium_mol)?;
+    out.set_item("water_mol", water_mol)?;
+    out.set_item("methanol_initial_mol", methanol_mol)?;
+    out.set_item("sodium_consumed_by_water_mol", sodium_consumed_by_water_mol)?;
+    out.set_item("generated_naoh_mol", generated_naoh_mol)?;
+    out.set_item("explicit_naoh_mol", explicit_naoh_mol)?;
+    out.set_item("total_naoh_mol", total_naoh_mol)?;
+    out.set_item("sodium_methoxide_mol", sodium_methoxide_mol)?;
+    out.set_item("remaining_methanol_mol", remaining_methanol_mol)?;
+    out.set_item("unreacted_sodium_mol", unreacted_sodium_mol)?;
+    out.set_item("excess_water_mol", excess_water_mol)?;
+    out.set_item("warnings", warnings)?;
+    Ok(out.unbind())
+}
+
+#[pyfunction]
+#[pyo3(signature = (species_rows, step_rows, gas_uptake_mol, gas_species_id, yield_species_id, target_reactant_species_id=None, actual_yield_mass_g=None))]
+/// Compute template-neutral Reaction Dashboard stoichiometry and yield metrics.
+///
+/// The kernel mirrors the Python fallback schema while keeping pH/ChemPy
+/// behavior in Python. Species rows provide initial moles and molar masses;
+/// step rows provide signed stoichiometry maps with negative reactants and
+/// positive products.
+fn reaction_dashboard_core(
+    py: Python<'_>,
+    species_rows: &Bound<'_, PyList>,
+    step_rows: &Bound<'_, PyList>,
+    gas_uptake_mol: f64,
+    gas_species_id: &str,
+    yield_species_id: &str,
+    target_reactant_species_id: Option<&str>,
+    actual_yield_mass_g: Option<f64>,
+) -> PyResult<Py<PyDict>> {
+    let mut inventory: BTreeMap<String, f64> = BTreeMap::new();
+    let mut initial_inventory: BTreeMap<String, f64> = BTreeMap::new();
+    let mut molar_masses: BTreeMap<String, f64> = BTreeMap::new();
+    let mut species_names: BTreeMap<String, String> = BTreeMap::new();
+    let warnings = PyList::empty(py);
+
+    for item in species_rows.iter() {
+        let Ok(row) = item.cast_into::<PyDict>() else {
+            continue;
+        };
+        let mut species_id = dict_string_value(&row, "species_id");
+        if species_id.is_empty() {
+            species_id = dict_string_value(&row, "id");
+        }
+        if species_id.is_empty() {
+            continue;
+        }
+        let initial = dict_optional_float_value(&row, "initial_moles")
+            .unwrap_or(0.0)
+            .max(0.0);
+        let molar_mass = dict_optional_float_value(&row, "molar_mass_g_mol")
+            .unwrap_or(0.0)
+            .max(0.0);
+        inventory.insert(species_id.clone(), initial);
+        initial_inventory.insert(species_id.clone(), initial);
+        molar_masses.insert(species_id.clone(), molar_mass);
+        let name = dict_string_value(&row, "name");
+        species_names.insert(
+            species_id.clone(),
+            if name.is_empty() {
+                species_id.clone()
+            } else {
+                name
+            },
+        );
+    }
+
+    let gas_id = gas_species_id.trim().to_string();
+    let gas_uptake = gas_uptake_mol.max(0.0);
+    if gas_id.is_empty() {
+        warnings.append("Template does not define a gas species.")?;
+    } else {
+        let next_value = inventory.get(&gas_id).copied().unwrap_or(0.0) + gas_uptake;
+        inventory.insert(gas_id.clone(), next_value);
+        let next_initial = initial_inventory.get(&gas_id).copied().unwrap_or(0.0) + gas_uptake;
+        initial_inventory.insert(gas_id.clone(), next_initial);
+    }
+
+    let step_results = PyList::empty(py);
+    for (idx, item) in step_rows.iter().enumerate() {
+        let Ok(step) = item.cast_into::<PyDict>() else {
+            continue;
+        };
+        let stoich_any = step.get_item("stoichiometry").ok().flatten();
+        let Some(stoich_dict) = stoich_any.and_then(|value| value.cast_into::<PyDict>().ok())
+        else {
+            warnings.append(format!(
+                "{} has no stoichiometry.",
+                dict_string_value(&step, "name")
+            ))?;
+            continue;
+        };
+        let mut stoich: BTreeMap<String, f64> = BTreeMap::new();
+        for (key, value) in stoich_dict.iter() {
+            let species_id = py_any_to_trimmed_string(&key);
+            if species_id.is_empty() {
+                continue;
+            }
+            let coeff = value.extract::<f64>().unwrap_or(0.0);
+            if coeff.is_finite() && coeff.abs() > 0.0 {
+                stoich.insert(species_id, coeff);
+            }
+        }
+        let mut limiting_species: Option<String> = None;
+        let mut extent = f64::INFINITY;
+        for (species_id, coeff) in stoich.iter() {
+            if *coeff >= 0.0 {
+                continue;
+            }
+            let candidate =
+                inventory.get(species_id).copied().unwrap_or(0.0) / coeff.abs().max(1e-30);
+            if candidate < extent {
+                extent = candidate.max(0.0);
+                limiting_species = Some(species_id.clone());
+            }
+        }
+        if !extent.is_finite() {
+            extent = 0.0;
+            warnings.append(format!(
+                "{} has no consumed species.",
+                dict_string_value(&step, "name")
+            ))?;
+        }
+        for (species_id, coeff) in stoich.iter() {
+            let current = inventory.get(species_id).copied().unwrap_or(0.0);
+            inventory.insert(species_id.clone(), (current + coeff * extent).max(0.0));
+        }
+        let step_row = PyDict::new(py);
+        let step_id = {
+            let value = dict_string_value(&step, "step_id");
+            if value.is_empty() {
+                dict_string_value(&step, "id")
+            } else {
+                value
+            }
+        };
+        step_row.set_item(
+            "step_id",
+            if step_id.is_empty() {
+                format!("{}", idx + 1)
+            } else {
+                step_id
+            },
+        )?;
+        let step_name = dict_string_value(&step, "name");
+        step_row.set_item(
+            "name",
+            if step_name.is_empty() {
+                format!("Step {}", idx + 1)
+            } else {
+                step_name
+            },
+        )?;
+        step_row.set_item("extent_mol", extent)?;
+        if let Some(limiting_id) = limiting_species {
+            step_row.set_item("limiting_species_id", limiting_id.clone())?;
+            step_row.set_item(
+                "limiting_species",
+                species_names.get(&limiting_id).cloned().unwrap_or_default(),
+            )?;
+        } else {
+            step_row.set_item("limiting_species_id", py.None())?;
+            step_row.set_item("limiting_species", "")?;
+        }
+        step_results.append(step_row)?;
+    }
+
+    let product_id = yield_species_id.trim().to_string();
+    let product_mol = inventory.get(&product_id).copied().unwrap_or(0.0);
+    let product_mw = molar_masses.get(&product_id).copied().unwrap_or(0.0);
+    let theoretical_yield_g = if product_mol >= 0.0 && product_mw > 0.0 {
+        Some(product_mol * product_mw)
+    } else {
+        None
+    };
+    let actual_yield_pct = match (actual_yield_mass_g, theoretical_yield_g) {
+        (Some(actual), Some(theoretical)) if actual.is_finite() && theoretical > 1e-12 => {
+            Some((actual.max(0.0) / theoretical) * 100.0)
+        }
+        _ => None,
+    };
+    let target_id = target_reactant_species_id.unwrap_or("").trim().to_string();
+    let target_initial = if target_id.is_empty() {
+        0.0
+    } else {
+        initial_inventory.get(&target_id).copied().unwrap_or(0.0)
+    };
+    let completion_pct = if target_initial > 1e-12 {
+        Some(((product_mol / target_initial) * 100.0).clamp(0.0, 100.0))
+    } else if let Some(actual_pct) = actual_yield_pct {
+        Some(actual_pct.clamp(0.0, 100.0))
+    } else if product_mol > 1e-12 {
+        Some(100.0)
+    } else {
+        None
+    };
+    let gas_mw = molar_masses.get(&gas_id).copied().unwrap_or(0.0);
+    let out = PyDict::new(py);
+    let initial_dict = PyDict::new(py);
+    for (key, value) in initial_inventory.iter() {
+        initial_dict.set_item(key, *value)?;
+    }
+    let final_dict = PyDict::new(py);
+    for (key, value) in inventory.iter() {
+        final_dict.set_item(key, *value)?;
+    }
+    out.set_item("backend", "rust")?;
+    out.set_item("gas_species_id", gas_id)?;
+    out.set_item("gas_uptake_mol", gas_uptake)?;
+    if gas_mw > 0.0 {
+        out.set_item("gas_uptake_g", gas_uptake * gas_mw)?;
+    } else {
+        out.set_item("gas_uptake_g", py.None())?;
+    }
+    out.set_item("initial_inventory_mol", initial_dict)?;
+    out.set_item("final_inventory_mol", final_dict)?;
+    out.set_item("step_results", step_results)?;
+    out.set_item("yield_species_id", product_id)?;
+    out.set_item("yield_species_moles", product_mol)?;
+    if let Some(value) = theoretical_yield_g {
+        out.set_item("theoretical_yield_g", value)?;
+    } else {
+        out.set_item("theoretical_yield_g", py.None())?;
+    }
+    if let Some(value) = actual_yield_mass_g {
+        out.set_item("actual_yield_mass_g", value)?;
+    } else {
+        out.set_item("actual_yield_mass_g", py.None())?;
+    }
+    if let Some(value) = actual_yield_pct {
+        out.set_item("actual_yield_pct", value)?;
+    } else {
+        out.set_item("actual_yield_pct", py.None())?;
+    }
+    if let Some(value) = completion_pct {
+        out.set_item("completion_pct", value)?;
+    } else {
+        out.set_item("completion_pct", py.None())?;
+    }
+    out.set_item("warnings", warnings)?;
+    Ok(out.unbind())
+}
+
+#[pyfunction]
+#[pyo3(signature = (timeline_rows))]
+/// Format Final Report timeline rows using the Rust speciation outputs.
+///
+/// The formatter keeps the existing table contract intact while using owned
+/// PyO3 dictionary casts that are compatible with current PyO3 releases.
+fn final_report_cycle_timeline_rows_core(
+    py: Python<'_>,
+    timeline_rows: &Bound<'_, PyList>,
+) -> PyResult<Py<PyList>> {
+    // Format timeline rows for Final Report preview/export table builders.
+    let rows = PyList::empty(py);
+    for item in timeline_rows.iter() {
+        let Ok(entry) = item.cast_into::<PyDict>() else {
+            continue;
+        };
+        let co2_total = dict_optional_float_value(&entry, "co2_g")
+            .or_else(|| dict_optional_float_value(&entry, "co2_mass_g"))
+            .or_else(|| dict_optional_float_value(&entry, "cumulative_co2_added_mass_g"))
+            .unwrap_or(0.0);
+        let h2co3_fraction = normalize_fraction_value(extract_fraction_field(&entry, "H2CO3"));
+        let hco3_fraction = normalize_fraction_value(extract_fraction_field(&entry, "HCO3-"));
+        let co3_fraction = normalize_fraction_value(extract_fraction_field(&entry, "CO3^2-"));
+        let ph_value = dict_optional_float_value(&entry, "solution_ph")
+            .or_else(|| dict_optional_float_value(&entry, "speciation_ph"));
+        let row = PyDict::new(py);
+        row.set_item("Cycle", dict_truthy_or_empty_pyobject(py, &entry, "cycle_id"))?;
+        row.set_item("CO\u{2082} total (g)", format!("{co2_total:.2}"))?;
+        row.set_item("pH", format_optional_decimal(ph_value, 2))?;
+        row.set_item("H\u{2082}CO\u{2083} (%)", format!("{:.1}", h2co3_fraction * 100.0))?;
+        row.set_item("HCO\u{2083}\u{207B} (%)", format!("{:.1}", hco3_fraction * 100.0))?;
+        row.set_item("CO\u{2083}\u{00B2}\u{207B} (%)", format!("{:.1}", co3_fraction * 100.0))?;
+        rows.append(row)?;
+    }
+    Ok(rows.unbind())
+}
+
+#[pyfunction]
+#[pyo3(signature = (timeline_rows))]
+/// Normalize cycle timeline rows for staged table/callout rendering paths.
+///
+/// The function preserves passthrough fields from each source row while
+/// coercing key numeric/fraction terms into a stable schema used by both
+/// Rust and Python render payload builders.
+fn cycle_timeline_normalize_core(
+    py: Python<'_>,
+    timeline_rows: &Bound<'_, PyList>,
+) -> PyResult<Py<PyList>> {
+    // Normalize mixed workflow timeline row payloads into one renderer-safe
+    // schema without dropping unknown passthrough fields.
+    let co2_total_keys = ["co2_g", "co2_mass_g", "cumulative_co2_added_mass_g"];
+    let co2_cycle_keys = ["co2_mass_g", "cycle_co2_mass_g", "selected_mass_g"];
+    let ph_keys = ["ph", "actual_ph", "solution_ph", "speciation_ph"];
+    let actual_ph_keys = ["actual_ph", "ph"];
+    let solution_ph_keys = ["solution_ph", "ph", "actual_ph"];
+    let normalized_rows = PyList::empty(py);
+    for (idx, item) in timeline_rows.iter().enumerate() {
+        let Ok(entry) = item.cast_into::<PyDict>() else {
+            continue;
+        };
+        let row = PyDict::new(py);
+        for (key, value) in entry.iter() {
+            row.set_item(key, value)?;
+        }
+        let cycle_id = compare_cycle_row_index(&entry, idx + 1);
+        let co2_total = dict_optional_finite_by_keys(&entry, &co2_total_keys).unwrap_or(0.0);
+        let co2_cycle = dict_optional_finite_by_keys(&entry, &co2_cycle_keys).unwrap_or(0.0);
+        let ph_value = dict_optional_finite_by_keys(&entry, &ph_keys);
+        let actual_ph = dict_optional_finite_by_keys(&entry, &actual_ph_keys);
+        let solution_ph = dict_optional_finite_by_keys(&entry, &solution_ph_keys);
+        let calculated_ph = dict_optional_finite_by_keys(
+            &entry,
+            &["calculated_ph", "calculated_ph_raw_co2", "ph", "solution_ph"],
+        );
+        let calculated_ph_raw_co2 = dict_optional_finite_by_keys(
+            &entry,
+            &[
+                "calculated_ph",
+                "calculated_ph_raw_co2",
+                "planning_co2_aligned_ph",
+                "ph",
+            ],
+        );
+        let calculated_ph_corrected_co2 = dict_optional_finite_by_keys(
+            &entry,
+            &["calculated_ph_corrected_co2", "corrected_ph", "calibrated_estimated_ph"],
+        );
+        let fractions = PyDict::new(py);
+        fractions.set_item(
+            "H2CO3",
+            normalize_fraction_value(extract_fraction_field(&entry, "H2CO3")),
+        )?;
+        fractions.set_item(
+            "HCO3-",
+            normalize_fraction_value(extract_fraction_field(&entry, "HCO3-")),
+        )?;
+        fractions.set_item(
+            "CO3^2-",
+            normalize_fraction_value(extract_fraction_field(&entry, "CO3^2-")),
+        )?;
+        let warnings = PyList::empty(py);
+        if let Some(warnings_any) = entry.get_item("warnings").ok().flatten() {
+            if let Ok(warnings_list) = warnings_any.clone().cast_into::<PyList>() {
+                for warning in warnings_list.iter() {
+                    let text = py_any_to_trimmed_string(&warning);
+                    if !text.is_empty() {
+                        warnings.append(text)?;
+                    }
+                }
+            } else {
+                let warning_text = py_any_to_trimmed_string(&warnings_any);
+                if !warning_text.is_empty() {
+                    warnings.append(warning_text)?;
+                }
+            }
+        }
+        let analysis_prediction = PyDict::new(py);
+        let mut prediction_target_ph: Option<f64> = None;
+        let mut prediction_co2_to_target: Option<f64> = None;
+        if let Some(prediction_any) = entry.get_item("analysis_prediction").ok().flatten() {
+            if let Ok(prediction_map) = prediction_any.cast_into::<PyDict>() {
+                prediction_target_ph =
+                    dict_optional_finite_by_keys(&prediction_map, &["target_ph"]);
+                prediction_co2_to_target =
+                    dict_optional_finite_by_keys(&prediction_map, &["co2_to_target_g"]);
+            }
+        }
+        if let Some(value) = prediction_target_ph {
+            analysis_prediction.set_item("target_ph", value)?;
+        } else {
+            analysis_prediction.set_item("target_ph", py.None())?;
+        }
+        if let Some(value) = prediction_co2_to_target {
+            analysis_prediction.set_item("co2_to_target_g", value)?;
+        } else {
+            analysis_prediction.set_item("co2_to_target_g", py.None())?;
+        }
+        row.set_item("cycle_id", cycle_id)?;
+        row.set_item("co2_g", co2_total)?;
+        row.set_item("co2_mass_g", co2_cycle)?;
+        if let Some(value) = dict_optional_finite_by_keys(&entry, &["co2_to_target_g"]) {
+            row.set_item("co2_to_target_g", value)?;
+        } else {
+            row.set_item("co2_to_target_g", py.None())?;
+        }
+        if let Some(value) = ph_value {
+            row.set_item("ph", value)?;
+        } else {
+            row.set_item("ph", py.None())?;
+        }
+        if let Some(value) = actual_ph {
+            row.set_item("actual_ph", value)?;
+        } else {
+            row.set_item("actual_ph", py.None())?;
+        }
+        if let Some(value) = solution_ph {
+            row.set_item("solution_ph", value)?;
+        } else {
+            row.set_item("solution_ph", py.None())?;
+        }
+        if let Some(value) = calculated_ph {
+            row.set_item("calculated_ph", value)?;
+        } else {
+            row.set_item("calculated_ph", py.None())?;
+        }
+        if let Some(value) = calculated_ph_raw_co2 {
+            row.set_item("calculated_ph_raw_co2", value)?;
+        } else {
+            row.set_item("calculated_ph_raw_co2", py.None())?;
+        }
+        if let Some(value) = calculated_ph_corrected_co2 {
+            row.set_item("calculated_ph_corrected_co2", value)?;
+        } else {
+            row.set_item("calculated_ph_corrected_co2", py.None())?;
+        }
+        let calculated_delta = match (calculated_ph_corrected_co2, calculated_ph_raw_co2) {
+            (Some(corrected), Some(raw)) => Some(corrected - raw),
+            _ => dict_optional_finite_by_keys(
+                &entry,
+                &["calculated_ph_corrected_minus_raw_delta"],
+            ),
+        };
+        if let Some(value) = calculated_delta {
+            row.set_item("calculated_ph_corrected_minus_raw_delta", value)?;
+        } else {
+            row.set_item("calculated_ph_corrected_minus_raw_delta", py.None())?;
+        }
+        for key in [
+            "forecast_ph",
+            "pco2_atm",
+            "planning_cycle_ph",
+            "planning_co2_aligned_ph",
+            "ph_delta_cycle",
+            "ph_delta_co2",
+            "actual_cycle_co2_mol",
+            "planning_cycle_co2_mol",
+            "co2_mol_delta",
+            "co2_mol_delta_pct",
+            "actual_hco3_pct",
+            "actual_co3_pct",
+            "planning_hco3_pct",
+            "planning_co3_pct",
+            "hco3_pct_delta",
+            "co3_pct_delta",
+            "delta_pressure_psi",
+            "co2_moles",
+            "peak_pressure_psi",
+            "trough_pressure_psi",
+            "headspace_pressure_psi",
+        ] {
+            if let Some(value) = dict_optional_finite_by_keys(&entry, &[key]) {
+                row.set_item(key, value)?;
+            } else {
+                row.set_item(key, py.None())?;
+            }
+        }
+        row.set_item(
+            "solid_na2co3_g",
+            dict_optional_finite_by_keys(&entry, &["solid_na2co3_g"]).unwrap_or(0.0),
+        )?;
+        row.set_item(
+            "solid_nahco3_g",
+            dict_optional_finite_by_keys(&entry, &["solid_nahco3_g"]).unwrap_or(0.0),
+        )?;
+        row.set_item("alignment_quality_flag", dict_string_value(&entry, "alignment_quality_flag"))?;
+        row.set_item("fractions", fractions)?;
+        row.set_item("warnings", warnings)?;
+        row.set_item("analysis_prediction", analysis_prediction)?;
+        let error_flag = entry
+            .get_item("error")
+            .ok()
+            .flatten()
+            .and_then(|value| value.is_truthy().ok())
+            .unwrap_or(false);
+        row.set_item("error", error_flag)?;
+        normalized_rows.append(row)?;
+    }
+    Ok(normalized_rows.unbind())
+}
+
+#[pyfunction]
+/// Return Rust backend interface metadata consumed by Python capability checks.
+fn rust_backend_manifest(py: Python<'_>) -> PyResult<Py<PyDict>> {
+    let payload = PyDict::new(py);
+    payload.set_item("interface_id", RUST_BACKEND_INTERFACE_ID)?;
+    payload.set_item("interface_version", RUST_BACKEND_INTERFACE_VERSION)?;
+    payload.set_item("crate_version", RUST_BACKEND_CRATE_VERSION)?;
+    payload.set_item("module_name", RUST_BACKEND_MODULE_NAME)?;
+    let exported_kernels = PyList::empty(py);
+    for kernel_name in RUST_EXPORTED_KERNELS {
+        exported_kernels.append(kernel_name)?;
+    }
+    payload.set_item("exported_kernels", exported_kernels)?;
+    Ok(payload.unbind())
+}
+
+/// Outcome of one `self_test` check.
+struct SelfTestCheck {
+    name: &'static str,
+    expected: f64,
+    found: Option<f64>,
+    tolerance: f64,
+    residual_norm: Option<f64>,
+    message: Option<String>,
+}
+
+impl SelfTestCheck {
+    fn passed(&self) -> bool {
+        self.found
+            .is_some_and(|found| (found - self.expected).abs() <= self.tolerance)
+    }
+}
+
+/// pH of a closed carbonate solve, or the solver error, for `self_test`.
+fn self_test_solve_ph(
+    total_carbon_m: f64,
+    na_conc: f64,
+    activity_model: ActivityModel,
+    guess: f64,
+) -> Result<(f64, f64), String> {
+    solve_carbonate_state(
+        CarbonateSystem::new(total_carbon_m, na_conc, (SOL_KA1, SOL_KA2, SOL_KW)),
+        ActivityTreatment::new(activity_model, None),
+        NewtonOptions::from_guess(guess),
+    )
+    .map(|(h, _, _, _, _, _, _, residual_norm)| (-h.max(1e-30).log10(), residual_norm))
+}
+
+#[pyfunction]
+/// Run known-answer equilibrium solves and report pass/fail for a runtime health check.
+///
+/// Ideal-activity round trips rebuild the sodium that puts 0.1 M carbon at a chosen
+/// pH and require the solver to land back on it; a hybrid 1 M bicarbonate solve
+/// must reproduce the committed reference pH; and the default constant table and
+/// pKa2 fit are checked against their documented values. Nothing here raises:
+/// solver failures are reported as failed checks with their message, so a service
+/// can call this at startup and refuse to serve on `passed == False`.
+fn self_test(py: Python<'_>) -> PyResult<Py<PyDict>> {
+    let mut checks = Vec::new();
+    let total_carbon_m = 0.1;
+    for (name, ph) in [
+        ("ideal_round_trip_ph_6_35", 6.35),
+        ("ideal_round_trip_ph_8_3", 8.3),
+        ("ideal_round_trip_ph_10_33", 10.33),
+    ] {
+        let h = 10f64.powf(-ph);
+        let denom = h * h + SOL_KA1 * h + SOL_KA1 * SOL_KA2;
+        let (a1, a2) = (SOL_KA1 * h / denom, SOL_KA1 * SOL_KA2 / denom);
+        let na_conc = total_carbon_m * (a1 + 2.0 * a2) + SOL_KW / h - h;
+        let solved = self_test_solve_ph(total_carbon_m, na_conc, ActivityModel::Ideal, 7.0);
+        checks.push(SelfTestCheck {
+            name,
+            expected: ph,
+            found: solved.as_ref().ok().map(|value| value.0),
+            tolerance: SELF_TEST_ROUND_TRIP_PH_TOL,
+            residual_norm: solved.as_ref().ok().map(|value| value.1),
+            message: solved.err(),
+        });
+    }
+    let solved = self_test_solve_ph(1.0, 1.0, ActivityModel::Hybrid, 8.0);
+    checks.push(SelfTestCheck {
+        name: "hybrid_bicarbonate_reference",
+        expected: SELF_TEST_HYBRID_BICARBONATE_PH,
+        found: solved.as_ref().ok().map(|value| value.0),
+        tolerance: SELF_TEST_REFERENCE_PH_TOL,
+        residual_norm: solved.as_ref().ok().map(|value| value.1),
+        message: solved.err(),
+    });
+    let (ka1, ka2, kw) = basic_carbonate_constants(None, false);
+    let table_intact = ka1 == SOL_KA1 && ka2 == SOL_KA2 && kw == SOL_KW;
+    checks.push(SelfTestCheck {
+        name: "default_constant_table",
+        expected: 1.0,
+        found: Some(if table_intact { 1.0 } else { 0.0 }),
+        tolerance: 0.0,
+        residual_norm: None,
+        message: (!table_intact)
+            .then(|| format!("default constants are ({ka1}, {ka2}, {kw})")),
+    });
+    checks.push(SelfTestCheck {
+        name: "pka2_fit_25c",
+        expected: SELF_TEST_PKA2_25C,
+        found: Some(resolve_pka2_value(Some(25.0), true)),
+        tolerance: SELF_TEST_PKA2_TOL,
+        residual_norm: None,
+        message: None,
+    });
+
+    let rows = PyList::empty(py);
+    let mut n_failed = 0usize;
+    for check in &checks {
+        let passed = check.passed();
+        n_failed += usize::from(!passed);
+        let row = PyDict::new(py);
+        row.set_item("name", check.name)?;
+        row.set_item("passed", passed)?;
+        row.set_item("expected", check.expected)?;
+        row.set_item("found", check.found)?;
+        row.set_item("error", check.found.map(|found| found - check.expected))?;
+        row.set_item("tolerance", check.tolerance)?;
+        row.set_item("residual_norm", check.residual_norm)?;
+        row.set_item("message", check.message.as_deref())?;
+        rows.append(row)?;
+    }
+    let payload = PyDict::new(py);
+    payload.set_item("passed", n_failed == 0)?;
+    payload.set_item("n_checks", checks.len())?;
+    payload.set_item("n_failed", n_failed)?;
+    payload.set_item("checks", rows)?;
+    payload.set_item("crate_version", RUST_BACKEND_CRATE_VERSION)?;
+    payload.set_item("interface_version", RUST_BACKEND_INTERFACE_VERSION)?;
+    Ok(payload.unbind())
+}
+
+#[pyfunction]
+/// Return the crate version and the default model constants behind every result.
+///
+/// This is the provenance record for an analysis: the equilibrium constants used
+/// when temperature adjustment is off, the molar masses, the Debye-Huckel/Davies
+/// coefficients, and the pKa/pKw temperature fits (`a*T^2 + b*T + c` and
+/// `slope*T + intercept`, `T` in degC within the temperature clamp). Keys are
+/// stable; a changed value means a changed model.
+fn model_info(py: Python<'_>) -> PyResult<Py<PyDict>> {
+    let payload = PyDict::new(py);
+    payload.set_item("crate_version", RUST_BACKEND_CRATE_VERSION)?;
+    payload.set_item("module_name", RUST_BACKEND_MODULE_NAME)?;
+    payload.set_item("interface_version", RUST_BACKEND_INTERFACE_VERSION)?;
+
+    let constants = PyDict::new(py);
+    constants.set_item("ka1", SOL_KA1)?;
+    constants.set_item("ka2", SOL_KA2)?;
+    constants.set_item("kw", SOL_KW)?;
+    payload.set_item("default_constants", constants)?;
+
+    let molar_masses = PyDict::new(py);
+    molar_masses.set_item("naoh", SOL_MW_NAOH)?;
+    molar_masses.set_item("co2", SOL_MW_CO2)?;
+    molar_masses.set_item("na2co3", SOL_MW_NA2CO3)?;
+    molar_masses.set_item("nahco3", SOL_MW_NAHCO3)?;
+    molar_masses.set_item("caco3_per_equivalent", CACO3_G_PER_EQ)?;
+    payload.set_item("molar_masses_g_mol", molar_masses)?;
+
+    let debye = PyDict::new(py);
+    debye.set_item("a", SOL_A_DEBYE)?;
+    debye.set_item("b", SOL_B_DEBYE)?;
+    debye.set_item("davies_coefficient", SOL_DAVIES_COEFF)?;
+    debye.set_item("davies_ionic_strength_limit", SOL_DAVIES_LIMIT)?;
+    payload.set_item("debye_huckel", debye)?;
+
+    let fits = PyDict::new(py);
+    let (a1, b1, c1) = SOL_PKA1_COEFFS;
+    let (a2, b2, c2) = SOL_PKA2_COEFFS;
+    let (pkw_slope, pkw_intercept) = SOL_PKW_COEFFS;
+    fits.set_item("pka1", [a1, b1, c1])?;
+    fits.set_item("pka2", [a2, b2, c2])?;
+    fits.set_item("pkw", [pkw_slope, pkw_intercept])?;
+    fits.set_item("temperature_clamp_c", [TEMPERATURE_CLAMP_MIN_C, TEMPERATURE_CLAMP_MAX_C])?;
+    payload.set_item("temperature_fits", fits)?;
+
+    let henry = PyDict::new(py);
+    henry.set_item("kh_co2_25c_mol_l_atm", SOL_HENRY_KH_CO2_25C)?;
+    henry.set_item("vant_hoff_k", SOL_HENRY_VANT_HOFF_K)?;
+    payload.set_item("henry", henry)?;
+    Ok(payload.unbind())
+}
+
+/// Ledger pools plus pH in the `state` shape returned by
+/// `simulate_reaction_state_with_accounting`.
+fn ledger_state_dict<'py>(
+    py: Python<'py>,
+    state: LedgerState,
+    ph: f64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let state_dict = PyDict::new(py);
+    state_dict.set_item("naoh_remaining_mol", state.naoh_remaining_mol.max(0.0))?;
+    state_dict.set_item("na2co3_mol", state.na2co3_mol.max(0.0))?;
+    state_dict.set_item("nahco3_mol", state.nahco3_mol.max(0.0))?;
+    state_dict.set_item("co2_excess_mol", state.co2_excess_mol.max(0.0))?;
+    state_dict.set_item("ph", ph)?;
+    Ok(state_dict)
+}
+
+fn planning_adjustments_list<'py>(
+    py: Python<'py>,
+    adjustments: &[PlanningAdjustment],
+) -> PyResult<Bound<'py, PyList>> {
+    let rows = PyList::empty(py);
+    for adjustment in adjustments {
+        let row = PyDict::new(py);
+        row.set_item("kind", adjustment.kind)?;
+        row.set_item("ph_before", adjustment.ph_before)?;
+        row.set_item("ph_after", adjustment.ph_after)?;
+        row.set_item("weight", adjustment.weight)?;
+        rows.append(row)?;
+    }
+    Ok(rows)
+}
+
+/// `simulate_reaction_state_with_accounting` settings past its original positional
+/// arguments, read once from `**options`. Keyword names and defaults are unchanged
+/// from when each was a parameter of its own.
+struct SimulateReactionOptions<'py> {
+    raw_solver_ph: bool,
+    iteration_callback: Option<Bound<'py, PyAny>>,
+    clamp_bounds: ClampBounds,
+    activity_model: ActivityModel,
+    initial_naoh_mol: Option<f64>,
+    excess_co2_fate: ExcessCo2Fate,
+    pka_constants: Option<(f64, f64, f64)>,
+    planning_transition_width: f64,
+}
+
+impl<'py> SimulateReactionOptions<'py> {
+    fn from_kwargs(kwargs: Option<&Bound<'py, PyDict>>) -> PyResult<Self> {
+        let kwargs = KernelKwargs::new("simulate_reaction_state_with_accounting", kwargs)?;
+        let excess_co2_fate = parse_excess_co2_fate(
+            &kwargs.take_or("excess_co2_fate", EXCESS_CO2_FATE_DISSOLVED.to_string())?,
+        )?;
+        let options = Self {
+            raw_solver_ph: kwargs.take_or("raw_solver_ph", false)?,
+            iteration_callback: kwargs.take_any("iteration_callback")?,
+            clamp_bounds: kwargs.take_clamp_bounds()?,
+            activity_model: kwargs.take_activity_model()?,
+            initial_naoh_mol: kwargs.take("initial_naoh_mol")?,
+            excess_co2_fate,
+            pka_constants: kwargs.take("pka_constants")?,
+            planning_transition_width: kwargs.take_planning_transition_width()?,
+        };
+        kwargs.finish()?;
+        Ok(options)
+    }
+}
+
+/// Apply one CO2 dose to a ledger. The positional arguments are the original
+/// contract; every later setting is a keyword collected into
+/// `SimulateReactionOptions`.
+#[pyfunction]
+#[pyo3(signature = (ledger, delta_mol, pka2_value, solution_volume_l=None, temperature_c=None, ionic_strength_cap=None, use_temp_adjusted_constants=false, initial_ph_guess=None, constants=None, planning_mode=false, **options))]
+fn simulate_reaction_state_with_accounting(
+    py: Python<'_>,
+    ledger: &Bound<'_, PyDict>,
+    delta_mol: f64,
+    pka2_value: f64,
+    solution_volume_l: Option<f64>,
+    temperature_c: Option<f64>,
+    ionic_strength_cap: Option<f64>,
+    use_temp_adjusted_constants: bool,
+    initial_ph_guess: Option<f64>,
+    constants: Option<(f64, f64, f64)>,
+    planning_mode: bool,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    let SimulateReactionOptions {
+        raw_solver_ph,
+        iteration_callback,
+        clamp_bounds,
+        activity_model,
+        initial_naoh_mol,
+        excess_co2_fate,
+        pka_constants,
+        planning_transition_width,
+    } = SimulateReactionOptions::from_kwargs(options)?;
+    let constants = resolve_equilibrium_constants(constants, pka_constants)?;
+    let settings = SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        constants,
+        ionic_strength_cap,
+        activity_model,
+        clamp_bounds,
+        raw_solver_ph,
+    };
+    let solve = LedgerSolve {
+        settings,
+        pka2_value,
+        solution_volume_l,
+        planning_mode,
+        planning_transition_width,
+        excess_co2_fate,
+    };
+    let callback_error = RefCell::new(None);
+    let observer = python_iteration_observer(iteration_callback.as_ref(), &callback_error)?;
+    let input_state = LedgerState {
+        naoh_remaining_mol: dict_float_value(ledger, "naoh_remaining_mol"),
+        na2co3_mol: dict_float_value(ledger, "na2co3_mol"),
+        nahco3_mol: dict_float_value(ledger, "nahco3_mol"),
+        co2_excess_mol: dict_float_value(ledger, "co2_excess_mol"),
+    };
+    validate_co2_removal(input_state, delta_mol)?;
+    let (state, accounting, ph, residual_norm, _, adjustments) =
+        simulate_reaction_state_with_accounting_impl(
+            input_state,
+            delta_mol,
+            &solve,
+            initial_ph_guess,
+            observer.as_deref(),
+            None,
+        );
+    if let Some(err) = callback_error.take() {
+        return Err(err);
+    }
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let co2_supersaturated = co2_supersaturation_warning(
+        state,
+        solution_volume_l,
+        temperature_c,
+        clamp_bounds,
+        &warnings,
+    )?;
+    let response = PyDict::new(py);
+    let state_dict = ledger_state_dict(py, state, clamp_bounds.ph(ph))?;
+    let accounting_dict = PyDict::new(py);
+    accounting_dict.set_item(
+        "co2_consumed_to_carbonate_mol",
+        accounting.co2_consumed_to_carbonate_mol,
+    )?;
+    accounting_dict.set_item(
+        "co2_consumed_to_bicarbonate_mol",
+        accounting.co2_consumed_to_bicarbonate_mol,
+    )?;
+    accounting_dict.set_item("co2_consumed_total_mol", accounting.co2_consumed_total_mol)?;
+    accounting_dict.set_item("co2_unconsumed_mol", accounting.co2_unconsumed_mol)?;
+    accounting_dict.set_item("co2_degassed_mol", accounting.co2_degassed_mol)?;
+    accounting_dict.set_item("naoh_consumed_mol", accounting.naoh_consumed_mol)?;
+    // The conversion basis defaults to the NaOH on the incoming ledger; pass the
+    // original charge to track conversion across a multi-step run.
+    accounting_dict.set_item(
+        "naoh_conversion_fraction",
+        naoh_conversion_fraction(
+            initial_naoh_mol.unwrap_or(input_state.naoh_remaining_mol),
+            state.naoh_remaining_mol,
+        ),
+    )?;
+    accounting_dict.set_item("free_caustic_m", accounting.free_caustic_m)?;
+    response.set_item("state", state_dict)?;
+    response.set_item("accounting", accounting_dict)?;
+    response.set_item("residual_norm", residual_norm)?;
+    response.set_item("ph_clamped", clamp_bounds.ph_at_bound(ph))?;
+    response.set_item("activity_model", activity_model_name(activity_model))?;
+    response.set_item("excess_co2_fate", excess_co2_fate.name())?;
+    response.set_item("planning_adjustments", planning_adjustments_list(py, &adjustments)?)?;
+    response.set_item("co2_saturation_m", co2_saturation_m(temperature_c, clamp_bounds))?;
+    response.set_item("co2_supersaturated", co2_supersaturated)?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &response, &warnings)?;
+    response.set_item("warnings", warnings)?;
+    Ok(response.unbind())
+}
+
+/// Apply one CO2 dose with and without the planning heuristics and report both pH
+/// values side by side.
+///
+/// The ledger staging is identical in both runs; only the pH estimate differs.
+/// `planning_shift` is `planning_ph - actual_ph`, the net effect of the
+/// plateau/rescue adjustments listed in `planning_adjustments`. Use it to judge
+/// whether those clamps are appropriate for a composition before relying on a
+/// planning-mode prediction.
+#[pyfunction]
+#[pyo3(signature = (ledger, delta_mol, solution_volume_l=None, **options))]
+fn compare_planning_modes(
+    py: Python<'_>,
+    ledger: &Bound<'_, PyDict>,
+    delta_mol: f64,
+    solution_volume_l: Option<f64>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    let kwargs = KernelKwargs::new("compare_planning_modes", options)?;
+    let settings = SolverSettings::take_from(&kwargs)?;
+    let planning_transition_width = kwargs.take_planning_transition_width()?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        clamp_bounds,
+        ..
+    } = settings;
+    let input_state = LedgerState {
+        naoh_remaining_mol: dict_float_value(ledger, "naoh_remaining_mol"),
+        na2co3_mol: dict_float_value(ledger, "na2co3_mol"),
+        nahco3_mol: dict_float_value(ledger, "nahco3_mol"),
+        co2_excess_mol: dict_float_value(ledger, "co2_excess_mol"),
+    };
+    validate_co2_removal(input_state, delta_mol)?;
+    let pka2_value = settings.pka2_value();
+    let run = |planning_mode: bool| {
+        let solve = LedgerSolve {
+            planning_mode,
+            planning_transition_width,
+            ..LedgerSolve::new(settings, pka2_value, solution_volume_l)
+        };
+        simulate_reaction_state_with_accounting_impl(
+            input_state,
+            delta_mol,
+            &solve,
+            None,
+            None,
+            None,
+        )
+    };
+    let (state, _, actual_ph, actual_residual, ..) = run(false);
+    let (_, _, planning_ph, planning_residual, _, adjustments) = run(true);
+    let actual_ph = clamp_bounds.ph(actual_ph);
+    let planning_ph = clamp_bounds.ph(planning_ph);
+    let planning_shift = planning_ph - actual_ph;
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let out = PyDict::new(py);
+    out.set_item("state", ledger_state_dict(py, state, actual_ph)?)?;
+    out.set_item("actual_ph", actual_ph)?;
+    out.set_item("planning_ph", planning_ph)?;
+    out.set_item("planning_shift", planning_shift)?;
+    out.set_item("planning_shift_abs", planning_shift.abs())?;
+    out.set_item("planning_adjusted", !adjustments.is_empty())?;
+    out.set_item("planning_adjustments", planning_adjustments_list(py, &adjustments)?)?;
+    out.set_item("actual_residual_norm", actual_residual)?;
+    out.set_item("planning_residual_norm", planning_residual)?;
+    out.set_item("pka2_value", pka2_value)?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(out.unbind())
+}
+
+/// Evaluate one ledger composition across a temperature sweep.
+///
+/// Each point uses temperature-adjusted pKa1/pKa2/pKw; temperatures outside the
+/// supported correlation range are clamped and the row is flagged. The Debye
+/// coefficients of the activity model stay at their 25 C values.
+#[pyfunction]
+#[pyo3(signature = (ledger, delta_mol, temps_c, solution_volume_l, **options))]
+fn ph_vs_temperature(
+    py: Python<'_>,
+    ledger: &Bound<'_, PyDict>,
+    delta_mol: f64,
+    temps_c: Vec<f64>,
+    solution_volume_l: Option<f64>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyList>> {
+    // The sweep sets the temperature and constants itself, so only the rest of
+    // the shared settings are read.
+    let kwargs = KernelKwargs::new("ph_vs_temperature", options)?;
+    let ionic_strength_cap = kwargs.take("ionic_strength_cap")?;
+    let planning_mode = kwargs.take_or("planning_mode", false)?;
+    let planning_transition_width = kwargs.take_planning_transition_width()?;
+    let raw_solver_ph = kwargs.take_or("raw_solver_ph", false)?;
+    let clamp_bounds = kwargs.take_clamp_bounds()?;
+    let activity_model = kwargs.take_activity_model()?;
+    kwargs.finish()?;
+    let input_state = LedgerState {
+        naoh_remaining_mol: dict_float_value(ledger, "naoh_remaining_mol"),
+        na2co3_mol: dict_float_value(ledger, "na2co3_mol"),
+        nahco3_mol: dict_float_value(ledger, "nahco3_mol"),
+        co2_excess_mol: dict_float_value(ledger, "co2_excess_mol"),
+    };
+    validate_co2_removal(input_state, delta_mol)?;
+    if temps_c.iter().any(|temp_c| !temp_c.is_finite()) {
+        return Err(PyValueError::new_err("temps_c entries must be finite"));
+    }
+    // The sweep is pure Rust, so it runs with the GIL released.
+    let solved = detach_solver(py, || {
+        let mut step_guess: Option<f64> = None;
+        let mut solved = Vec::with_capacity(temps_c.len());
+        for &temp_c in &temps_c {
+            let effective_temp_c = clamp_bounds.temperature(temp_c);
+            let settings = SolverSettings {
+                temperature_c: Some(effective_temp_c),
+                use_temp_adjusted_constants: true,
+                constants: None,
+                ionic_strength_cap,
+                activity_model,
+                clamp_bounds,
+                raw_solver_ph,
+            };
+            let pka2_value = settings.pka2_value();
+            let solve = LedgerSolve {
+                planning_mode,
+                planning_transition_width,
+                ..LedgerSolve::new(settings, pka2_value, solution_volume_l)
+            };
+            let (state, _, ph, residual_norm, _, _) = simulate_reaction_state_with_accounting_impl(
+                input_state,
+                delta_mol,
+                &solve,
+                step_guess,
+                None,
+                None,
+            );
+            step_guess = Some(ph);
+            solved.push((temp_c, effective_temp_c, pka2_value, state, ph, residual_norm));
+        }
+        solved
+    });
+    let rows = PyList::empty(py);
+    for (temp_c, effective_temp_c, pka2_value, state, ph, residual_norm) in solved {
+        let row = PyDict::new(py);
+        row.set_item("temperature_c", temp_c)?;
+        row.set_item("effective_temperature_c", effective_temp_c)?;
+        row.set_item("temperature_clamped", effective_temp_c != temp_c)?;
+        let pkw = carbonate_pkw_from_temp(effective_temp_c);
+        row.set_item("pka2_value", pka2_value)?;
+        row.set_item("pkw", pkw)?;
+        row.set_item("neutral_ph", pkw / 2.0)?;
+        row.set_item("ph", clamp_bounds.ph(ph))?;
+        row.set_item("na2co3_mol", state.na2co3_mol)?;
+        row.set_item("nahco3_mol", state.nahco3_mol)?;
+        row.set_item("residual_norm", residual_norm)?;
+        rows.append(row)?;
+    }
+    Ok(rows.unbind())
+}
+
+/// Titrate a ledger with `n_steps` additions of `increment_mol` of one reagent and
+/// report the pH after each.
+///
+/// Every addition goes through the reagent's ledger update (CO2 staging, NaOH
+/// blending, strong-acid neutralization, or a salt spike) and is equilibrated on
+/// the ledger the previous one left, so all reagents share one curve shape. Strong
+/// acid past the ledger's alkalinity would leave free acid the ledger cannot hold
+/// and is rejected.
+#[pyfunction]
+#[pyo3(signature = (ledger, reagent, increment_mol, n_steps, solution_volume_l, **options))]
+fn titrate(
+    py: Python<'_>,
+    ledger: &Bound<'_, PyDict>,
+    reagent: &str,
+    increment_mol: f64,
+    n_steps: usize,
+    solution_volume_l: Option<f64>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    let kwargs = KernelKwargs::new("titrate", options)?;
+    let settings = SolverSettings::take_from(&kwargs)?;
+    let planning_mode = kwargs.take_or("planning_mode", false)?;
+    let planning_transition_width = kwargs.take_planning_transition_width()?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        clamp_bounds,
+        ..
+    } = settings;
+    let reagent = parse_reagent(reagent)?;
+    if n_steps == 0 {
+        return Err(PyValueError::new_err("n_steps must be at least 1"));
+    }
+    if !increment_mol.is_finite() || increment_mol <= 0.0 {
+        return Err(PyValueError::new_err("increment_mol must be finite and positive"));
+    }
+    let mut state = LedgerState {
+        naoh_remaining_mol: dict_float_value(ledger, "naoh_remaining_mol"),
+        na2co3_mol: dict_float_value(ledger, "na2co3_mol"),
+        nahco3_mol: dict_float_value(ledger, "nahco3_mol"),
+        co2_excess_mol: dict_float_value(ledger, "co2_excess_mol"),
+    };
+    let total_mol = increment_mol * n_steps as f64;
+    let alkalinity = ledger_alkalinity_mol(state);
+    if reagent == Reagent::StrongAcid
+        && total_mol > alkalinity * (1.0 + STOICHIOMETRY_AUDIT_REL_TOL)
+    {
+        return Err(PyValueError::new_err(format!(
+            "{total_mol} mol of strong acid exceeds the ledger's {alkalinity} mol of \
+             alkalinity; free acid cannot be represented"
+        )));
+    }
+    let solve = LedgerSolve {
+        planning_mode,
+        planning_transition_width,
+        ..LedgerSolve::new(settings, settings.pka2_value(), solution_volume_l)
+    };
+    // Additions are solved with the GIL released; the Python rows are built after.
+    let solved = detach_solver(py, || {
+        let mut step_guess: Option<f64> = None;
+        let mut solved = Vec::with_capacity(n_steps);
+        for _ in 0..n_steps {
+            let (dosed, delta_mol) = match reagent {
+                Reagent::Co2 => (state, increment_mol),
+                Reagent::StrongBase => {
+                    let caustic = LedgerState {
+                        naoh_remaining_mol: increment_mol,
+                        na2co3_mol: 0.0,
+                        nahco3_mol: 0.0,
+                        co2_excess_mol: 0.0,
+                    };
+                    (blend_ledgers_impl(state, caustic), 0.0)
+                }
+                Reagent::StrongAcid => (add_strong_acid_impl(state, increment_mol), 0.0),
+                Reagent::Na2co3 => (add_salt_impl(state, increment_mol, 0.0), 0.0),
+                Reagent::Nahco3 => (add_salt_impl(state, 0.0, increment_mol), 0.0),
+            };
+            let (next_state, _, ph, residual_norm, _, _) =
+                simulate_reaction_state_with_accounting_impl(
+                    dosed,
+                    delta_mol,
+                    &solve,
+                    step_guess,
+                    None,
+                    None,
+                );
+            state = next_state;
+            step_guess = Some(ph);
+            solved.push((state, ph, residual_norm));
+        }
+        solved
+    });
+    let curve = PyList::empty(py);
+    let mut max_residual: Option<f64> = None;
+    let mut final_ph = 0.0_f64;
+    for (index, (step_state, ph, residual_norm)) in solved.into_iter().enumerate() {
+        let ph = clamp_bounds.ph(ph);
+        final_ph = ph;
+        max_residual = max_optional_residual(max_residual, residual_norm);
+        let row = PyDict::new(py);
+        row.set_item("step", index)?;
+        row.set_item("cumulative_added_mol", increment_mol * (index + 1) as f64)?;
+        row.set_item("ph", ph)?;
+        row.set_item("residual_norm", residual_norm)?;
+        row.set_item("state", ledger_state_dict(py, step_state, ph)?)?;
+        curve.append(row)?;
+    }
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let out = PyDict::new(py);
+    out.set_item("reagent", reagent_name(reagent))?;
+    out.set_item("increment_mol", increment_mol)?;
+    out.set_item("total_added_mol", total_mol)?;
+    out.set_item("curve", curve)?;
+    out.set_item("final_ph", final_ph)?;
+    out.set_item("final_state", ledger_state_dict(py, state, final_ph)?)?;
+    out.set_item("max_residual_achieved", max_residual)?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(out.unbind())
+}
+
+/// Stage a CO2 dose in `n_substeps` equal increments and track the transient pH.
+///
+/// Each increment is equilibrated on the ledger left by the previous one, so the
+/// trajectory shows how far into the dose the pH first falls below `target_ph`;
+/// `co2_at_crossing_mol` is the metered amount that can go in before it does.
+#[pyfunction]
+#[pyo3(signature = (ledger, total_delta_mol, target_ph, n_substeps, solution_volume_l, **options))]
+fn dose_overshoot(
+    py: Python<'_>,
+    ledger: &Bound<'_, PyDict>,
+    total_delta_mol: f64,
+    target_ph: f64,
+    n_substeps: usize,
+    solution_volume_l: Option<f64>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    let kwargs = KernelKwargs::new("dose_overshoot", options)?;
+    let settings = SolverSettings::take_from(&kwargs)?;
+    let planning_mode = kwargs.take_or("planning_mode", false)?;
+    let planning_transition_width = kwargs.take_planning_transition_width()?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        clamp_bounds,
+        ..
+    } = settings;
+    if n_substeps == 0 {
+        return Err(PyValueError::new_err("n_substeps must be at least 1"));
+    }
+    if !total_delta_mol.is_finite() || total_delta_mol < 0.0 {
+        return Err(PyValueError::new_err("total_delta_mol must be finite and non-negative"));
+    }
+    let mut state = LedgerState {
+        naoh_remaining_mol: dict_float_value(ledger, "naoh_remaining_mol"),
+        na2co3_mol: dict_float_value(ledger, "na2co3_mol"),
+        nahco3_mol: dict_float_value(ledger, "nahco3_mol"),
+        co2_excess_mol: dict_float_value(ledger, "co2_excess_mol"),
+    };
+    let solve = LedgerSolve {
+        planning_mode,
+        planning_transition_width,
+        ..LedgerSolve::new(settings, settings.pka2_value(), solution_volume_l)
+    };
+    let substep_mol = total_delta_mol / n_substeps as f64;
+    // Substeps are solved with the GIL released; the Python rows are built after.
+    let solved = detach_solver(py, || {
+        let mut step_guess: Option<f64> = None;
+        let mut solved = Vec::with_capacity(n_substeps);
+        for _ in 0..n_substeps {
+            let (next_state, _, ph, residual_norm, _, _) =
+                simulate_reaction_state_with_accounting_impl(
+                    state,
+                    substep_mol,
+                    &solve,
+                    step_guess,
+                    None,
+                    None,
+                );
+            state = next_state;
+            step_guess = Some(ph);
+            solved.push((ph, residual_norm));
+        }
+        solved
+    });
+    let substeps = PyList::empty(py);
+    let mut min_ph = f64::INFINITY;
+    let mut final_ph = 0.0_f64;
+    let mut crossing: Option<(usize, f64)> = None;
+    let mut max_residual: Option<f64> = None;
+    for (index, (ph, residual_norm)) in solved.into_iter().enumerate() {
+        let ph = clamp_bounds.ph(ph);
+        let cumulative_mol = substep_mol * (index + 1) as f64;
+        min_ph = min_ph.min(ph);
+        final_ph = ph;
+        max_residual = max_optional_residual(max_residual, residual_norm);
+        if crossing.is_none() && ph < target_ph {
+            crossing = Some((index, cumulative_mol - substep_mol));
+        }
+        let row = PyDict::new(py);
+        row.set_item("substep", index)?;
+        row.set_item("cumulative_delta_mol", cumulative_mol)?;
+        row.set_item("ph", ph)?;
+        row.set_item("residual_norm", residual_norm)?;
+        substeps.append(row)?;
+    }
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let out = PyDict::new(py);
+    out.set_item("min_ph", min_ph)?;
+    out.set_item("final_ph", final_ph)?;
+    out.set_item("undershoots_target", crossing.is_some())?;
+    out.set_item("crossing_substep", crossing.map(|(index, _)| index))?;
+    out.set_item("co2_at_crossing_mol", crossing.map(|(_, mol)| mol))?;
+    out.set_item("final_state", ledger_state_dict(py, state, final_ph)?)?;
+    out.set_item("max_residual_achieved", max_residual)?;
+    out.set_item("substeps", substeps)?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(out.unbind())
+}
+
+/// Add solid sodium carbonate/bicarbonate to a ledger and report the new pH.
+#[pyfunction]
+#[pyo3(signature = (ledger, na2co3_mol, nahco3_mol, solution_volume_l, **options))]
+fn add_salt(
+    py: Python<'_>,
+    ledger: &Bound<'_, PyDict>,
+    na2co3_mol: f64,
+    nahco3_mol: f64,
+    solution_volume_l: Option<f64>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    let kwargs = KernelKwargs::new("add_salt", options)?;
+    let settings = SolverSettings::take_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        clamp_bounds,
+        ..
+    } = settings;
+    if na2co3_mol.is_nan() || nahco3_mol.is_nan() || na2co3_mol < 0.0 || nahco3_mol < 0.0 {
+        return Err(PyValueError::new_err("na2co3_mol and nahco3_mol must be non-negative"));
+    }
+    let input_state = LedgerState {
+        naoh_remaining_mol: dict_float_value(ledger, "naoh_remaining_mol"),
+        na2co3_mol: dict_float_value(ledger, "na2co3_mol"),
+        nahco3_mol: dict_float_value(ledger, "nahco3_mol"),
+        co2_excess_mol: dict_float_value(ledger, "co2_excess_mol"),
+    };
+    let solve = LedgerSolve::new(settings, settings.pka2_value(), solution_volume_l);
+    let spiked = add_salt_impl(input_state, na2co3_mol, nahco3_mol);
+    let (state, _, ph, residual_norm, _, _) =
+        simulate_reaction_state_with_accounting_impl(spiked, 0.0, &solve, None, None, None);
+    let fractions = ledger_carbon_fractions(state, solution_volume_l, &settings, ph);
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let state_dict = ledger_state_dict(py, state, clamp_bounds.ph(ph))?;
+    let out = PyDict::new(py);
+    out.set_item("state", state_dict)?;
+    out.set_item("sodium_added_mol", 2.0 * na2co3_mol + nahco3_mol)?;
+    out.set_item("carbon_added_mol", na2co3_mol + nahco3_mol)?;
+    out.set_item("frac_h2co3", fractions[0])?;
+    out.set_item("frac_hco3", fractions[1])?;
+    out.set_item("frac_co3", fractions[2])?;
+    set_dominant_species(&out, fractions)?;
+    out.set_item("residual_norm", residual_norm)?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(out.unbind())
+}
+
+/// Mix two batches and report the combined ledger and its re-solved pH.
+#[pyfunction]
+#[pyo3(signature = (ledger_a, volume_a, ledger_b, volume_b, **options))]
+fn blend(
+    py: Python<'_>,
+    ledger_a: &Bound<'_, PyDict>,
+    volume_a: f64,
+    ledger_b: &Bound<'_, PyDict>,
+    volume_b: f64,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    let kwargs = KernelKwargs::new("blend", options)?;
+    let settings = SolverSettings::take_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        clamp_bounds,
+        ..
+    } = settings;
+    let volumes_valid = [volume_a, volume_b]
+        .iter()
+        .all(|volume| volume.is_finite() && *volume > 0.0);
+    if !volumes_valid {
+        return Err(PyValueError::new_err("volume_a and volume_b must be positive"));
+    }
+    let read_ledger = |ledger: &Bound<'_, PyDict>| LedgerState {
+        naoh_remaining_mol: dict_float_value(ledger, "naoh_remaining_mol"),
+        na2co3_mol: dict_float_value(ledger, "na2co3_mol"),
+        nahco3_mol: dict_float_value(ledger, "nahco3_mol"),
+        co2_excess_mol: dict_float_value(ledger, "co2_excess_mol"),
+    };
+    let blended = blend_ledgers_impl(read_ledger(ledger_a), read_ledger(ledger_b));
+    let volume_l = volume_a + volume_b;
+    let solve = LedgerSolve::new(settings, settings.pka2_value(), Some(volume_l));
+    let (state, _, ph, residual_norm, _, _) =
+        simulate_reaction_state_with_accounting_impl(blended, 0.0, &solve, None, None, None);
+    let fractions = ledger_carbon_fractions(state, Some(volume_l), &settings, ph);
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let out = PyDict::new(py);
+    out.set_item("state", ledger_state_dict(py, state, clamp_bounds.ph(ph))?)?;
+    out.set_item("volume_l", volume_l)?;
+    out.set_item("frac_h2co3", fractions[0])?;
+    out.set_item("frac_hco3", fractions[1])?;
+    out.set_item("frac_co3", fractions[2])?;
+    set_dominant_species(&out, fractions)?;
+    out.set_item("residual_norm", residual_norm)?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(out.unbind())
+}
+
+/// Dilute a batch with pure water and report the pH before and after.
+///
+/// The mole pools are unchanged; only the volume grows, so the CO3/HCO3 ratio is
+/// kept and the shift comes from the lower ionic strength (larger activity
+/// coefficients) and lower absolute concentrations. Both states are re-solved.
+#[pyfunction]
+#[pyo3(signature = (ledger, current_volume_l, added_water_l, **options))]
+fn dilute(
+    py: Python<'_>,
+    ledger: &Bound<'_, PyDict>,
+    current_volume_l: f64,
+    added_water_l: f64,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    let kwargs = KernelKwargs::new("dilute", options)?;
+    let settings = SolverSettings::take_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        activity_model,
+        clamp_bounds,
+        ..
+    } = settings;
+    if !current_volume_l.is_finite() || current_volume_l <= 0.0 {
+        return Err(PyValueError::new_err("current_volume_l must be positive"));
+    }
+    if !added_water_l.is_finite() || added_water_l < 0.0 {
+        return Err(PyValueError::new_err("added_water_l must be finite and non-negative"));
+    }
+    let input_state = LedgerState {
+        naoh_remaining_mol: dict_float_value(ledger, "naoh_remaining_mol"),
+        na2co3_mol: dict_float_value(ledger, "na2co3_mol"),
+        nahco3_mol: dict_float_value(ledger, "nahco3_mol"),
+        co2_excess_mol: dict_float_value(ledger, "co2_excess_mol"),
+    };
+    let volume_l = current_volume_l + added_water_l;
+    let pka2_value = settings.pka2_value();
+    let solve_at = |volume: f64, ph_guess: Option<f64>| {
+        let solve = LedgerSolve::new(settings, pka2_value, Some(volume));
+        simulate_reaction_state_with_accounting_impl(input_state, 0.0, &solve, ph_guess, None, None)
+    };
+    let (_, _, ph_before, _, _, _) = solve_at(current_volume_l, None);
+    let (state, _, ph_after, residual_norm, _, _) = solve_at(volume_l, Some(ph_before));
+    let ionic_before = ledger_ionic_state(state, current_volume_l, &settings, ph_before);
+    let ionic_after = ledger_ionic_state(state, volume_l, &settings, ph_after);
+    let fractions = ledger_carbon_fractions(state, Some(volume_l), &settings, ph_after);
+    let ph_before = clamp_bounds.ph(ph_before);
+    let ph_after = clamp_bounds.ph(ph_after);
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let out = PyDict::new(py);
+    out.set_item("state", ledger_state_dict(py, state, ph_after)?)?;
+    out.set_item("volume_l", volume_l)?;
+    out.set_item("dilution_factor", volume_l / current_volume_l)?;
+    out.set_item("ph_before", ph_before)?;
+    out.set_item("ph_after", ph_after)?;
+    out.set_item("ph_shift", ph_after - ph_before)?;
+    for (suffix, ionic) in [("before", ionic_before), ("after", ionic_after)] {
+        out.set_item(format!("ionic_strength_{suffix}"), ionic.map(|(value, _)| value))?;
+        let gammas = match ionic {
+            Some((_, gammas)) => Some(activity_coefficients_dict(py, gammas)?),
+            None => None,
+        };
+        out.set_item(format!("activity_coefficients_{suffix}"), gammas)?;
+    }
+    out.set_item("frac_h2co3", fractions[0])?;
+    out.set_item("frac_hco3", fractions[1])?;
+    out.set_item("frac_co3", fractions[2])?;
+    set_dominant_species(&out, fractions)?;
+    out.set_item("residual_norm", residual_norm)?;
+    out.set_item("activity_model", activity_model_name(activity_model))?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(out.unbind())
+}
+
+/// Strip dissolved CO2 from a batch (vacuum degassing or inert sparging) and report
+/// the new ledger and its re-solved pH.
+///
+/// CO2 leaves the excess pool first and then bicarbonate, which reverts to
+/// carbonate (see [`degas_ledger_impl`]). Removal only proceeds while the
+/// solution's equilibrium dissolved CO2 stays above what the degasser can reach,
+/// `residual_pco2_atm` through Henry's law: as the pH climbs the H2CO3* left to
+/// strip vanishes, so the carbonate buffer caps the achievable removal. That cap is
+/// found by bisection on the removed amount and reported as `co2_removable_mol`;
+/// `limited_by_buffer` flags a request that ran into it.
+#[pyfunction]
+#[pyo3(signature = (ledger, co2_removed_mol, solution_volume_l, residual_pco2_atm=4.2e-4, **options))]
+fn degas(
+    py: Python<'_>,
+    ledger: &Bound<'_, PyDict>,
+    co2_removed_mol: f64,
+    solution_volume_l: f64,
+    residual_pco2_atm: f64,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    let kwargs = KernelKwargs::new("degas", options)?;
+    let settings = SolverSettings::take_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        activity_model,
+        clamp_bounds,
+        ..
+    } = settings;
+    if !co2_removed_mol.is_finite() || co2_removed_mol < 0.0 {
+        return Err(PyValueError::new_err("co2_removed_mol must be finite and non-negative"));
+    }
+    if !solution_volume_l.is_finite() || solution_volume_l <= 0.0 {
+        return Err(PyValueError::new_err("solution_volume_l must be positive"));
+    }
+    if !residual_pco2_atm.is_finite() || residual_pco2_atm < 0.0 {
+        return Err(PyValueError::new_err("residual_pco2_atm must be finite and non-negative"));
+    }
+    let input_state = LedgerState {
+        naoh_remaining_mol: dict_float_value(ledger, "naoh_remaining_mol"),
+        na2co3_mol: dict_float_value(ledger, "na2co3_mol"),
+        nahco3_mol: dict_float_value(ledger, "nahco3_mol"),
+        co2_excess_mol: dict_float_value(ledger, "co2_excess_mol"),
+    };
+    let solve = LedgerSolve::new(settings, settings.pka2_value(), Some(solution_volume_l));
+    let solve_at = |state: LedgerState, ph_guess: Option<f64>| {
+        simulate_reaction_state_with_accounting_impl(state, 0.0, &solve, ph_guess, None, None)
+    };
+    let (_, _, ph_before, _, _, _) = solve_at(input_state, None);
+    let residual_h2co3_m = henry_dissolved_co2(
+        1.0,
+        residual_pco2_atm,
+        clamp_bounds.temperature(temperature_c.unwrap_or(25.0)),
+    );
+    // Equilibrium H2CO3* falls monotonically as CO2 is removed, so the removable
+    // amount is the point where it meets the degasser's floor.
+    let strippable = |removed_mol: f64| {
+        let (state, ..) = degas_ledger_impl(input_state, removed_mol);
+        ledger_speciation(state, solution_volume_l, &settings, ph_before)
+            .is_none_or(|(_, _, solution)| solution.3 > residual_h2co3_m)
+    };
+    let source_mol =
+        input_state.co2_excess_mol.max(0.0) + input_state.nahco3_mol.max(0.0) / 2.0;
+    let removable_mol = if strippable(source_mol) {
+        source_mol
+    } else if !strippable(0.0) {
+        0.0
+    } else {
+        let (mut lo, mut hi) = (0.0_f64, source_mol);
+        for _ in 0..60 {
+            let mid = 0.5 * (lo + hi);
+            if strippable(mid) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    };
+    let removed_mol = co2_removed_mol.min(removable_mol);
+    let (degassed, from_excess, from_bicarbonate) = degas_ledger_impl(input_state, removed_mol);
+    let (state, _, ph, residual_norm, _, _) = solve_at(degassed, Some(ph_before));
+    let solved_h2co3_m = ledger_speciation(state, solution_volume_l, &settings, ph)
+        .map(|(_, _, solution)| solution.3);
+    let fractions = ledger_carbon_fractions(state, Some(solution_volume_l), &settings, ph);
+    let limited_by_buffer = co2_removed_mol > removable_mol;
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    if limited_by_buffer {
+        warnings.append(format!(
+            "Only {removable_mol:.6} of the {co2_removed_mol:.6} mol CO2 requested can be \
+             stripped before dissolved CO2 reaches the {residual_pco2_atm} atm floor."
+        ))?;
+    }
+    let ph_before = clamp_bounds.ph(ph_before);
+    let ph = clamp_bounds.ph(ph);
+    let out = PyDict::new(py);
+    out.set_item("state", ledger_state_dict(py, state, ph)?)?;
+    out.set_item("ph_before", ph_before)?;
+    out.set_item("ph", ph)?;
+    out.set_item("ph_shift", ph - ph_before)?;
+    out.set_item("co2_requested_mol", co2_removed_mol)?;
+    out.set_item("co2_removed_mol", removed_mol)?;
+    out.set_item("co2_removed_g", removed_mol * SOL_MW_CO2)?;
+    out.set_item("co2_removed_from_excess_mol", from_excess)?;
+    out.set_item("co2_removed_from_bicarbonate_mol", from_bicarbonate)?;
+    out.set_item("co2_removable_mol", removable_mol)?;
+    out.set_item("limited_by_buffer", limited_by_buffer)?;
+    out.set_item("residual_h2co3_m", residual_h2co3_m)?;
+    out.set_item("h2co3_m", solved_h2co3_m)?;
+    out.set_item("frac_h2co3", fractions[0])?;
+    out.set_item("frac_hco3", fractions[1])?;
+    out.set_item("frac_co3", fractions[2])?;
+    set_dominant_species(&out, fractions)?;
+    out.set_item("residual_norm", residual_norm)?;
+    out.set_item("activity_model", activity_model_name(activity_model))?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(out.unbind())
+}
+
+/// Stateful ledger for live carbonation runs.
+///
+/// Holds the current ledger and solver options so each CO2 increment is staged
+/// without re-marshaling a ledger dict, and warm-starts every speciation solve
+/// from the previous pH.
+#[pyclass]
+struct DosingSession {
+    initial_state: LedgerState,
+    state: LedgerState,
+    solve: LedgerSolve,
+    last_ph: Option<f64>,
+    co2_added_g: f64,
+}
+
+#[pymethods]
+impl DosingSession {
+    #[new]
+    #[pyo3(signature = (ledger, solution_volume_l=None, **options))]
+    fn new(
+        ledger: &Bound<'_, PyDict>,
+        solution_volume_l: Option<f64>,
+        options: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        let kwargs = KernelKwargs::new("DosingSession", options)?;
+        let settings = SolverSettings::take_from(&kwargs)?;
+        let planning_mode = kwargs.take_or("planning_mode", false)?;
+        let planning_transition_width = kwargs.take_planning_transition_width()?;
+        kwargs.finish()?;
+        let initial_state = LedgerState {
+            naoh_remaining_mol: dict_float_value(ledger, "naoh_remaining_mol"),
+            na2co3_mol: dict_float_value(ledger, "na2co3_mol"),
+            nahco3_mol: dict_float_value(ledger, "nahco3_mol"),
+            co2_excess_mol: dict_float_value(ledger, "co2_excess_mol"),
+        };
+        // Resolve the constants once rather than on every increment.
+        let settings = SolverSettings {
+            constants: Some(settings.equilibrium_constants()),
+            ..settings
+        };
+        Ok(Self {
+            initial_state,
+            state: initial_state,
+            solve: LedgerSolve {
+                planning_mode,
+                planning_transition_width,
+                ..LedgerSolve::new(settings, settings.pka2_value(), solution_volume_l)
+            },
+            last_ph: None,
+            co2_added_g: 0.0,
+        })
+    }
+
+    /// Stage `grams` of CO2 onto the current ledger and return the new state.
+    fn add_co2(&mut self, py: Python<'_>, grams: f64) -> PyResult<Py<PyDict>> {
+        if !grams.is_finite() || grams < 0.0 {
+            return Err(PyValueError::new_err("grams must be finite and non-negative"));
+        }
+        let (state, accounting, ph, residual_norm, _, adjustments) =
+            simulate_reaction_state_with_accounting_impl(
+                self.state,
+                grams / SOL_MW_CO2,
+                &self.solve,
+                self.last_ph,
+                None,
+                None,
+            );
+        self.state = state;
+        self.last_ph = Some(ph);
+        self.co2_added_g += grams;
+        let out = PyDict::new(py);
+        let SolverSettings {
+            temperature_c,
+            clamp_bounds,
+            ..
+        } = self.solve.settings;
+        out.set_item("state", ledger_state_dict(py, state, clamp_bounds.ph(ph))?)?;
+        out.set_item("co2_consumed_total_mol", accounting.co2_consumed_total_mol)?;
+        out.set_item("co2_unconsumed_mol", accounting.co2_unconsumed_mol)?;
+        out.set_item(
+            "naoh_conversion_fraction",
+            naoh_conversion_fraction(
+                self.initial_state.naoh_remaining_mol,
+                state.naoh_remaining_mol,
+            ),
+        )?;
+        out.set_item("free_caustic_m", accounting.free_caustic_m)?;
+        out.set_item("co2_added_g", self.co2_added_g)?;
+        out.set_item("residual_norm", residual_norm)?;
+        out.set_item("planning_adjustments", planning_adjustments_list(py, &adjustments)?)?;
+        let warnings = PyList::empty(py);
+        temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+        out.set_item("warnings", warnings)?;
+        Ok(out.unbind())
+    }
+
+    /// Restore the ledger the session was created with.
+    fn reset(&mut self) {
+        self.state = self.initial_state;
+        self.last_ph = None;
+        self.co2_added_g = 0.0;
+    }
+
+    /// Current ledger in the `simulate_reaction_state_with_accounting` state shape;
+    /// `ph` is `None` until the first increment.
+    fn state(&self, py: Python<'_>) -> PyResult<Py<PyDict>> {
+        let state_dict = ledger_state_dict(py, self.state, 0.0)?;
+        let clamp_bounds = self.solve.settings.clamp_bounds;
+        state_dict.set_item("ph", self.last_ph.map(|ph| clamp_bounds.ph(ph)))?;
+        Ok(state_dict.unbind())
+    }
+
+    #[getter]
+    fn co2_added_g(&self) -> f64 {
+        self.co2_added_g
+    }
+}
+
+/// `analyze_bicarbonate_core` settings past its original positional arguments,
+/// read once from `**options`. Only what was validated ahead of the
+/// `naoh_mass_g <= 0` early return is checked here; the dose checks still run after it.
+struct BicarbonateAnalysisOptions<'py> {
+    raw_solver_ph: bool,
+    iteration_callback: Option<Bound<'py, PyAny>>,
+    absorption_efficiency: f64,
+    initial_na2co3_mol: Option<f64>,
+    initial_nahco3_mol: Option<f64>,
+    clamp_bounds: ClampBounds,
+    return_stage_ledgers: bool,
+    activity_model: ActivityModel,
+    excess_co2_fate: ExcessCo2Fate,
+    pka_constants: Option<(f64, f64, f64)>,
+    na2co3_impurity_mass_g: f64,
+    nahco3_impurity_mass_g: f64,
+    report_as_caco3: bool,
+    warm_start: Option<(f64, f64, f64)>,
+    naoh_purity: f64,
+    naoh_assay_basis: Option<String>,
+    gas_volume_basis: GasVolumeBasis,
+    mass_basis: bool,
+}
+
+impl<'py> BicarbonateAnalysisOptions<'py> {
+    fn from_kwargs(kwargs: Option<&Bound<'py, PyDict>>) -> PyResult<Self> {
+        let kwargs = KernelKwargs::new("analyze_bicarbonate_core", kwargs)?;
+        let clamp_bounds = kwargs.take_clamp_bounds()?;
+        let warm_start: Option<(f64, f64, f64)> = kwargs.take("warm_start")?;
+        if let Some((ph, hco3_frac, co3_frac)) = warm_start
+            && !(ph.is_finite() && hco3_frac.is_finite() && co3_frac.is_finite())
+        {
+            return Err(PyValueError::new_err("warm_start must hold three finite numbers"));
+        }
+        let activity_model = kwargs.take_activity_model()?;
+        let excess_co2_fate = parse_excess_co2_fate(
+            &kwargs.take_or("excess_co2_fate", EXCESS_CO2_FATE_DISSOLVED.to_string())?,
+        )?;
+        let gas_volume_basis = parse_gas_volume_basis(
+            &kwargs.take_or("gas_volume_basis", GAS_VOLUME_BASIS_STP.to_string())?,
+            kwargs.take("molar_volume_l")?,
+        )?;
+        let options = Self {
+            raw_solver_ph: kwargs.take_or("raw_solver_ph", false)?,
+            iteration_callback: kwargs.take_any("iteration_callback")?,
+            absorption_efficiency: kwargs.take_or("absorption_efficiency", 1.0)?,
+            initial_na2co3_mol: kwargs.take("initial_na2co3_mol")?,
+            initial_nahco3_mol: kwargs.take("initial_nahco3_mol")?,
+            clamp_bounds,
+            return_stage_ledgers: kwargs.take_or("return_stage_ledgers", false)?,
+            activity_model,
+            excess_co2_fate,
+            pka_constants: kwargs.take("pka_constants")?,
+            na2co3_impurity_mass_g: kwargs.take_or("na2co3_impurity_mass_g", 0.0)?,
+            nahco3_impurity_mass_g: kwargs.take_or("nahco3_impurity_mass_g", 0.0)?,
+            report_as_caco3: kwargs.take_or("report_as_caco3", false)?,
+            warm_start,
+            naoh_purity: kwargs.take_or("naoh_purity", 1.0)?,
+            naoh_assay_basis: kwargs.take("naoh_assay_basis")?,
+            gas_volume_basis,
+            mass_basis: kwargs.take_or("mass_basis", false)?,
+        };
+        kwargs.finish()?;
+        Ok(options)
+    }
+}
+
+/// Stage a caustic batch and plan the CO2 still needed. The positional arguments
+/// are the original contract; every later setting is a keyword collected into
+/// `BicarbonateAnalysisOptions`.
+#[pyfunction]
+#[pyo3(signature = (naoh_mass_g, co2_charged_g, solution_volume_l, measured_ph, slurry_ph, target_ph, temperature_c, use_temp_adjusted_constants, ionic_strength_cap=None, constants=None, **options))]
+fn analyze_bicarbonate_core(
+    py: Python<'_>,
+    naoh_mass_g: f64,
+    co2_charged_g: f64,
+    solution_volume_l: Option<f64>,
+    measured_ph: Option<f64>,
+    slurry_ph: Option<f64>,
+    target_ph: Option<f64>,
+    temperature_c: Option<f64>,
+    use_temp_adjusted_constants: bool,
+    ionic_strength_cap: Option<f64>,
+    constants: Option<(f64, f64, f64)>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Option<Py<PyDict>>> {
+    let BicarbonateAnalysisOptions {
+        raw_solver_ph,
+        iteration_callback,
+        absorption_efficiency,
+        initial_na2co3_mol,
+        initial_nahco3_mol,
+        clamp_bounds,
+        return_stage_ledgers,
+        activity_model,
+        excess_co2_fate,
+        pka_constants,
+        na2co3_impurity_mass_g,
+        nahco3_impurity_mass_g,
+        report_as_caco3,
+        warm_start,
+        naoh_purity,
+        naoh_assay_basis,
+        gas_volume_basis,
+        mass_basis,
+    } = BicarbonateAnalysisOptions::from_kwargs(options)?;
+    let constants = resolve_equilibrium_constants(constants, pka_constants)?;
+    if naoh_mass_g <= 0.0 || co2_charged_g < 0.0 {
+        return Ok(None);
+    }
+    if !(0.0..=1.0).contains(&absorption_efficiency) {
+        return Err(PyValueError::new_err(
+            "absorption_efficiency must be between 0 and 1",
+        ));
+    }
+    if !naoh_purity.is_finite() || naoh_purity <= 0.0 || naoh_purity > 1.0 {
+        return Err(PyValueError::new_err("naoh_purity must be in (0, 1]"));
+    }
+    // `naoh_mass_g` is the caustic as weighed; only its assayed NaOH fraction is
+    // active base. The remainder (water, chloride, ...) is treated as inert.
+    let naoh_active_g = naoh_mass_g * naoh_purity;
+    let naoh_mol = naoh_active_g / SOL_MW_NAOH;
+    let co2_mol = co2_charged_g / SOL_MW_CO2;
+    if naoh_mol <= 0.0 {
+        return Ok(None);
+    }
+    let impurities_valid = [na2co3_impurity_mass_g, nahco3_impurity_mass_g]
+        .iter()
+        .all(|mass| mass.is_finite() && *mass >= 0.0);
+    if !impurities_valid {
+        return Err(PyValueError::new_err(
+            "na2co3_impurity_mass_g and nahco3_impurity_mass_g must be finite and non-negative",
+        ));
+    }
+    // Partly carbonated caustic: the feed's soda ash and bicarbonate sit in the
+    // ledger before any CO2 is staged, so stage 2 can convert feed carbonate too.
+    let feed_na2co3_mol = na2co3_impurity_mass_g / SOL_MW_NA2CO3;
+    let feed_nahco3_mol = nahco3_impurity_mass_g / SOL_MW_NAHCO3;
+    let has_feed_impurity = feed_na2co3_mol > 0.0 || feed_nahco3_mol > 0.0;
+    let seeded_pools = if initial_na2co3_mol.is_some() || initial_nahco3_mol.is_some() {
+        if has_feed_impurity {
+            return Err(PyValueError::new_err(
+                "feed impurities cannot be combined with initial_na2co3_mol/initial_nahco3_mol; \
+                 seeded pools already include the feed's carbonate",
+            ));
+        }
+        let na2co3 = initial_na2co3_mol.unwrap_or(0.0);
+        let nahco3 = initial_nahco3_mol.unwrap_or(0.0);
+        if !(na2co3.is_finite() && nahco3.is_finite()) || na2co3 < 0.0 || nahco3 < 0.0 {
+            return Err(PyValueError::new_err(
+                "initial_na2co3_mol and initial_nahco3_mol must be finite and non-negative",
+            ));
+        }
+        Some((na2co3, nahco3))
+    } else {
+        None
+    };
+    // A known CO3/HCO3 split (mid-batch re-planning) replaces the stoichiometric
+    // derivation of the liquor so far: its sodium was charged as NaOH, so only the
+    // rest of the caustic is still free. The charged CO2 is then staged on top of
+    // those pools exactly as it is onto feed carbonate.
+    let (pool_na2co3_mol, pool_nahco3_mol, free_naoh_mol) = match seeded_pools {
+        Some((na2co3, nahco3)) => {
+            let bound_sodium = 2.0 * na2co3 + nahco3;
+            if bound_sodium > naoh_mol * (1.0 + 1e-12) {
+                return Err(PyValueError::new_err(
+                    "initial_na2co3_mol and initial_nahco3_mol hold more sodium than \
+                     naoh_mass_g supplies",
+                ));
+            }
+            (na2co3, nahco3, (naoh_mol - bound_sodium).max(0.0))
+        }
+        None => (feed_na2co3_mol, feed_nahco3_mol, naoh_mol),
+    };
+    // Only the absorbed share of the charged CO2 takes part in the staged
+    // carbonate/bicarbonate accounting; the nominal charge is still reported.
+    let co2_reacted_mol = co2_mol * absorption_efficiency;
+    let (staged_state, (stage1_co2, co2_after_stage1, stage2_co2, co2_excess)) = staged_ledger(
+        free_naoh_mol,
+        co2_reacted_mol,
+        (pool_na2co3_mol, pool_nahco3_mol),
+    );
+    let naoh_after_stage1 = staged_state.naoh_remaining_mol;
+    let na2co3_from_stage1 = stage1_co2;
+    let na2co3_remaining = staged_state.na2co3_mol;
+    let nahco3_produced = (stage2_co2 * 2.0).max(0.0);
+    let nahco3_pool = staged_state.nahco3_mol;
+    let buffer_carbon = na2co3_remaining + nahco3_pool;
+    let pka2_value =
+        resolve_pka2_value_within(temperature_c, use_temp_adjusted_constants, clamp_bounds);
+    let measurement_value = measured_ph.or(slurry_ph);
+    // Carbonate/bicarbonate split of the staged buffer carbon implied by a pH reading.
+    let implied_split = |ph: f64| {
+        let ratio = hh_carbonate_ratio(ph, pka2_value);
+        let co3 = buffer_carbon * ratio / (1.0 + ratio);
+        (co3, (buffer_carbon - co3).max(0.0))
+    };
+    let (co3_current, hco3_current) = match measurement_value {
+        Some(value) if buffer_carbon > 0.0 => implied_split(value),
+        _ => (na2co3_remaining, nahco3_pool),
+    };
+    let measured_split = measured_ph.filter(|_| buffer_carbon > 0.0).map(implied_split);
+    let slurry_split = slurry_ph.filter(|_| buffer_carbon > 0.0).map(implied_split);
+    // Slurry minus filtrate pH; a large gap points at undissolved solids.
+    let ph_source_discrepancy =
+        slurry_ph.zip(measured_ph).map(|(slurry, filtrate)| slurry - filtrate);
+    let ph_source_discrepant = ph_source_discrepancy
+        .is_some_and(|gap| !gap.is_finite() || gap.abs() > PH_SOURCE_DISCREPANCY_LIMIT);
+    let desired_ph = target_ph.unwrap_or(8.0);
+    let ratio_target = hh_carbonate_ratio(desired_ph, pka2_value);
+    let numerator = co3_current - ratio_target * hco3_current;
+    let denom = 1.0 + 2.0 * ratio_target;
+    let mut co2_for_ratio = 0.0;
+    if denom > 0.0 && numerator > 0.0 {
+        co2_for_ratio = (numerator / denom).min(co3_current.max(0.0));
+    }
+    let co2_for_naoh = naoh_after_stage1 / 2.0;
+    let total_extra_mol = co2_for_ratio.max(0.0) + co2_for_naoh.max(0.0);
+    let total_extra_g = total_extra_mol * SOL_MW_CO2;
+    let total_extra_charged_g = if absorption_efficiency > 0.0 {
+        total_extra_g / absorption_efficiency
+    } else {
+        f64::INFINITY
+    };
+    let eq_constants = constants.unwrap_or_else(|| {
+        basic_carbonate_constants_within(temperature_c, use_temp_adjusted_constants, clamp_bounds)
+    });
+    let settings = SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        constants: Some(eq_constants),
+        ionic_strength_cap,
+        activity_model,
+        clamp_bounds,
+        raw_solver_ph,
+    };
+    let solve = LedgerSolve {
+        excess_co2_fate,
+        ..LedgerSolve::new(settings, pka2_value, solution_volume_l)
+    };
+    let initial_guess = measurement_value.unwrap_or(desired_ph);
+    let callback_error = RefCell::new(None);
+    let observer = python_iteration_observer(iteration_callback.as_ref(), &callback_error)?;
+    let (predicted_state, _, predicted_ph, predicted_residual, _, _) =
+        simulate_reaction_state_with_accounting_impl(
+            LedgerState {
+                naoh_remaining_mol: naoh_after_stage1,
+                na2co3_mol: na2co3_remaining,
+                nahco3_mol: nahco3_pool,
+                co2_excess_mol: co2_excess,
+            },
+            total_extra_mol,
+            &solve,
+            Some(initial_guess),
+            observer.as_deref(),
+            warm_start,
+        );
+    // Cross-check: the pH the HH plan implies for its own post-dose pools (the
+    // NaOH top-up adds carbonate) against the full solve. Undefined once the plan
+    // leaves no carbonate/bicarbonate pair to take a ratio of.
+    let heuristic_co3 = co3_current - co2_for_ratio.max(0.0) + co2_for_naoh.max(0.0);
+    let heuristic_hco3 = hco3_current + 2.0 * co2_for_ratio.max(0.0);
+    let heuristic_ph = (heuristic_co3 > 1e-12 && heuristic_hco3 > 1e-12)
+        .then(|| clamp_bounds.ph(hh_ph_from_ratio(heuristic_co3 / heuristic_hco3, pka2_value)));
+    let heuristic_ph_gap = heuristic_ph.map(|ph| predicted_ph - ph);
+    let heuristic_unreliable =
+        heuristic_ph_gap.is_some_and(|gap| !gap.is_finite() || gap.abs() > HEURISTIC_PH_GAP_LIMIT);
+    let predicted_fractions = ledger_carbon_fractions(
+        excess_co2_fate.solve_basis(predicted_state),
+        solution_volume_l,
+        &settings,
+        predicted_ph,
+    );
+    let slider_max_g = (total_extra_g * 1.6).max(2.0);
+    let rows = pyo3::types::PyList::empty(py);
+    let mut step_guess = initial_guess;
+    let mut max_residual_achieved = predicted_residual;
+    // Curve point closest to the CO2 actually charged, as (|delta_g|, pH).
+    let mut charged_point: Option<(f64, f64)> = None;
+    for idx in 0..=12 {
+        let delta_g = slider_max_g * (idx as f64 / 12.0);
+        let delta_mol = delta_g / SOL_MW_CO2 * absorption_efficiency;
+        let (state, _, ph, residual_norm, ionic_strength, _) =
+            simulate_reaction_state_with_accounting_impl(
+                LedgerState {
+                    naoh_remaining_mol: naoh_after_stage1,
+                    na2co3_mol: na2co3_remaining,
+                    nahco3_mol: nahco3_pool,
+                    co2_excess_mol: co2_excess,
+                },
+                delta_mol,
+                &solve,
+                Some(step_guess),
+                observer.as_deref(),
+                None,
+            );
+        step_guess = ph;
+        max_residual_achieved = max_optional_residual(max_residual_achieved, residual_norm);
+        if charged_point.is_none_or(|(distance, _)| delta_g.abs() < distance) {
+            charged_point = Some((delta_g.abs(), ph));
+        }
+        let row = PyDict::new(py);
+        row.set_item("delta_g", delta_g)?;
+        row.set_item("total_co2_g", co2_charged_g + delta_g)?;
+        row.set_item("ph", ph)?;
+        row.set_item("na2co3_mol", state.na2co3_mol)?;
+        row.set_item("nahco3_mol", state.nahco3_mol)?;
+        row.set_item("residual_norm", residual_norm)?;
+        row.set_item("ionic_strength", ionic_strength)?;
+        row.set_item("ph_clamped", clamp_bounds.ph_at_bound(ph))?;
+        rows.append(row)?;
+    }
+    if let Some(err) = callback_error.take() {
+        return Err(err);
+    }
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let co2_supersaturated =
+        co2_supersaturation_warning(
+            predicted_state,
+            solution_volume_l,
+            temperature_c,
+            clamp_bounds,
+            &warnings,
+        )?;
+    if heuristic_unreliable {
+        warnings.append(format!(
+            "Henderson-Hasselbalch dosing plan misses the full solve by more than \
+             {HEURISTIC_PH_GAP_LIMIT} pH; rely on predicted_ph and the simulation curve."
+        ))?;
+    }
+    if let Some(gap) = ph_source_discrepancy.filter(|_| ph_source_discrepant) {
+        warnings.append(format!(
+            "Slurry pH differs from measured_ph by {gap:.2} (limit \
+             {PH_SOURCE_DISCREPANCY_LIMIT}); undissolved solids or a faulty reading are \
+             likely. The carbonate split uses measured_ph."
+        ))?;
+    }
+    let out = PyDict::new(py);
+    out.set_item("naoh_mol", naoh_mol)?;
+    out.set_item("naoh_purity", naoh_purity)?;
+    out.set_item("naoh_active_g", naoh_active_g)?;
+    out.set_item("naoh_assay_basis", naoh_assay_basis)?;
+    out.set_item("feed_na2co3_mol", feed_na2co3_mol)?;
+    out.set_item("feed_nahco3_mol", feed_nahco3_mol)?;
+    out.set_item("feed_carbonate_carbon_mol", feed_na2co3_mol + feed_nahco3_mol)?;
+    out.set_item(
+        "feed_carbonate_mass_fraction",
+        (na2co3_impurity_mass_g + nahco3_impurity_mass_g)
+            / (naoh_mass_g + na2co3_impurity_mass_g + nahco3_impurity_mass_g),
+    )?;
+    out.set_item("co2_mol", co2_mol)?;
+    out.set_item("absorption_efficiency", absorption_efficiency)?;
+    out.set_item("co2_reacted_mol", co2_reacted_mol)?;
+    out.set_item("ledger_seeded", seeded_pools.is_some())?;
+    out.set_item("stage1_co2", stage1_co2)?;
+    out.set_item("naoh_after_stage1", naoh_after_stage1)?;
+    out.set_item("na2co3_from_stage1", na2co3_from_stage1)?;
+    out.set_item("co2_after_stage1", co2_after_stage1)?;
+    out.set_item("stage2_co2", stage2_co2)?;
+    out.set_item("na2co3_remaining", na2co3_remaining)?;
+    out.set_item("nahco3_produced", nahco3_produced)?;
+    out.set_item("co2_excess", co2_excess)?;
+    out.set_item("buffer_carbon", buffer_carbon)?;
+    out.set_item("pka2_value", pka2_value)?;
+    out.set_item("co3_current", co3_current)?;
+    out.set_item("hco3_current", hco3_current)?;
+    out.set_item(
+        "ph_source",
+        if measured_ph.is_some() {
+            Some("measured")
+        } else {
+            slurry_ph.map(|_| "slurry")
+        },
+    )?;
+    out.set_item("measured_co3", measured_split.map(|split| split.0))?;
+    out.set_item("measured_hco3", measured_split.map(|split| split.1))?;
+    out.set_item("slurry_co3", slurry_split.map(|split| split.0))?;
+    out.set_item("slurry_hco3", slurry_split.map(|split| split.1))?;
+    out.set_item("ph_source_discrepancy", ph_source_discrepancy)?;
+    out.set_item("ph_source_discrepant", ph_source_discrepant)?;
+    out.set_item("desired_ph", desired_ph)?;
+    out.set_item("ratio_target", ratio_target)?;
+    out.set_item("co2_for_ratio", co2_for_ratio)?;
+    out.set_item("co2_for_naoh", co2_for_naoh)?;
+    out.set_item("total_extra_mol", total_extra_mol)?;
+    out.set_item("total_extra_g", total_extra_g)?;
+    out.set_item("total_extra_charged_g", total_extra_charged_g)?;
+    out.set_item("total_extra_kg", total_extra_g / 1000.0)?;
+    out.set_item("total_extra_l", total_extra_mol * gas_volume_basis.molar_volume_l())?;
+    out.set_item("gas_volume_basis", gas_volume_basis.name())?;
+    out.set_item("gas_molar_volume_l", gas_volume_basis.molar_volume_l())?;
+    out.set_item("total_extra_scf", total_extra_mol * CO2_SCF_PER_MOL)?;
+    // Dry-reagent cross-check on a carbon-equivalent basis: one mole of soda ash or
+    // sodium bicarbonate carries the same carbon as one mole of dosed CO2.
+    out.set_item("soda_ash_equivalent_g", total_extra_mol * SOL_MW_NA2CO3)?;
+    out.set_item("sodium_bicarbonate_equivalent_g", total_extra_mol * SOL_MW_NAHCO3)?;
+    out.set_item("predicted_ph", predicted_ph)?;
+    out.set_item("predicted_ph_clamped", clamp_bounds.ph_at_bound(predicted_ph))?;
+    out.set_item("heuristic_ph", heuristic_ph)?;
+    out.set_item("heuristic_vs_solver_ph_gap", heuristic_ph_gap)?;
+    out.set_item("heuristic_ph_unreliable", heuristic_unreliable)?;
+    out.set_item("activity_model", activity_model_name(activity_model))?;
+    out.set_item("excess_co2_fate", excess_co2_fate.name())?;
+    out.set_item("co2_saturation_m", co2_saturation_m(temperature_c, clamp_bounds))?;
+    out.set_item("co2_supersaturated", co2_supersaturated)?;
+    out.set_item("max_residual_achieved", max_residual_achieved)?;
+    out.set_item("ph_error", measured_ph.map(|value| predicted_ph - value))?;
+    out.set_item(
+        "curve_ph_error",
+        measured_ph.zip(charged_point).map(|(value, (_, ph))| ph - value),
+    )?;
+    out.set_item("frac_h2co3", predicted_fractions[0])?;
+    out.set_item("frac_hco3", predicted_fractions[1])?;
+    out.set_item("frac_co3", predicted_fractions[2])?;
+    set_dominant_species(&out, predicted_fractions)?;
+    // Seed for the next call's predicted-point solve: the prediction's
+    // `(pH, hco3_frac, co3_frac)`, not its log-space Newton vector. Passed back as
+    // `warm_start` while the inputs only drift (e.g. a slider drag), it becomes the
+    // first Newton seed, ahead of the built-in seed table.
+    out.set_item(
+        "warm_start",
+        (predicted_ph, predicted_fractions[1], predicted_fractions[2]),
+    )?;
+    out.set_item("slider_max_g", slider_max_g)?;
+    out.set_item("eq_ka1", eq_constants.0)?;
+    out.set_item("eq_ka2", eq_constants.1)?;
+    out.set_item("eq_kw", eq_constants.2)?;
+    let pkw = -eq_constants.2.max(1e-30).log10();
+    out.set_item("pkw", pkw)?;
+    out.set_item("neutral_ph", pkw / 2.0)?;
+    out.set_item("simulation_curve", rows)?;
+    out.set_item(
+        "predicted_ledger_naoh_remaining",
+        predicted_state.naoh_remaining_mol,
+    )?;
+    out.set_item("predicted_ledger_na2co3", predicted_state.na2co3_mol)?;
+    out.set_item("predicted_ledger_nahco3", predicted_state.nahco3_mol)?;
+    out.set_item(
+        "predicted_ledger_co2_excess",
+        predicted_state.co2_excess_mol,
+    )?;
+    // Conservation audit of the staging: every sodium charged as NaOH must sit in
+    // the stage-2 ledger, and every mole of absorbed CO2 plus the seeded or feed
+    // carbon in its carbon pools; the prediction must then carry the same sodium
+    // and exactly the extra carbon dosed.
+    let staged_carbon_in = co2_reacted_mol + pool_na2co3_mol + pool_nahco3_mol;
+    let sodium_in = naoh_mol + 2.0 * feed_na2co3_mol + feed_nahco3_mol;
+    let audit_checks = [
+        (
+            "sodium",
+            stoichiometry_balance_dict(py, sodium_in, ledger_sodium_mol(staged_state))?,
+        ),
+        (
+            "carbon",
+            stoichiometry_balance_dict(py, staged_carbon_in, ledger_carbon_mol(staged_state))?,
+        ),
+        (
+            "predicted_sodium",
+            stoichiometry_balance_dict(
+                py,
+                ledger_sodium_mol(staged_state),
+                ledger_sodium_mol(predicted_state),
+            )?,
+        ),
+        (
+            "predicted_carbon",
+            stoichiometry_balance_dict(
+                py,
+                ledger_carbon_mol(staged_state) + total_extra_mol,
+                ledger_carbon_mol(predicted_state),
+            )?,
+        ),
+    ];
+    let audit = PyDict::new(py);
+    let mut audit_balanced = true;
+    for (label, (check, balanced)) in audit_checks {
+        audit_balanced &= balanced;
+        audit.set_item(label, check)?;
+    }
+    audit.set_item("balanced", audit_balanced)?;
+    out.set_item("audit", audit)?;
+    if return_stage_ledgers {
+        // Snapshots at the end of each stage: after stage 1 only the CO2 that
+        // exhausted the NaOH has been absorbed, so no excess is carried yet.
+        let stage_states = [
+            (
+                "after_stage1",
+                LedgerState {
+                    naoh_remaining_mol: naoh_after_stage1,
+                    na2co3_mol: na2co3_from_stage1 + pool_na2co3_mol,
+                    nahco3_mol: pool_nahco3_mol,
+                    co2_excess_mol: 0.0,
+                },
+            ),
+            (
+                "after_stage2",
+                LedgerState {
+                    naoh_remaining_mol: naoh_after_stage1,
+                    na2co3_mol: na2co3_remaining,
+                    nahco3_mol: nahco3_pool,
+                    co2_excess_mol: co2_excess,
+                },
+            ),
+        ];
+        let stage_ledgers = PyDict::new(py);
+        for (label, stage_state) in stage_states {
+            let (_, _, stage_ph, _, _, _) = simulate_reaction_state_with_accounting_impl(
+                stage_state,
+                0.0,
+                &solve,
+                Some(initial_guess),
+                None,
+                None,
+            );
+            stage_ledgers.set_item(label, ledger_state_dict(py, stage_state, stage_ph)?)?;
+        }
+        out.set_item("stage_ledgers", stage_ledgers)?;
+    }
+    if report_as_caco3 {
+        out.set_item(
+            "as_caco3",
+            caco3_alkalinity_dict(
+                py,
+                excess_co2_fate.solve_basis(predicted_state),
+                solution_volume_l,
+                predicted_fractions,
+            )?,
+        )?;
+    }
+    if mass_basis {
+        // Gram-basis copy of the staging table; carbonate and bicarbonate pools are
+        // weighed as their sodium salts, free and dosed carbon as CO2.
+        let stoichiometry = [
+            ("naoh_g", naoh_mol, SOL_MW_NAOH),
+            ("naoh_after_stage1_g", naoh_after_stage1, SOL_MW_NAOH),
+            ("feed_na2co3_g", feed_na2co3_mol, SOL_MW_NA2CO3),
+            ("feed_nahco3_g", feed_nahco3_mol, SOL_MW_NAHCO3),
+            ("co2_reacted_g", co2_reacted_mol, SOL_MW_CO2),
+            ("stage1_co2_g", stage1_co2, SOL_MW_CO2),
+            ("co2_after_stage1_g", co2_after_stage1, SOL_MW_CO2),
+            ("stage2_co2_g", stage2_co2, SOL_MW_CO2),
+            ("co2_excess_g", co2_excess, SOL_MW_CO2),
+            ("na2co3_from_stage1_g", na2co3_from_stage1, SOL_MW_NA2CO3),
+            ("na2co3_remaining_g", na2co3_remaining, SOL_MW_NA2CO3),
+            ("nahco3_produced_g", nahco3_produced, SOL_MW_NAHCO3),
+            ("co3_current_g", co3_current, SOL_MW_NA2CO3),
+            ("hco3_current_g", hco3_current, SOL_MW_NAHCO3),
+            ("co2_for_ratio_g", co2_for_ratio, SOL_MW_CO2),
+            ("co2_for_naoh_g", co2_for_naoh, SOL_MW_CO2),
+            ("total_extra_g", total_extra_mol, SOL_MW_CO2),
+            (
+                "predicted_ledger_naoh_remaining_g",
+                predicted_state.naoh_remaining_mol,
+                SOL_MW_NAOH,
+            ),
+            ("predicted_ledger_na2co3_g", predicted_state.na2co3_mol, SOL_MW_NA2CO3),
+            ("predicted_ledger_nahco3_g", predicted_state.nahco3_mol, SOL_MW_NAHCO3),
+            ("predicted_ledger_co2_excess_g", predicted_state.co2_excess_mol, SOL_MW_CO2),
+        ];
+        let mass_table = PyDict::new(py);
+        for (key, mol, molar_mass) in stoichiometry {
+            mass_table.set_item(key, mol * molar_mass)?;
+        }
+        out.set_item("stoichiometry_g", mass_table)?;
+    }
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(Some(out.unbind()))
+}
+
+/// Predicted liquor in mg/L as CaCO3: total alkalinity from the sodium charge
+/// balance, carbonate (2 eq/mol) and bicarbonate (1 eq/mol) alkalinity from the
+/// speciated DIC split. `None` without a positive solution volume.
+fn caco3_alkalinity_dict<'py>(
+    py: Python<'py>,
+    state: LedgerState,
+    solution_volume_l: Option<f64>,
+    fractions: [f64; 3],
+) -> PyResult<Option<Bound<'py, PyDict>>> {
+    let Some(volume) = solution_volume_l.filter(|volume| *volume > 0.0) else {
+        return Ok(None);
+    };
+    let mg_per_eq = CACO3_G_PER_EQ * 1000.0;
+    let carbon_m = ledger_carbon_mol(state) / volume;
+    let out = PyDict::new(py);
+    out.set_item("total_alkalinity_mg_l", ledger_sodium_mol(state) / volume * mg_per_eq)?;
+    out.set_item("carbonate_mg_l", 2.0 * carbon_m * fractions[2] * mg_per_eq)?;
+    out.set_item("bicarbonate_mg_l", carbon_m * fractions[1] * mg_per_eq)?;
+    Ok(Some(out))
+}
+
+/// Two-stage absorption of `co2_mol` into `naoh_mol` of caustic: stage 1 turns
+/// NaOH into Na2CO3, stage 2 turns that carbonate (plus any `feed_na2co3_mol`)
+/// into NaHCO3. Returns `(stage1_co2, co2_after_stage1, stage2_co2, co2_excess)`.
+fn stage_co2_charge(naoh_mol: f64, co2_mol: f64, feed_na2co3_mol: f64) -> (f64, f64, f64, f64) {
+    let stage1 = co2_mol.min(naoh_mol / 2.0);
+    let after_stage1 = (co2_mol - stage1).max(0.0);
+    let stage2 = after_stage1.min(stage1 + feed_na2co3_mol);
+    (stage1, after_stage1, stage2, (after_stage1 - stage2).max(0.0))
+}
+
+/// Stage-2 ledger of [`stage_co2_charge`] applied to `naoh_mol` of free caustic on
+/// top of existing `(na2co3, nahco3)` pools, returned with the stage split.
+fn staged_ledger(
+    naoh_mol: f64,
+    co2_mol: f64,
+    pools: (f64, f64),
+) -> (LedgerState, (f64, f64, f64, f64)) {
+    let (na2co3, nahco3) = pools;
+    let stages = stage_co2_charge(naoh_mol, co2_mol, na2co3);
+    let (stage1, _, stage2, excess) = stages;
+    let state = LedgerState {
+        naoh_remaining_mol: (naoh_mol - stage1 * 2.0).max(0.0),
+        na2co3_mol: (stage1 + na2co3 - stage2).max(0.0),
+        nahco3_mol: (stage2 * 2.0).max(0.0) + nahco3,
+        co2_excess_mol: excess,
+    };
+    (state, stages)
+}
+
+/// Ledger left by charging `co2_mol` into `naoh_mol` of fresh caustic.
+fn charged_ledger(naoh_mol: f64, co2_mol: f64) -> LedgerState {
+    let (stage1, _, stage2, excess) = stage_co2_charge(naoh_mol, co2_mol, 0.0);
+    LedgerState {
+        naoh_remaining_mol: (naoh_mol - stage1 * 2.0).max(0.0),
+        na2co3_mol: (stage1 - stage2).max(0.0),
+        nahco3_mol: stage2 * 2.0,
+        co2_excess_mol: excess,
+    }
+}
+
+/// Sodium held by a ledger, in mol (NaOH + 2·Na2CO3 + NaHCO3).
+fn ledger_sodium_mol(state: LedgerState) -> f64 {
+    state.naoh_remaining_mol + 2.0 * state.na2co3_mol + state.nahco3_mol
+}
+
+/// Carbon held by a ledger, in mol (Na2CO3 + NaHCO3 + excess CO2).
+fn ledger_carbon_mol(state: LedgerState) -> f64 {
+    state.na2co3_mol + state.nahco3_mol + state.co2_excess_mol
+}
+
+/// One conservation check: expected and found totals, their difference, and
+/// whether it stays inside `STOICHIOMETRY_AUDIT_REL_TOL`.
+fn stoichiometry_balance_dict<'py>(
+    py: Python<'py>,
+    expected_mol: f64,
+    found_mol: f64,
+) -> PyResult<(Bound<'py, PyDict>, bool)> {
+    let imbalance = found_mol - expected_mol;
+    let scale = expected_mol.abs().max(found_mol.abs()).max(1e-30);
+    let balanced = imbalance.is_finite() && imbalance.abs() <= STOICHIOMETRY_AUDIT_REL_TOL * scale;
+    let out = PyDict::new(py);
+    out.set_item("expected_mol", expected_mol)?;
+    out.set_item("found_mol", found_mol)?;
+    out.set_item("imbalance_mol", imbalance)?;
+    out.set_item("balanced", balanced)?;
+    Ok((out, balanced))
+}
+
+/// pH map over every (NaOH, CO2) charge pair.
+///
+/// Each cell is the equilibrium pH of the ledger left by absorbing
+/// `co2_masses_g[j] · absorption_efficiency` into `naoh_masses_g[i]` of caustic
+/// (the stage-2 state of `analyze_bicarbonate_core`). `ph` and `residual_norm` are
+/// flat, row-major over NaOH × CO2 (index `i * len(co2_masses_g) + j`), with
+/// `shape = (len(naoh_masses_g), len(co2_masses_g))`; cells with a non-positive
+/// NaOH or negative CO2 mass are `None`. The sweep runs with the GIL released.
+///
+/// With `as_arrays`, `ph` and `residual_norm` are instead C-contiguous float64
+/// numpy arrays of that shape, handed over without copying, with NaN for the
+/// `None` cells.
+#[pyfunction]
+#[pyo3(signature = (naoh_masses_g, co2_masses_g, volume_l, **options))]
+fn evaluate_grid(
+    py: Python<'_>,
+    naoh_masses_g: Vec<f64>,
+    co2_masses_g: Vec<f64>,
+    volume_l: f64,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    let kwargs = KernelKwargs::new("evaluate_grid", options)?;
+    let settings = SolverSettings::take_from(&kwargs)?;
+    let absorption_efficiency: f64 = kwargs.take_or("absorption_efficiency", 1.0)?;
+    let as_arrays = kwargs.take_or("as_arrays", false)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        clamp_bounds,
+        ..
+    } = settings;
+    if !volume_l.is_finite() || volume_l <= 0.0 {
+        return Err(PyValueError::new_err("volume_l must be positive"));
+    }
+    if !(0.0..=1.0).contains(&absorption_efficiency) {
+        return Err(PyValueError::new_err(
+            "absorption_efficiency must be between 0 and 1",
+        ));
+    }
+    let settings = SolverSettings {
+        constants: Some(settings.equilibrium_constants()),
+        ..settings
+    };
+    let solve = LedgerSolve::new(settings, settings.pka2_value(), Some(volume_l));
+    let cells = detach_solver(py, || {
+        let mut cells = Vec::with_capacity(naoh_masses_g.len() * co2_masses_g.len());
+        for &naoh_g in &naoh_masses_g {
+            // Warm-start along each CO2 row; the charge changes smoothly within it.
+            let mut step_guess: Option<f64> = None;
+            for &co2_g in &co2_masses_g {
+                if !(naoh_g > 0.0 && co2_g >= 0.0 && naoh_g.is_finite() && co2_g.is_finite()) {
+                    cells.push(None);
+                    continue;
+                }
+                let ledger = charged_ledger(
+                    naoh_g / SOL_MW_NAOH,
+                    co2_g / SOL_MW_CO2 * absorption_efficiency,
+                );
+                let (_, _, ph, residual_norm, _, _) = simulate_reaction_state_with_accounting_impl(
+                    ledger,
+                    0.0,
+                    &solve,
+                    step_guess,
+                    None,
+                    None,
+                );
+                step_guess = Some(ph);
+                cells.push(Some((clamp_bounds.ph(ph), residual_norm)));
+            }
+        }
+        cells
+    });
+    let mut max_residual: Option<f64> = None;
+    for (_, residual_norm) in cells.iter().flatten() {
+        max_residual = max_optional_residual(max_residual, *residual_norm);
+    }
+    let shape = (naoh_masses_g.len(), co2_masses_g.len());
+    let out = PyDict::new(py);
+    if as_arrays {
+        let ph_values: Vec<f64> =
+            cells.iter().map(|cell| cell.map_or(f64::NAN, |(ph, _)| ph)).collect();
+        let residuals: Vec<f64> = cells
+            .iter()
+            .map(|cell| cell.and_then(|(_, residual_norm)| residual_norm).unwrap_or(f64::NAN))
+            .collect();
+        let as_matrix = |values: Vec<f64>| {
+            Array2::from_shape_vec(shape, values)
+                .map_err(|err| PyRuntimeError::new_err(err.to_string()))
+        };
+        out.set_item("ph", as_matrix(ph_values)?.into_pyarray(py))?;
+        out.set_item("residual_norm", as_matrix(residuals)?.into_pyarray(py))?;
+    } else {
+        let ph_values = PyList::empty(py);
+        let residuals = PyList::empty(py);
+        for cell in cells {
+            ph_values.append(cell.map(|(ph, _)| ph))?;
+            residuals.append(cell.and_then(|(_, residual_norm)| residual_norm))?;
+        }
+        out.set_item("ph", ph_values)?;
+        out.set_item("residual_norm", residuals)?;
+    }
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    out.set_item("shape", shape)?;
+    out.set_item("naoh_masses_g", naoh_masses_g)?;
+    out.set_item("co2_masses_g", co2_masses_g)?;
+    out.set_item("max_residual_achieved", max_residual)?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(out.unbind())
+}
+
+const ANALYZE_JSON_KEYS: [&str; 30] = [
+    "naoh_mass_g",
+    "co2_charged_g",
+    "solution_volume_l",
+    "measured_ph",
+    "slurry_ph",
+    "target_ph",
+    "temperature_c",
+    "use_temp_adjusted_constants",
+    "ionic_strength_cap",
+    "constants",
+    "raw_solver_ph",
+    "absorption_efficiency",
+    "initial_na2co3_mol",
+    "initial_nahco3_mol",
+    "clamp_bounds",
+    "return_stage_ledgers",
+    "activity_model",
+    "sit_coefficients",
+    "pitzer_params",
+    "excess_co2_fate",
+    "pka_constants",
+    "na2co3_impurity_mass_g",
+    "nahco3_impurity_mass_g",
+    "report_as_caco3",
+    "warm_start",
+    "naoh_purity",
+    "naoh_assay_basis",
+    "gas_volume_basis",
+    "molar_volume_l",
+    "mass_basis",
+];
+
+/// Present, non-null member of a decoded JSON object.
+fn json_member<'py>(payload: &Bound<'py, PyDict>, key: &str) -> Option<Bound<'py, PyAny>> {
+    payload
+        .get_item(key)
+        .ok()
+        .flatten()
+        .filter(|value| !value.is_none())
+}
+
+fn json_optional_float(payload: &Bound<'_, PyDict>, key: &str) -> PyResult<Option<f64>> {
+    let Some(value) = json_member(payload, key) else {
+        return Ok(None);
+    };
+    if value.is_instance_of::<PyBool>() {
+        return Err(PyValueError::new_err(format!("{key} must be a number")));
+    }
+    value
+        .extract::<f64>()
+        .map(Some)
+        .map_err(|_| PyValueError::new_err(format!("{key} must be a number")))
+}
+
+fn json_bool(payload: &Bound<'_, PyDict>, key: &str, default: bool) -> PyResult<bool> {
+    let Some(value) = json_member(payload, key) else {
+        return Ok(default);
+    };
+    if !value.is_instance_of::<PyBool>() {
+        return Err(PyValueError::new_err(format!("{key} must be a boolean")));
+    }
+    value.extract::<bool>()
+}
+
+fn json_object<'py>(
+    payload: &Bound<'py, PyDict>,
+    key: &str,
+) -> PyResult<Option<Bound<'py, PyDict>>> {
+    let Some(value) = json_member(payload, key) else {
+        return Ok(None);
+    };
+    value
+        .cast_into::<PyDict>()
+        .map(Some)
+        .map_err(|_| PyValueError::new_err(format!("{key} must be an object")))
+}
+
+fn json_string(payload: &Bound<'_, PyDict>, key: &str) -> PyResult<Option<String>> {
+    json_member(payload, key)
+        .map(|value| {
+            value
+                .extract::<String>()
+                .map_err(|_| PyValueError::new_err(format!("{key} must be a string")))
+        })
+        .transpose()
+}
+
+fn json_triple(
+    payload: &Bound<'_, PyDict>,
+    key: &str,
+    shape: &str,
+) -> PyResult<Option<(f64, f64, f64)>> {
+    let Some(value) = json_member(payload, key) else {
+        return Ok(None);
+    };
+    match value.extract::<Vec<f64>>().ok().as_deref() {
+        Some(&[a, b, c]) => Ok(Some((a, b, c))),
+        _ => Err(PyValueError::new_err(format!(
+            "{key} must be an array of three numbers {shape}"
+        ))),
+    }
+}
+
+fn analyze_bicarbonate_json_impl(py: Python<'_>, input_json: &str) -> PyResult<String> {
+    let json = py.import("json")?;
+    let payload = json
+        .call_method1("loads", (input_json,))
+        .map_err(|err| PyValueError::new_err(format!("invalid JSON: {}", err.value(py))))?
+        .cast_into::<PyDict>()
+        .map_err(|_| PyValueError::new_err("input must be a JSON object"))?;
+    for key in payload.keys() {
+        let key = key.extract::<String>()?;
+        if !ANALYZE_JSON_KEYS.contains(&key.as_str()) {
+            return Err(PyValueError::new_err(format!("unknown key: {key}")));
+        }
+    }
+    let naoh_mass_g = json_optional_float(&payload, "naoh_mass_g")?
+        .ok_or_else(|| PyValueError::new_err("naoh_mass_g is required"))?;
+    let co2_charged_g = json_optional_float(&payload, "co2_charged_g")?
+        .ok_or_else(|| PyValueError::new_err("co2_charged_g is required"))?;
+    let constants = json_triple(&payload, "constants", "[ka1, ka2, kw]")?;
+    // Only the JSON shapes are checked here; the options then go through the same
+    // keyword parsing as a Python call, with JSON null read as the default.
+    let options = PyDict::new(py);
+    for key in ["raw_solver_ph", "return_stage_ledgers", "report_as_caco3", "mass_basis"] {
+        options.set_item(key, json_bool(&payload, key, false)?)?;
+    }
+    for key in [
+        "absorption_efficiency",
+        "initial_na2co3_mol",
+        "initial_nahco3_mol",
+        "na2co3_impurity_mass_g",
+        "nahco3_impurity_mass_g",
+        "naoh_purity",
+        "molar_volume_l",
+    ] {
+        options.set_item(key, json_optional_float(&payload, key)?)?;
+    }
+    for key in ["clamp_bounds", "sit_coefficients", "pitzer_params"] {
+        options.set_item(key, json_object(&payload, key)?)?;
+    }
+    for key in ["activity_model", "excess_co2_fate", "naoh_assay_basis", "gas_volume_basis"] {
+        options.set_item(key, json_string(&payload, key)?)?;
+    }
+    options.set_item(
+        "pka_constants",
+        json_triple(&payload, "pka_constants", "[pka1, pka2, pkw]")?,
+    )?;
+    options.set_item(
+        "warm_start",
+        json_triple(&payload, "warm_start", "[ph, hco3_frac, co3_frac]")?,
+    )?;
+    let result = analyze_bicarbonate_core(
+        py,
+        naoh_mass_g,
+        co2_charged_g,
+        json_optional_float(&payload, "solution_volume_l")?,
+        json_optional_float(&payload, "measured_ph")?,
+        json_optional_float(&payload, "slurry_ph")?,
+        json_optional_float(&payload, "target_ph")?,
+        json_optional_float(&payload, "temperature_c")?,
+        json_bool(&payload, "use_temp_adjusted_constants", false)?,
+        json_optional_float(&payload, "ionic_strength_cap")?,
+        constants,
+        Some(&options),
+    )?;
+    json.call_method1("dumps", (result,))?.extract::<String>()
+}
+
+/// JSON-string front end to `analyze_bicarbonate_core` for callers without dicts.
+///
+/// The input object uses the keyword names of `analyze_bicarbonate_core`
+/// (`constants`/`pka_constants` as three-number arrays, `warm_start` as the
+/// `[ph, hco3_frac, co3_frac]` a previous result returned; no iteration callback).
+/// The result is the same payload serialized as JSON, or `null` when the core
+/// returns `None`. Validation and solver errors come back as
+/// `{"error": {"type", "message"}}` instead of raising.
+#[pyfunction]
+fn analyze_bicarbonate_json(py: Python<'_>, input_json: &str) -> PyResult<String> {
+    match analyze_bicarbonate_json_impl(py, input_json) {
+        Ok(serialized) => Ok(serialized),
+        Err(err) => {
+            let detail = PyDict::new(py);
+            detail.set_item("type", err.get_type(py).name()?)?;
+            detail.set_item("message", err.value(py).str()?)?;
+            let envelope = PyDict::new(py);
+            envelope.set_item("error", detail)?;
+            py.import("json")?.call_method1("dumps", (envelope,))?.extract::<String>()
+        }
+    }
+}
+
+/// `carbonate_state_core` settings past its original positional arguments, read
+/// and validated once from `**options`. Keyword names and defaults are unchanged
+/// from when each was a parameter of its own.
+struct CarbonateStateOptions<'py> {
+    iteration_callback: Option<Bound<'py, PyAny>>,
+    activity_model: ActivityModel,
+    extra_seeds: Vec<(f64, f64, f64)>,
+    co2_gas_fraction: Option<f64>,
+    total_pressure_atm: f64,
+    temperature_c: Option<f64>,
+    liquid_junction_offset: Option<f64>,
+    ionic_strength_control: IonicStrengthControl,
+    return_jacobian_condition: bool,
+    ammonia_total_m: Option<f64>,
+    pka_nh4: Option<f64>,
+    formulation: &'static str,
+    retry_attempts: usize,
+    return_log_species: bool,
+    phosphate_total_m: Option<f64>,
+    pka_phosphate: Option<(f64, f64, f64)>,
+    trust_region: Option<NewtonTrustRegion>,
+    clamp_bounds: ClampBounds,
+}
+
+impl<'py> CarbonateStateOptions<'py> {
+    fn from_kwargs(kwargs: Option<&Bound<'py, PyDict>>) -> PyResult<Self> {
+        let kwargs = KernelKwargs::new("carbonate_state_core", kwargs)?;
+        let clamp_bounds = kwargs.take_clamp_bounds()?;
+        let activity_model = kwargs.take_activity_model()?;
+        // `ideal` is the teaching/validation switch: unit activity coefficients
+        // whatever `activity_model` says.
+        let activity_model = if kwargs.take_or("ideal", false)? {
+            ActivityModel::Ideal
+        } else {
+            activity_model
+        };
+        let ionic_strength_control = ionic_strength_control_from_args(
+            kwargs.take("fixed_ionic_strength")?,
+            kwargs.take_or("ionic_strength_max_iterations", SOL_IONIC_STRENGTH_MAX_ITER)?,
+            kwargs.take_or("ionic_strength_tolerance", SOL_IONIC_STRENGTH_TOL)?,
+        )?;
+        let extra_seeds: Vec<(f64, f64, f64)> = kwargs.take_or("extra_seeds", Vec::new())?;
+        for (ph_seed, hco3_frac, co3_frac) in &extra_seeds {
+            let fractions_valid = (0.0..=1.0).contains(hco3_frac)
+                && (0.0..=1.0).contains(co3_frac)
+                && hco3_frac + co3_frac <= 1.0;
+            if !ph_seed.is_finite() || !fractions_valid {
+                return Err(PyValueError::new_err(
+                    "extra_seeds entries must be (pH, hco3_frac, co3_frac) with fractions in [0, 1] summing to at most 1",
+                ));
+            }
+        }
+        let total_pressure_atm: f64 = kwargs.take_or("total_pressure_atm", 1.0)?;
+        if !total_pressure_atm.is_finite() || total_pressure_atm <= 0.0 {
+            return Err(PyValueError::new_err("total_pressure_atm must be positive"));
+        }
+        let trust_radius: f64 = kwargs.take_or("trust_radius", SOL_TRUST_RADIUS_DEFAULT)?;
+        if !trust_radius.is_finite() || trust_radius <= 0.0 {
+            return Err(PyValueError::new_err("trust_radius must be positive"));
+        }
+        let trust_acceptance_ratio: f64 =
+            kwargs.take_or("trust_acceptance_ratio", SOL_TRUST_ACCEPTANCE_DEFAULT)?;
+        if !(0.0..1.0).contains(&trust_acceptance_ratio) {
+            return Err(PyValueError::new_err("trust_acceptance_ratio must be in [0, 1)"));
+        }
+        let trust_region = kwargs.take_or("trust_region", false)?.then_some(NewtonTrustRegion {
+            initial_radius: trust_radius,
+            acceptance_ratio: trust_acceptance_ratio,
+        });
+        let formulation = parse_solver_formulation(
+            &kwargs.take_or("formulation", FORMULATION_NEWTON_SYSTEM.to_string())?,
+        )?;
+        let options = Self {
+            iteration_callback: kwargs.take_any("iteration_callback")?,
+            activity_model,
+            extra_seeds,
+            co2_gas_fraction: kwargs.take("co2_gas_fraction")?,
+            total_pressure_atm,
+            temperature_c: kwargs.take("temperature_c")?,
+            liquid_junction_offset: kwargs.take("liquid_junction_offset")?,
+            ionic_strength_control,
+            return_jacobian_condition: kwargs.take_or("return_jacobian_condition", false)?,
+            ammonia_total_m: kwargs.take("ammonia_total_m")?,
+            pka_nh4: kwargs.take("pka_nh4")?,
+            formulation,
+            retry_attempts: kwargs.take_or("retry_attempts", SOL_NEWTON_RETRY_ATTEMPTS)?,
+            return_log_species: kwargs.take_or("return_log_species", false)?,
+            phosphate_total_m: kwargs.take("phosphate_total_m")?,
+            pka_phosphate: kwargs.take("pka_phosphate")?,
+            trust_region,
+            clamp_bounds,
+        };
+        kwargs.finish()?;
+        Ok(options)
+    }
+}
+
+/// Solve one carbonate state. The positional arguments are the original contract;
+/// every later setting is a keyword collected into `CarbonateStateOptions`.
+#[pyfunction]
+#[pyo3(signature = (total_carbon_m, na_conc, ka1, ka2, kw, ionic_strength_cap=None, initial_ph_guess=8.35, speciation_mode="closed_carbon", fixed_h2co3=None, **options))]
+fn carbonate_state_core(
+    py: Python<'_>,
+    total_carbon_m: f64,
+    na_conc: f64,
+    ka1: f64,
+    ka2: f64,
+    kw: f64,
+    ionic_strength_cap: Option<f64>,
+    initial_ph_guess: f64,
+    speciation_mode: &str,
+    fixed_h2co3: Option<f64>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    let CarbonateStateOptions {
+        iteration_callback,
+        activity_model,
+        extra_seeds,
+        co2_gas_fraction,
+        total_pressure_atm,
+        temperature_c,
+        liquid_junction_offset,
+        ionic_strength_control,
+        return_jacobian_condition,
+        ammonia_total_m,
+        pka_nh4,
+        formulation,
+        retry_attempts,
+        return_log_species,
+        phosphate_total_m,
+        pka_phosphate,
+        trust_region,
+        clamp_bounds,
+    } = CarbonateStateOptions::from_kwargs(options)?;
+    // An explicit fixed_h2co3 wins; otherwise a headspace composition is turned
+    // into the fixed dissolved CO2 through Henry's law at the vessel pressure.
+    let henry_h2co3 = match co2_gas_fraction {
+        Some(fraction) if fixed_h2co3.is_none() => {
+            if !(0.0..=1.0).contains(&fraction) {
+                return Err(PyValueError::new_err("co2_gas_fraction must be between 0 and 1"));
+            }
+            Some(henry_dissolved_co2(
+                fraction,
+                total_pressure_atm,
+                clamp_bounds.temperature(temperature_c.unwrap_or(25.0)),
+            ))
+        }
+        _ => None,
+    };
+    let open_h2co3 = fixed_h2co3.or(henry_h2co3);
+    // Without an explicit pKa the NH4+ constant follows the liquor temperature.
+    let ammonia = match ammonia_total_m {
+        Some(total) if !total.is_finite() || total < 0.0 => {
+            return Err(PyValueError::new_err("ammonia_total_m must be finite and non-negative"));
+        }
+        Some(total) if total > 0.0 => {
+            let pka = pka_nh4.unwrap_or_else(|| {
+                ammonium_pka(clamp_bounds.temperature(temperature_c.unwrap_or(25.0)))
+            });
+            if !pka.is_finite() {
+                return Err(PyValueError::new_err("pka_nh4 must be finite"));
+            }
+            Some(AmmoniaBuffer {
+                total_m: total,
+                ka: 10f64.powf(-pka),
+            })
+        }
+        _ => None,
+    };
+    // Absent or zero phosphate leaves the carbonate-only system untouched.
+    let phosphate = match phosphate_total_m {
+        Some(total) if !total.is_finite() || total < 0.0 => {
+            return Err(PyValueError::new_err(
+                "phosphate_total_m must be finite and non-negative",
+            ));
+        }
+        Some(total) if total > 0.0 => {
+            let (pka1, pka2, pka3) = pka_phosphate.unwrap_or(PHOSPHATE_PKA_DEFAULT);
+            let pkas = [pka1, pka2, pka3];
+            if !pkas.iter().all(|pka| pka.is_finite())
+                || pkas.windows(2).any(|pair| pair[0] >= pair[1])
+            {
+                return Err(PyValueError::new_err(
+                    "pka_phosphate must be three finite, increasing pKa values",
+                ));
+            }
+            Some(PhosphateBuffer {
+                total_m: total,
+                ka: pkas.map(|pka| 10f64.powf(-pka)),
+            })
+        }
+        _ => None,
+    };
+    if formulation == FORMULATION_PROTON_CONDITION
+        && normalize_speciation_mode(speciation_mode) == SPEC_MODE_FIXED_PCO2
+    {
+        return Err(PyValueError::new_err(
+            "formulation 'proton_condition' requires speciation_mode 'closed_carbon'",
+        ));
+    }
+    let activity = ActivityTreatment {
+        model: activity_model,
+        ionic_strength_cap,
+        ionic_strength: ionic_strength_control,
+    };
+    let system = CarbonateSystem {
+        total_carbon_m,
+        na_conc,
+        ka1,
+        ka2,
+        kw,
+        ammonia,
+        phosphate,
+    };
+    let callback_error = RefCell::new(None);
+    let observer = python_iteration_observer(iteration_callback.as_ref(), &callback_error)?;
+    // The proton condition is a bracketed scalar solve, so there are no Newton
+    // iterations for the callback to observe.
+    let solved = if formulation == FORMULATION_PROTON_CONDITION {
+        solve_carbonate_state_proton_condition(system, activity)
+            .map(|solution| (solution, None))
+    } else {
+        let newton = NewtonOptions {
+            initial_ph_guess,
+            extra_seeds: &extra_seeds,
+            observer: observer.as_deref(),
+            retry_attempts,
+            trust_region,
+        };
+        solve_carbonate_state_with_mode(system, activity, speciation_mode, open_h2co3, newton)
+    };
+    if let Some(err) = callback_error.take() {
+        return Err(err);
+    }
+    let (solution, log_species) = solved.map_err(PyRuntimeError::new_err)?;
+    // A Newton request without a converged log vector was settled by the dilute or
+    // proton-condition bisection, so that is the formulation the state came from.
+    let newton_fallback = formulation == FORMULATION_NEWTON_SYSTEM && log_species.is_none();
+    let solved_formulation = if newton_fallback {
+        FORMULATION_PROTON_CONDITION
+    } else {
+        formulation
+    };
+    let (h, hco3, co3, h2co3, oh, gammas, ionic_strength, residual_norm) = solution;
+    let nh4 = ammonia.map(|buffer| buffer.ammonium_m(h, &gammas));
+    let phosphate_species = phosphate.map(|buffer| buffer.species_m(h, &gammas));
+    let (mono_slot, di_slot) = phosphate_ionic_slots(phosphate_species);
+    // The solvers only keep the settled species, so replay the ionic-strength loop
+    // once at the solution to report whether it converged within its budget.
+    let (_, _, _, ionic_strength_converged) = solubility_ionic_state(
+        na_conc.max(0.0) + nh4.unwrap_or(0.0),
+        h,
+        hco3 + mono_slot,
+        co3 + di_slot,
+        kw,
+        activity,
+    );
+    // Opt-in: the extra Jacobian costs as much as one more Newton iteration.
+    let jacobian_condition = return_jacobian_condition.then(|| {
+        let fixed_basis = (normalize_speciation_mode(speciation_mode) == SPEC_MODE_FIXED_PCO2)
+            .then(|| open_h2co3.unwrap_or(0.0));
+        carbonate_jacobian_condition(&solution, system, activity, fixed_basis)
+    });
+    let out = PyDict::new(py);
+    let gamma_map = PyDict::new(py);
+    gamma_map.set_item("Na", gammas[0])?;
+    gamma_map.set_item("H", gammas[1])?;
+    gamma_map.set_item("HCO3", gammas[2])?;
+    gamma_map.set_item("CO3", gammas[3])?;
+    gamma_map.set_item("OH", gammas[4])?;
+    out.set_item("h", h)?;
+    out.set_item("hco3", hco3)?;
+    out.set_item("co3", co3)?;
+    out.set_item("h2co3", h2co3)?;
+    set_dominant_species(&out, [h2co3, hco3, co3])?;
+    out.set_item("oh", oh)?;
+    out.set_item("nh4", nh4)?;
+    out.set_item("nh3", ammonia.zip(nh4).map(|(buffer, nh4)| buffer.total_m - nh4))?;
+    out.set_item("h3po4", phosphate_species.map(|species| species[0]))?;
+    out.set_item("h2po4", phosphate_species.map(|species| species[1]))?;
+    out.set_item("hpo4", phosphate_species.map(|species| species[2]))?;
+    out.set_item("po4", phosphate_species.map(|species| species[3]))?;
+    out.set_item("ionic_strength", ionic_strength)?;
+    out.set_item("ionic_strength_converged", ionic_strength_converged)?;
+    out.set_item("residual_norm", residual_norm)?;
+    out.set_item("jacobian_condition", jacobian_condition)?;
+    // Opt-in: the raw log10 Newton unknowns, unrounded by the 10^x back-transform, so
+    // callers can warm-start or inspect trace-level species directly. Omitted when
+    // the state came from a bisection path, which has no such vector.
+    if let (true, Some(log_species)) = (return_log_species, log_species) {
+        out.set_item("log_species", log_species)?;
+    }
+    out.set_item("gammas", gamma_map)?;
+    out.set_item("activity_coefficients", activity_coefficients_dict(py, gammas)?)?;
+    out.set_item("ph_scales", ph_scales_dict(py, h, gammas[1], liquid_junction_offset)?)?;
+    out.set_item("speciation_mode", normalize_speciation_mode(speciation_mode))?;
+    out.set_item("formulation", solved_formulation)?;
+    out.set_item("formulation_requested", formulation)?;
+    out.set_item("newton_fallback", newton_fallback)?;
+    out.set_item("activity_model", activity_model_name(activity_model))?;
+    out.set_item("henry_h2co3", henry_h2co3)?;
+    out.set_item("pco2_atm", co2_gas_fraction.map(|fraction| fraction * total_pressure_atm))?;
+    let warnings = PyList::empty(py);
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(out.unbind())
+}
+
+/// `forced_ph_distribution_core` settings past its original positional arguments,
+/// read once from `**options`. Keyword names and defaults are unchanged from when
+/// each was a parameter of its own.
+struct ForcedPhDistributionOptions {
+    activity_model: ActivityModel,
+    liquid_junction_offset: Option<f64>,
+    ionic_strength_control: IonicStrengthControl,
+}
+
+impl ForcedPhDistributionOptions {
+    fn from_kwargs(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let kwargs = KernelKwargs::new("forced_ph_distribution_core", kwargs)?;
+        let ionic_strength_control = ionic_strength_control_from_args(
+            kwargs.take("fixed_ionic_strength")?,
+            kwargs.take_or("ionic_strength_max_iterations", SOL_IONIC_STRENGTH_MAX_ITER)?,
+            kwargs.take_or("ionic_strength_tolerance", SOL_IONIC_STRENGTH_TOL)?,
+        )?;
+        let options = Self {
+            activity_model: kwargs.take_activity_model()?,
+            liquid_junction_offset: kwargs.take("liquid_junction_offset")?,
+            ionic_strength_control,
+        };
+        kwargs.finish()?;
+        Ok(options)
+    }
+}
+
+/// Speciate a closed-carbon liquor held at a forced pH. The positional arguments
+/// are the original contract; every later setting is a keyword collected into
+/// `ForcedPhDistributionOptions`.
+#[pyfunction]
+#[pyo3(signature = (total_carbon_m, na_conc, forced_ph, ka1, ka2, kw, ionic_strength_cap=None, fixed_h2co3=None, max_iter=80, **options))]
+fn forced_ph_distribution_core(
+    py: Python<'_>,
+    total_carbon_m: f64,
+    na_conc: f64,
+    forced_ph: f64,
+    ka1: f64,
+    ka2: f64,
+    kw: f64,
+    ionic_strength_cap: Option<f64>,
+    fixed_h2co3: Option<f64>,
+    max_iter: usize,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    let ForcedPhDistributionOptions {
+        activity_model,
+        liquid_junction_offset,
+        ionic_strength_control,
+    } = ForcedPhDistributionOptions::from_kwargs(options)?;
+    let activity = ActivityTreatment {
+        model: activity_model,
+        ionic_strength_cap,
+        ionic_strength: ionic_strength_control,
+    };
+    let (total_carbon, h, hco3, co3, h2co3, oh, charge_residual, gammas, ionic_strength) =
+        forced_ph_distribution_impl(
+            total_carbon_m,
+            na_conc,
+            forced_ph,
+            ka1,
+            ka2,
+            kw,
+            activity,
+            fixed_h2co3,
+            max_iter,
+        )
+        .map_err(PyRuntimeError::new_err)?;
+    let (_, _, _, ionic_strength_converged) = solubility_ionic_state(
+        na_conc.max(0.0),
+        h,
+        hco3,
+        co3,
+        kw,
+        activity,
+    );
+    let out = PyDict::new(py);
+    let gamma_map = PyDict::new(py);
+    gamma_map.set_item("Na", gammas[0])?;
+    gamma_map.set_item("H", gammas[1])?;
+    gamma_map.set_item("HCO3", gammas[2])?;
+    gamma_map.set_item("CO3", gammas[3])?;
+    gamma_map.set_item("OH", gammas[4])?;
+    out.set_item("total_carbon_m", total_carbon)?;
+    out.set_item("h", h)?;
+    out.set_item("hco3", hco3)?;
+    out.set_item("co3", co3)?;
+    out.set_item("h2co3", h2co3)?;
+    set_dominant_species(&out, [h2co3, hco3, co3])?;
+    out.set_item("oh", oh)?;
+    out.set_item("charge_balance_residual", charge_residual)?;
+    out.set_item("ionic_strength", ionic_strength)?;
+    out.set_item("ionic_strength_converged", ionic_strength_converged)?;
+    out.set_item("gammas", gamma_map)?;
+    out.set_item("activity_coefficients", activity_coefficients_dict(py, gammas)?)?;
+    out.set_item("ph_scales", ph_scales_dict(py, h, gammas[1], liquid_junction_offset)?)?;
+    out.set_item("activity_model", activity_model_name(activity_model))?;
+    Ok(out.unbind())
+}
+
+#[pyfunction]
+#[pyo3(signature = (total_inorganic_carbon_m, ka1=SOL_KA1, ka2=SOL_KA2, kw=SOL_KW, ph_low=AQION_DEFAULT_PH_LOW, ph_high=AQION_DEFAULT_PH_HIGH))]
+fn aqion_closed_speciation_core(
+    py: Python<'_>,
+    total_inorganic_carbon_m: f64,
+    ka1: f64,
+    ka2: f64,
+    kw: f64,
+    ph_low: f64,
+    ph_high: f64,
+) -> PyResult<Py<PyDict>> {
+    let (
+        ph,
+        h,
+        oh,
+        h2co3,
+        hco3,
+        co3,
+        a0,
+        a1,
+        a2,
+        ionic_strength,
+        residual,
+        solver_tag,
+    ) = solve_aqion_closed_speciation(total_inorganic_carbon_m, ka1, ka2, kw, ph_low, ph_high)
+        .map_err(PyRuntimeError::new_err)?;
+    let out = PyDict::new(py);
+    let species = PyDict::new(py);
+    species.set_item("H+", h)?;
+    species.set_item("H2CO3", h2co3)?;
+    species.set_item("HCO3-", hco3)?;
+    species.set_item("CO3^2-", co3)?;
+    species.set_item("OH-", oh)?;
+    let alpha = PyDict::new(py);
+    alpha.set_item("a0", a0)?;
+    alpha.set_item("a1", a1)?;
+    alpha.set_item("a2", a2)?;
+    out.set_item("ph", ph)?;
+    out.set_item("h_conc", h)?;
+    out.set_item("oh_conc", oh)?;
+    out.set_item("species_m", species)?;
+    out.set_item("alpha", alpha)?;
+    out.set_item("ionic_strength", ionic_strength)?;
+    out.set_item("charge_balance_residual", residual)?;
+    out.set_item("solver", solver_tag)?;
+    Ok(out.unbind())
+}
+
+#[pyfunction]
+#[pyo3(signature = (total_carbon_m, total_sodium_m, pitzer_params, max_iter=60))]
+fn pitzer_solve_total_carbon_core(
+    py: Python<'_>,
+    total_carbon_m: f64,
+    total_sodium_m: f64,
+    pitzer_params: &Bound<'_, PyDict>,
+    max_iter: usize,
+) -> PyResult<Option<Py<PyDict>>> {
+    if total_carbon_m < 0.0 || total_sodium_m <= 0.0 {
+        return Ok(None);
+    }
+    let parse = |key: &str| -> Option<f64> {
+        let value = pitzer_params.get_item(key).ok().flatten()?;
+        let parsed = value.extract::<f64>().ok()?;
+        if parsed.is_finite() {
+            Some(parsed)
+        } else {
+            None
+        }
+    };
+    let Some(b0_na_oh) = parse("b0_na_oh") else {
+        return Ok(None);
+    };
+    let Some(b1_na_oh) = parse("b1_na_oh") else {
+        return Ok(None);
+    };
+    let Some(c0_na_oh) = parse("c0_na_oh") else {
+        return Ok(None);
+    };
+    let Some(b0_na_hco3) = parse("b0_na_hco3") else {
+        return Ok(None);
+    };
+    let Some(b1_na_hco3) = parse("b1_na_hco3") else {
+        return Ok(None);
+    };
+    let Some(c0_na_hco3) = parse("c0_na_hco3") else {
+        return Ok(None);
+    };
+    let Some(b0_na_co3) = parse("b0_na_co3") else {
+        return Ok(None);
+    };
+    let Some(b1_na_co3) = parse("b1_na_co3") else {
+        return Ok(None);
+    };
+    let Some(c0_na_co3) = parse("c0_na_co3") else {
+        return Ok(None);
+    };
+    let Some(theta_co3_oh) = parse("theta_co3_oh") else {
+        return Ok(None);
+    };
+    let Some(psi_co3_na_oh) = parse("psi_co3_na_oh") else {
+        return Ok(None);
+    };
+    let Some(psi_co3_hco3_na) = parse("psi_co3_hco3_na") else {
+        return Ok(None);
+    };
+    let params = PitzerParamsLite {
+        b0_na_oh,
+        b1_na_oh,
+        c0_na_oh,
+        b0_na_hco3,
+        b1_na_hco3,
+        c0_na_hco3,
+        b0_na_co3,
+        b1_na_co3,
+        c0_na_co3,
+        theta_co3_oh,
+        psi_co3_na_oh,
+        psi_co3_hco3_na,
     };
-    let gas_mw = molar_masses.get(&gas_id).copied().unwrap_or(0.0);
-    let out = PyDict::new(py);
-    let initial_dict = PyDict::new(py);
-    for (key, value) in initial_inventory.iter() {
-        initial_dict.set_item(key, *value)?;
-    }
-    let final_dict = PyDict::new(py);
-    for (key, value) in inventory.iter() {
-        final_dict.set_item(key, *value)?;
-    }
-    out.set_item("backend", "rust")?;
-    out.set_item("gas_species_id", gas_id)?;
-    out.set_item("gas_uptake_mol", gas_uptake)?;
-    if gas_mw > 0.0 {
-        out.set_item("gas_uptake_g", gas_uptake * gas_mw)?;
-    } else {
-        out.set_item("gas_uptake_g", py.None())?;
-    }
-    out.set_item("initial_inventory_mol", initial_dict)?;
-    out.set_item("final_inventory_mol", final_dict)?;
-    out.set_item("step_results", step_results)?;
-    out.set_item("yield_species_id", product_id)?;
-    out.set_item("yield_species_moles", product_mol)?;
-    if let Some(value) = theoretical_yield_g {
-        out.set_item("theoretical_yield_g", value)?;
-    } else {
-        out.set_item("theoretical_yield_g", py.None())?;
-    }
-    if let Some(value) = actual_yield_mass_g {
-        out.set_item("actual_yield_mass_g", value)?;
-    } else {
-        out.set_item("actual_yield_mass_g", py.None())?;
-    }
-    if let Some(value) = actual_yield_pct {
-        out.set_item("actual_yield_pct", value)?;
-    } else {
-        out.set_item("actual_yield_pct", py.None())?;
-    }
-    if let Some(value) = completion_pct {
-        out.set_item("completion_pct", value)?;
-    } else {
-        out.set_item("completion_pct", py.None())?;
+    let solved = solve_pitzer_total_carbon_impl(total_carbon_m, total_sodium_m, params, max_iter);
+    let (ph, na, h, oh, hco3, co3, co2) = match solved {
+        Ok(value) => value,
+        Err(_) => return Ok(None),
+    };
+    if !ph.is_finite() {
+        return Ok(None);
     }
-    out.set_item("warnings", warnings)?;
-    Ok(out.unbind())
+    let out = PyDict::new(py);
+    out.set_item("ph", ph)?;
+    out.set_item("m_Na", na)?;
+    out.set_item("m_H", h)?;
+    out.set_item("m_OH", oh)?;
+    out.set_item("m_HCO3", hco3)?;
+    out.set_item("m_CO3", co3)?;
+    out.set_item("m_CO2", co2)?;
+    out.set_item("Na+", na)?;
+    out.set_item("H+", h)?;
+    out.set_item("OH-", oh)?;
+    out.set_item("HCO3-", hco3)?;
+    out.set_item("CO3-2", co3)?;
+    out.set_item("CO2", co2)?;
+    Ok(Some(out.unbind()))
 }
 
 #[pyfunction]
-#[pyo3(signature = (timeline_rows))]
-/// Format Final Report timeline rows using the Rust speciation outputs.
+#[pyo3(signature = (naoh_mass_g, co2_charged_g, solution_volume_l, measured_ph, **options))]
+/// Fit the CO2 absorption efficiency that makes the simulated pH match a measurement.
 ///
-/// The formatter keeps the existing table contract intact while using owned
-/// PyO3 dictionary casts that are compatible with current PyO3 releases.
-fn final_report_cycle_timeline_rows_core(
+/// `tolerance` (default 1e-4) and `max_iter` (default 80) are keyword options next
+/// to the solver settings. Returns `None` for non-positive NaOH/CO2 charges or a
+/// non-finite measurement, mirroring `analyze_bicarbonate_core`.
+fn fit_absorption_efficiency(
     py: Python<'_>,
-    timeline_rows: &Bound<'_, PyList>,
-) -> PyResult<Py<PyList>> {
-    // Format timeline rows for Final Report preview/export table builders.
-    let rows = PyList::empty(py);
-    for item in timeline_rows.iter() {
-        let Ok(entry) = item.cast_into::<PyDict>() else {
-            continue;
-        };
-        let co2_total = dict_optional_float_value(&entry, "co2_g")
-            .or_else(|| dict_optional_float_value(&entry, "co2_mass_g"))
-            .or_else(|| dict_optional_float_value(&entry, "cumulative_co2_added_mass_g"))
-            .unwrap_or(0.0);
-        let h2co3_fraction = normalize_fraction_value(extract_fraction_field(&entry, "H2CO3"));
-        let hco3_fraction = normalize_fraction_value(extract_fraction_field(&entry, "HCO3-"));
-        let co3_fraction = normalize_fraction_value(extract_fraction_field(&entry, "CO3^2-"));
-        let ph_value = dict_optional_float_value(&entry, "solution_ph")
-            .or_else(|| dict_optional_float_value(&entry, "speciation_ph"));
-        let row = PyDict::new(py);
-        row.set_item("Cycle", dict_truthy_or_empty_pyobject(py, &entry, "cycle_id"))?;
-        row.set_item("CO\u{2082} total (g)", format!("{co2_total:.2}"))?;
-        row.set_item("pH", format_optional_decimal(ph_value, 2))?;
-        row.set_item("H\u{2082}CO\u{2083} (%)", format!("{:.1}", h2co3_fraction * 100.0))?;
-        row.set_item("HCO\u{2083}\u{207B} (%)", format!("{:.1}", hco3_fraction * 100.0))?;
-        row.set_item("CO\u{2083}\u{00B2}\u{207B} (%)", format!("{:.1}", co3_fraction * 100.0))?;
-        rows.append(row)?;
+    naoh_mass_g: f64,
+    co2_charged_g: f64,
+    solution_volume_l: Option<f64>,
+    measured_ph: f64,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Option<Py<PyDict>>> {
+    let kwargs = KernelKwargs::new("fit_absorption_efficiency", options)?;
+    let tolerance: f64 = kwargs.take_or("tolerance", 1e-4)?;
+    let max_iter: usize = kwargs.take_or("max_iter", 80)?;
+    let settings = SolverSettings::take_equilibrium_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        clamp_bounds,
+        ..
+    } = settings;
+    if naoh_mass_g <= 0.0 || co2_charged_g <= 0.0 || !measured_ph.is_finite() {
+        return Ok(None);
     }
-    Ok(rows.unbind())
-}
-
-#[pyfunction]
-#[pyo3(signature = (timeline_rows))]
-/// Normalize cycle timeline rows for staged table/callout rendering paths.
-///
-/// The function preserves passthrough fields from each source row while
-/// coercing key numeric/fraction terms into a stable schema used by both
-/// Rust and Python render payload builders.
-fn cycle_timeline_normalize_core(
-    py: Python<'_>,
-    timeline_rows: &Bound<'_, PyList>,
-) -> PyResult<Py<PyList>> {
-    // Normalize mixed workflow timeline row payloads into one renderer-safe
-    // schema without dropping unknown passthrough fields.
-    let co2_total_keys = ["co2_g", "co2_mass_g", "cumulative_co2_added_mass_g"];
-    let co2_cycle_keys = ["co2_mass_g", "cycle_co2_mass_g", "selected_mass_g"];
-    let ph_keys = ["ph", "actual_ph", "solution_ph", "speciation_ph"];
-    let actual_ph_keys = ["actual_ph", "ph"];
-    let solution_ph_keys = ["solution_ph", "ph", "actual_ph"];
-    let normalized_rows = PyList::empty(py);
-    for (idx, item) in timeline_rows.iter().enumerate() {
-        let Ok(entry) = item.cast_into::<PyDict>() else {
-            continue;
-        };
-        let row = PyDict::new(py);
-        for (key, value) in entry.iter() {
-            row.set_item(key, value)?;
-        }
-        let cycle_id = compare_cycle_row_index(&entry, idx + 1);
-        let co2_total = dict_optional_finite_by_keys(&entry, &co2_total_keys).unwrap_or(0.0);
-        let co2_cycle = dict_optional_finite_by_keys(&entry, &co2_cycle_keys).unwrap_or(0.0);
-        let ph_value = dict_optional_finite_by_keys(&entry, &ph_keys);
-        let actual_ph = dict_optional_finite_by_keys(&entry, &actual_ph_keys);
-        let solution_ph = dict_optional_finite_by_keys(&entry, &solution_ph_keys);
-        let calculated_ph = dict_optional_finite_by_keys(
-            &entry,
-            &["calculated_ph", "calculated_ph_raw_co2", "ph", "solution_ph"],
-        );
-        let calculated_ph_raw_co2 = dict_optional_finite_by_keys(
-            &entry,
-            &[
-                "calculated_ph",
-                "calculated_ph_raw_co2",
-                "planning_co2_aligned_ph",
-                "ph",
-            ],
-        );
-        let calculated_ph_corrected_co2 = dict_optional_finite_by_keys(
-            &entry,
-            &["calculated_ph_corrected_co2", "corrected_ph", "calibrated_estimated_ph"],
-        );
-        let fractions = PyDict::new(py);
-        fractions.set_item(
-            "H2CO3",
-            normalize_fraction_value(extract_fraction_field(&entry, "H2CO3")),
-        )?;
-        fractions.set_item(
-            "HCO3-",
-            normalize_fraction_value(extract_fraction_field(&entry, "HCO3-")),
-        )?;
-        fractions.set_item(
-            "CO3^2-",
-            normalize_fraction_value(extract_fraction_field(&entry, "CO3^2-")),
-        )?;
-        let warnings = PyList::empty(py);
-        if let Some(warnings_any) = entry.get_item("warnings").ok().flatten() {
-            if let Ok(warnings_list) = warnings_any.clone().cast_into::<PyList>() {
-                for warning in warnings_list.iter() {
-                    let text = py_any_to_trimmed_string(&warning);
-                    if !text.is_empty() {
-                        warnings.append(text)?;
-                    }
-                }
-            } else {
-                let warning_text = py_any_to_trimmed_string(&warnings_any);
-                if !warning_text.is_empty() {
-                    warnings.append(warning_text)?;
-                }
-            }
-        }
-        let analysis_prediction = PyDict::new(py);
-        let mut prediction_target_ph: Option<f64> = None;
-        let mut prediction_co2_to_target: Option<f64> = None;
-        if let Some(prediction_any) = entry.get_item("analysis_prediction").ok().flatten() {
-            if let Ok(prediction_map) = prediction_any.cast_into::<PyDict>() {
-                prediction_target_ph =
-                    dict_optional_finite_by_keys(&prediction_map, &["target_ph"]);
-                prediction_co2_to_target =
-                    dict_optional_finite_by_keys(&prediction_map, &["co2_to_target_g"]);
-            }
-        }
-        if let Some(value) = prediction_target_ph {
-            analysis_prediction.set_item("target_ph", value)?;
-        } else {
-            analysis_prediction.set_item("target_ph", py.None())?;
-        }
-        if let Some(value) = prediction_co2_to_target {
-            analysis_prediction.set_item("co2_to_target_g", value)?;
-        } else {
-            analysis_prediction.set_item("co2_to_target_g", py.None())?;
-        }
-        row.set_item("cycle_id", cycle_id)?;
-        row.set_item("co2_g", co2_total)?;
-        row.set_item("co2_mass_g", co2_cycle)?;
-        if let Some(value) = dict_optional_finite_by_keys(&entry, &["co2_to_target_g"]) {
-            row.set_item("co2_to_target_g", value)?;
-        } else {
-            row.set_item("co2_to_target_g", py.None())?;
-        }
-        if let Some(value) = ph_value {
-            row.set_item("ph", value)?;
-        } else {
-            row.set_item("ph", py.None())?;
-        }
-        if let Some(value) = actual_ph {
-            row.set_item("actual_ph", value)?;
-        } else {
-            row.set_item("actual_ph", py.None())?;
-        }
-        if let Some(value) = solution_ph {
-            row.set_item("solution_ph", value)?;
-        } else {
-            row.set_item("solution_ph", py.None())?;
-        }
-        if let Some(value) = calculated_ph {
-            row.set_item("calculated_ph", value)?;
-        } else {
-            row.set_item("calculated_ph", py.None())?;
-        }
-        if let Some(value) = calculated_ph_raw_co2 {
-            row.set_item("calculated_ph_raw_co2", value)?;
-        } else {
-            row.set_item("calculated_ph_raw_co2", py.None())?;
-        }
-        if let Some(value) = calculated_ph_corrected_co2 {
-            row.set_item("calculated_ph_corrected_co2", value)?;
-        } else {
-            row.set_item("calculated_ph_corrected_co2", py.None())?;
-        }
-        let calculated_delta = match (calculated_ph_corrected_co2, calculated_ph_raw_co2) {
-            (Some(corrected), Some(raw)) => Some(corrected - raw),
-            _ => dict_optional_finite_by_keys(
-                &entry,
-                &["calculated_ph_corrected_minus_raw_delta"],
-            ),
+    let naoh_mol = naoh_mass_g / SOL_MW_NAOH;
+    let co2_mol = co2_charged_g / SOL_MW_CO2;
+    let solve = LedgerSolve::new(settings, settings.pka2_value(), solution_volume_l);
+    let (efficiency, simulated_ph, residual, clamped, iterations) = fit_absorption_efficiency_impl(
+        naoh_mol,
+        co2_mol,
+        measured_ph,
+        &solve,
+        tolerance.abs().max(1e-12),
+        max_iter,
+    );
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let out = PyDict::new(py);
+    out.set_item("absorption_efficiency", efficiency)?;
+    out.set_item("simulated_ph", simulated_ph)?;
+    out.set_item("measured_ph", measured_ph)?;
+    out.set_item("residual", residual)?;
+    out.set_item("clamped", clamped)?;
+    out.set_item("iterations", iterations)?;
+    out.set_item("co2_reacted_mol", co2_mol * efficiency)?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(Some(out.unbind()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (total_sodium_mol, total_carbon_mol, solution_volume_l, **options))]
+/// Report the CO2 dosing window of the bicarbonate/carbonate buffer (pKa2 ± 1).
+///
+/// Both edges are reverse-solved as cumulative CO2 doses onto a fresh NaOH charge
+/// carrying the given sodium; the window is the mass needed to move from the upper
+/// edge to the lower one, and the headroom is what remains from the current total
+/// carbon before the solution leaves the buffer. Returns `None` for non-positive
+/// sodium or volume, or when either edge cannot be reached.
+fn carbonate_buffer_window_core(
+    py: Python<'_>,
+    total_sodium_mol: f64,
+    total_carbon_mol: f64,
+    solution_volume_l: f64,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Option<Py<PyDict>>> {
+    let kwargs = KernelKwargs::new("carbonate_buffer_window_core", options)?;
+    let settings = SolverSettings::take_equilibrium_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        clamp_bounds,
+        ..
+    } = settings;
+    if total_sodium_mol <= 0.0 || !total_sodium_mol.is_finite() || solution_volume_l <= 0.0 {
+        return Ok(None);
+    }
+    let pka2_value = settings.pka2_value();
+    let solve = LedgerSolve::new(settings, pka2_value, Some(solution_volume_l));
+    let base = LedgerState {
+        naoh_remaining_mol: total_sodium_mol,
+        na2co3_mol: 0.0,
+        nahco3_mol: 0.0,
+        co2_excess_mol: 0.0,
+    };
+    let dose_for = |target_ph: f64| co2_dose_for_target_ph_impl(base, target_ph, &solve);
+    let ph_upper = pka2_value + 1.0;
+    let ph_lower = pka2_value - 1.0;
+    let (Some(upper_dose_mol), Some(lower_dose_mol)) = (dose_for(ph_upper), dose_for(ph_lower))
+    else {
+        return Ok(None);
+    };
+    let window_mol = (lower_dose_mol - upper_dose_mol).max(0.0);
+    let headroom_mol = (lower_dose_mol - total_carbon_mol.max(0.0)).max(0.0);
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let out = PyDict::new(py);
+    out.set_item("pka2_value", pka2_value)?;
+    out.set_item("ph_upper", ph_upper)?;
+    out.set_item("ph_lower", ph_lower)?;
+    out.set_item("co2_at_ph_upper_mol", upper_dose_mol)?;
+    out.set_item("co2_at_ph_lower_mol", lower_dose_mol)?;
+    out.set_item("buffer_window_mol", window_mol)?;
+    out.set_item("buffer_window_g", window_mol * SOL_MW_CO2)?;
+    out.set_item("headroom_mol", headroom_mol)?;
+    out.set_item("headroom_g", headroom_mol * SOL_MW_CO2)?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(Some(out.unbind()))
+}
+
+/// CO2 dosing tolerance for a pH specification band.
+///
+/// Both band edges are reverse-solved as cumulative CO2 doses onto the fresh NaOH
+/// charge: the dose that brings the pH down to `ph_hi` opens the window and the one
+/// that reaches `ph_lo` closes it. When `co2_min_g`/`co2_max_g` give the dosing
+/// range actually available, the window is clipped to it and `window_in_range`
+/// reports whether any in-spec dose remains; both are keyword options next to the
+/// solver settings. Returns `None` for non-positive NaOH or volume, or when either
+/// edge cannot be reached.
+#[pyfunction]
+#[pyo3(signature = (naoh_mass_g, solution_volume_l, ph_lo, ph_hi, **options))]
+fn acceptable_co2_window(
+    py: Python<'_>,
+    naoh_mass_g: f64,
+    solution_volume_l: f64,
+    ph_lo: f64,
+    ph_hi: f64,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Option<Py<PyDict>>> {
+    let kwargs = KernelKwargs::new("acceptable_co2_window", options)?;
+    let co2_min_g: f64 = kwargs.take_or("co2_min_g", 0.0)?;
+    let co2_max_g: Option<f64> = kwargs.take("co2_max_g")?;
+    let settings = SolverSettings::take_equilibrium_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        clamp_bounds,
+        ..
+    } = settings;
+    if !ph_lo.is_finite() || !ph_hi.is_finite() || ph_lo >= ph_hi {
+        return Err(PyValueError::new_err("ph_lo and ph_hi must be finite with ph_lo < ph_hi"));
+    }
+    let co2_max = co2_max_g.unwrap_or(f64::INFINITY);
+    if !co2_min_g.is_finite() || co2_min_g < 0.0 || co2_max.is_nan() || co2_max < co2_min_g {
+        return Err(PyValueError::new_err(
+            "co2_min_g must be non-negative and no larger than co2_max_g",
+        ));
+    }
+    if !naoh_mass_g.is_finite() || naoh_mass_g <= 0.0 || solution_volume_l <= 0.0 {
+        return Ok(None);
+    }
+    let pka2_value = settings.pka2_value();
+    let solve = LedgerSolve::new(settings, pka2_value, Some(solution_volume_l));
+    let base = LedgerState {
+        naoh_remaining_mol: naoh_mass_g / SOL_MW_NAOH,
+        na2co3_mol: 0.0,
+        nahco3_mol: 0.0,
+        co2_excess_mol: 0.0,
+    };
+    let dose_for = |target_ph: f64| co2_dose_for_target_ph_impl(base, target_ph, &solve);
+    let (Some(open_mol), Some(close_mol)) = (dose_for(ph_hi), dose_for(ph_lo)) else {
+        return Ok(None);
+    };
+    let (open_g, close_g) = (open_mol * SOL_MW_CO2, close_mol * SOL_MW_CO2);
+    let in_range_lo = open_g.max(co2_min_g);
+    let in_range_hi = close_g.min(co2_max);
+    let window_in_range = in_range_lo <= in_range_hi;
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    if !window_in_range {
+        warnings.append(format!(
+            "No CO2 dose between {co2_min_g} g and {co2_max} g keeps the pH within \
+             [{ph_lo}, {ph_hi}]."
+        ))?;
+    }
+    let out = PyDict::new(py);
+    out.set_item("ph_lo", ph_lo)?;
+    out.set_item("ph_hi", ph_hi)?;
+    out.set_item("pka2_value", pka2_value)?;
+    out.set_item("co2_at_ph_hi_g", open_g)?;
+    out.set_item("co2_at_ph_lo_g", close_g)?;
+    out.set_item("window_width_g", close_g - open_g)?;
+    out.set_item("window_center_g", 0.5 * (open_g + close_g))?;
+    out.set_item("window_in_range", window_in_range)?;
+    out.set_item("co2_min_in_spec_g", window_in_range.then_some(in_range_lo))?;
+    out.set_item("co2_max_in_spec_g", window_in_range.then_some(in_range_hi))?;
+    out.set_item(
+        "in_range_width_g",
+        if window_in_range { in_range_hi - in_range_lo } else { 0.0 },
+    )?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(Some(out.unbind()))
+}
+
+/// Fixed inputs of a ledger CO2 dose sweep (excess CO2 kept dissolved).
+#[derive(Clone, Copy)]
+struct Co2DoseSweep {
+    input_state: LedgerState,
+    solve: LedgerSolve,
+}
+
+impl Co2DoseSweep {
+    /// Sweep `input_state` in `solution_volume_l`, with the equilibrium constants
+    /// resolved once for every point.
+    fn new(input_state: LedgerState, settings: SolverSettings, solution_volume_l: f64) -> Self {
+        let settings = SolverSettings {
+            constants: Some(settings.equilibrium_constants()),
+            ..settings
         };
-        if let Some(value) = calculated_delta {
-            row.set_item("calculated_ph_corrected_minus_raw_delta", value)?;
-        } else {
-            row.set_item("calculated_ph_corrected_minus_raw_delta", py.None())?;
+        Co2DoseSweep {
+            input_state,
+            solve: LedgerSolve::new(settings, settings.pka2_value(), Some(solution_volume_l)),
+        }
+    }
+
+    /// `(ph, state, residual_norm)` after dosing `delta_g` grams of CO2, with the
+    /// solve seeded from `ph_guess`.
+    fn point(&self, delta_g: f64, ph_guess: Option<f64>) -> (f64, LedgerState, Option<f64>) {
+        let (state, _, ph, residual_norm, _, _) = simulate_reaction_state_with_accounting_impl(
+            self.input_state,
+            delta_g / SOL_MW_CO2,
+            &self.solve,
+            ph_guess,
+            None,
+            None,
+        );
+        (ph, state, residual_norm)
+    }
+}
+
+/// Dose the sweep's ledger with `n_points` evenly spaced CO2 masses `0, step_g, ...`
+/// (excess CO2 kept dissolved), seeding each solve from the previous pH.
+///
+/// Returns the `(delta_g, ph, state)` rows and the worst solver residual.
+fn co2_dose_curve(
+    sweep: &Co2DoseSweep,
+    step_g: f64,
+    n_points: usize,
+) -> (Vec<(f64, f64, LedgerState)>, Option<f64>) {
+    let mut curve = Vec::with_capacity(n_points);
+    let mut step_guess: Option<f64> = None;
+    let mut max_residual: Option<f64> = None;
+    for idx in 0..n_points {
+        let delta_g = step_g * idx as f64;
+        let (ph, state, residual_norm) = sweep.point(delta_g, step_guess);
+        step_guess = Some(ph);
+        max_residual = max_optional_residual(max_residual, residual_norm);
+        curve.push((delta_g, ph, state));
+    }
+    (curve, max_residual)
+}
+
+/// Bisect the dose interval `[lo, hi]` (as `(delta_g, ph)` endpoints) until no
+/// sub-interval spans more than `max_ph_step`, or `depth_left` runs out. Interior
+/// points are appended to `curve` in ascending `delta_g`; each midpoint solve is
+/// seeded from the mean endpoint pH. Returns the number of intervals left
+/// unresolved at the depth limit.
+fn refine_co2_dose_interval(
+    sweep: &Co2DoseSweep,
+    lo: (f64, f64),
+    hi: (f64, f64),
+    max_ph_step: f64,
+    depth_left: usize,
+    curve: &mut Vec<(f64, f64)>,
+    max_residual: &mut Option<f64>,
+) -> usize {
+    if (hi.1 - lo.1).abs() <= max_ph_step {
+        return 0;
+    }
+    if depth_left == 0 {
+        return 1;
+    }
+    let mid_g = 0.5 * (lo.0 + hi.0);
+    let (ph, _, residual_norm) = sweep.point(mid_g, Some(0.5 * (lo.1 + hi.1)));
+    *max_residual = max_optional_residual(*max_residual, residual_norm);
+    let mid = (mid_g, ph);
+    let left = refine_co2_dose_interval(
+        sweep,
+        lo,
+        mid,
+        max_ph_step,
+        depth_left - 1,
+        curve,
+        max_residual,
+    );
+    curve.push(mid);
+    left + refine_co2_dose_interval(
+        sweep,
+        mid,
+        hi,
+        max_ph_step,
+        depth_left - 1,
+        curve,
+        max_residual,
+    )
+}
+
+/// Locate the CO2 dose of minimum buffer intensity between the two carbonate
+/// buffer regions.
+///
+/// The ledger is dosed over `n_points` evenly spaced CO2 masses up to
+/// `max_delta_g` (default: 1.5x the dose that takes every pool to bicarbonate),
+/// and the CO2 buffer intensity `β = -d[CO2 added]/dpH` (mol/L per pH unit) is
+/// taken by central differences. Only ledgers between the two buffer midpoints
+/// are considered (no free NaOH, bicarbonate past carbonate, dissolved CO2 still
+/// below bicarbonate), so the steep strong-base end point is not mistaken for the
+/// bicarbonate one. Returns `None` without a positive volume or when no interior
+/// point qualifies.
+#[pyfunction]
+#[pyo3(signature = (ledger, solution_volume_l, max_delta_g=None, n_points=241, **options))]
+fn minimum_buffer_point(
+    py: Python<'_>,
+    ledger: &Bound<'_, PyDict>,
+    solution_volume_l: f64,
+    max_delta_g: Option<f64>,
+    n_points: usize,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Option<Py<PyDict>>> {
+    let kwargs = KernelKwargs::new("minimum_buffer_point", options)?;
+    let settings = SolverSettings::take_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        clamp_bounds,
+        ..
+    } = settings;
+    if n_points < 3 {
+        return Err(PyValueError::new_err("n_points must be at least 3"));
+    }
+    if !solution_volume_l.is_finite() || solution_volume_l <= 0.0 {
+        return Ok(None);
+    }
+    let input_state = LedgerState {
+        naoh_remaining_mol: dict_float_value(ledger, "naoh_remaining_mol"),
+        na2co3_mol: dict_float_value(ledger, "na2co3_mol"),
+        nahco3_mol: dict_float_value(ledger, "nahco3_mol"),
+        co2_excess_mol: dict_float_value(ledger, "co2_excess_mol"),
+    };
+    let to_bicarbonate_mol =
+        input_state.naoh_remaining_mol.max(0.0) + input_state.na2co3_mol.max(0.0);
+    let max_delta_g = max_delta_g.unwrap_or((1.5 * to_bicarbonate_mol * SOL_MW_CO2).max(2.0));
+    if !max_delta_g.is_finite() || max_delta_g <= 0.0 {
+        return Err(PyValueError::new_err("max_delta_g must be positive"));
+    }
+    let sweep = Co2DoseSweep::new(input_state, settings, solution_volume_l);
+    let pka2_value = sweep.solve.pka2_value;
+    let step_g = max_delta_g / (n_points - 1) as f64;
+    // The dose curve never touches Python objects, so it is swept without the GIL.
+    let (curve, max_residual) = detach_solver(py, || co2_dose_curve(&sweep, step_g, n_points));
+    let step_conc = 2.0 * step_g / SOL_MW_CO2 / solution_volume_l;
+    let mut best: Option<(f64, f64, f64)> = None;
+    for window in curve.windows(3) {
+        let (delta_g, ph, state) = window[1];
+        let ph_drop = window[0].1 - window[2].1;
+        let between_buffers = state.naoh_remaining_mol <= 1e-12
+            && state.na2co3_mol < state.nahco3_mol
+            && state.co2_excess_mol < state.nahco3_mol;
+        if ph_drop <= 0.0 || !between_buffers {
+            continue;
         }
-        for key in [
-            "forecast_ph",
-            "pco2_atm",
-            "planning_cycle_ph",
-            "planning_co2_aligned_ph",
-            "ph_delta_cycle",
-            "ph_delta_co2",
-            "actual_cycle_co2_mol",
-            "planning_cycle_co2_mol",
-            "co2_mol_delta",
-            "co2_mol_delta_pct",
-            "actual_hco3_pct",
-            "actual_co3_pct",
-            "planning_hco3_pct",
-            "planning_co3_pct",
-            "hco3_pct_delta",
-            "co3_pct_delta",
-            "delta_pressure_psi",
-            "co2_moles",
-            "peak_pressure_psi",
-            "trough_pressure_psi",
-            "headspace_pressure_psi",
-        ] {
-            if let Some(value) = dict_optional_finite_by_keys(&entry, &[key]) {
-                row.set_item(key, value)?;
-            } else {
-                row.set_item(key, py.None())?;
-            }
+        let beta = step_conc / ph_drop;
+        if best.is_none_or(|(_, _, best_beta)| beta < best_beta) {
+            best = Some((delta_g, ph, beta));
         }
-        row.set_item(
-            "solid_na2co3_g",
-            dict_optional_finite_by_keys(&entry, &["solid_na2co3_g"]).unwrap_or(0.0),
-        )?;
-        row.set_item(
-            "solid_nahco3_g",
-            dict_optional_finite_by_keys(&entry, &["solid_nahco3_g"]).unwrap_or(0.0),
-        )?;
-        row.set_item("alignment_quality_flag", dict_string_value(&entry, "alignment_quality_flag"))?;
-        row.set_item("fractions", fractions)?;
-        row.set_item("warnings", warnings)?;
-        row.set_item("analysis_prediction", analysis_prediction)?;
-        let error_flag = entry
-            .get_item("error")
-            .ok()
-            .flatten()
-            .and_then(|value| value.is_truthy().ok())
-            .unwrap_or(false);
-        row.set_item("error", error_flag)?;
-        normalized_rows.append(row)?;
     }
-    Ok(normalized_rows.unbind())
+    let Some((delta_g, ph, beta)) = best else {
+        return Ok(None);
+    };
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let out = PyDict::new(py);
+    out.set_item("delta_g", delta_g)?;
+    out.set_item("delta_mol", delta_g / SOL_MW_CO2)?;
+    out.set_item("ph", ph)?;
+    out.set_item("beta", beta)?;
+    out.set_item("pka2_value", pka2_value)?;
+    out.set_item("max_delta_g", max_delta_g)?;
+    out.set_item("max_residual_achieved", max_residual)?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(Some(out.unbind()))
 }
 
+/// Condense the shape of a ledger's CO2 dose curve into one sharpness number.
+///
+/// The ledger is swept exactly as in [`minimum_buffer_point`] and the steepest
+/// central-difference slope `|dpH/d delta_g|` located. `sharpness_index` divides
+/// that peak by the curve's mean slope (total pH travel over `max_delta_g`), so it
+/// is dimensionless and comparable across recipes and batch sizes: near 1 for a
+/// gentle, evenly spread response, large when most of the drop happens in one
+/// abrupt, hard-to-control step. `max_slope_ph_per_m` is the same peak per mol/L of
+/// CO2 added. Returns `None` without a positive volume or when the sweep does not
+/// move the pH.
 #[pyfunction]
-/// Return Rust backend interface metadata consumed by Python capability checks.
-fn rust_backend_manifest(py: Python<'_>) -> PyResult<Py<PyDict>> {
-    let payload = PyDict::new(py);
-    payload.set_item("interface_id", RUST_BACKEND_INTERFACE_ID)?;
-    payload.set_item("interface_version", RUST_BACKEND_INTERFACE_VERSION)?;
-    payload.set_item("crate_version", RUST_BACKEND_CRATE_VERSION)?;
-    payload.set_item("module_name", RUST_BACKEND_MODULE_NAME)?;
-    let exported_kernels = PyList::empty(py);
-    for kernel_name in RUST_EXPORTED_KERNELS {
-        exported_kernels.append(kernel_name)?;
+#[pyo3(signature = (ledger, solution_volume_l, max_delta_g=None, n_points=241, **options))]
+fn titration_sharpness(
+    py: Python<'_>,
+    ledger: &Bound<'_, PyDict>,
+    solution_volume_l: f64,
+    max_delta_g: Option<f64>,
+    n_points: usize,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Option<Py<PyDict>>> {
+    let kwargs = KernelKwargs::new("titration_sharpness", options)?;
+    let settings = SolverSettings::take_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        clamp_bounds,
+        ..
+    } = settings;
+    if n_points < 3 {
+        return Err(PyValueError::new_err("n_points must be at least 3"));
     }
-    payload.set_item("exported_kernels", exported_kernels)?;
-    Ok(payload.unbind())
+    if !solution_volume_l.is_finite() || solution_volume_l <= 0.0 {
+        return Ok(None);
+    }
+    let input_state = LedgerState {
+        naoh_remaining_mol: dict_float_value(ledger, "naoh_remaining_mol"),
+        na2co3_mol: dict_float_value(ledger, "na2co3_mol"),
+        nahco3_mol: dict_float_value(ledger, "nahco3_mol"),
+        co2_excess_mol: dict_float_value(ledger, "co2_excess_mol"),
+    };
+    let to_bicarbonate_mol =
+        input_state.naoh_remaining_mol.max(0.0) + input_state.na2co3_mol.max(0.0);
+    let max_delta_g = max_delta_g.unwrap_or((1.5 * to_bicarbonate_mol * SOL_MW_CO2).max(2.0));
+    if !max_delta_g.is_finite() || max_delta_g <= 0.0 {
+        return Err(PyValueError::new_err("max_delta_g must be positive"));
+    }
+    let sweep = Co2DoseSweep::new(input_state, settings, solution_volume_l);
+    let pka2_value = sweep.solve.pka2_value;
+    let step_g = max_delta_g / (n_points - 1) as f64;
+    let (curve, max_residual) = detach_solver(py, || co2_dose_curve(&sweep, step_g, n_points));
+    let ph_start = curve[0].1;
+    let ph_end = curve[n_points - 1].1;
+    let mean_slope = (ph_start - ph_end).abs() / max_delta_g;
+    let mut peak: Option<(f64, f64, f64)> = None;
+    for window in curve.windows(3) {
+        let slope = (window[2].1 - window[0].1).abs() / (2.0 * step_g);
+        if slope.is_finite() && peak.is_none_or(|(_, _, best)| slope > best) {
+            peak = Some((window[1].0, window[1].1, slope));
+        }
+    }
+    let Some((delta_g, ph, max_slope)) = peak else {
+        return Ok(None);
+    };
+    if !mean_slope.is_finite() || mean_slope <= 0.0 {
+        return Ok(None);
+    }
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let out = PyDict::new(py);
+    out.set_item("sharpness_index", max_slope / mean_slope)?;
+    out.set_item("max_slope_ph_per_g", max_slope)?;
+    out.set_item("max_slope_ph_per_mol", max_slope * SOL_MW_CO2)?;
+    out.set_item("max_slope_ph_per_m", max_slope * SOL_MW_CO2 * solution_volume_l)?;
+    out.set_item("mean_slope_ph_per_g", mean_slope)?;
+    out.set_item("delta_g", delta_g)?;
+    out.set_item("delta_mol", delta_g / SOL_MW_CO2)?;
+    out.set_item("ph", ph)?;
+    out.set_item("ph_start", ph_start)?;
+    out.set_item("ph_end", ph_end)?;
+    out.set_item("pka2_value", pka2_value)?;
+    out.set_item("max_delta_g", max_delta_g)?;
+    out.set_item("max_residual_achieved", max_residual)?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(Some(out.unbind()))
 }
 
+/// CO2 dose curve of a ledger sampled to a pH resolution rather than a point count.
+///
+/// The ledger is first swept over `coarse_points` evenly spaced CO2 masses up to
+/// `max_delta_g` (default as in [`minimum_buffer_point`]); every interval whose
+/// endpoints differ by more than `max_ph_step` is then bisected, recursively, up to
+/// `max_depth` times. Points therefore crowd into the steep end-point stretches and
+/// stay sparse on the buffered plateaus. `delta_g`/`ph` come back sorted by dose;
+/// `unresolved_intervals` counts the intervals still coarser than `max_ph_step`
+/// at the depth limit (a warning is added when it is non-zero). `coarse_points`
+/// (default 13) and `max_depth` (default 8) are keyword options next to the solver
+/// settings. Returns `None` without a positive volume.
 #[pyfunction]
-#[pyo3(signature = (ledger, delta_mol, pka2_value, solution_volume_l=None, temperature_c=None, ionic_strength_cap=None, use_temp_adjusted_constants=false, initial_ph_guess=None, constants=None, planning_mode=false))]
-fn simulate_reaction_state_with_accounting(
+#[pyo3(signature = (ledger, solution_volume_l, max_ph_step=0.1, max_delta_g=None, **options))]
+fn adaptive_dose_curve(
     py: Python<'_>,
     ledger: &Bound<'_, PyDict>,
-    delta_mol: f64,
-    pka2_value: f64,
-    solution_volume_l: Option<f64>,
-    temperature_c: Option<f64>,
-    ionic_strength_cap: Option<f64>,
-    use_temp_adjusted_constants: bool,
-    initial_ph_guess: Option<f64>,
-    constants: Option<(f64, f64, f64)>,
-    planning_mode: bool,
-) -> PyResult<Py<PyDict>> {
+    solution_volume_l: f64,
+    max_ph_step: f64,
+    max_delta_g: Option<f64>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Option<Py<PyDict>>> {
+    let kwargs = KernelKwargs::new("adaptive_dose_curve", options)?;
+    let coarse_points: usize = kwargs.take_or("coarse_points", 13)?;
+    let max_depth: usize = kwargs.take_or("max_depth", 8)?;
+    let settings = SolverSettings::take_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        clamp_bounds,
+        ..
+    } = settings;
+    if !max_ph_step.is_finite() || max_ph_step <= 0.0 {
+        return Err(PyValueError::new_err("max_ph_step must be positive"));
+    }
+    if coarse_points < 2 {
+        return Err(PyValueError::new_err("coarse_points must be at least 2"));
+    }
+    if max_depth > 20 {
+        return Err(PyValueError::new_err("max_depth must be at most 20"));
+    }
+    if !solution_volume_l.is_finite() || solution_volume_l <= 0.0 {
+        return Ok(None);
+    }
     let input_state = LedgerState {
         naoh_remaining_mol: dict_float_value(ledger, "naoh_remaining_mol"),
         na2co3_mol: dict_float_value(ledger, "na2co3_mol"),
         nahco3_mol: dict_float_value(ledger, "nahco3_mol"),
         co2_excess_mol: dict_float_value(ledger, "co2_excess_mol"),
     };
-    let (state, accounting, ph) = simulate_reaction_state_with_accounting_impl(
-        input_state,
-        delta_mol,
-        pka2_value,
-        solution_volume_l,
+    let to_bicarbonate_mol =
+        input_state.naoh_remaining_mol.max(0.0) + input_state.na2co3_mol.max(0.0);
+    let max_delta_g = max_delta_g.unwrap_or((1.5 * to_bicarbonate_mol * SOL_MW_CO2).max(2.0));
+    if !max_delta_g.is_finite() || max_delta_g <= 0.0 {
+        return Err(PyValueError::new_err("max_delta_g must be positive"));
+    }
+    let sweep = Co2DoseSweep::new(input_state, settings, solution_volume_l);
+    let pka2_value = sweep.solve.pka2_value;
+    let step_g = max_delta_g / (coarse_points - 1) as f64;
+    let (curve, max_residual, unresolved) = detach_solver(py, || {
+        let (coarse, mut max_residual) = co2_dose_curve(&sweep, step_g, coarse_points);
+        let mut curve = vec![(coarse[0].0, coarse[0].1)];
+        let mut unresolved = 0;
+        for window in coarse.windows(2) {
+            let (lo, hi) = ((window[0].0, window[0].1), (window[1].0, window[1].1));
+            unresolved += refine_co2_dose_interval(
+                &sweep,
+                lo,
+                hi,
+                max_ph_step,
+                max_depth,
+                &mut curve,
+                &mut max_residual,
+            );
+            curve.push(hi);
+        }
+        (curve, max_residual, unresolved)
+    });
+    let max_ph_step_achieved = curve
+        .windows(2)
+        .map(|window| (window[1].1 - window[0].1).abs())
+        .fold(0.0_f64, f64::max);
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    if unresolved > 0 {
+        warnings.append(format!(
+            "{unresolved} dose interval(s) still exceed max_ph_step {max_ph_step} after \
+             {max_depth} bisections; raise max_depth or coarse_points."
+        ))?;
+    }
+    let out = PyDict::new(py);
+    out.set_item("delta_g", curve.iter().map(|point| point.0).collect::<Vec<f64>>())?;
+    out.set_item("ph", curve.iter().map(|point| point.1).collect::<Vec<f64>>())?;
+    out.set_item("n_points", curve.len())?;
+    out.set_item("max_ph_step", max_ph_step)?;
+    out.set_item("max_ph_step_achieved", max_ph_step_achieved)?;
+    out.set_item("unresolved_intervals", unresolved)?;
+    out.set_item("pka2_value", pka2_value)?;
+    out.set_item("max_delta_g", max_delta_g)?;
+    out.set_item("max_residual_achieved", max_residual)?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(Some(out.unbind()))
+}
+
+/// Exact local pH response of a ledger to one more unit of dissolved CO2.
+///
+/// The current composition is solved in the closed-carbon system and the slope
+/// `d pH / d C_T` taken by implicit differentiation of that system (see
+/// [`carbonate_ph_sensitivity`]), so it stays clean on the steep end-point
+/// stretches where finite differences of the dose curve are noisy. The dose slopes
+/// are per mol/g of CO2 added to `solution_volume_l`; `beta` is the matching CO2
+/// buffer intensity `-dC_T/dpH`. The pH is on the concentration scale the system is
+/// solved on. Returns `None` without a positive volume, for a carbon- or
+/// sodium-free ledger, or when the state cannot be solved.
+#[pyfunction]
+#[pyo3(signature = (ledger, solution_volume_l, **options))]
+fn ph_sensitivity_to_co2(
+    py: Python<'_>,
+    ledger: &Bound<'_, PyDict>,
+    solution_volume_l: f64,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Option<Py<PyDict>>> {
+    let kwargs = KernelKwargs::new("ph_sensitivity_to_co2", options)?;
+    // The ledger solve only seeds the speciation, so it keeps the raw root.
+    let settings = SolverSettings {
+        raw_solver_ph: true,
+        ..SolverSettings::take_equilibrium_from(&kwargs)?
+    };
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        activity_model,
+        clamp_bounds,
+        ..
+    } = settings;
+    if !solution_volume_l.is_finite() || solution_volume_l <= 0.0 {
+        return Ok(None);
+    }
+    let input_state = LedgerState {
+        naoh_remaining_mol: dict_float_value(ledger, "naoh_remaining_mol"),
+        na2co3_mol: dict_float_value(ledger, "na2co3_mol"),
+        nahco3_mol: dict_float_value(ledger, "nahco3_mol"),
+        co2_excess_mol: dict_float_value(ledger, "co2_excess_mol"),
+    };
+    let solve = LedgerSolve::new(settings, settings.pka2_value(), Some(solution_volume_l));
+    let (_, _, ph_guess, _, _, _) =
+        simulate_reaction_state_with_accounting_impl(input_state, 0.0, &solve, None, None, None);
+    let Some((total_carbon_m, na_conc, solution)) =
+        ledger_speciation(input_state, solution_volume_l, &settings, ph_guess)
+    else {
+        return Ok(None);
+    };
+    let Some(slope) = carbonate_ph_sensitivity(&solution, total_carbon_m, na_conc, &settings)
+    else {
+        return Ok(None);
+    };
+    let dph_dco2_mol = slope / solution_volume_l;
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let out = PyDict::new(py);
+    out.set_item("ph", -solution.0.max(1e-300).log10())?;
+    out.set_item("dph_dtotal_carbon_m", slope)?;
+    out.set_item("dph_dco2_mol", dph_dco2_mol)?;
+    out.set_item("dph_dco2_g", dph_dco2_mol / SOL_MW_CO2)?;
+    out.set_item("beta", (slope != 0.0).then(|| -1.0 / slope))?;
+    out.set_item("total_carbon_m", total_carbon_m)?;
+    out.set_item("na_conc", na_conc)?;
+    out.set_item("residual_norm", solution.7)?;
+    out.set_item("activity_model", activity_model_name(activity_model))?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(Some(out.unbind()))
+}
+
+/// pH at the three equivalence points of a carbonate titration at fixed total
+/// carbon, each solved with the full activity-corrected closed system.
+///
+/// The compositions are taken at exact stoichiometry: `carbonate` is pure Na2CO3
+/// (Na = 2·C_T), `bicarbonate` pure NaHCO3 (Na = C_T), and `carbonic_acid` the
+/// sodium-free H2CO3 end point (the spectator salt of an acid titration is not
+/// counted). Each entry also carries the textbook estimate it replaces:
+/// `(pKw + pKa2 + log C)/2`, `(pKa1 + pKa2)/2` and `(pKa1 - log C)/2`. An entry is
+/// `None` when its solve fails.
+#[pyfunction]
+#[pyo3(signature = (total_carbon_mol, volume_l, **options))]
+fn equivalence_point_phs(
+    py: Python<'_>,
+    total_carbon_mol: f64,
+    volume_l: f64,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    let kwargs = KernelKwargs::new("equivalence_point_phs", options)?;
+    let settings = SolverSettings::take_equilibrium_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        activity_model,
+        clamp_bounds,
+        ..
+    } = settings;
+    if !total_carbon_mol.is_finite() || total_carbon_mol <= 0.0 {
+        return Err(PyValueError::new_err("total_carbon_mol must be positive"));
+    }
+    if !volume_l.is_finite() || volume_l <= 0.0 {
+        return Err(PyValueError::new_err("volume_l must be positive"));
+    }
+    let (ka1, ka2, kw) = settings.equilibrium_constants();
+    let (pka1, pka2, pkw) = (-ka1.log10(), -ka2.log10(), -kw.log10());
+    let total_carbon_m = total_carbon_mol / volume_l;
+    let log_c = total_carbon_m.log10();
+    let points = [
+        ("carbonate", 2.0, (pkw + pka2 + log_c) / 2.0),
+        ("bicarbonate", 1.0, (pka1 + pka2) / 2.0),
+        ("carbonic_acid", 0.0, (pka1 - log_c) / 2.0),
+    ];
+    let out = PyDict::new(py);
+    for (label, sodium_per_carbon, approximate_ph) in points {
+        let na_conc = sodium_per_carbon * total_carbon_m;
+        let solved = solve_carbonate_state(
+            CarbonateSystem::new(total_carbon_m, na_conc, (ka1, ka2, kw)),
+            settings.activity(),
+            NewtonOptions::from_guess(approximate_ph),
+        );
+        let entry = match solved {
+            Ok((h, hco3, co3, h2co3, _, gammas, ionic_strength, residual_norm)) => {
+                let entry = PyDict::new(py);
+                entry.set_item("ph", -h.max(1e-30).log10())?;
+                entry.set_item("ph_scales", ph_scales_dict(py, h, gammas[1], None)?)?;
+                entry.set_item("approximate_ph", approximate_ph)?;
+                entry.set_item("na_conc", na_conc)?;
+                entry.set_item("ionic_strength", ionic_strength)?;
+                entry.set_item("frac_h2co3", h2co3 / total_carbon_m)?;
+                entry.set_item("frac_hco3", hco3 / total_carbon_m)?;
+                entry.set_item("frac_co3", co3 / total_carbon_m)?;
+                set_dominant_species(&entry, [h2co3, hco3, co3])?;
+                entry.set_item("residual_norm", residual_norm)?;
+                Some(entry)
+            }
+            Err(_) => None,
+        };
+        out.set_item(label, entry)?;
+    }
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    out.set_item("total_carbon_m", total_carbon_m)?;
+    out.set_item("pka1", pka1)?;
+    out.set_item("pka2", pka2)?;
+    out.set_item("pkw", pkw)?;
+    out.set_item("activity_model", activity_model_name(activity_model))?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(out.unbind())
+}
+
+/// pH of one closed carbonate composition diluted or concentrated at a locked Na:C
+/// ratio.
+///
+/// Every scale multiplies both `total_carbon_m` and `na_conc`, so any pH drift
+/// along the sweep comes from ionic strength through the activity model rather
+/// than from composition. Each point is seeded with the previous pH. `points`
+/// holds `(scale, ionic_strength, ph)` for the scales that solved; the rest are
+/// listed in `failed_scales`.
+#[pyfunction]
+#[pyo3(signature = (total_carbon_m, na_conc, scales, **options))]
+fn ph_vs_concentration_scale(
+    py: Python<'_>,
+    total_carbon_m: f64,
+    na_conc: f64,
+    scales: Vec<f64>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    let kwargs = KernelKwargs::new("ph_vs_concentration_scale", options)?;
+    let settings = SolverSettings::take_equilibrium_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
         temperature_c,
-        ionic_strength_cap,
         use_temp_adjusted_constants,
-        initial_ph_guess,
-        constants,
-        planning_mode,
-    );
-    let response = PyDict::new(py);
-    let state_dict = PyDict::new(py);
-    state_dict.set_item("naoh_remaining_mol", state.naoh_remaining_mol.max(0.0))?;
-    state_dict.set_item("na2co3_mol", state.na2co3_mol.max(0.0))?;
-    state_dict.set_item("nahco3_mol", state.nahco3_mol.max(0.0))?;
-    state_dict.set_item("co2_excess_mol", state.co2_excess_mol.max(0.0))?;
-    state_dict.set_item("ph", clamp_ph_value(ph))?;
-    let accounting_dict = PyDict::new(py);
-    accounting_dict.set_item(
-        "co2_consumed_to_carbonate_mol",
-        accounting.co2_consumed_to_carbonate_mol,
-    )?;
-    accounting_dict.set_item(
-        "co2_consumed_to_bicarbonate_mol",
-        accounting.co2_consumed_to_bicarbonate_mol,
-    )?;
-    accounting_dict.set_item("co2_consumed_total_mol", accounting.co2_consumed_total_mol)?;
-    accounting_dict.set_item("co2_unconsumed_mol", accounting.co2_unconsumed_mol)?;
-    response.set_item("state", state_dict)?;
-    response.set_item("accounting", accounting_dict)?;
-    Ok(response.unbind())
+        activity_model,
+        clamp_bounds,
+        ..
+    } = settings;
+    if !total_carbon_m.is_finite() || total_carbon_m <= 0.0 {
+        return Err(PyValueError::new_err("total_carbon_m must be positive"));
+    }
+    if !na_conc.is_finite() || na_conc < 0.0 {
+        return Err(PyValueError::new_err("na_conc must be non-negative"));
+    }
+    if scales.iter().any(|scale| !scale.is_finite() || *scale <= 0.0) {
+        return Err(PyValueError::new_err("scales entries must be positive and finite"));
+    }
+    let (ka1, ka2, kw) = settings.equilibrium_constants();
+    let mut guess = 7.0;
+    let points = PyList::empty(py);
+    let failed_scales = PyList::empty(py);
+    for scale in scales {
+        let solved = solve_carbonate_state(
+            CarbonateSystem::new(total_carbon_m * scale, na_conc * scale, (ka1, ka2, kw)),
+            settings.activity(),
+            NewtonOptions::from_guess(guess),
+        );
+        match solved {
+            Ok((h, _, _, _, _, _, ionic_strength, _)) => {
+                let ph = -h.max(1e-30).log10();
+                guess = ph;
+                points.append((scale, ionic_strength, ph))?;
+            }
+            Err(_) => failed_scales.append(scale)?,
+        }
+    }
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let out = PyDict::new(py);
+    out.set_item("points", points)?;
+    out.set_item("failed_scales", failed_scales)?;
+    out.set_item("total_carbon_m", total_carbon_m)?;
+    out.set_item("na_conc", na_conc)?;
+    out.set_item("na_to_carbon_ratio", na_conc / total_carbon_m)?;
+    out.set_item("activity_model", activity_model_name(activity_model))?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(out.unbind())
 }
 
+/// Carbonate species distribution (Bjerrum plot) over `points` evenly spaced pH
+/// values from `ph_min` to `ph_max`.
+///
+/// Total carbon is held at `total_carbon_mol / volume_l` and pH is the independent
+/// variable; the sodium (or, where negative, strong acid) needed to close the
+/// charge balance at each pH is reported as `sodium_m`. Fractions use the
+/// concentration-scale constants with no activity correction, so they do not
+/// depend on the carbon level; `**options` therefore takes only `temperature_c`,
+/// `use_temp_adjusted_constants`, `constants` or `pka_constants` and `clamp_bounds`.
 #[pyfunction]
-#[pyo3(signature = (naoh_mass_g, co2_charged_g, solution_volume_l, measured_ph, slurry_ph, target_ph, temperature_c, use_temp_adjusted_constants, ionic_strength_cap=None, constants=None))]
-fn analyze_bicarbonate_core(
+#[pyo3(signature = (total_carbon_mol, volume_l, ph_min=0.0, ph_max=14.0, points=141, **options))]
+fn speciation_distribution(
     py: Python<'_>,
-    naoh_mass_g: f64,
-    co2_charged_g: f64,
-    solution_volume_l: Option<f64>,
-    measured_ph: Option<f64>,
-    slurry_ph: Option<f64>,
-    target_ph: Option<f64>,
-    temperature_c: Option<f64>,
-    use_temp_adjusted_constants: bool,
-    ionic_strength_cap: Option<f64>,
-    constants: Option<(f64, f64, f64)>,
-) -> PyResult<Option<Py<PyDict>>> {
-    if naoh_mass_g <= 0.0 || co2_charged_g < 0.0 {
-        return Ok(None);
+    total_carbon_mol: f64,
+    volume_l: f64,
+    ph_min: f64,
+    ph_max: f64,
+    points: usize,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyDict>> {
+    let kwargs = KernelKwargs::new("speciation_distribution", options)?;
+    let temperature_c: Option<f64> = kwargs.take("temperature_c")?;
+    let use_temp_adjusted_constants = kwargs.take_or("use_temp_adjusted_constants", false)?;
+    let constants = kwargs.take_constants()?;
+    let clamp_bounds = kwargs.take_clamp_bounds()?;
+    kwargs.finish()?;
+    if !total_carbon_mol.is_finite() || total_carbon_mol < 0.0 {
+        return Err(PyValueError::new_err("total_carbon_mol must be finite and non-negative"));
+    }
+    if !volume_l.is_finite() || volume_l <= 0.0 {
+        return Err(PyValueError::new_err("volume_l must be positive"));
+    }
+    if !(ph_min.is_finite() && ph_max.is_finite()) || ph_min >= ph_max {
+        return Err(PyValueError::new_err("ph_min must be below ph_max"));
+    }
+    if points < 2 {
+        return Err(PyValueError::new_err("points must be at least 2"));
+    }
+    let (ka1, ka2, kw) = constants.unwrap_or_else(|| {
+        basic_carbonate_constants_within(temperature_c, use_temp_adjusted_constants, clamp_bounds)
+    });
+    let total_carbon_m = total_carbon_mol / volume_l;
+    let ph_values = PyList::empty(py);
+    let frac_h2co3 = PyList::empty(py);
+    let frac_hco3 = PyList::empty(py);
+    let frac_co3 = PyList::empty(py);
+    let sodium_m = PyList::empty(py);
+    for idx in 0..points {
+        let ph = ph_min + (ph_max - ph_min) * (idx as f64 / (points - 1) as f64);
+        let h = 10f64.powf(-ph);
+        let (a0, a1, a2) = aqion_alpha_fractions(h, ka1, ka2);
+        ph_values.append(ph)?;
+        frac_h2co3.append(a0)?;
+        frac_hco3.append(a1)?;
+        frac_co3.append(a2)?;
+        sodium_m.append(total_carbon_m * (a1 + 2.0 * a2) + kw / h - h)?;
     }
-    let naoh_mol = naoh_mass_g / SOL_MW_NAOH;
-    let co2_mol = co2_charged_g / SOL_MW_CO2;
-    if naoh_mol <= 0.0 {
-        return Ok(None);
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let out = PyDict::new(py);
+    out.set_item("ph", ph_values)?;
+    out.set_item("frac_h2co3", frac_h2co3)?;
+    out.set_item("frac_hco3", frac_hco3)?;
+    out.set_item("frac_co3", frac_co3)?;
+    out.set_item("sodium_m", sodium_m)?;
+    out.set_item("total_carbon_m", total_carbon_m)?;
+    out.set_item("pka1", -ka1.max(1e-30).log10())?;
+    out.set_item("pka2", -ka2.max(1e-30).log10())?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(out.unbind())
+}
+
+/// Henderson-Hasselbalch `[CO3^2-]/[HCO3-]` ratio used by the planning
+/// heuristics; no activity correction is applied.
+#[pyfunction]
+fn carbonate_ratio_from_ph(ph: f64, pka2: f64) -> f64 {
+    hh_carbonate_ratio(ph, pka2)
+}
+
+/// Henderson-Hasselbalch pH for a `[CO3^2-]/[HCO3-]` ratio; no activity
+/// correction is applied.
+#[pyfunction]
+fn ph_from_carbonate_ratio(ratio: f64, pka2: f64) -> PyResult<f64> {
+    if ratio.is_nan() || ratio <= 0.0 {
+        return Err(PyValueError::new_err("ratio must be positive"));
     }
-    let stage1_co2 = co2_mol.min(naoh_mol / 2.0);
-    let naoh_after_stage1 = (naoh_mol - stage1_co2 * 2.0).max(0.0);
-    let na2co3_from_stage1 = stage1_co2;
-    let co2_after_stage1 = (co2_mol - stage1_co2).max(0.0);
-    let stage2_co2 = co2_after_stage1.min(na2co3_from_stage1);
-    let na2co3_remaining = (na2co3_from_stage1 - stage2_co2).max(0.0);
-    let nahco3_produced = (stage2_co2 * 2.0).max(0.0);
-    let co2_excess = (co2_after_stage1 - stage2_co2).max(0.0);
-    let buffer_carbon = na2co3_remaining + nahco3_produced;
-    let pka2_value = resolve_pka2_value(temperature_c, use_temp_adjusted_constants);
-    let measurement_value = measured_ph.or(slurry_ph);
-    let ratio_estimate = measurement_value.map(|v| 10f64.powf(v - pka2_value));
-    let (co3_current, hco3_current) = if buffer_carbon > 0.0 && ratio_estimate.is_some() {
-        let ratio = ratio_estimate.unwrap_or(0.0);
-        let frac_co3 = ratio / (1.0 + ratio);
-        let co3 = buffer_carbon * frac_co3;
-        let hco3 = (buffer_carbon - co3).max(0.0);
-        (co3, hco3)
-    } else {
-        (na2co3_remaining, nahco3_produced)
-    };
-    let desired_ph = target_ph.unwrap_or(8.0);
-    let ratio_target = 10f64.powf(desired_ph - pka2_value);
-    let numerator = co3_current - ratio_target * hco3_current;
-    let denom = 1.0 + 2.0 * ratio_target;
-    let mut co2_for_ratio = 0.0;
-    if denom > 0.0 && numerator > 0.0 {
-        co2_for_ratio = (numerator / denom).min(co3_current.max(0.0));
+    Ok(hh_ph_from_ratio(ratio, pka2))
+}
+
+/// Single-ion activity coefficient of the hybrid model: Davies up to
+/// I = 0.5, extended Debye-Hückel with ion size `ion_size_nm` above it.
+#[pyfunction]
+fn activity_coefficient(ionic_strength: f64, charge: i32, ion_size_nm: f64) -> PyResult<f64> {
+    if ionic_strength.is_nan() || ionic_strength < 0.0 {
+        return Err(PyValueError::new_err("ionic_strength must be non-negative"));
     }
-    let co2_for_naoh = naoh_after_stage1 / 2.0;
-    let total_extra_mol = co2_for_ratio.max(0.0) + co2_for_naoh.max(0.0);
-    let total_extra_g = total_extra_mol * SOL_MW_CO2;
-    let eq_constants = constants
-        .unwrap_or_else(|| basic_carbonate_constants(temperature_c, use_temp_adjusted_constants));
-    let initial_guess = measurement_value.unwrap_or(desired_ph);
-    let (predicted_state, _, predicted_ph) = simulate_reaction_state_with_accounting_impl(
-        LedgerState {
-            naoh_remaining_mol: naoh_after_stage1,
-            na2co3_mol: na2co3_remaining,
-            nahco3_mol: nahco3_produced,
-            co2_excess_mol: co2_excess,
-        },
-        total_extra_mol,
-        pka2_value,
-        solution_volume_l,
-        temperature_c,
-        ionic_strength_cap,
-        use_temp_adjusted_constants,
-        Some(initial_guess),
-        Some(eq_constants),
-        false,
-    );
-    let slider_max_g = (total_extra_g * 1.6).max(2.0);
-    let rows = pyo3::types::PyList::empty(py);
-    let mut step_guess = initial_guess;
-    for idx in 0..=12 {
-        let delta_g = slider_max_g * (idx as f64 / 12.0);
-        let delta_mol = delta_g / SOL_MW_CO2;
-        let (state, _, ph) = simulate_reaction_state_with_accounting_impl(
-            LedgerState {
-                naoh_remaining_mol: naoh_after_stage1,
-                na2co3_mol: na2co3_remaining,
-                nahco3_mol: nahco3_produced,
-                co2_excess_mol: co2_excess,
-            },
-            delta_mol,
-            pka2_value,
-            solution_volume_l,
-            temperature_c,
-            ionic_strength_cap,
-            use_temp_adjusted_constants,
-            Some(step_guess),
-            Some(eq_constants),
-            false,
-        );
-        step_guess = ph;
-        let row = PyDict::new(py);
-        row.set_item("delta_g", delta_g)?;
-        row.set_item("total_co2_g", co2_charged_g + delta_g)?;
-        row.set_item("ph", ph)?;
-        row.set_item("na2co3_mol", state.na2co3_mol)?;
-        row.set_item("nahco3_mol", state.nahco3_mol)?;
-        rows.append(row)?;
+    Ok(solubility_activity_coefficient(ionic_strength, charge, ion_size_nm))
+}
+
+/// CO2 that carries `naoh_mass_g` of caustic exactly to the stage-1 endpoint
+/// (2 NaOH + CO2 -> Na2CO3 + H2O), as `{mol, grams}`.
+#[pyfunction]
+fn co2_to_neutralize_caustic(py: Python<'_>, naoh_mass_g: f64) -> PyResult<Py<PyDict>> {
+    if !naoh_mass_g.is_finite() || naoh_mass_g < 0.0 {
+        return Err(PyValueError::new_err("naoh_mass_g must be finite and non-negative"));
     }
+    let co2_mol = naoh_mass_g / SOL_MW_NAOH / 2.0;
     let out = PyDict::new(py);
-    out.set_item("naoh_mol", naoh_mol)?;
-    out.set_item("co2_mol", co2_mol)?;
-    out.set_item("stage1_co2", stage1_co2)?;
-    out.set_item("naoh_after_stage1", naoh_after_stage1)?;
-    out.set_item("na2co3_from_stage1", na2co3_from_stage1)?;
-    out.set_item("co2_after_stage1", co2_after_stage1)?;
-    out.set_item("stage2_co2", stage2_co2)?;
-    out.set_item("na2co3_remaining", na2co3_remaining)?;
-    out.set_item("nahco3_produced", nahco3_produced)?;
-    out.set_item("co2_excess", co2_excess)?;
-    out.set_item("buffer_carbon", buffer_carbon)?;
-    out.set_item("pka2_value", pka2_value)?;
-    out.set_item("co3_current", co3_current)?;
-    out.set_item("hco3_current", hco3_current)?;
-    out.set_item("desired_ph", desired_ph)?;
-    out.set_item("ratio_target", ratio_target)?;
-    out.set_item("co2_for_ratio", co2_for_ratio)?;
-    out.set_item("co2_for_naoh", co2_for_naoh)?;
-    out.set_item("total_extra_mol", total_extra_mol)?;
-    out.set_item("total_extra_g", total_extra_g)?;
-    out.set_item("predicted_ph", predicted_ph)?;
-    out.set_item("slider_max_g", slider_max_g)?;
-    out.set_item("eq_ka1", eq_constants.0)?;
-    out.set_item("eq_ka2", eq_constants.1)?;
-    out.set_item("eq_kw", eq_constants.2)?;
-    out.set_item("simulation_curve", rows)?;
-    out.set_item(
-        "predicted_ledger_naoh_remaining",
-        predicted_state.naoh_remaining_mol,
-    )?;
-    out.set_item("predicted_ledger_na2co3", predicted_state.na2co3_mol)?;
-    out.set_item("predicted_ledger_nahco3", predicted_state.nahco3_mol)?;
-    out.set_item(
-        "predicted_ledger_co2_excess",
-        predicted_state.co2_excess_mol,
-    )?;
-    Ok(Some(out.unbind()))
+    out.set_item("mol", co2_mol)?;
+    out.set_item("grams", co2_mol * SOL_MW_CO2)?;
+    Ok(out.unbind())
 }
 
+/// CO3^2-/HCO3- concentration ratio that puts a liquor at `target_ph` (concentration
+/// scale, as the ledger solves report it) at the given ionic strength.
+///
+/// From `Ka2 = γH[H+]·γCO3[CO3^2-] / (γHCO3[HCO3-])` the ratio is the textbook
+/// `10^(pH - pKa2)` (`ideal_ratio`) times `gamma_factor = γHCO3 / (γH·γCO3)`; the
+/// planning heuristic's bias at high ionic strength is exactly that factor. SIT
+/// and Pitzer coefficients are evaluated against an NaHCO3 background at
+/// `ionic_strength`, which is given rather than solved, so `**options` takes no
+/// `ionic_strength_cap`.
 #[pyfunction]
-#[pyo3(signature = (total_carbon_m, na_conc, ka1, ka2, kw, ionic_strength_cap=None, initial_ph_guess=8.35, speciation_mode="closed_carbon", fixed_h2co3=None))]
-fn carbonate_state_core(
+#[pyo3(signature = (target_ph, ionic_strength, **options))]
+fn target_carbonate_ratio(
     py: Python<'_>,
-    total_carbon_m: f64,
-    na_conc: f64,
-    ka1: f64,
-    ka2: f64,
-    kw: f64,
-    ionic_strength_cap: Option<f64>,
-    initial_ph_guess: f64,
-    speciation_mode: &str,
-    fixed_h2co3: Option<f64>,
+    target_ph: f64,
+    ionic_strength: f64,
+    options: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<Py<PyDict>> {
-    let (h, hco3, co3, h2co3, oh, gammas, ionic_strength) = solve_carbonate_state_with_mode(
-        total_carbon_m,
-        na_conc,
-        ka1,
-        ka2,
-        kw,
-        ionic_strength_cap,
-        initial_ph_guess,
-        speciation_mode,
-        fixed_h2co3,
-    )
-    .map_err(PyRuntimeError::new_err)?;
+    let kwargs = KernelKwargs::new("target_carbonate_ratio", options)?;
+    let temperature_c: Option<f64> = kwargs.take("temperature_c")?;
+    let use_temp_adjusted_constants = kwargs.take_or("use_temp_adjusted_constants", false)?;
+    let constants = kwargs.take_constants()?;
+    let activity_model = kwargs.take_activity_model()?;
+    let clamp_bounds = kwargs.take_clamp_bounds()?;
+    kwargs.finish()?;
+    if !target_ph.is_finite() {
+        return Err(PyValueError::new_err("target_ph must be finite"));
+    }
+    if !ionic_strength.is_finite() || ionic_strength < 0.0 {
+        return Err(PyValueError::new_err("ionic_strength must be finite and non-negative"));
+    }
+    let (_, ka2, kw) = constants.unwrap_or_else(|| {
+        basic_carbonate_constants_within(temperature_c, use_temp_adjusted_constants, clamp_bounds)
+    });
+    let pka2 = -ka2.log10();
+    let h = 10f64.powf(-target_ph);
+    let gammas = solubility_gammas(
+        activity_model,
+        ionic_strength,
+        ionic_strength,
+        h,
+        ionic_strength,
+        0.0,
+        kw / h,
+    );
+    let ideal_ratio = hh_carbonate_ratio(target_ph, pka2);
+    let gamma_factor = gammas[2] / (gammas[1] * gammas[3]).max(1e-300);
+    let ratio = ideal_ratio * gamma_factor;
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
     let out = PyDict::new(py);
-    let gamma_map = PyDict::new(py);
-    gamma_map.set_item("Na", gammas[0])?;
-    gamma_map.set_item("H", gammas[1])?;
-    gamma_map.set_item("HCO3", gammas[2])?;
-    gamma_map.set_item("CO3", gammas[3])?;
-    gamma_map.set_item("OH", gammas[4])?;
-    out.set_item("h", h)?;
-    out.set_item("hco3", hco3)?;
-    out.set_item("co3", co3)?;
-    out.set_item("h2co3", h2co3)?;
-    out.set_item("oh", oh)?;
+    out.set_item("ratio", ratio)?;
+    out.set_item("ideal_ratio", ideal_ratio)?;
+    out.set_item("gamma_factor", gamma_factor)?;
+    out.set_item("frac_co3", ratio / (1.0 + ratio))?;
+    out.set_item("ideal_frac_co3", ideal_ratio / (1.0 + ideal_ratio))?;
+    out.set_item("effective_pka2", pka2 - gamma_factor.log10())?;
+    out.set_item("pka2", pka2)?;
     out.set_item("ionic_strength", ionic_strength)?;
-    out.set_item("gammas", gamma_map)?;
-    out.set_item("speciation_mode", normalize_speciation_mode(speciation_mode))?;
+    out.set_item("activity_coefficients", activity_coefficients_dict(py, gammas)?)?;
+    out.set_item("activity_model", activity_model_name(activity_model))?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
     Ok(out.unbind())
 }
 
+/// Highest pH a caustic charge can reach: fresh `naoh_mass_g` of NaOH in
+/// `volume_l` of water before any CO2 is absorbed.
+///
+/// `ph` is the activity-corrected strong-base root from [`strong_base_solution`]
+/// on the concentration scale (see `ph_scales` for the activity scale);
+/// `ideal_ph` is the `pKw + log10[NaOH]` limit and `ledger_ph` what the ledger pH
+/// path reports for the same NaOH-only ledger, so the three can be compared at the
+/// top of a titration curve.
 #[pyfunction]
-#[pyo3(signature = (total_carbon_m, na_conc, forced_ph, ka1, ka2, kw, ionic_strength_cap=None, fixed_h2co3=None, max_iter=80))]
-fn forced_ph_distribution_core(
+#[pyo3(signature = (naoh_mass_g, volume_l, **options))]
+fn max_ph(
     py: Python<'_>,
-    total_carbon_m: f64,
-    na_conc: f64,
-    forced_ph: f64,
-    ka1: f64,
-    ka2: f64,
-    kw: f64,
-    ionic_strength_cap: Option<f64>,
-    fixed_h2co3: Option<f64>,
-    max_iter: usize,
+    naoh_mass_g: f64,
+    volume_l: f64,
+    options: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<Py<PyDict>> {
-    let (total_carbon, h, hco3, co3, h2co3, oh, charge_residual, gammas, ionic_strength) =
-        forced_ph_distribution_impl(
-            total_carbon_m,
-            na_conc,
-            forced_ph,
-            ka1,
-            ka2,
-            kw,
-            ionic_strength_cap,
-            fixed_h2co3,
-            max_iter,
-        )
-        .map_err(PyRuntimeError::new_err)?;
+    let kwargs = KernelKwargs::new("max_ph", options)?;
+    let settings = SolverSettings::take_equilibrium_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        activity_model,
+        clamp_bounds,
+        ..
+    } = settings;
+    if !naoh_mass_g.is_finite() || naoh_mass_g <= 0.0 {
+        return Err(PyValueError::new_err("naoh_mass_g must be positive"));
+    }
+    if !volume_l.is_finite() || volume_l <= 0.0 {
+        return Err(PyValueError::new_err("volume_l must be positive"));
+    }
+    let settings = SolverSettings {
+        constants: Some(settings.equilibrium_constants()),
+        ..settings
+    };
+    let kw = settings.equilibrium_constants().2;
+    let pkw = -kw.log10();
+    let naoh_mol = naoh_mass_g / SOL_MW_NAOH;
+    let naoh_m = naoh_mol / volume_l;
+    let (h, oh, ionic_strength, gammas) =
+        strong_base_solution(naoh_m, kw, settings.ionic_strength_cap, activity_model);
+    let caustic_ledger = LedgerState {
+        naoh_remaining_mol: naoh_mol,
+        na2co3_mol: 0.0,
+        nahco3_mol: 0.0,
+        co2_excess_mol: 0.0,
+    };
+    let solve = LedgerSolve::new(settings, settings.pka2_value(), Some(volume_l));
+    let (ledger_ph, _, _, _) = estimate_ledger_ph(caustic_ledger, &solve, None, None, None);
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
     let out = PyDict::new(py);
-    let gamma_map = PyDict::new(py);
-    gamma_map.set_item("Na", gammas[0])?;
-    gamma_map.set_item("H", gammas[1])?;
-    gamma_map.set_item("HCO3", gammas[2])?;
-    gamma_map.set_item("CO3", gammas[3])?;
-    gamma_map.set_item("OH", gammas[4])?;
-    out.set_item("total_carbon_m", total_carbon)?;
-    out.set_item("h", h)?;
-    out.set_item("hco3", hco3)?;
-    out.set_item("co3", co3)?;
-    out.set_item("h2co3", h2co3)?;
+    out.set_item("ph", -h.max(1e-30).log10())?;
+    out.set_item("ph_scales", ph_scales_dict(py, h, gammas[1], None)?)?;
+    out.set_item("ideal_ph", pkw + naoh_m.log10())?;
+    out.set_item("ledger_ph", ledger_ph)?;
+    out.set_item("naoh_mol", naoh_mol)?;
+    out.set_item("naoh_m", naoh_m)?;
     out.set_item("oh", oh)?;
-    out.set_item("charge_balance_residual", charge_residual)?;
     out.set_item("ionic_strength", ionic_strength)?;
-    out.set_item("gammas", gamma_map)?;
+    out.set_item("activity_coefficients", activity_coefficients_dict(py, gammas)?)?;
+    out.set_item("pkw", pkw)?;
+    out.set_item("activity_model", activity_model_name(activity_model))?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
     Ok(out.unbind())
 }
 
+/// pH of sodium-free water equilibrated with CO2 at `pco2_atm` (about 3.9 at 1 atm
+/// and 25 °C): the acidic baseline before any caustic is charged.
+///
+/// Dissolved CO2 is pinned by Henry's law and the fixed-H2CO3 system is solved
+/// with zero sodium, skipping the ledger pH path and its planning rescues, which
+/// keep ledger results out of this range.
 #[pyfunction]
-#[pyo3(signature = (total_inorganic_carbon_m, ka1=SOL_KA1, ka2=SOL_KA2, kw=SOL_KW, ph_low=AQION_DEFAULT_PH_LOW, ph_high=AQION_DEFAULT_PH_HIGH))]
-fn aqion_closed_speciation_core(
-    py: Python<'_>,
-    total_inorganic_carbon_m: f64,
-    ka1: f64,
-    ka2: f64,
-    kw: f64,
-    ph_low: f64,
-    ph_high: f64,
+#[pyo3(signature = (temperature_c, pco2_atm=1.0, **options))]
+fn co2_saturated_water_ph(
+    py: Python<'_>,
+    temperature_c: f64,
+    pco2_atm: f64,
+    options: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<Py<PyDict>> {
-    let (
-        ph,
-        h,
-        oh,
-        h2co3,
-        hco3,
-        co3,
-        a0,
-        a1,
-        a2,
-        ionic_strength,
-        residual,
-        solver_tag,
-    ) = solve_aqion_closed_speciation(total_inorganic_carbon_m, ka1, ka2, kw, ph_low, ph_high)
+    let kwargs = KernelKwargs::new("co2_saturated_water_ph", options)?;
+    let settings = SolverSettings {
+        temperature_c: Some(temperature_c),
+        ..SolverSettings::take_equilibrium_from(&kwargs)?
+    };
+    kwargs.finish()?;
+    let clamp_bounds = settings.clamp_bounds;
+    if !temperature_c.is_finite() {
+        return Err(PyValueError::new_err("temperature_c must be finite"));
+    }
+    if !pco2_atm.is_finite() || pco2_atm <= 0.0 {
+        return Err(PyValueError::new_err("pco2_atm must be positive"));
+    }
+    let dissolved_co2_m =
+        henry_dissolved_co2(1.0, pco2_atm, clamp_bounds.temperature(temperature_c));
+    let (h, hco3, co3, h2co3, oh, gammas, ionic_strength, residual_norm) =
+        solve_carbonate_state_open(
+            CarbonateSystem::new(0.0, 0.0, settings.equilibrium_constants()),
+            settings.activity(),
+            dissolved_co2_m,
+            NewtonOptions::from_guess(4.0),
+        )
+        .map(|(solution, _)| solution)
         .map_err(PyRuntimeError::new_err)?;
+    let warnings = PyList::empty(py);
     let out = PyDict::new(py);
-    let species = PyDict::new(py);
-    species.set_item("H+", h)?;
-    species.set_item("H2CO3", h2co3)?;
-    species.set_item("HCO3-", hco3)?;
-    species.set_item("CO3^2-", co3)?;
-    species.set_item("OH-", oh)?;
-    let alpha = PyDict::new(py);
-    alpha.set_item("a0", a0)?;
-    alpha.set_item("a1", a1)?;
-    alpha.set_item("a2", a2)?;
-    out.set_item("ph", ph)?;
-    out.set_item("h_conc", h)?;
-    out.set_item("oh_conc", oh)?;
-    out.set_item("species_m", species)?;
-    out.set_item("alpha", alpha)?;
+    out.set_item("ph", -h.max(1e-30).log10())?;
+    out.set_item("ph_scales", ph_scales_dict(py, h, gammas[1], None)?)?;
+    out.set_item("pco2_atm", pco2_atm)?;
+    out.set_item("h2co3_m", h2co3)?;
+    out.set_item("hco3_m", hco3)?;
+    out.set_item("co3_m", co3)?;
+    out.set_item("oh_m", oh)?;
     out.set_item("ionic_strength", ionic_strength)?;
-    out.set_item("charge_balance_residual", residual)?;
-    out.set_item("solver", solver_tag)?;
+    out.set_item("residual_norm", residual_norm)?;
+    temperature_clamp_report(Some(temperature_c), clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
     Ok(out.unbind())
 }
 
+/// Infer total inorganic carbon from one pH reading at known sodium.
+///
+/// `tolerance` (default 1e-6) and `max_iter` (default 100) are keyword options next
+/// to the solver settings.
 #[pyfunction]
-#[pyo3(signature = (total_carbon_m, total_sodium_m, pitzer_params, max_iter=60))]
-fn pitzer_solve_total_carbon_core(
+#[pyo3(signature = (na_total_mol, measured_ph, solution_volume_l, **options))]
+fn infer_total_carbon(
     py: Python<'_>,
-    total_carbon_m: f64,
-    total_sodium_m: f64,
-    pitzer_params: &Bound<'_, PyDict>,
-    max_iter: usize,
+    na_total_mol: f64,
+    measured_ph: f64,
+    solution_volume_l: f64,
+    options: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<Option<Py<PyDict>>> {
-    if total_carbon_m < 0.0 || total_sodium_m <= 0.0 {
+    let kwargs = KernelKwargs::new("infer_total_carbon", options)?;
+    let tolerance: f64 = kwargs.take_or("tolerance", 1e-6)?;
+    let max_iter: usize = kwargs.take_or("max_iter", 100)?;
+    let settings = SolverSettings::take_equilibrium_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        activity_model,
+        clamp_bounds,
+        ..
+    } = settings;
+    if !na_total_mol.is_finite()
+        || na_total_mol < 0.0
+        || !solution_volume_l.is_finite()
+        || solution_volume_l <= 0.0
+    {
         return Ok(None);
     }
-    let parse = |key: &str| -> Option<f64> {
-        let value = pitzer_params.get_item(key).ok().flatten()?;
-        let parsed = value.extract::<f64>().ok()?;
-        if parsed.is_finite() {
-            Some(parsed)
-        } else {
-            None
-        }
-    };
-    let Some(b0_na_oh) = parse("b0_na_oh") else {
-        return Ok(None);
-    };
-    let Some(b1_na_oh) = parse("b1_na_oh") else {
-        return Ok(None);
-    };
-    let Some(c0_na_oh) = parse("c0_na_oh") else {
-        return Ok(None);
-    };
-    let Some(b0_na_hco3) = parse("b0_na_hco3") else {
-        return Ok(None);
-    };
-    let Some(b1_na_hco3) = parse("b1_na_hco3") else {
-        return Ok(None);
-    };
-    let Some(c0_na_hco3) = parse("c0_na_hco3") else {
+    let Some((total_carbon_m, solution, iterations)) = infer_total_carbon_impl(
+        na_total_mol / solution_volume_l,
+        measured_ph,
+        &settings,
+        tolerance.abs().max(1e-12),
+        max_iter,
+    ) else {
         return Ok(None);
     };
-    let Some(b0_na_co3) = parse("b0_na_co3") else {
-        return Ok(None);
-    };
-    let Some(b1_na_co3) = parse("b1_na_co3") else {
-        return Ok(None);
-    };
-    let Some(c0_na_co3) = parse("c0_na_co3") else {
+    let (h, hco3, co3, h2co3, _, _, ionic_strength, residual_norm) = solution;
+    let dic = (h2co3 + hco3 + co3).max(1e-30);
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let out = PyDict::new(py);
+    out.set_item("total_carbon_mol", total_carbon_m * solution_volume_l)?;
+    out.set_item("total_carbon_m", total_carbon_m)?;
+    out.set_item("total_carbon_g", total_carbon_m * solution_volume_l * SOL_MW_CO2)?;
+    out.set_item("h2co3_mol", h2co3 * solution_volume_l)?;
+    out.set_item("hco3_mol", hco3 * solution_volume_l)?;
+    out.set_item("co3_mol", co3 * solution_volume_l)?;
+    out.set_item("frac_h2co3", h2co3 / dic)?;
+    out.set_item("frac_hco3", hco3 / dic)?;
+    out.set_item("frac_co3", co3 / dic)?;
+    set_dominant_species(&out, [h2co3, hco3, co3])?;
+    out.set_item("solved_ph", -h.max(1e-30).log10())?;
+    out.set_item("measured_ph", measured_ph)?;
+    out.set_item("ionic_strength", ionic_strength)?;
+    out.set_item("iterations", iterations)?;
+    out.set_item("residual_norm", residual_norm)?;
+    out.set_item("activity_model", activity_model_name(activity_model))?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(Some(out.unbind()))
+}
+
+/// Solve the carbonate system from measured total alkalinity and DIC.
+///
+/// With sodium as the only cation the charge balance makes total alkalinity
+/// (`[HCO3-] + 2[CO3^2-] + [OH-] - [H+]`) equal to the sodium concentration, so the
+/// pair maps directly onto the closed-carbon solve. Negative alkalinity (mineral
+/// acidity) is outside the model; invalid inputs and failed solves return `None`.
+/// `initial_ph_guess` (default 8.35) is a keyword option next to the solver settings.
+#[pyfunction]
+#[pyo3(signature = (alkalinity_eq, dic_mol, volume_l, **options))]
+fn solve_from_alkalinity_dic(
+    py: Python<'_>,
+    alkalinity_eq: f64,
+    dic_mol: f64,
+    volume_l: f64,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Option<Py<PyDict>>> {
+    let kwargs = KernelKwargs::new("solve_from_alkalinity_dic", options)?;
+    let initial_ph_guess: f64 = kwargs.take_or("initial_ph_guess", 8.35)?;
+    let settings = SolverSettings::take_equilibrium_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        activity_model,
+        clamp_bounds,
+        ..
+    } = settings;
+    let inputs_valid = [alkalinity_eq, dic_mol]
+        .iter()
+        .all(|value| value.is_finite() && *value >= 0.0);
+    if !inputs_valid || !volume_l.is_finite() || volume_l <= 0.0 {
         return Ok(None);
-    };
-    let Some(theta_co3_oh) = parse("theta_co3_oh") else {
+    }
+    let alkalinity_m = alkalinity_eq / volume_l;
+    let dic_m = dic_mol / volume_l;
+    let Ok((h, hco3, co3, h2co3, oh, gammas, ionic_strength, residual_norm)) =
+        solve_carbonate_state(
+            CarbonateSystem::new(dic_m, alkalinity_m, settings.equilibrium_constants()),
+            settings.activity(),
+            NewtonOptions::from_guess(initial_ph_guess),
+        )
+    else {
         return Ok(None);
     };
-    let Some(psi_co3_na_oh) = parse("psi_co3_na_oh") else {
+    let dic = (h2co3 + hco3 + co3).max(1e-30);
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
+    let out = PyDict::new(py);
+    out.set_item("alkalinity_m", alkalinity_m)?;
+    out.set_item("dic_m", dic_m)?;
+    out.set_item("h", h)?;
+    out.set_item("hco3", hco3)?;
+    out.set_item("co3", co3)?;
+    out.set_item("h2co3", h2co3)?;
+    out.set_item("oh", oh)?;
+    out.set_item("frac_h2co3", h2co3 / dic)?;
+    out.set_item("frac_hco3", hco3 / dic)?;
+    out.set_item("frac_co3", co3 / dic)?;
+    set_dominant_species(&out, [h2co3, hco3, co3])?;
+    out.set_item("solved_ph", -h.max(1e-30).log10())?;
+    out.set_item("ph_scales", ph_scales_dict(py, h, gammas[1], None)?)?;
+    out.set_item("ionic_strength", ionic_strength)?;
+    out.set_item("activity_coefficients", activity_coefficients_dict(py, gammas)?)?;
+    out.set_item("residual_norm", residual_norm)?;
+    out.set_item("activity_model", activity_model_name(activity_model))?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
+    Ok(Some(out.unbind()))
+}
+
+/// Design the reagent charge for a target buffer: the NaOH that, once
+/// `target_dic_mol` of carbon is absorbed, leaves the liquor at `target_ph`.
+///
+/// The sodium comes from [`design_sodium_impl`]; the CO2 to charge is the target
+/// carbon divided by `absorption_efficiency`. `ledger` is the staged forward-model
+/// state of that charge. `absorption_efficiency` (default 1.0), `tolerance`
+/// (default 1e-6) and `max_iter` (default 100) are keyword options next to the
+/// solver settings. Unreachable targets and invalid inputs return `None`.
+#[pyfunction]
+#[pyo3(signature = (target_dic_mol, target_ph, volume_l, **options))]
+fn design_recipe(
+    py: Python<'_>,
+    target_dic_mol: f64,
+    target_ph: f64,
+    volume_l: f64,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Option<Py<PyDict>>> {
+    let kwargs = KernelKwargs::new("design_recipe", options)?;
+    let absorption_efficiency: f64 = kwargs.take_or("absorption_efficiency", 1.0)?;
+    let tolerance: f64 = kwargs.take_or("tolerance", 1e-6)?;
+    let max_iter: usize = kwargs.take_or("max_iter", 100)?;
+    let settings = SolverSettings::take_equilibrium_from(&kwargs)?;
+    kwargs.finish()?;
+    let SolverSettings {
+        temperature_c,
+        use_temp_adjusted_constants,
+        activity_model,
+        clamp_bounds,
+        ..
+    } = settings;
+    if !(absorption_efficiency > 0.0 && absorption_efficiency <= 1.0) {
+        return Err(PyValueError::new_err(
+            "absorption_efficiency must be greater than 0 and at most 1",
+        ));
+    }
+    if !target_dic_mol.is_finite()
+        || target_dic_mol <= 0.0
+        || !volume_l.is_finite()
+        || volume_l <= 0.0
+    {
         return Ok(None);
-    };
-    let Some(psi_co3_hco3_na) = parse("psi_co3_hco3_na") else {
+    }
+    let Some((na_conc, solution, iterations)) = design_sodium_impl(
+        target_dic_mol / volume_l,
+        target_ph,
+        &settings,
+        tolerance.abs().max(1e-12),
+        max_iter,
+    ) else {
         return Ok(None);
     };
-    let params = PitzerParamsLite {
-        b0_na_oh,
-        b1_na_oh,
-        c0_na_oh,
-        b0_na_hco3,
-        b1_na_hco3,
-        c0_na_hco3,
-        b0_na_co3,
-        b1_na_co3,
-        c0_na_co3,
-        theta_co3_oh,
-        psi_co3_na_oh,
-        psi_co3_hco3_na,
-    };
-    let solved = solve_pitzer_total_carbon_impl(total_carbon_m, total_sodium_m, params, max_iter);
-    let (ph, na, h, oh, hco3, co3, co2) = match solved {
-        Ok(value) => value,
-        Err(_) => return Ok(None),
-    };
-    if !ph.is_finite() {
-        return Ok(None);
-    }
+    let (h, hco3, co3, h2co3, _, _, ionic_strength, residual_norm) = solution;
+    let solved_ph = -h.max(1e-30).log10();
+    let naoh_mol = na_conc * volume_l;
+    let co2_charged_mol = target_dic_mol / absorption_efficiency;
+    let ledger = charged_ledger(naoh_mol, target_dic_mol);
+    let dic = (h2co3 + hco3 + co3).max(1e-30);
+    let warnings = PyList::empty(py);
+    missing_temperature_warning(temperature_c, use_temp_adjusted_constants, &warnings)?;
     let out = PyDict::new(py);
-    out.set_item("ph", ph)?;
-    out.set_item("m_Na", na)?;
-    out.set_item("m_H", h)?;
-    out.set_item("m_OH", oh)?;
-    out.set_item("m_HCO3", hco3)?;
-    out.set_item("m_CO3", co3)?;
-    out.set_item("m_CO2", co2)?;
-    out.set_item("Na+", na)?;
-    out.set_item("H+", h)?;
-    out.set_item("OH-", oh)?;
-    out.set_item("HCO3-", hco3)?;
-    out.set_item("CO3-2", co3)?;
-    out.set_item("CO2", co2)?;
+    out.set_item("naoh_mol", naoh_mol)?;
+    out.set_item("naoh_g", naoh_mol * SOL_MW_NAOH)?;
+    out.set_item("co2_mol", co2_charged_mol)?;
+    out.set_item("co2_g", co2_charged_mol * SOL_MW_CO2)?;
+    out.set_item("absorbed_co2_mol", target_dic_mol)?;
+    out.set_item("na_conc", na_conc)?;
+    out.set_item("frac_h2co3", h2co3 / dic)?;
+    out.set_item("frac_hco3", hco3 / dic)?;
+    out.set_item("frac_co3", co3 / dic)?;
+    set_dominant_species(&out, [h2co3, hco3, co3])?;
+    out.set_item("solved_ph", solved_ph)?;
+    out.set_item("target_ph", target_ph)?;
+    out.set_item("ledger", ledger_state_dict(py, ledger, solved_ph)?)?;
+    out.set_item("ionic_strength", ionic_strength)?;
+    out.set_item("iterations", iterations)?;
+    out.set_item("residual_norm", residual_norm)?;
+    out.set_item("activity_model", activity_model_name(activity_model))?;
+    temperature_clamp_report(temperature_c, clamp_bounds, &out, &warnings)?;
+    out.set_item("warnings", warnings)?;
     Ok(Some(out.unbind()))
 }
 
@@ -5609,17 +12103,60 @@ fn cycle_metrics_core(
 }
 
 #[pymodule(gil_used = false)]
-fn gl260_rust_ext(_py: Python<'_>, module: &Bound<'_, PyModule>) -> PyResult<()> {
+fn gl260_rust_ext(py: Python<'_>, module: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Forward solver events to Python `logging`; levels are cached per logger, so
+    // disabled records stay cheap. A host that already installed a `log` backend
+    // keeps it.
+    let _ = pyo3_log::Logger::new(py, pyo3_log::Caching::LoggersAndLevels)?
+        .filter(log::LevelFilter::Trace)
+        .install();
+    module.add("PH_CLAMP_MIN", PH_CLAMP_MIN)?;
+    module.add("PH_CLAMP_MAX", PH_CLAMP_MAX)?;
+    module.add("TEMPERATURE_CLAMP_MIN_C", TEMPERATURE_CLAMP_MIN_C)?;
+    module.add("TEMPERATURE_CLAMP_MAX_C", TEMPERATURE_CLAMP_MAX_C)?;
     module.add_function(wrap_pyfunction!(rust_backend_manifest, module)?)?;
+    module.add_function(wrap_pyfunction!(model_info, module)?)?;
+    module.add_function(wrap_pyfunction!(self_test, module)?)?;
     module.add_function(wrap_pyfunction!(
         simulate_reaction_state_with_accounting,
         module
     )?)?;
+    module.add_function(wrap_pyfunction!(compare_planning_modes, module)?)?;
+    module.add_function(wrap_pyfunction!(ph_vs_temperature, module)?)?;
+    module.add_function(wrap_pyfunction!(dose_overshoot, module)?)?;
+    module.add_function(wrap_pyfunction!(titrate, module)?)?;
+    module.add_function(wrap_pyfunction!(add_salt, module)?)?;
+    module.add_function(wrap_pyfunction!(blend, module)?)?;
+    module.add_function(wrap_pyfunction!(dilute, module)?)?;
+    module.add_function(wrap_pyfunction!(degas, module)?)?;
+    module.add_class::<DosingSession>()?;
     module.add_function(wrap_pyfunction!(analyze_bicarbonate_core, module)?)?;
+    module.add_function(wrap_pyfunction!(analyze_bicarbonate_json, module)?)?;
+    module.add_function(wrap_pyfunction!(evaluate_grid, module)?)?;
     module.add_function(wrap_pyfunction!(carbonate_state_core, module)?)?;
     module.add_function(wrap_pyfunction!(forced_ph_distribution_core, module)?)?;
     module.add_function(wrap_pyfunction!(aqion_closed_speciation_core, module)?)?;
     module.add_function(wrap_pyfunction!(pitzer_solve_total_carbon_core, module)?)?;
+    module.add_function(wrap_pyfunction!(fit_absorption_efficiency, module)?)?;
+    module.add_function(wrap_pyfunction!(carbonate_buffer_window_core, module)?)?;
+    module.add_function(wrap_pyfunction!(acceptable_co2_window, module)?)?;
+    module.add_function(wrap_pyfunction!(minimum_buffer_point, module)?)?;
+    module.add_function(wrap_pyfunction!(titration_sharpness, module)?)?;
+    module.add_function(wrap_pyfunction!(adaptive_dose_curve, module)?)?;
+    module.add_function(wrap_pyfunction!(ph_sensitivity_to_co2, module)?)?;
+    module.add_function(wrap_pyfunction!(equivalence_point_phs, module)?)?;
+    module.add_function(wrap_pyfunction!(ph_vs_concentration_scale, module)?)?;
+    module.add_function(wrap_pyfunction!(speciation_distribution, module)?)?;
+    module.add_function(wrap_pyfunction!(carbonate_ratio_from_ph, module)?)?;
+    module.add_function(wrap_pyfunction!(ph_from_carbonate_ratio, module)?)?;
+    module.add_function(wrap_pyfunction!(activity_coefficient, module)?)?;
+    module.add_function(wrap_pyfunction!(co2_to_neutralize_caustic, module)?)?;
+    module.add_function(wrap_pyfunction!(target_carbonate_ratio, module)?)?;
+    module.add_function(wrap_pyfunction!(max_ph, module)?)?;
+    module.add_function(wrap_pyfunction!(co2_saturated_water_ph, module)?)?;
+    module.add_function(wrap_pyfunction!(infer_total_carbon, module)?)?;
+    module.add_function(wrap_pyfunction!(solve_from_alkalinity_dic, module)?)?;
+    module.add_function(wrap_pyfunction!(design_recipe, module)?)?;
     module.add_function(wrap_pyfunction!(combined_decimation_indices, module)?)?;
     module.add_function(wrap_pyfunction!(combined_required_indices, module)?)?;
     module.add_function(wrap_pyfunction!(cycle_detect_markers_core, module)?)?;
@@ -5632,6 +12169,7 @@ fn gl260_rust_ext(_py: Python<'_>, module: &Bound<'_, PyModule>) -> PyResult<()>
         analysis_interpolate_reference_series_core,
         module
     )?)?;
+    module.add_function(wrap_pyfunction!(interpolate_curve, module)?)?;
     module.add_function(wrap_pyfunction!(analysis_dashboard_core, module)?)?;
     module.add_function(wrap_pyfunction!(
         measured_ph_uptake_calibration_core,
@@ -5660,3 +12198,628 @@ fn gl260_rust_ext(_py: Python<'_>, module: &Bound<'_, PyModule>) -> PyResult<()>
     module.add_function(wrap_pyfunction!(reaction_dashboard_core, module)?)?;
     Ok(())
 }
+
+/// Pure-Rust entry points for the criterion benches under `benches/`, which link
+/// the `rlib` and cannot call the pyo3 kernels without an interpreter. Each mirrors
+/// the solver work of the kernel it is named after, minus the dict marshaling.
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench_support {
+    use super::*;
+
+    pub const MW_NAOH: f64 = SOL_MW_NAOH;
+    pub const MW_CO2: f64 = SOL_MW_CO2;
+
+    /// Two-stage NaOH/CO2 ledger `(naoh, na2co3, nahco3, co2_excess)` for one charge,
+    /// as `analyze_bicarbonate_core` stages it without seeded pools.
+    pub fn staged_ledger_mol(naoh_mol: f64, co2_mol: f64) -> (f64, f64, f64, f64) {
+        let (state, _) = staged_ledger(naoh_mol, co2_mol, (0.0, 0.0));
+        (state.naoh_remaining_mol, state.na2co3_mol, state.nahco3_mol, state.co2_excess_mol)
+    }
+
+    /// One closed-system `solve_carbonate_state` at the 25 C constants; returns pH.
+    pub fn solve_closed_ph(total_carbon_m: f64, na_conc: f64) -> f64 {
+        let (h, ..) = solve_carbonate_state(
+            CarbonateSystem::new(total_carbon_m, na_conc, (SOL_KA1, SOL_KA2, SOL_KW)),
+            ActivityTreatment::new(ActivityModel::Hybrid, None),
+            NewtonOptions::from_guess(8.35),
+        )
+        .unwrap_or_else(|err| panic!("bench solve failed: {err}"));
+        -h.log10()
+    }
+
+    /// `points` ledger pH solves dosing `0..=total_delta_mol` CO2 onto `ledger`,
+    /// each seeded from the previous point as the analysis and dosing curves do.
+    pub fn dosing_curve_ph(
+        ledger: (f64, f64, f64, f64),
+        total_delta_mol: f64,
+        points: usize,
+        solution_volume_l: f64,
+    ) -> Vec<f64> {
+        let (naoh_remaining_mol, na2co3_mol, nahco3_mol, co2_excess_mol) = ledger;
+        let start = LedgerState {
+            naoh_remaining_mol,
+            na2co3_mol,
+            nahco3_mol,
+            co2_excess_mol,
+        };
+        let settings = SolverSettings {
+            temperature_c: Some(25.0),
+            use_temp_adjusted_constants: true,
+            ..SolverSettings::default()
+        };
+        let solve = LedgerSolve::new(settings, settings.pka2_value(), Some(solution_volume_l));
+        let steps = points.max(2) - 1;
+        let mut guess = None;
+        (0..=steps)
+            .map(|idx| {
+                let (_, _, ph, _, _, _) = simulate_reaction_state_with_accounting_impl(
+                    start,
+                    total_delta_mol * idx as f64 / steps as f64,
+                    &solve,
+                    guess,
+                    None,
+                    None,
+                );
+                guess = Some(ph);
+                ph
+            })
+            .collect()
+    }
+
+    /// `analyze_bicarbonate_core` at 25 C with full absorption and no feed impurities:
+    /// the staged ledger, the Henderson-Hasselbalch plan toward `target_ph`, the
+    /// predicted post-dose solve and the 13-point simulation curve. Returns the
+    /// predicted pH and the curve pH.
+    pub fn analyze_bicarbonate_ph(
+        naoh_mass_g: f64,
+        co2_charged_g: f64,
+        solution_volume_l: f64,
+        target_ph: f64,
+    ) -> (f64, Vec<f64>) {
+        let (start, _) =
+            staged_ledger(naoh_mass_g / SOL_MW_NAOH, co2_charged_g / SOL_MW_CO2, (0.0, 0.0));
+        let settings = SolverSettings {
+            temperature_c: Some(25.0),
+            use_temp_adjusted_constants: true,
+            ..SolverSettings::default()
+        };
+        let pka2_value = settings.pka2_value();
+        let ratio_target = hh_carbonate_ratio(target_ph, pka2_value);
+        let numerator = start.na2co3_mol - ratio_target * start.nahco3_mol;
+        let co2_for_ratio = if numerator > 0.0 {
+            (numerator / (1.0 + 2.0 * ratio_target)).min(start.na2co3_mol)
+        } else {
+            0.0
+        };
+        let total_extra_mol = co2_for_ratio + start.naoh_remaining_mol / 2.0;
+        let solve = LedgerSolve::new(settings, pka2_value, Some(solution_volume_l));
+        let (_, _, predicted_ph, _, _, _) = simulate_reaction_state_with_accounting_impl(
+            start,
+            total_extra_mol,
+            &solve,
+            Some(target_ph),
+            None,
+            None,
+        );
+        let slider_max_g = (total_extra_mol * SOL_MW_CO2 * 1.6).max(2.0);
+        let mut guess = target_ph;
+        let curve = (0..=12)
+            .map(|idx| {
+                let delta_mol = slider_max_g * (idx as f64 / 12.0) / SOL_MW_CO2;
+                let (_, _, ph, _, _, _) = simulate_reaction_state_with_accounting_impl(
+                    start,
+                    delta_mol,
+                    &solve,
+                    Some(guess),
+                    None,
+                    None,
+                );
+                guess = ph;
+                ph
+            })
+            .collect();
+        (predicted_ph, curve)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ledger(naoh: f64, na2co3: f64, nahco3: f64, co2_excess: f64) -> LedgerState {
+        LedgerState {
+            naoh_remaining_mol: naoh,
+            na2co3_mol: na2co3,
+            nahco3_mol: nahco3,
+            co2_excess_mol: co2_excess,
+        }
+    }
+
+    /// Actual-mode solve on the default settings.
+    fn default_solve(volume_l: Option<f64>) -> LedgerSolve {
+        let settings = SolverSettings::default();
+        LedgerSolve::new(settings, settings.pka2_value(), volume_l)
+    }
+
+    fn ledger_ph(state: LedgerState, volume_l: f64) -> (f64, Option<f64>) {
+        let (ph, residual_norm, _, _) = estimate_ledger_ph(
+            state,
+            &default_solve(Some(volume_l)),
+            None,
+            None,
+            None,
+        );
+        (ph, residual_norm)
+    }
+
+    #[test]
+    fn shared_solver_types_are_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<LedgerState>();
+        assert_send_sync::<AccountingState>();
+        assert_send_sync::<PlanningAdjustment>();
+        assert_send_sync::<CarbonateSolution>();
+        assert_send_sync::<ClampBounds>();
+        assert_send_sync::<ActivityTreatment>();
+        assert_send_sync::<ActivityModel>();
+        assert_send_sync::<ExcessCo2Fate>();
+        assert_send_sync::<CarbonateSystem>();
+        assert_send_sync::<NewtonFailure>();
+    }
+
+    #[test]
+    fn sodium_free_excess_co2_is_acidic() {
+        // 10 mM dissolved CO2 in water sits near pH 4.2, far below the plateau floor.
+        let (ph, _) = ledger_ph(ledger(0.0, 0.0, 0.0, 0.01), 1.0);
+        assert!(ph.is_finite());
+        assert!((3.9..4.5).contains(&ph), "pH {ph}");
+        assert!(ph < PLANNING_PLATEAU_PH_MIN);
+    }
+
+    #[test]
+    fn femtomolar_carbon_solves_through_dilute_branch() {
+        for na_conc in [0.0, 1e-6, 1e-3] {
+            let solved = solve_carbonate_state(
+                CarbonateSystem::new(1e-15, na_conc, (SOL_KA1, SOL_KA2, SOL_KW)),
+                ActivityTreatment::new(ActivityModel::Hybrid, None),
+                NewtonOptions::from_guess(7.0),
+            );
+            let (h, hco3, co3, h2co3, oh, _, ionic_strength, residual_norm) =
+                solved.unwrap_or_else(|err| panic!("Na {na_conc}: {err}"));
+            for value in [h, hco3, co3, h2co3, oh, ionic_strength, residual_norm] {
+                assert!(value.is_finite(), "Na {na_conc}: non-finite state");
+            }
+            assert!(residual_norm < SOL_NEWTON_RELAXED_TOL, "Na {na_conc}: {residual_norm}");
+            assert!((hco3 + co3 + h2co3 - 1e-15).abs() < 1e-18);
+        }
+    }
+
+    #[test]
+    fn non_physical_concentrations_fail_without_panicking() {
+        // 0.1 mol over a 1e-300 L volume: every composition-scaled log floor would
+        // sit far above the shared upper edge without its cap.
+        let absurd_m = 0.1 / 1e-300;
+        let ammonia = AmmoniaBuffer {
+            total_m: absurd_m,
+            ka: 10f64.powf(-9.25),
+        };
+        let phosphate = PhosphateBuffer {
+            total_m: absurd_m,
+            ka: [
+                PHOSPHATE_PKA_DEFAULT.0,
+                PHOSPHATE_PKA_DEFAULT.1,
+                PHOSPHATE_PKA_DEFAULT.2,
+            ]
+            .map(|pka| 10f64.powf(-pka)),
+        };
+        for (ammonia, phosphate) in [(None, None), (Some(ammonia), None), (None, Some(phosphate))] {
+            let solved = std::panic::catch_unwind(|| {
+                solve_carbonate_state(
+                    CarbonateSystem {
+                        ammonia,
+                        phosphate,
+                        ..CarbonateSystem::new(absurd_m, absurd_m, (SOL_KA1, SOL_KA2, SOL_KW))
+                    },
+                    ActivityTreatment::new(ActivityModel::Hybrid, None),
+                    NewtonOptions::from_guess(8.35),
+                )
+            });
+            let solved = solved.expect("solver panicked on a non-physical concentration");
+            assert!(solved.is_err(), "expected an error, got {:?}", solved.map(|sol| sol.0));
+        }
+    }
+
+    #[test]
+    fn planning_curve_steps_are_bounded_across_equivalence() {
+        // 0.2 mol NaOH in 1 L reaches carbonate-only equivalence at 0.1 mol CO2.
+        let caustic = ledger(0.2, 0.0, 0.0, 0.0);
+        for width in [PLANNING_TRANSITION_WIDTH, 0.05] {
+            let solve = LedgerSolve {
+                planning_mode: true,
+                planning_transition_width: width,
+                ..default_solve(Some(1.0))
+            };
+            let mut previous: Option<f64> = None;
+            for step in 0..=400 {
+                let delta_mol = 0.099 + step as f64 * 1e-5;
+                let (_, _, ph, _, _, _) = simulate_reaction_state_with_accounting_impl(
+                    caustic,
+                    delta_mol,
+                    &solve,
+                    previous,
+                    None,
+                    None,
+                );
+                assert!(ph.is_finite());
+                if let Some(last) = previous {
+                    assert!(ph <= last, "width {width}: pH rose {last} -> {ph} at {delta_mol}");
+                    assert!(last - ph < 0.05, "width {width}: step {last} -> {ph}");
+                }
+                previous = Some(ph);
+            }
+        }
+    }
+
+    #[test]
+    fn pure_carbonate_ph_follows_hydrolysis() {
+        // 0.1 M Na2CO3 with no bicarbonate: Kb = Kw/Ka2 hydrolysis puts it near pH 11.65.
+        let pka2 = resolve_pka2_value(None, false);
+        let soda = ledger(0.0, 0.1, 0.0, 0.0);
+        let expected = carbonate_hydrolysis_ph(0.1, 0.0, 10f64.powf(-pka2), SOL_KW);
+        assert!((11.55..11.75).contains(&expected), "hydrolysis pH {expected}");
+        let ph_for = |volume_l: Option<f64>, activity_model: ActivityModel| {
+            let mut solve = default_solve(volume_l);
+            solve.settings.activity_model = activity_model;
+            estimate_ledger_ph(soda, &solve, None, None, None).0
+        };
+        // Without a volume the estimate is the hydrolysis fallback itself, not a
+        // pKa2 + log10(ratio) reading against a 1e-12 bicarbonate denominator.
+        let fallback = ph_for(None, ActivityModel::Hybrid);
+        assert!((fallback - expected).abs() < 1e-9, "fallback pH {fallback}");
+        let solved = ph_for(Some(1.0), ActivityModel::Ideal);
+        assert!((solved - expected).abs() < 0.02, "ideal solver pH {solved}");
+    }
+
+    #[test]
+    fn over_carbonated_dosing_falls_below_neutral() {
+        // 0.2 mol NaOH in 1 L is all bicarbonate at 0.2 mol CO2; the rest stays dissolved.
+        let caustic = ledger(0.2, 0.0, 0.0, 0.0);
+        for planning in [false, true] {
+            let solve = LedgerSolve {
+                planning_mode: planning,
+                ..default_solve(Some(1.0))
+            };
+            let mut previous = f64::INFINITY;
+            for delta_mol in [0.25, 0.3, 0.5, 1.0] {
+                let (state, _, ph, _, _, _) = simulate_reaction_state_with_accounting_impl(
+                    caustic,
+                    delta_mol,
+                    &solve,
+                    None,
+                    None,
+                    None,
+                );
+                assert!(state.co2_excess_mol > 0.0);
+                assert!(ph < 7.0, "planning {planning}: pH {ph} at {delta_mol} mol");
+                assert!(ph < previous, "planning {planning}: {previous} -> {ph}");
+                previous = ph;
+            }
+        }
+    }
+
+    #[test]
+    fn trace_h2co3_mass_action_row_vanishes_at_the_root() {
+        // 1 uM carbon in 1 M NaOH leaves H2CO3 near 1e-18 M, below the old 1e-16 floor.
+        let (total_carbon_m, na_conc) = (1e-6, 1.0);
+        let solved = solve_carbonate_state(
+            CarbonateSystem::new(total_carbon_m, na_conc, (SOL_KA1, SOL_KA2, SOL_KW)),
+            ActivityTreatment::new(ActivityModel::Hybrid, None),
+            NewtonOptions::from_guess(13.0),
+        );
+        let (h, hco3, co3, h2co3, ..) = solved.unwrap_or_else(|err| panic!("{err}"));
+        assert!(h2co3 > 0.0 && h2co3 < 1e-16, "H2CO3 {h2co3:e}");
+        let residuals = closed_carbonate_residuals(
+            CarbonateSystem::new(total_carbon_m, na_conc, (SOL_KA1, SOL_KA2, SOL_KW)),
+            ActivityTreatment::new(ActivityModel::Hybrid, None),
+        );
+        let rows = residuals(&[h.log10(), hco3.log10(), co3.log10(), h2co3.log10()]);
+        assert!(rows.iter().all(|row| row.abs() < 1e-9), "rows {rows:?}");
+    }
+
+    #[test]
+    fn seeded_pools_plus_charged_co2_balance() {
+        // 1 mol NaOH with 0.6 mol of its sodium already bound in the seeded pools.
+        let (na2co3, nahco3) = (0.2, 0.2);
+        let naoh_mol = 1.0;
+        let free_naoh = naoh_mol - (2.0 * na2co3 + nahco3);
+        for co2_mol in [0.0, 0.1, 0.5, 1.5] {
+            let (state, _) = staged_ledger(free_naoh, co2_mol, (na2co3, nahco3));
+            let sodium = ledger_sodium_mol(state);
+            let carbon = ledger_carbon_mol(state);
+            assert!((sodium - naoh_mol).abs() < 1e-12, "sodium {sodium} at {co2_mol}");
+            let carbon_in = co2_mol + na2co3 + nahco3;
+            assert!((carbon - carbon_in).abs() < 1e-12, "carbon {carbon} at {co2_mol}");
+        }
+    }
+
+    #[test]
+    fn pchip_curve_stays_monotone_between_monotone_knots() {
+        // A titration-like drop: flat caustic shelf, steep equivalence, flat plateau.
+        let xs = [0.0, 1.0, 2.0, 2.5, 3.0, 4.0, 6.0];
+        let ys = [13.0, 12.9, 12.2, 10.1, 8.6, 8.4, 8.3];
+        for (x, y) in xs.iter().zip(ys) {
+            assert_eq!(pchip_interpolate(&xs, &ys, *x), (y, false));
+        }
+        let mut previous = f64::INFINITY;
+        for step in 0..=600 {
+            let x = step as f64 * 0.01;
+            let (ph, extrapolated) = pchip_interpolate(&xs, &ys, x);
+            assert!(!extrapolated);
+            assert!(ph <= previous, "rose {previous} -> {ph} at {x}");
+            let k = xs.partition_point(|knot| *knot <= x).clamp(1, xs.len() - 1);
+            assert!((ys[k]..=ys[k - 1]).contains(&ph), "overshoot {ph} at {x}");
+            previous = ph;
+        }
+        assert_eq!(pchip_interpolate(&xs, &ys, -1.0), (13.0, true));
+        assert_eq!(pchip_interpolate(&xs, &ys, 7.0), (8.3, true));
+    }
+
+    #[test]
+    fn inferred_total_carbon_round_trips_a_forward_solve() {
+        let settings = SolverSettings::default();
+        let na_conc = 1.0;
+        for total_carbon_m in [0.1, 0.55, 0.8, 1.05] {
+            let forward = solve_carbonate_state(
+                CarbonateSystem::new(total_carbon_m, na_conc, settings.equilibrium_constants()),
+                settings.activity(),
+                NewtonOptions::from_guess(9.0),
+            )
+            .unwrap_or_else(|err| panic!("C_T {total_carbon_m}: {err}"));
+            let measured_ph = -forward.0.log10();
+            let (inferred, solution, _) =
+                infer_total_carbon_impl(na_conc, measured_ph, &settings, 1e-10, 200)
+                    .unwrap_or_else(|| panic!("C_T {total_carbon_m}: pH {measured_ph} outside"));
+            let rel_err = (inferred - total_carbon_m).abs() / total_carbon_m;
+            assert!(rel_err < 1e-6, "C_T {total_carbon_m}: inferred {inferred}");
+            assert!((-solution.0.log10() - measured_ph).abs() <= 1e-10);
+        }
+        assert!(infer_total_carbon_impl(na_conc, 15.0, &settings, 1e-10, 200).is_none());
+    }
+
+    #[test]
+    fn bisection_fallback_matches_newton() {
+        let activity = ActivityTreatment::new(ActivityModel::Hybrid, None);
+        for (total_carbon_m, na_conc) in [(0.05, 1.0), (0.5, 1.0), (0.7, 1.0), (1.0, 1.0)] {
+            let system = CarbonateSystem::new(total_carbon_m, na_conc, (SOL_KA1, SOL_KA2, SOL_KW));
+            let newton = solve_carbonate_state(system, activity, NewtonOptions::from_guess(9.0))
+                .unwrap_or_else(|err| panic!("C_T {total_carbon_m}: {err}"));
+            let (h, hco3, co3, h2co3, ..) = solve_carbonate_state_bisection(system, activity);
+            let gap = (h.log10() - newton.0.log10()).abs();
+            assert!(gap < 1e-8, "C_T {total_carbon_m}: pH gap {gap:e}");
+            assert!((h2co3 + hco3 + co3 - total_carbon_m).abs() < 1e-12 * total_carbon_m);
+        }
+    }
+
+    #[test]
+    fn physical_gate_rejects_broken_balances() {
+        let (total_carbon_m, na_conc) = (0.7, 1.0);
+        let (h, hco3, co3, h2co3, oh, ..) = solve_carbonate_state(
+            CarbonateSystem::new(total_carbon_m, na_conc, (SOL_KA1, SOL_KA2, SOL_KW)),
+            ActivityTreatment::new(ActivityModel::Hybrid, None),
+            NewtonOptions::from_guess(9.0),
+        )
+        .unwrap_or_else(|err| panic!("{err}"));
+        let gate = |species| carbonate_solution_is_physical(total_carbon_m, na_conc, species, 0.0);
+        assert!(gate((h, hco3, co3, h2co3, oh)));
+        // CO3 above total carbon, a negative pool, a non-finite pool, and a charge
+        // imbalance from sodium the anions do not match.
+        assert!(!gate((h, hco3, 1.5 * total_carbon_m, h2co3, oh)));
+        assert!(!gate((h, hco3 + h2co3, co3, -h2co3, oh)));
+        assert!(!gate((h, hco3, co3, f64::NAN, oh)));
+        assert!(!carbonate_solution_is_physical(
+            total_carbon_m,
+            1.1 * na_conc,
+            (h, hco3, co3, h2co3, oh),
+            0.0,
+        ));
+    }
+
+    #[test]
+    fn proton_condition_matches_the_newton_system() {
+        for activity_model in [ActivityModel::Hybrid, ActivityModel::Ideal] {
+            let activity = ActivityTreatment::new(activity_model, None);
+            for (total_carbon_m, na_conc) in [(0.05, 1.0), (0.7, 1.0), (1.0, 1.0), (0.01, 0.01)] {
+                let system =
+                    CarbonateSystem::new(total_carbon_m, na_conc, (SOL_KA1, SOL_KA2, SOL_KW));
+                let newton =
+                    solve_carbonate_state(system, activity, NewtonOptions::from_guess(9.0))
+                        .unwrap_or_else(|err| panic!("C_T {total_carbon_m}: {err}"));
+                let proton = solve_carbonate_state_proton_condition(system, activity)
+                    .unwrap_or_else(|err| panic!("C_T {total_carbon_m}: {err}"));
+                let gap = (proton.0.log10() - newton.0.log10()).abs();
+                assert!(gap < 1e-8, "C_T {total_carbon_m}: pH gap {gap:e}");
+                assert!(proton.7 < SOL_NEWTON_RELAXED_TOL, "residual {:e}", proton.7);
+            }
+        }
+    }
+
+    #[test]
+    fn newton_retries_from_perturbed_points_recover_the_root() {
+        let (total_carbon_m, na_conc) = (0.7, 1.0);
+        let system = CarbonateSystem::new(total_carbon_m, na_conc, (SOL_KA1, SOL_KA2, SOL_KW));
+        let activity = ActivityTreatment::new(ActivityModel::Hybrid, None);
+        let traced = solve_carbonate_state_traced(system, activity, NewtonOptions::from_guess(9.0));
+        let root = traced.unwrap_or_else(|err| panic!("{err}")).1.expect("Newton root vector");
+        let bounds = closed_carbonate_log_bounds(total_carbon_m, na_conc, SOL_KW);
+        let residuals = closed_carbonate_residuals(system, activity);
+        let mut seen: Vec<Vec<f64>> = Vec::new();
+        for attempt in 0..2 * SOL_NEWTON_RETRY_ATTEMPTS {
+            let nudged = perturbed_log_point(&root, &bounds, attempt);
+            let expected_step = SOL_NEWTON_RETRY_STEP * (attempt / 2 + 1) as f64;
+            for (moved, base) in nudged.iter().zip(&root) {
+                assert!(((moved - base).abs() - expected_step).abs() < 1e-12);
+            }
+            assert!(!seen.contains(&nudged), "attempt {attempt} repeats an earlier point");
+            let (sol, _) = newton_system_solve(
+                &residuals,
+                nudged.clone(),
+                &bounds,
+                SOL_NEWTON_RELAXED_TOL,
+                60,
+                None,
+                None,
+            )
+            .unwrap_or_else(|err| panic!("attempt {attempt}: {err}"));
+            assert!((sol[0] - root[0]).abs() < 1e-8, "attempt {attempt}: {sol:?}");
+            seen.push(nudged);
+        }
+    }
+
+    #[test]
+    fn ph_sensitivity_matches_a_central_difference() {
+        let settings = SolverSettings::default();
+        let na_conc = 1.0;
+        let solved_at = |total_carbon_m: f64| {
+            solve_carbonate_state(
+                CarbonateSystem::new(total_carbon_m, na_conc, settings.equilibrium_constants()),
+                settings.activity(),
+                NewtonOptions::from_guess(9.0),
+            )
+            .unwrap_or_else(|err| panic!("C_T {total_carbon_m}: {err}"))
+        };
+        for total_carbon_m in [0.2, 0.6, 0.8, 1.02] {
+            let slope = carbonate_ph_sensitivity(
+                &solved_at(total_carbon_m),
+                total_carbon_m,
+                na_conc,
+                &settings,
+            )
+            .expect("non-singular Jacobian");
+            let step = 1e-5;
+            let ph = |total_carbon_m: f64| -solved_at(total_carbon_m).0.log10();
+            let difference = (ph(total_carbon_m + step) - ph(total_carbon_m - step)) / (2.0 * step);
+            assert!(slope < 0.0, "C_T {total_carbon_m}: slope {slope}");
+            let rel_err = (slope - difference).abs() / difference.abs();
+            assert!(rel_err < 1e-3, "C_T {total_carbon_m}: {slope} vs {difference}");
+        }
+    }
+
+    #[test]
+    fn phosphate_co_buffer_closes_its_balances() {
+        let (total_carbon_m, na_conc) = (0.7, 1.0);
+        let activity = ActivityTreatment::new(ActivityModel::Hybrid, None);
+        let buffered = |total_m: f64| CarbonateSystem {
+            phosphate: Some(PhosphateBuffer {
+                total_m,
+                ka: [
+                    PHOSPHATE_PKA_DEFAULT.0,
+                    PHOSPHATE_PKA_DEFAULT.1,
+                    PHOSPHATE_PKA_DEFAULT.2,
+                ]
+                .map(|pka| 10f64.powf(-pka)),
+            }),
+            ..CarbonateSystem::new(total_carbon_m, na_conc, (SOL_KA1, SOL_KA2, SOL_KW))
+        };
+        let solve = |system: CarbonateSystem| {
+            let solved = solve_carbonate_state(system, activity, NewtonOptions::from_guess(9.0));
+            solved.unwrap_or_else(|err| panic!("{err}"))
+        };
+        let carbonate_only = solve(CarbonateSystem::new(
+            total_carbon_m,
+            na_conc,
+            (SOL_KA1, SOL_KA2, SOL_KW),
+        ));
+        let trace = solve(buffered(1e-14));
+        assert!((trace.0.log10() - carbonate_only.0.log10()).abs() < 1e-9);
+        let system = buffered(0.05);
+        let (h, hco3, co3, _, oh, gammas, ..) = solve(system);
+        // Phosphate anions take up sodium the carbonate pools would otherwise hold.
+        assert!(h > carbonate_only.0, "phosphate raised pH");
+        let species = system.phosphate.expect("phosphate").species_m(h, &gammas);
+        assert!((species.iter().sum::<f64>() - 0.05).abs() < 1e-12);
+        let charge = na_conc + h - hco3 - 2.0 * co3 - oh - phosphate_anion_charge(&species);
+        assert!(charge.abs() < 1e-9, "charge imbalance {charge:e}");
+        let (bisected_h, ..) = solve_carbonate_state_bisection(system, activity);
+        assert!((bisected_h.log10() - h.log10()).abs() < 1e-8);
+    }
+
+    #[test]
+    fn adaptive_dose_curve_meets_its_ph_resolution() {
+        // 0.2 mol NaOH in 1 L dosed to 1.5x its bicarbonate point over 9 coarse points.
+        let sweep = Co2DoseSweep::new(ledger(0.2, 0.0, 0.0, 0.0), SolverSettings::default(), 1.0);
+        let max_ph_step = 0.1;
+        let (coarse, mut max_residual) = co2_dose_curve(&sweep, 0.3 * SOL_MW_CO2 / 8.0, 9);
+        let mut curve = vec![(coarse[0].0, coarse[0].1)];
+        let mut unresolved = 0;
+        for window in coarse.windows(2) {
+            let (lo, hi) = ((window[0].0, window[0].1), (window[1].0, window[1].1));
+            unresolved += refine_co2_dose_interval(
+                &sweep,
+                lo,
+                hi,
+                max_ph_step,
+                12,
+                &mut curve,
+                &mut max_residual,
+            );
+            curve.push(hi);
+        }
+        assert_eq!(unresolved, 0);
+        assert!(curve.len() > coarse.len(), "no refinement across equivalence");
+        for pair in curve.windows(2) {
+            let ((lo_g, lo_ph), (hi_g, hi_ph)) = (pair[0], pair[1]);
+            assert!(lo_g < hi_g, "unsorted at {lo_g} -> {hi_g}");
+            assert!((hi_ph - lo_ph).abs() <= max_ph_step, "{lo_ph} -> {hi_ph} at {lo_g}");
+        }
+        // The refined points are solves in their own right, not interpolations.
+        for &(delta_g, ph) in &curve {
+            assert!((sweep.point(delta_g, Some(ph)).0 - ph).abs() < 1e-6, "at {delta_g}");
+        }
+    }
+
+    #[test]
+    fn trust_region_solve_matches_the_undamped_root() {
+        let activity = ActivityTreatment::new(ActivityModel::Hybrid, None);
+        for (total_carbon_m, na_conc) in [(0.05, 1.0), (0.499, 1.0), (0.7, 1.0), (1.0, 1.0)] {
+            let system = CarbonateSystem::new(total_carbon_m, na_conc, (SOL_KA1, SOL_KA2, SOL_KW));
+            let undamped = solve_carbonate_state(system, activity, NewtonOptions::from_guess(9.0))
+                .unwrap_or_else(|err| panic!("C_T {total_carbon_m}: {err}"));
+            for initial_radius in [SOL_TRUST_RADIUS_DEFAULT, 0.05] {
+                let newton = NewtonOptions {
+                    trust_region: Some(NewtonTrustRegion {
+                        initial_radius,
+                        acceptance_ratio: SOL_TRUST_ACCEPTANCE_DEFAULT,
+                    }),
+                    ..NewtonOptions::from_guess(9.0)
+                };
+                let (damped, root) = solve_carbonate_state_traced(system, activity, newton)
+                    .unwrap_or_else(|err| panic!("C_T {total_carbon_m}: {err}"));
+                assert!(root.is_some(), "C_T {total_carbon_m}: fell back to bisection");
+                let gap = (damped.0.log10() - undamped.0.log10()).abs();
+                assert!(gap < 1e-9, "C_T {total_carbon_m}, radius {initial_radius}: {gap:e}");
+                assert!(damped.7 < SOL_NEWTON_STRICT_TOL);
+            }
+        }
+    }
+
+    #[test]
+    fn roots_pinned_at_a_clamp_bound_are_flagged() {
+        // A residual that only fades toward the floor: Newton meets the tolerance by
+        // clamping onto the lower edge, not at a stationary point.
+        let bounds = [(-10.0, SOL_NEWTON_LOG_UPPER)];
+        let fading = |x: &[f64]| vec![0.99e-11 * 10f64.powf(x[0] / 10.0)];
+        let (sol, _) = newton_system_solve(&fading, vec![0.0], &bounds, 1e-12, 60, None, None)
+            .unwrap_or_else(|err| panic!("{err}"));
+        assert_eq!(sol, vec![-10.0]);
+        assert_eq!(pinned_log_components(&sol, &bounds), vec![0]);
+        // An interior point, and one just outside the pin band, are not flagged.
+        let closed = closed_carbonate_log_bounds(0.7, 1.0, SOL_KW);
+        let interior: Vec<f64> = closed.iter().map(|(low, high)| 0.5 * (low + high)).collect();
+        assert!(pinned_log_components(&interior, &closed).is_empty());
+        let near_edge = [closed[0].0 + 2.0 * SOL_NEWTON_BOUND_PIN_EPS, interior[1], interior[2]];
+        assert!(pinned_log_components(&near_edge, &closed[..3]).is_empty());
+        let pinned = [interior[0], closed[1].1, interior[2], closed[3].0];
+        assert_eq!(pinned_log_components(&pinned, &closed), vec![1, 3]);
+    }
+}
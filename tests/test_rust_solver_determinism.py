@@ -0,0 +1,262 @@
+"""Reproducibility contract for the Rust carbonate speciation solver.
+
+Run ``python tests/test_rust_solver_determinism.py --regenerate`` to rewrite the
+committed reference after an intentional model change.
+"""
+
+from __future__ import annotations
+
+import argparse
+import json
+import math
+from pathlib import Path
+from typing import Any, Dict, List
+
+import pytest
+
+REFERENCE_PATH = Path(__file__).resolve().parent / "data" / "rust_solver_reference.json"
+
+# pH is compared absolutely; species concentrations and residual-free scalars
+# relatively. Both sit far above the few-ulp libm differences between platforms
+# and far below the 1e-6 pH that golden files print.
+PH_ABS_TOL = 1e-9
+VALUE_REL_TOL = 1e-9
+VALUE_ABS_TOL = 1e-18
+PH_KEYS = {"ph", "predicted_ph", "curve_ph"}
+
+KA1, KA2, KW = 4.45e-7, 4.69e-11, 1.0e-14
+
+# name -> (total carbon M, sodium M)
+SPECIATION_CASES: Dict[str, tuple[float, float]] = {
+    "caustic_heavy": (0.05, 1.0),
+    "carbonate_equivalence": (0.5, 1.0),
+    "buffer_region": (0.7, 1.0),
+    "bicarbonate_plateau": (1.0, 1.0),
+    "dilute_bicarbonate": (0.01, 0.01),
+    "over_carbonated": (1.5, 1.0),
+}
+
+# name -> (NaOH g, CO2 charged g, target pH)
+ANALYSIS_CASES: Dict[str, tuple[float, float, float]] = {
+    "dilute_partial": (2.0, 1.0, 8.5),
+    "concentrated_partial": (40.0, 15.0, 8.3),
+    "over_carbonated": (40.0, 60.0, 8.3),
+}
+
+
+def evaluate_reference_cases(ext: Any) -> Dict[str, Any]:
+    """Evaluate every fixed composition against the Rust backend.
+
+    Purpose:
+        Produce the values the reproducibility contract pins.
+    Why:
+        The test and ``--regenerate`` must evaluate exactly the same calls.
+    Inputs:
+        ext: Imported ``gl260_rust_ext`` module.
+    Outputs:
+        JSON-serializable mapping of case name to result scalars.
+    Side Effects:
+        None.
+    Exceptions:
+        Solver errors from the backend propagate.
+    """
+    results: Dict[str, Any] = {}
+    for name, (total_carbon_m, na_conc) in SPECIATION_CASES.items():
+        for model in ("hybrid", "davies", "ideal"):
+            state = ext.carbonate_state_core(
+                total_carbon_m, na_conc, KA1, KA2, KW, activity_model=model
+            )
+            results[f"speciation/{name}/{model}"] = {
+                "ph": -math.log10(state["h"]),
+                "hco3": state["hco3"],
+                "co3": state["co3"],
+                "h2co3": state["h2co3"],
+                "ionic_strength": state["ionic_strength"],
+            }
+    for name, (naoh_g, co2_g, target_ph) in ANALYSIS_CASES.items():
+        analysis = ext.analyze_bicarbonate_core(
+            naoh_g, co2_g, 1.0, None, None, target_ph, 25.0, True
+        )
+        results[f"analysis/{name}"] = {
+            "predicted_ph": analysis["predicted_ph"],
+            "total_extra_g": analysis["total_extra_g"],
+            "curve_ph": [row["ph"] for row in analysis["simulation_curve"]],
+        }
+    grid = ext.evaluate_grid(
+        [10.0, 40.0],
+        [0.0, 10.0, 20.0, 30.0, 60.0],
+        1.0,
+        temperature_c=25.0,
+        use_temp_adjusted_constants=True,
+    )
+    results["grid"] = {"ph": grid["ph"]}
+    return results
+
+
+def _collect_mismatches(
+    path: str, expected: Any, found: Any, mismatches: List[str]
+) -> None:
+    """Recursively compare one result tree against the reference.
+
+    Purpose:
+        Apply the documented tolerances leaf by leaf.
+    Why:
+        One report listing every drifting value is easier to act on than the
+        first failing assert.
+    Inputs:
+        path: Slash-joined key path of the current node.
+        expected: Reference node.
+        found: Freshly evaluated node.
+        mismatches: Accumulator for human-readable mismatch lines.
+    Outputs:
+        None; mismatches are appended in place.
+    Side Effects:
+        Mutates ``mismatches``.
+    Exceptions:
+        None.
+    """
+    if isinstance(expected, dict):
+        if not isinstance(found, dict) or set(expected) != set(found):
+            mismatches.append(f"{path}: keys differ")
+            return
+        for key in expected:
+            _collect_mismatches(f"{path}/{key}", expected[key], found[key], mismatches)
+        return
+    if isinstance(expected, list):
+        if not isinstance(found, list) or len(expected) != len(found):
+            mismatches.append(f"{path}: length differs")
+            return
+        for idx, (lhs, rhs) in enumerate(zip(expected, found)):
+            _collect_mismatches(f"{path}[{idx}]", lhs, rhs, mismatches)
+        return
+    if expected is None or found is None:
+        if expected is not found:
+            mismatches.append(f"{path}: expected {expected!r}, found {found!r}")
+        return
+    leaf = path.rsplit("/", 1)[-1].split("[", 1)[0]
+    if leaf in PH_KEYS:
+        close = abs(found - expected) <= PH_ABS_TOL
+    else:
+        close = math.isclose(
+            found, expected, rel_tol=VALUE_REL_TOL, abs_tol=VALUE_ABS_TOL
+        )
+    if not close:
+        mismatches.append(f"{path}: expected {expected!r}, found {found!r}")
+
+
+def test_solver_matches_committed_reference() -> None:
+    """Fixed compositions reproduce the committed reference within tolerance.
+
+    Purpose:
+        Enforce the cross-platform reproducibility contract.
+    Why:
+        Downstream golden-file tests rely on solver output being stable.
+    Inputs:
+        None.
+    Outputs:
+        None.
+    Side Effects:
+        Imports the Rust extension; skipped when it is not built.
+    Exceptions:
+        ``AssertionError`` listing every value outside tolerance.
+    """
+    ext = pytest.importorskip("gl260_rust_ext")
+    reference = json.loads(REFERENCE_PATH.read_text(encoding="utf-8"))
+    mismatches: List[str] = []
+    _collect_mismatches("", reference, evaluate_reference_cases(ext), mismatches)
+    assert not mismatches, "\n".join(mismatches)
+
+
+def test_solver_is_deterministic_within_process() -> None:
+    """Repeated evaluation in one process is bit-for-bit identical.
+
+    Purpose:
+        Catch hidden state (caches, warm starts) leaking between calls.
+    Why:
+        Run-to-run drift would make the cross-platform tolerance meaningless.
+    Inputs:
+        None.
+    Outputs:
+        None.
+    Side Effects:
+        Imports the Rust extension; skipped when it is not built.
+    Exceptions:
+        ``AssertionError`` when two evaluations differ.
+    """
+    ext = pytest.importorskip("gl260_rust_ext")
+    assert evaluate_reference_cases(ext) == evaluate_reference_cases(ext)
+
+
+def test_evaluate_grid_arrays_match_list_output() -> None:
+    """``as_arrays=True`` returns float64 matrices of the documented shape.
+
+    Purpose:
+        Pin the numpy layout of ``evaluate_grid`` against its list output.
+    Why:
+        Callers reshape nothing; a transposed or flattened array would silently
+        misplace every cell of a heat map.
+    Inputs:
+        None.
+    Outputs:
+        None.
+    Side Effects:
+        Imports numpy and the Rust extension; skipped when either is missing.
+    Exceptions:
+        ``AssertionError`` when shape, dtype, layout or values differ.
+    """
+    np = pytest.importorskip("numpy")
+    ext = pytest.importorskip("gl260_rust_ext")
+    naoh_g = [10.0, -1.0, 40.0]
+    co2_g = [0.0, 10.0, 20.0, 30.0]
+    kwargs = {"temperature_c": 25.0, "use_temp_adjusted_constants": True}
+    flat = ext.evaluate_grid(naoh_g, co2_g, 1.0, **kwargs)
+    arrays = ext.evaluate_grid(naoh_g, co2_g, 1.0, as_arrays=True, **kwargs)
+    for key in ("ph", "residual_norm"):
+        matrix = arrays[key]
+        assert isinstance(matrix, np.ndarray)
+        assert matrix.dtype == np.float64
+        assert matrix.shape == (len(naoh_g), len(co2_g))
+        assert matrix.flags["C_CONTIGUOUS"]
+        expected = np.array(
+            [np.nan if value is None else value for value in flat[key]]
+        ).reshape(matrix.shape)
+        np.testing.assert_array_equal(matrix, expected)
+    assert np.isnan(arrays["ph"][1]).all()
+
+
+def main() -> int:
+    """Rewrite the committed reference from the installed backend.
+
+    Purpose:
+        Refresh the reference after an intentional solver change.
+    Why:
+        The reference must come from the same calls the test makes.
+    Inputs:
+        ``--regenerate`` CLI flag.
+    Outputs:
+        Process exit code.
+    Side Effects:
+        Writes ``tests/data/rust_solver_reference.json``.
+    Exceptions:
+        ImportError when the Rust extension is unavailable.
+    """
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument(
+        "--regenerate", action="store_true", help="Rewrite the reference file."
+    )
+    args = parser.parse_args()
+    if not args.regenerate:
+        parser.print_help()
+        return 1
+    import gl260_rust_ext as ext
+
+    REFERENCE_PATH.parent.mkdir(parents=True, exist_ok=True)
+    REFERENCE_PATH.write_text(
+        json.dumps(evaluate_reference_cases(ext), indent=2) + "\n", encoding="utf-8"
+    )
+    print(f"Wrote {REFERENCE_PATH}")
+    return 0
+
+
+if __name__ == "__main__":
+    raise SystemExit(main())